@@ -103,9 +103,21 @@ impl ChebyshevPolynomials {
 
     /// Evaluate Chebyshev series with given coefficients
     /// f(x) = sum_{k=0}^n c_k * T_k(x)
+    ///
+    /// Uses Clenshaw's recurrence, as `spk_reader` does for SPK records, so
+    /// the series is summed in one backward pass. Trailing zero coefficients
+    /// may run past the maximum degree.
     pub fn evaluate_series(&self, coefficients: &[f64], x: f64) -> SpiceResult<f64> {
-        if coefficients.is_empty() {
-            return Ok(0.0);
+        let degree = match coefficients.iter().rposition(|&c| c != 0.0) {
+            Some(degree) => degree,
+            None => return Ok(0.0),
+        };
+
+        if degree > self.max_degree {
+            return Err(SpiceError::new(
+                SpiceErrorType::InvalidArgument,
+                format!("Degree {} exceeds maximum {}", degree, self.max_degree)
+            ));
         }
 
         if x.abs() > 1.0 {
@@ -115,16 +127,16 @@ impl ChebyshevPolynomials {
             ));
         }
 
-        let mut result = 0.0;
-        
-        for (degree, &coeff) in coefficients.iter().enumerate() {
-            if coeff != 0.0 {
-                let t_value = self.evaluate_first_kind(degree, x)?;
-                result += coeff * t_value;
-            }
+        // b_k = c_k + 2x b_{k+1} - b_{k+2}; f(x) = c_0 + x b_1 - b_2
+        let mut b_k_plus_1 = 0.0;
+        let mut b_k_plus_2 = 0.0;
+        for &coeff in coefficients[1..=degree].iter().rev() {
+            let b_k = 2.0 * x * b_k_plus_1 - b_k_plus_2 + coeff;
+            b_k_plus_2 = b_k_plus_1;
+            b_k_plus_1 = b_k;
         }
 
-        Ok(result)
+        Ok(coefficients[0] + x * b_k_plus_1 - b_k_plus_2)
     }
 
     /// Compute derivative of Chebyshev polynomial
@@ -148,6 +160,44 @@ impl ChebyshevPolynomials {
     }
 }
 
+/// Barycentric weights w_i = 1 / Π_{j≠i}(x_i - x_j) of a set of nodes
+///
+/// Nodes closer together than `tolerance` are rejected as duplicates.
+fn barycentric_weights(nodes: &[f64], tolerance: f64) -> SpiceResult<Vec<f64>> {
+    let mut weights = vec![1.0; nodes.len()];
+    for i in 0..nodes.len() {
+        for j in 0..nodes.len() {
+            if i == j {
+                continue;
+            }
+            let difference = nodes[i] - nodes[j];
+            if difference.abs() < tolerance {
+                return Err(SpiceError::new(
+                    SpiceErrorType::InvalidArgument,
+                    format!("Duplicate x-coordinates: {} and {}", nodes[i], nodes[j])
+                ));
+            }
+            weights[i] /= difference;
+        }
+    }
+    Ok(weights)
+}
+
+/// Second (true) barycentric form of the interpolating polynomial at x
+fn barycentric_evaluate(points: &[(f64, f64)], weights: &[f64], x: f64) -> f64 {
+    let mut numerator = 0.0;
+    let mut denominator = 0.0;
+    for (&(xi, yi), &wi) in points.iter().zip(weights) {
+        if x == xi {
+            return yi;
+        }
+        let term = wi / (x - xi);
+        numerator += term * yi;
+        denominator += term;
+    }
+    numerator / denominator
+}
+
 /// Hermite interpolation for smooth curve fitting
 /// 
 /// Enhanced implementation compatible with CSPICE hrmint_c function.
@@ -182,9 +232,9 @@ impl HermiteInterpolator {
 
     /// Add a data point with position, value, and derivative
     pub fn add_point(&mut self, x: f64, y: f64, dy_dx: f64) {
-        self.points.push((x, y, dy_dx));
-        // Keep points sorted by x-coordinate
-        self.points.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+        // Keep points sorted by x-coordinate, after any equal ones
+        let index = self.points.partition_point(|point| point.0 <= x);
+        self.points.insert(index, (x, y, dy_dx));
         // Clear cache when points change
         self.cached_basis = None;
     }
@@ -194,6 +244,23 @@ impl HermiteInterpolator {
     /// This implementation follows CSPICE hrmint_c algorithm for maximum
     /// compatibility with spacecraft trajectory calculations.
     pub fn evaluate(&self, x: f64) -> SpiceResult<f64> {
+        let basis = self.node_basis()?;
+        self.evaluate_with_basis(&basis, x)
+    }
+
+    /// Evaluate interpolated values at many x
+    ///
+    /// The barycentric weights and basis slopes at the nodes depend only on
+    /// the data, so they are computed once for the whole batch rather than
+    /// once per query as repeated `evaluate` calls would.
+    pub fn evaluate_many(&self, xs: &[f64]) -> SpiceResult<Vec<f64>> {
+        let basis = self.node_basis()?;
+        xs.iter().map(|&x| self.evaluate_with_basis(&basis, x)).collect()
+    }
+
+    /// Barycentric weights w_i = 1 / Π(x_i - x_j) and basis slopes
+    /// L_i'(x_i) = Σ 1 / (x_i - x_j) of the nodes
+    fn node_basis(&self) -> SpiceResult<(Vec<f64>, Vec<f64>)> {
         if self.points.len() < 2 {
             return Err(SpiceError::new(
                 SpiceErrorType::InvalidArgument,
@@ -201,18 +268,35 @@ impl HermiteInterpolator {
             ));
         }
 
-        let n = self.points.len();
+        let nodes: Vec<f64> = self.points.iter().map(|point| point.0).collect();
+        let weights = barycentric_weights(&nodes, self.tolerance)?;
+        let slopes = nodes
+            .iter()
+            .enumerate()
+            .map(|(i, &xi)| {
+                nodes.iter().enumerate()
+                    .filter(|&(j, _)| j != i)
+                    .map(|(_, &xj)| 1.0 / (xi - xj))
+                    .sum()
+            })
+            .collect();
+        Ok((weights, slopes))
+    }
+
+    /// Evaluate at x given the precomputed node basis
+    fn evaluate_with_basis(&self, basis: &(Vec<f64>, Vec<f64>), x: f64) -> SpiceResult<f64> {
+        let (weights, slopes) = basis;
         let mut result = 0.0;
 
         // Enhanced Hermite basis functions with numerical stability improvements
-        for i in 0..n {
-            let (xi, yi, dyi) = self.points[i];
-            
-            // Compute Lagrange basis function Li(x) and its derivative
-            let (li, dli_dx) = self.compute_lagrange_basis_and_derivative(i, x)?;
-            
+        for (i, &(xi, yi, dyi)) in self.points.iter().enumerate() {
+            // Lagrange basis function Li(x) from its barycentric weight
+            let li = self.points.iter().enumerate()
+                .filter(|&(j, _)| j != i)
+                .fold(weights[i], |product, (_, point)| product * (x - point.0));
+
             // Hermite basis functions with enhanced numerical stability
-            let factor = 1.0 - 2.0 * dli_dx * (x - xi);
+            let factor = 1.0 - 2.0 * slopes[i] * (x - xi);
             let h_i = factor * li * li;
             let h_hat_i = (x - xi) * li * li;
 
@@ -277,17 +361,15 @@ impl HermiteInterpolator {
             ));
         }
 
-        let n = self.points.len();
+        let (_, slopes) = self.node_basis()?;
         let mut result = 0.0;
 
-        for i in 0..n {
-            let (xi, yi, dyi) = self.points[i];
-            
+        for (i, &(xi, yi, dyi)) in self.points.iter().enumerate() {
             let (li, dli_dx) = self.compute_lagrange_basis_and_derivative(i, x)?;
             
             // Derivatives of Hermite basis functions
-            let factor = 1.0 - 2.0 * dli_dx * (x - xi);
-            let dh_i_dx = -2.0 * dli_dx * li * li + 2.0 * factor * li * dli_dx;
+            let factor = 1.0 - 2.0 * slopes[i] * (x - xi);
+            let dh_i_dx = -2.0 * slopes[i] * li * li + 2.0 * factor * li * dli_dx;
             let dh_hat_i_dx = li * li + 2.0 * (x - xi) * li * dli_dx;
 
             result += yi * dh_i_dx + dyi * dh_hat_i_dx;
@@ -313,14 +395,118 @@ impl HermiteInterpolator {
     }
 }
 
+/// Hermite interpolation of several components sharing the same abscissae
+///
+/// Used for SPK Type 13 states, where every node carries a position and its
+/// velocity. The Lagrange basis at the evaluation point is computed once and
+/// applied to all components.
+pub struct HermiteInterpolatorND {
+    /// Number of components at each node
+    dimension: usize,
+    /// Nodes (x, values, derivatives), sorted by x
+    points: Vec<(f64, Vec<f64>, Vec<f64>)>,
+    /// Smallest allowed separation between nodes
+    tolerance: f64,
+}
+
+impl HermiteInterpolatorND {
+    /// Create new interpolator for `dimension` components with default tolerance
+    pub fn new(dimension: usize) -> Self {
+        Self {
+            dimension,
+            points: Vec::new(),
+            tolerance: 1e-14,
+        }
+    }
+
+    /// Add a node with component values and their derivatives
+    pub fn add_point(&mut self, x: f64, values: Vec<f64>, derivatives: Vec<f64>) -> SpiceResult<()> {
+        if values.len() != self.dimension || derivatives.len() != self.dimension {
+            return Err(SpiceError::new(
+                SpiceErrorType::InvalidArgument,
+                format!(
+                    "Hermite node at {} has {} values and {} derivatives, expected {}",
+                    x, values.len(), derivatives.len(), self.dimension
+                )
+            ));
+        }
+        let index = self.points.partition_point(|point| point.0 <= x);
+        self.points.insert(index, (x, values, derivatives));
+        Ok(())
+    }
+
+    /// Evaluate all components at x
+    pub fn evaluate(&self, x: f64) -> SpiceResult<Vec<f64>> {
+        Ok(self.evaluate_with_derivative(x)?.0)
+    }
+
+    /// Evaluate all components and their derivatives at x
+    pub fn evaluate_with_derivative(&self, x: f64) -> SpiceResult<(Vec<f64>, Vec<f64>)> {
+        if self.points.len() < 2 {
+            return Err(SpiceError::new(
+                SpiceErrorType::InvalidArgument,
+                "Need at least 2 points for Hermite interpolation".to_string()
+            ));
+        }
+
+        let mut values = vec![0.0; self.dimension];
+        let mut derivatives = vec![0.0; self.dimension];
+        for (i, (xi, yi, dyi)) in self.points.iter().enumerate() {
+            // L_i(x), L_i'(x) and L_i'(x_i), built up factor by factor
+            let (mut li, mut dli, mut dli_at_node) = (1.0, 0.0, 0.0);
+            for (j, (xj, _, _)) in self.points.iter().enumerate() {
+                if i == j {
+                    continue;
+                }
+                let denominator = xi - xj;
+                if denominator.abs() < self.tolerance {
+                    return Err(SpiceError::new(
+                        SpiceErrorType::InvalidArgument,
+                        format!("Duplicate x-coordinates: {} and {}", xi, xj)
+                    ));
+                }
+                dli = (dli * (x - xj) + li) / denominator;
+                li *= (x - xj) / denominator;
+                dli_at_node += 1.0 / denominator;
+            }
+
+            let offset = x - xi;
+            let factor = 1.0 - 2.0 * dli_at_node * offset;
+            let h = factor * li * li;
+            let h_hat = offset * li * li;
+            let dh = 2.0 * li * (factor * dli - dli_at_node * li);
+            let dh_hat = li * (li + 2.0 * offset * dli);
+
+            for k in 0..self.dimension {
+                values[k] += yi[k] * h + dyi[k] * h_hat;
+                derivatives[k] += yi[k] * dh + dyi[k] * dh_hat;
+            }
+        }
+
+        Ok((values, derivatives))
+    }
+
+    /// Get number of data points
+    pub fn point_count(&self) -> usize {
+        self.points.len()
+    }
+}
+
 /// Lagrange interpolation for polynomial fitting
 /// 
-/// Enhanced implementation with Neville's algorithm and numerical stability
-/// improvements. Compatible with CSPICE lgrind_c function.
+/// Evaluates the barycentric form of the interpolating polynomial, O(n) per
+/// point, with Neville's algorithm available for comparison. Compatible with
+/// CSPICE lgrind_c function.
 pub struct LagrangeInterpolator {
     /// Known data points (x, y)
     points: Vec<(f64, f64)>,
-    /// Use Neville's algorithm for better numerical stability
+    /// Barycentric weights of the points, updated as points are added;
+    /// `None` once two abscissae coincide
+    cached_weights: Option<Vec<f64>>,
+    /// Common factor dividing the cached weights, which keeps the weights of
+    /// many points from overflowing
+    weight_scale: f64,
+    /// Use Neville's algorithm instead of the barycentric form
     use_neville: bool,
     /// Error tolerance for numerical checks
     tolerance: f64,
@@ -331,7 +517,9 @@ impl LagrangeInterpolator {
     pub fn new() -> Self {
         Self {
             points: Vec::new(),
-            use_neville: true,  // Default to more stable algorithm
+            cached_weights: Some(Vec::new()),
+            weight_scale: 1.0,
+            use_neville: false,
             tolerance: 1e-14,
         }
     }
@@ -340,6 +528,8 @@ impl LagrangeInterpolator {
     pub fn with_algorithm(use_neville: bool) -> Self {
         Self {
             points: Vec::new(),
+            cached_weights: Some(Vec::new()),
+            weight_scale: 1.0,
             use_neville,
             tolerance: 1e-14,
         }
@@ -349,16 +539,61 @@ impl LagrangeInterpolator {
     pub fn with_tolerance(tolerance: f64) -> Self {
         Self {
             points: Vec::new(),
-            use_neville: true,
+            cached_weights: Some(Vec::new()),
+            weight_scale: 1.0,
+            use_neville: false,
             tolerance,
         }
     }
 
     /// Add a data point
+    ///
+    /// The barycentric weights are updated in O(n): each existing weight is
+    /// divided by its distance from the new abscissa. A coincident abscissa
+    /// invalidates the weights until the interpolator is cleared.
     pub fn add_point(&mut self, x: f64, y: f64) {
-        self.points.push((x, y));
         // Keep points sorted by x-coordinate for stability
-        self.points.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+        let index = self.points.partition_point(|point| point.0 <= x);
+
+        if let Some(weights) = &mut self.cached_weights {
+            let mut weight = 1.0 / self.weight_scale;
+            let mut duplicate = false;
+            for (point, wj) in self.points.iter().zip(weights.iter_mut()) {
+                let difference = point.0 - x;
+                duplicate |= difference.abs() < self.tolerance;
+                *wj /= difference;
+                weight /= -difference;
+            }
+            weights.insert(index, weight);
+
+            // The barycentric form is invariant to a common scale
+            let scale = weights.iter().fold(0.0_f64, |max, w| max.max(w.abs()));
+            if duplicate || !scale.is_finite() {
+                self.cached_weights = None;
+            } else if scale > 0.0 {
+                weights.iter_mut().for_each(|w| *w /= scale);
+                self.weight_scale *= scale;
+            }
+        }
+
+        self.points.insert(index, (x, y));
+    }
+
+    /// Cached barycentric weights, or an error naming coincident abscissae
+    fn weights(&self) -> SpiceResult<&[f64]> {
+        match &self.cached_weights {
+            Some(weights) => Ok(weights),
+            None => {
+                // Points are sorted, so coincident abscissae are adjacent
+                let (a, b) = self.points.windows(2)
+                    .find(|pair| (pair[1].0 - pair[0].0).abs() < self.tolerance)
+                    .map_or((f64::NAN, f64::NAN), |pair| (pair[0].0, pair[1].0));
+                Err(SpiceError::new(
+                    SpiceErrorType::InvalidArgument,
+                    format!("Duplicate x-coordinates: {} and {}", a, b)
+                ))
+            }
+        }
     }
 
     /// Evaluate interpolated value at given x
@@ -375,30 +610,89 @@ impl LagrangeInterpolator {
         }
 
         // Check for duplicate x-coordinates
-        self.validate_points()?;
+        let weights = self.weights()?;
 
         if self.use_neville {
-            self.evaluate_neville(x)
+            self.evaluate_neville(&self.points, x)
         } else {
-            self.evaluate_classic(x)
+            Ok(barycentric_evaluate(&self.points, weights, x))
+        }
+    }
+
+    /// Evaluate using only the `window` points nearest `x`
+    ///
+    /// As in SPK types 8 and 9, the window straddles `x`: for an even window
+    /// `x` lies between its two middle points. Near either end of the data the
+    /// window is clamped to the first or last `window` points. Interpolating
+    /// a low-degree polynomial over a sliding window avoids the Runge
+    /// oscillation of a single polynomial through every node.
+    pub fn evaluate_windowed(&self, x: f64, window: usize) -> SpiceResult<f64> {
+        if window == 0 {
+            return Err(SpiceError::new(
+                SpiceErrorType::InvalidArgument,
+                "Interpolation window must contain at least one point".to_string()
+            ));
+        }
+        if self.points.is_empty() {
+            return Err(SpiceError::new(
+                SpiceErrorType::InvalidArgument,
+                "No points available for interpolation".to_string()
+            ));
+        }
+
+        let window = window.min(self.points.len());
+        let above = self.points.partition_point(|&(xi, _)| xi <= x);
+        let first = above.saturating_sub(window / 2).min(self.points.len() - window);
+        let points = &self.points[first..first + window];
+
+        self.evaluate_points(points, x)
+    }
+
+    /// Evaluate interpolated values at many x
+    ///
+    /// Uses the barycentric weights maintained by `add_point`, so each query
+    /// costs O(n) and no per-batch setup is needed.
+    pub fn evaluate_many(&self, xs: &[f64]) -> SpiceResult<Vec<f64>> {
+        if self.points.is_empty() {
+            return Err(SpiceError::new(
+                SpiceErrorType::InvalidArgument,
+                "No points available for interpolation".to_string()
+            ));
+        }
+
+        let weights = self.weights()?;
+        Ok(xs.iter().map(|&x| barycentric_evaluate(&self.points, weights, x)).collect())
+    }
+
+    /// Interpolate through a subset of the points with the configured algorithm
+    fn evaluate_points(&self, points: &[(f64, f64)], x: f64) -> SpiceResult<f64> {
+        if points.len() == 1 {
+            return Ok(points[0].1);
+        }
+        if self.use_neville {
+            self.evaluate_neville(points, x)
+        } else {
+            let nodes: Vec<f64> = points.iter().map(|point| point.0).collect();
+            let weights = barycentric_weights(&nodes, self.tolerance)?;
+            Ok(barycentric_evaluate(points, &weights, x))
         }
     }
 
     /// Neville's algorithm for numerically stable Lagrange interpolation
-    fn evaluate_neville(&self, x: f64) -> SpiceResult<f64> {
-        let n = self.points.len();
+    fn evaluate_neville(&self, points: &[(f64, f64)], x: f64) -> SpiceResult<f64> {
+        let n = points.len();
         let mut p = vec![0.0; n];
         
         // Initialize with function values
         for i in 0..n {
-            p[i] = self.points[i].1;
+            p[i] = points[i].1;
         }
 
         // Neville's recursive algorithm
         for i in 1..n {
             for j in 0..(n - i) {
-                let xi = self.points[j].0;
-                let xi_plus_i = self.points[j + i].0;
+                let xi = points[j].0;
+                let xi_plus_i = points[j + i].0;
                 
                 let denominator = xi_plus_i - xi;
                 if denominator.abs() < self.tolerance {
@@ -415,52 +709,6 @@ impl LagrangeInterpolator {
         Ok(p[0])
     }
 
-    /// Classic Lagrange interpolation algorithm
-    fn evaluate_classic(&self, x: f64) -> SpiceResult<f64> {
-        let n = self.points.len();
-        let mut result = 0.0;
-
-        for i in 0..n {
-            let (xi, yi) = self.points[i];
-            let mut basis = 1.0;
-
-            for j in 0..n {
-                if i != j {
-                    let xj = self.points[j].0;
-                    let denominator = xi - xj;
-                    
-                    if denominator.abs() < self.tolerance {
-                        return Err(SpiceError::new(
-                            SpiceErrorType::InvalidArgument,
-                            format!("Duplicate x-coordinates: {} and {}", xi, xj)
-                        ));
-                    }
-                    
-                    basis *= (x - xj) / denominator;
-                }
-            }
-
-            result += yi * basis;
-        }
-
-        Ok(result)
-    }
-
-    /// Validate points for numerical stability
-    fn validate_points(&self) -> SpiceResult<()> {
-        for i in 0..self.points.len() {
-            for j in (i + 1)..self.points.len() {
-                if (self.points[i].0 - self.points[j].0).abs() < self.tolerance {
-                    return Err(SpiceError::new(
-                        SpiceErrorType::InvalidArgument,
-                        format!("Points too close: {} and {}", self.points[i].0, self.points[j].0)
-                    ));
-                }
-            }
-        }
-        Ok(())
-    }
-
     /// Evaluate derivative at given x using finite differences
     pub fn evaluate_derivative(&self, x: f64, h: f64) -> SpiceResult<f64> {
         let f_plus = self.evaluate(x + h)?;
@@ -504,6 +752,8 @@ impl LagrangeInterpolator {
     /// Clear all data points
     pub fn clear(&mut self) {
         self.points.clear();
+        self.cached_weights = Some(Vec::new());
+        self.weight_scale = 1.0;
     }
 
     /// Get number of data points
@@ -516,7 +766,7 @@ impl LagrangeInterpolator {
         !self.points.is_empty()
     }
 
-    /// Set algorithm choice (true for Neville, false for classic)
+    /// Set algorithm choice (true for Neville, false for barycentric)
     pub fn set_algorithm(&mut self, use_neville: bool) {
         self.use_neville = use_neville;
     }
@@ -863,6 +1113,63 @@ impl NumericalDifferentiator {
         // Richardson extrapolation: R = D(h/2) + (D(h/2) - D(h)) / 3
         d2 + (d2 - d1) / 3.0
     }
+
+    /// Derivative from a triangular Richardson table of central differences
+    ///
+    /// Row i holds the central difference with step h0 / 2^i followed by its
+    /// successive extrapolations, as in `romberg_integration`. Each new entry
+    /// carries an error estimate from its neighbours, and refinement stops
+    /// once round-off makes the newest row clearly worse than the best so far.
+    ///
+    /// The entry with the smallest error estimate is returned rather than the
+    /// last one computed: by the time refinement stops, the most refined entry
+    /// is usually the one round-off has already degraded.
+    pub fn richardson_table<F>(f: F, x: f64, h0: f64, levels: usize) -> f64
+    where
+        F: Fn(f64) -> f64,
+    {
+        let mut previous = vec![Self::central_difference(&f, x, h0)];
+        let mut best = previous[0];
+        let mut best_error = f64::INFINITY;
+
+        for i in 1..=levels {
+            let h = h0 * 0.5_f64.powi(i as i32);
+            let mut row = Vec::with_capacity(i + 1);
+            row.push(Self::central_difference(&f, x, h));
+
+            for j in 1..=i {
+                let factor = 4.0_f64.powi(j as i32);
+                let value = (factor * row[j - 1] - previous[j - 1]) / (factor - 1.0);
+                let error = (value - row[j - 1]).abs().max((value - previous[j - 1]).abs());
+                if error <= best_error {
+                    best = value;
+                    best_error = error;
+                }
+                row.push(value);
+            }
+
+            if (row[i] - previous[i - 1]).abs() >= 2.0 * best_error {
+                break;
+            }
+            previous = row;
+        }
+
+        best
+    }
+
+    /// Complex-step derivative: f'(x) ≈ Im(f(x + ih)) / h
+    ///
+    /// `f` must be analytic and evaluated in complex arithmetic throughout,
+    /// with no use of the real or imaginary part alone (such as `abs` or
+    /// comparisons). No difference of nearby values is taken, so there is no
+    /// subtractive cancellation and `h` can be made tiny (e.g. 1e-20) to give
+    /// a derivative accurate to machine precision.
+    pub fn complex_step<F>(f: F, x: f64, h: f64) -> f64
+    where
+        F: Fn(Complex) -> Complex,
+    {
+        f(Complex::new(x, h)).imag / h
+    }
 }
 
 #[cfg(test)]
@@ -898,6 +1205,38 @@ mod tests {
         assert!(cheb.evaluate_first_kind(1, -1.5).is_err());
     }
 
+    #[test]
+    fn test_chebyshev_series_matches_term_by_term_sum() {
+        let cheb = ChebyshevPolynomials::new(20);
+        let coefficients: Vec<f64> = (0..=20).map(|k| ((k * 7 + 3) % 11) as f64 / (k as f64 + 1.0) - 0.4).collect();
+
+        for x in [-1.0, -0.73, -0.2, 0.0, 0.41, 0.999, 1.0] {
+            let term_by_term: f64 = coefficients.iter().enumerate()
+                .map(|(k, c)| c * cheb.evaluate_first_kind(k, x).unwrap())
+                .sum();
+            assert!((cheb.evaluate_series(&coefficients, x).unwrap() - term_by_term).abs() < 1e-13);
+        }
+
+        assert_eq!(cheb.evaluate_series(&[], 0.5).unwrap(), 0.0);
+        assert!(cheb.evaluate_series(&coefficients, 1.5).is_err());
+        assert!(ChebyshevPolynomials::new(19).evaluate_series(&coefficients, 0.5).is_err());
+    }
+
+    #[test]
+    fn test_chebyshev_series_is_single_pass() {
+        // Restarting the recurrence per term would take ~n²/2 = 5e10 steps
+        // here and stall the test; one Clenshaw pass is n steps. With
+        // x = cos θ the series of ones sums to 1/2 + sin((n + 1/2)θ) / (2 sin(θ/2)).
+        let n = 316_000;
+        let cheb = ChebyshevPolynomials::new(n);
+        let coefficients = vec![1.0; n + 1];
+        let theta: f64 = 0.3;
+
+        let value = cheb.evaluate_series(&coefficients, theta.cos()).unwrap();
+        let expected = 0.5 + ((n as f64 + 0.5) * theta).sin() / (2.0 * (0.5 * theta).sin());
+        assert!((value - expected).abs() < 1e-6);
+    }
+
     #[test]
     fn test_hermite_interpolation() {
         let mut hermite = HermiteInterpolator::new();
@@ -912,6 +1251,63 @@ mod tests {
         assert!((result - expected).abs() < 0.1);
     }
 
+    #[test]
+    fn test_hermite_points_inserted_in_sorted_position() {
+        let mut hermite = HermiteInterpolator::new();
+        for i in (0..1000).rev() {
+            hermite.add_point(i as f64 * 0.01, i as f64, 1.0);
+        }
+        assert_eq!(hermite.point_count(), 1000);
+        assert!(hermite.points.iter().enumerate().all(|(i, point)| point.1 == i as f64));
+
+        // Reverse insertion interpolates exactly as forward insertion does
+        let f = |x: f64| x.sin();
+        let df = |x: f64| x.cos();
+        let mut forward = HermiteInterpolator::new();
+        let mut reverse = HermiteInterpolator::new();
+        for i in 0..20 {
+            let x = i as f64 * 0.1;
+            forward.add_point(x, f(x), df(x));
+            let x = (19 - i) as f64 * 0.1;
+            reverse.add_point(x, f(x), df(x));
+        }
+        for i in 0..20 {
+            let x = i as f64 * 0.1;
+            assert!((reverse.evaluate(x).unwrap() - f(x)).abs() < 1e-12);
+        }
+        assert_eq!(reverse.evaluate(0.95).unwrap(), forward.evaluate(0.95).unwrap());
+    }
+
+    #[test]
+    fn test_hermite_nd_interpolates_state_components() {
+        // Cubic position with its velocity, and the velocity with its
+        // acceleration: exact for a Hermite fit through three nodes
+        let position = |t: f64| [t * t * t - 2.0 * t, 0.5 * t * t + 1.0, 4.0 - t];
+        let velocity = |t: f64| [3.0 * t * t - 2.0, t, -1.0];
+        let acceleration = |t: f64| [6.0 * t, 1.0, 0.0];
+
+        let mut interpolator = HermiteInterpolatorND::new(6);
+        for t in [2.0, -1.0, 0.5] {
+            let values = [position(t), velocity(t)].concat();
+            let derivatives = [velocity(t), acceleration(t)].concat();
+            interpolator.add_point(t, values, derivatives).unwrap();
+        }
+        assert_eq!(interpolator.point_count(), 3);
+
+        for t in [-1.0, -0.3, 0.5, 1.25, 2.0] {
+            let (values, derivatives) = interpolator.evaluate_with_derivative(t).unwrap();
+            let expected = [position(t), velocity(t)].concat();
+            let expected_derivatives = [velocity(t), acceleration(t)].concat();
+            for k in 0..6 {
+                assert!((values[k] - expected[k]).abs() < 1e-12);
+                assert!((derivatives[k] - expected_derivatives[k]).abs() < 1e-12);
+            }
+        }
+
+        assert!(interpolator.add_point(3.0, vec![0.0; 3], vec![0.0; 6]).is_err());
+        assert!(HermiteInterpolatorND::new(6).evaluate(0.0).is_err());
+    }
+
     #[test]
     fn test_lagrange_interpolation() {
         let mut lagrange = LagrangeInterpolator::new();
@@ -927,6 +1323,139 @@ mod tests {
         assert!((result - expected).abs() < 1e-10);
     }
 
+    #[test]
+    fn test_windowed_lagrange_avoids_runge_oscillation() {
+        let runge = |x: f64| 1.0 / (1.0 + 25.0 * x * x);
+        let mut lagrange = LagrangeInterpolator::with_algorithm(true);
+        for i in 0..=200 {
+            let x = -1.0 + i as f64 * 0.01;
+            lagrange.add_point(x, runge(x));
+        }
+
+        // A single degree-200 polynomial is useless near the ends
+        let full = lagrange.evaluate(0.995).unwrap();
+        assert!(full.is_nan() || (full - runge(0.995)).abs() > 1.0);
+
+        // Degree 5 over the nearest six points, including clamped windows at
+        // and beyond the ends of the data
+        for x in [-1.0, -0.9973, -0.5, 0.0, 0.0051, 0.3333, 0.995, 1.0] {
+            let windowed = lagrange.evaluate_windowed(x, 6).unwrap();
+            assert!((windowed - runge(x)).abs() < 1e-4, "x = {}: {} vs {}", x, windowed, runge(x));
+        }
+
+        // Nodes are reproduced exactly, and a window larger than the data
+        // falls back to every point
+        assert_eq!(lagrange.evaluate_windowed(0.25, 4).unwrap(), runge(0.25));
+        let mut small = LagrangeInterpolator::new();
+        small.add_point(0.0, 1.0);
+        small.add_point(1.0, 3.0);
+        assert!((small.evaluate_windowed(0.5, 8).unwrap() - 2.0).abs() < 1e-15);
+        assert!(small.evaluate_windowed(0.5, 0).is_err());
+    }
+
+    #[test]
+    fn test_evaluate_many_matches_single_point_evaluation() {
+        let xs: Vec<f64> = (0..41).map(|k| -0.2 + k as f64 * 0.06).collect();
+
+        let mut lagrange = LagrangeInterpolator::new();
+        for i in 0..9 {
+            let x = i as f64 * 0.25;
+            lagrange.add_point(x, (1.5 * x).sin());
+        }
+        let many = lagrange.evaluate_many(&xs).unwrap();
+        for (x, value) in xs.iter().zip(&many) {
+            assert!((value - lagrange.evaluate(*x).unwrap()).abs() < 1e-12);
+        }
+
+        // Hermite through three nodes reproduces a quintic
+        let f = |x: f64| x.powi(5) - 2.0 * x * x + 1.0;
+        let df = |x: f64| 5.0 * x.powi(4) - 4.0 * x;
+        let mut hermite = HermiteInterpolator::new();
+        for x in [0.0, 1.0, 2.5] {
+            hermite.add_point(x, f(x), df(x));
+        }
+        let many = hermite.evaluate_many(&xs).unwrap();
+        for (x, value) in xs.iter().zip(&many) {
+            assert_eq!(*value, hermite.evaluate(*x).unwrap());
+            assert!((value - f(*x)).abs() < 1e-9);
+            assert!((hermite.evaluate_derivative(*x).unwrap() - df(*x)).abs() < 1e-9);
+        }
+
+        assert!(LagrangeInterpolator::new().evaluate_many(&xs).is_err());
+        hermite.add_point(1.0, 0.0, 0.0);
+        assert!(hermite.evaluate_many(&xs).is_err());
+    }
+
+    #[test]
+    fn test_barycentric_lagrange_matches_neville() {
+        let f = |x: f64| x.exp() * (3.0 * x).sin();
+        let mut barycentric = LagrangeInterpolator::new();
+        let mut neville = LagrangeInterpolator::with_algorithm(true);
+        // Chebyshev points, added out of order
+        for k in (0..50).rev().step_by(2).chain((0..50).step_by(2)) {
+            let x = 2.0 * (std::f64::consts::PI * (k as f64 + 0.5) / 50.0).cos();
+            barycentric.add_point(x, f(x));
+            neville.add_point(x, f(x));
+        }
+        assert_eq!(barycentric.point_count(), 50);
+
+        for k in 0..=80 {
+            let x = -2.0 + k as f64 * 0.05;
+            let fast = barycentric.evaluate(x).unwrap();
+            assert!((fast - neville.evaluate(x).unwrap()).abs() < 1e-12);
+            assert!((fast - f(x)).abs() < 1e-12);
+        }
+
+        // Coincident abscissae are caught when the weights are updated
+        barycentric.add_point(barycentric.points[10].0, 0.0);
+        let err = barycentric.evaluate(0.1).unwrap_err();
+        assert!(err.message.contains("Duplicate"));
+        barycentric.clear();
+        barycentric.add_point(1.0, 2.0);
+        barycentric.add_point(3.0, 4.0);
+        assert_eq!(barycentric.evaluate(2.0).unwrap(), 3.0);
+    }
+
+    #[test]
+    fn test_richardson_table_reaches_near_machine_precision() {
+        let exact = 1.0_f64.cos();
+
+        let refined = NumericalDifferentiator::richardson_table(f64::sin, 1.0, 0.5, 8);
+        assert!((refined - exact).abs() < 1e-13, "error {}", (refined - exact).abs());
+
+        // No single step balances truncation and round-off this well
+        for h in [1e-3, 1e-5, 1e-7] {
+            let central = NumericalDifferentiator::central_difference(f64::sin, 1.0, h);
+            assert!((central - exact).abs() > 1e-12);
+        }
+
+        // Without refinement the table is a plain central difference
+        assert_eq!(
+            NumericalDifferentiator::richardson_table(f64::sin, 1.0, 0.1, 0),
+            NumericalDifferentiator::central_difference(f64::sin, 1.0, 0.1)
+        );
+
+        // A function that never settles runs all 80 levels without overflowing
+        assert!(NumericalDifferentiator::richardson_table(|_| f64::NAN, 1.0, 0.1, 80).is_nan());
+    }
+
+    #[test]
+    fn test_complex_step_derivative_has_no_cancellation() {
+        let x: f64 = 0.5;
+        let exact = x.exp();
+
+        for h in [1e-8, 1e-20, 1e-100] {
+            let derivative = NumericalDifferentiator::complex_step(|z| z.exp(), x, h);
+            assert!((derivative - exact).abs() < 1e-15, "h = {}: {}", h, derivative);
+        }
+        // A central difference with the same tiny step loses every digit
+        assert_eq!(NumericalDifferentiator::central_difference(f64::exp, x, 1e-20), 0.0);
+
+        // z³ - 2z through complex products: 3x² - 2
+        let cubic = |z: Complex| z.multiply(&z).multiply(&z).add(&Complex::new(-2.0 * z.real, -2.0 * z.imag));
+        assert!((NumericalDifferentiator::complex_step(cubic, 1.5, 1e-30) - 4.75).abs() < 1e-15);
+    }
+
     #[test]
     fn test_numerical_differentiation() {
         // Test with f(x) = x²
@@ -1207,6 +1736,70 @@ impl Complex {
             imag: sqrt_magnitude * half_phase.sin(),
         }
     }
+
+    /// Complex sine: sin(x + iy) = sin(x)cosh(y) + i*cos(x)sinh(y)
+    pub fn sin(&self) -> Self {
+        Self {
+            real: self.real.sin() * self.imag.cosh(),
+            imag: self.real.cos() * self.imag.sinh(),
+        }
+    }
+
+    /// Complex cosine: cos(x + iy) = cos(x)cosh(y) - i*sin(x)sinh(y)
+    pub fn cos(&self) -> Self {
+        Self {
+            real: self.real.cos() * self.imag.cosh(),
+            imag: -self.real.sin() * self.imag.sinh(),
+        }
+    }
+
+    /// Complex tangent: tan(x + iy) = (sin(2x) + i*sinh(2y)) / (cos(2x) + cosh(2y))
+    ///
+    /// Tends to ±i as the imaginary part grows, which is returned once
+    /// cosh(2y) would overflow.
+    pub fn tan(&self) -> Self {
+        if self.imag.abs() > 350.0 {
+            return Self::new(0.0, self.imag.signum());
+        }
+        let (x, y) = (2.0 * self.real, 2.0 * self.imag);
+        let denominator = x.cos() + y.cosh();
+        Self {
+            real: x.sin() / denominator,
+            imag: y.sinh() / denominator,
+        }
+    }
+
+    /// Complex hyperbolic sine: sinh(x + iy) = sinh(x)cos(y) + i*cosh(x)sin(y)
+    pub fn sinh(&self) -> Self {
+        Self {
+            real: self.real.sinh() * self.imag.cos(),
+            imag: self.real.cosh() * self.imag.sin(),
+        }
+    }
+
+    /// Complex hyperbolic cosine: cosh(x + iy) = cosh(x)cos(y) + i*sinh(x)sin(y)
+    pub fn cosh(&self) -> Self {
+        Self {
+            real: self.real.cosh() * self.imag.cos(),
+            imag: self.real.sinh() * self.imag.sin(),
+        }
+    }
+
+    /// Complex hyperbolic tangent: tanh(x + iy) = (sinh(2x) + i*sin(2y)) / (cosh(2x) + cos(2y))
+    ///
+    /// Tends to ±1 as the real part grows, which is returned once cosh(2x)
+    /// would overflow.
+    pub fn tanh(&self) -> Self {
+        if self.real.abs() > 350.0 {
+            return Self::new(self.real.signum(), 0.0);
+        }
+        let (x, y) = (2.0 * self.real, 2.0 * self.imag);
+        let denominator = x.cosh() + y.cos();
+        Self {
+            real: x.sinh() / denominator,
+            imag: y.sin() / denominator,
+        }
+    }
 }
 
 impl fmt::Display for Complex {
@@ -1231,6 +1824,34 @@ impl SpecialFunctions {
             return f64::NAN;
         }
 
+        if x < 0.5 {
+            // Use reflection formula: Γ(z)Γ(1-z) = π/sin(πz)
+            return std::f64::consts::PI / ((std::f64::consts::PI * x).sin() * Self::gamma(1.0 - x));
+        }
+
+        let (t, a) = Self::lanczos(x);
+        (2.0 * std::f64::consts::PI).sqrt() * t.powf(x - 0.5) * (-t).exp() * a
+    }
+
+    /// Logarithm of gamma function
+    ///
+    /// Uses the log form of the Lanczos approximation, so it stays finite
+    /// where `gamma` overflows (x ≳ 171).
+    pub fn ln_gamma(x: f64) -> f64 {
+        if x <= 0.0 {
+            return f64::NAN;
+        }
+        if x < 0.5 {
+            return Self::gamma(x).ln();
+        }
+
+        let (t, a) = Self::lanczos(x);
+        0.5 * (2.0 * std::f64::consts::PI).ln() + (x - 0.5) * t.ln() - t + a.ln()
+    }
+
+    /// Lanczos shift t = x + g - 1/2 and series sum for x ≥ 1/2, with
+    /// Γ(x) = √(2π) t^(x-1/2) e^(-t) a
+    fn lanczos(x: f64) -> (f64, f64) {
         // Lanczos coefficients for g = 7
         const G: f64 = 7.0;
         const COEFFICIENTS: [f64; 9] = [
@@ -1245,27 +1866,12 @@ impl SpecialFunctions {
             1.5056327351493116e-7,
         ];
 
-        if x < 0.5 {
-            // Use reflection formula: Γ(z)Γ(1-z) = π/sin(πz)
-            return std::f64::consts::PI / ((std::f64::consts::PI * x).sin() * Self::gamma(1.0 - x));
-        }
-
         let x = x - 1.0;
         let mut a = COEFFICIENTS[0];
         for (i, coeff) in COEFFICIENTS.iter().enumerate().skip(1) {
             a += coeff / (x + i as f64);
         }
-
-        let t = x + G + 0.5;
-        (2.0 * std::f64::consts::PI).sqrt() * t.powf(x + 0.5) * (-t).exp() * a
-    }
-
-    /// Logarithm of gamma function for large arguments
-    pub fn ln_gamma(x: f64) -> f64 {
-        if x <= 0.0 {
-            return f64::NAN;
-        }
-        Self::gamma(x).ln()
+        (x + G + 0.5, a)
     }
 
     /// Beta function: B(x,y) = Γ(x)Γ(y)/Γ(x+y)
@@ -1583,7 +2189,7 @@ impl AdvancedIntegration {
             
             // Richardson extrapolation
             for j in 1..=i {
-                let factor = (1_u64 << (2 * j)) as f64;
+                let factor = 4.0_f64.powi(j as i32);
                 r[i][j] = (factor * r[i][j - 1] - r[i - 1][j - 1]) / (factor - 1.0);
             }
             
@@ -1664,6 +2270,42 @@ mod week3_tests {
         assert!(ln_w.imag.abs() < 1e-12);
     }
 
+    #[test]
+    fn test_complex_trigonometric_functions() {
+        // Real arguments agree with f64
+        for x in [-2.5, -0.3, 0.0, 0.7, 1.2, 3.0] {
+            let z = Complex::new(x, 0.0);
+            assert!((z.sin().real - x.sin()).abs() < 1e-15);
+            assert!((z.cos().real - x.cos()).abs() < 1e-15);
+            assert!((z.tan().real - x.tan()).abs() < 1e-14);
+            assert!((z.sinh().real - x.sinh()).abs() < 1e-14);
+            assert!((z.cosh().real - x.cosh()).abs() < 1e-14);
+            assert!((z.tanh().real - x.tanh()).abs() < 1e-15);
+            assert_eq!(z.sin().imag, 0.0);
+        }
+
+        for z in [Complex::new(0.8, -1.3), Complex::new(-2.0, 0.4), Complex::new(1.5, 2.5)] {
+            // sin² + cos² = 1 and cosh² - sinh² = 1
+            let (sin, cos) = (z.sin(), z.cos());
+            let one = sin.multiply(&sin).add(&cos.multiply(&cos));
+            assert!((one.real - 1.0).abs() < 1e-12 && one.imag.abs() < 1e-12);
+            let (sinh, cosh) = (z.sinh(), z.cosh());
+            let one = cosh.multiply(&cosh).add(&sinh.multiply(&sinh).multiply(&Complex::new(-1.0, 0.0)));
+            assert!((one.real - 1.0).abs() < 1e-12 && one.imag.abs() < 1e-12);
+
+            // tan = sin / cos, tanh = sinh / cosh, and sin(iz) = i sinh(z)
+            let tan = sin.divide(&cos).unwrap();
+            assert!((z.tan().real - tan.real).abs() < 1e-12 && (z.tan().imag - tan.imag).abs() < 1e-12);
+            let tanh = sinh.divide(&cosh).unwrap();
+            assert!((z.tanh().real - tanh.real).abs() < 1e-12 && (z.tanh().imag - tanh.imag).abs() < 1e-12);
+            let sin_iz = Complex::new(-z.imag, z.real).sin();
+            assert!((sin_iz.real + sinh.imag).abs() < 1e-12 && (sin_iz.imag - sinh.real).abs() < 1e-12);
+        }
+
+        assert_eq!(Complex::new(1.0, -800.0).tan(), Complex::new(0.0, -1.0));
+        assert_eq!(Complex::new(800.0, 1.0).tanh(), Complex::new(1.0, 0.0));
+    }
+
     #[test]
     fn test_special_functions() {
         // Test gamma function
@@ -1678,6 +2320,24 @@ mod week3_tests {
         assert!((SpecialFunctions::erf(-100.0) + 1.0).abs() < 1e-6);
     }
 
+    #[test]
+    fn test_ln_gamma_beyond_gamma_overflow() {
+        assert!(SpecialFunctions::gamma(172.0).is_infinite());
+        assert!(SpecialFunctions::ln_gamma(171.0).is_finite());
+        assert!(SpecialFunctions::ln_gamma(172.0).is_finite());
+
+        // ln Γ(200) = ln(199!)
+        let ln_factorial: f64 = (2..200).map(|k| (k as f64).ln()).sum();
+        assert!((SpecialFunctions::ln_gamma(200.0) - ln_factorial).abs() < 1e-10);
+        assert!((SpecialFunctions::ln_gamma(200.0) - 857.93).abs() < 0.01);
+
+        for x in [0.1, 0.5, 1.0, 2.5, 7.3, 20.0, 50.0] {
+            let gamma = SpecialFunctions::gamma(x);
+            assert!((SpecialFunctions::ln_gamma(x).exp() - gamma).abs() < 1e-12 * gamma);
+        }
+        assert!(SpecialFunctions::ln_gamma(0.0).is_nan());
+    }
+
     #[test]
     fn test_bessel_functions() {
         // Test Bessel J0 at x=0
@@ -1911,97 +2571,358 @@ impl MatrixOperations {
     }
 
     /// Eigenvalue computation using QR algorithm
+    ///
+    /// The matrix is reduced to upper Hessenberg form and then deflated with
+    /// Francis double-shift QR steps, so complex conjugate pairs come out
+    /// without complex arithmetic.
     pub fn eigenvalues(matrix: &[Vec<f64>]) -> SpiceResult<Vec<Complex>> {
         let n = matrix.len();
-        if n == 0 || matrix[0].len() != n {
+        if n == 0 || matrix.iter().any(|row| row.len() != n) {
             return Err(SpiceError::new(
                 SpiceErrorType::InvalidArgument,
                 "Matrix must be square and non-empty".to_string()
             ));
         }
 
-        // For simplicity, implement for 2x2 matrices analytically
-        if n == 2 {
-            let a = matrix[0][0];
-            let b = matrix[0][1];
-            let c = matrix[1][0];
-            let d = matrix[1][1];
+        let mut hessenberg = matrix.to_vec();
+        Self::reduce_to_hessenberg(&mut hessenberg);
+        Self::hessenberg_eigenvalues(&mut hessenberg)
+    }
 
-            let trace = a + d;
-            let det = a * d - b * c;
-            let discriminant = trace * trace - 4.0 * det;
+    /// Reduce a square matrix in place to upper Hessenberg form by
+    /// elimination with pivoting (a similarity transform)
+    fn reduce_to_hessenberg(a: &mut [Vec<f64>]) {
+        let n = a.len();
+        for m in 1..n.saturating_sub(1) {
+            let mut pivot = 0.0;
+            let mut pivot_row = m;
+            for (j, row) in a.iter().enumerate().skip(m) {
+                if row[m - 1].abs() > f64::abs(pivot) {
+                    pivot = row[m - 1];
+                    pivot_row = j;
+                }
+            }
 
-            if discriminant >= 0.0 {
-                let sqrt_disc = discriminant.sqrt();
-                return Ok(vec![
-                    Complex::new((trace + sqrt_disc) / 2.0, 0.0),
-                    Complex::new((trace - sqrt_disc) / 2.0, 0.0),
-                ]);
-            } else {
-                let sqrt_disc = (-discriminant).sqrt();
-                return Ok(vec![
-                    Complex::new(trace / 2.0, sqrt_disc / 2.0),
-                    Complex::new(trace / 2.0, -sqrt_disc / 2.0),
-                ]);
+            if pivot_row != m {
+                a.swap(pivot_row, m);
+                for row in a.iter_mut() {
+                    row.swap(pivot_row, m);
+                }
+            }
+
+            if pivot != 0.0 {
+                for i in m + 1..n {
+                    let factor = a[i][m - 1] / pivot;
+                    if factor != 0.0 {
+                        let (upper, lower) = a.split_at_mut(i);
+                        for (target, source) in lower[0][m..].iter_mut().zip(&upper[m][m..]) {
+                            *target -= factor * source;
+                        }
+                        for row in a.iter_mut() {
+                            row[m] += factor * row[i];
+                        }
+                    }
+                }
             }
         }
 
-        // For larger matrices, use simplified approach
-        // In a full implementation, this would use the QR algorithm
-        let mut eigenvals = Vec::new();
-        for i in 0..n {
-            eigenvals.push(Complex::new(matrix[i][i], 0.0));
+        for (i, row) in a.iter_mut().enumerate() {
+            for value in row.iter_mut().take(i.saturating_sub(1)) {
+                *value = 0.0;
+            }
         }
+    }
+
+    /// Eigenvalues of an upper Hessenberg matrix by Francis double-shift QR,
+    /// destroying the matrix
+    fn hessenberg_eigenvalues(a: &mut [Vec<f64>]) -> SpiceResult<Vec<Complex>> {
+        // Iterations allowed per eigenvalue; exceptional shifts at 10 and 20
+        const MAX_ITERATIONS: usize = 30;
+
+        let n = a.len();
+        let mut eigenvalues = vec![Complex::new(0.0, 0.0); n];
+        let norm: f64 = a.iter().enumerate()
+            .map(|(i, row)| row[i.saturating_sub(1)..].iter().map(|v| v.abs()).sum::<f64>())
+            .sum();
+
+        // Accumulated exceptional shifts
+        let mut t = 0.0;
+        // Size of the active (undeflated) leading block
+        let mut active = n;
+
+        while active > 0 {
+            let nn = active - 1;
+            let mut iterations = 0;
+
+            loop {
+                // Find the last negligible subdiagonal element
+                let mut l = nn;
+                while l >= 1 {
+                    let mut s = a[l - 1][l - 1].abs() + a[l][l].abs();
+                    if s == 0.0 {
+                        s = norm;
+                    }
+                    if a[l][l - 1].abs() + s == s {
+                        a[l][l - 1] = 0.0;
+                        break;
+                    }
+                    l -= 1;
+                }
+
+                let mut x = a[nn][nn];
+                if l == nn {
+                    // One root deflated
+                    eigenvalues[nn] = Complex::new(x + t, 0.0);
+                    active -= 1;
+                    break;
+                }
+
+                let mut y = a[nn - 1][nn - 1];
+                let mut w = a[nn][nn - 1] * a[nn - 1][nn];
+                if l == nn - 1 {
+                    // Two roots deflated from the trailing 2x2 block
+                    let p = 0.5 * (y - x);
+                    let q = p * p + w;
+                    let z = q.abs().sqrt();
+                    x += t;
+                    if q >= 0.0 {
+                        let z = p + z.copysign(p);
+                        eigenvalues[nn - 1] = Complex::new(x + z, 0.0);
+                        eigenvalues[nn] = Complex::new(if z != 0.0 { x - w / z } else { x + z }, 0.0);
+                    } else {
+                        eigenvalues[nn - 1] = Complex::new(x + p, -z);
+                        eigenvalues[nn] = Complex::new(x + p, z);
+                    }
+                    active -= 2;
+                    break;
+                }
+
+                if iterations == MAX_ITERATIONS {
+                    return Err(SpiceError::new(
+                        SpiceErrorType::ComputationError,
+                        format!("QR iteration did not converge after {} iterations", MAX_ITERATIONS)
+                    ));
+                }
+                if iterations == 10 || iterations == 20 {
+                    // Exceptional shift to break cycles
+                    t += x;
+                    for (i, row) in a.iter_mut().enumerate().take(nn + 1) {
+                        row[i] -= x;
+                    }
+                    let s = a[nn][nn - 1].abs() + a[nn - 1][nn - 2].abs();
+                    x = 0.75 * s;
+                    y = x;
+                    w = -0.4375 * s * s;
+                }
+                iterations += 1;
+
+                // Form the double shift and look for two consecutive small
+                // subdiagonal elements
+                let mut m = nn - 2;
+                let (mut p, mut q, mut r);
+                loop {
+                    let z = a[m][m];
+                    let rr = x - z;
+                    let ss = y - z;
+                    p = (rr * ss - w) / a[m + 1][m] + a[m][m + 1];
+                    q = a[m + 1][m + 1] - z - rr - ss;
+                    r = a[m + 2][m + 1];
+                    let s = p.abs() + q.abs() + r.abs();
+                    p /= s;
+                    q /= s;
+                    r /= s;
+                    if m == l {
+                        break;
+                    }
+                    let u = a[m][m - 1].abs() * (q.abs() + r.abs());
+                    let v = p.abs() * (a[m - 1][m - 1].abs() + z.abs() + a[m + 1][m + 1].abs());
+                    if u + v == v {
+                        break;
+                    }
+                    m -= 1;
+                }
+                for i in m + 2..=nn {
+                    a[i][i - 2] = 0.0;
+                    if i != m + 2 {
+                        a[i][i - 3] = 0.0;
+                    }
+                }
 
-        Ok(eigenvals)
+                // Double QR step on rows l..=nn and columns m..=nn
+                for k in m..nn {
+                    if k != m {
+                        p = a[k][k - 1];
+                        q = a[k + 1][k - 1];
+                        r = if k != nn - 1 { a[k + 2][k - 1] } else { 0.0 };
+                        x = p.abs() + q.abs() + r.abs();
+                        if x != 0.0 {
+                            p /= x;
+                            q /= x;
+                            r /= x;
+                        }
+                    }
+                    let s = (p * p + q * q + r * r).sqrt().copysign(p);
+                    if s == 0.0 {
+                        continue;
+                    }
+                    if k == m {
+                        if l != m {
+                            a[k][k - 1] = -a[k][k - 1];
+                        }
+                    } else {
+                        a[k][k - 1] = -s * x;
+                    }
+                    p += s;
+                    x = p / s;
+                    y = q / s;
+                    let z = r / s;
+                    q /= p;
+                    r /= p;
+                    for j in k..=nn {
+                        let mut p = a[k][j] + q * a[k + 1][j];
+                        if k != nn - 1 {
+                            p += r * a[k + 2][j];
+                            a[k + 2][j] -= p * z;
+                        }
+                        a[k + 1][j] -= p * y;
+                        a[k][j] -= p * x;
+                    }
+                    for row in a.iter_mut().take(nn.min(k + 3) + 1).skip(l) {
+                        let mut p = x * row[k] + y * row[k + 1];
+                        if k != nn - 1 {
+                            p += z * row[k + 2];
+                            row[k + 2] -= p * r;
+                        }
+                        row[k + 1] -= p * q;
+                        row[k] -= p;
+                    }
+                }
+            }
+        }
+
+        Ok(eigenvalues)
     }
 
-    /// Singular Value Decomposition (simplified implementation)
+    /// Singular Value Decomposition by one-sided Jacobi rotations
+    ///
+    /// For an m×n matrix with m ≥ n, returns the thin factors U (m×n with
+    /// orthonormal columns), the singular values in descending order and V
+    /// (n×n orthogonal) such that A = U Σ Vᵀ.
     pub fn svd(matrix: &[Vec<f64>]) -> SpiceResult<(Vec<Vec<f64>>, Vec<f64>, Vec<Vec<f64>>)> {
+        // Sweeps over all column pairs before giving up
+        const MAX_SWEEPS: usize = 60;
+
         let m = matrix.len();
         let n = if m > 0 { matrix[0].len() } else { 0 };
         
-        if m == 0 || n == 0 {
+        if m == 0 || n == 0 || matrix.iter().any(|row| row.len() != n) {
             return Err(SpiceError::new(
                 SpiceErrorType::InvalidArgument,
-                "Matrix must be non-empty".to_string()
+                "Matrix must be non-empty and rectangular".to_string()
             ));
         }
-
-        // For this implementation, we'll provide a simplified version
-        // that works for square matrices by computing eigendecomposition of A^T A
-        if m != n {
+        if m < n {
             return Err(SpiceError::new(
                 SpiceErrorType::InvalidArgument,
-                "Simplified SVD implementation requires square matrix".to_string()
+                format!("SVD requires at least as many rows as columns, got {}x{}", m, n)
             ));
         }
 
-        // Compute A^T A
-        let mut ata = vec![vec![0.0; n]; n];
-        for i in 0..n {
-            for j in 0..n {
-                for k in 0..m {
-                    ata[i][j] += matrix[k][i] * matrix[k][j];
+        // Rotate column pairs of A until they are mutually orthogonal,
+        // accumulating the rotations in V
+        let mut u = matrix.to_vec();
+        let mut v = Self::identity_matrix(n);
+        let mut converged = false;
+        for _ in 0..MAX_SWEEPS {
+            let mut rotated = false;
+            for p in 0..n - 1 {
+                for q in p + 1..n {
+                    let (mut alpha, mut beta, mut gamma) = (0.0, 0.0, 0.0);
+                    for row in &u {
+                        alpha += row[p] * row[p];
+                        beta += row[q] * row[q];
+                        gamma += row[p] * row[q];
+                    }
+                    if gamma == 0.0 || gamma.abs() <= f64::EPSILON * (alpha * beta).sqrt() {
+                        continue;
+                    }
+                    rotated = true;
+
+                    let zeta = (beta - alpha) / (2.0 * gamma);
+                    let t = 1.0_f64.copysign(zeta) / (zeta.abs() + (1.0 + zeta * zeta).sqrt());
+                    let c = 1.0 / (1.0 + t * t).sqrt();
+                    let s = c * t;
+                    for row in u.iter_mut().chain(v.iter_mut()) {
+                        let (x, y) = (row[p], row[q]);
+                        row[p] = c * x - s * y;
+                        row[q] = s * x + c * y;
+                    }
                 }
             }
+            if !rotated {
+                converged = true;
+                break;
+            }
+        }
+        if !converged {
+            return Err(SpiceError::new(
+                SpiceErrorType::ComputationError,
+                format!("Jacobi SVD did not converge after {} sweeps", MAX_SWEEPS)
+            ));
         }
 
-        // The singular values are sqrt of eigenvalues of A^T A
-        let eigenvals = Self::eigenvalues(&ata)?;
-        let mut singular_values = Vec::new();
-        for eval in &eigenvals {
-            if eval.imag.abs() < 1e-10 && eval.real >= 0.0 {
-                singular_values.push(eval.real.sqrt());
-            } else {
-                singular_values.push(0.0);
+        // Column norms are the singular values; sort them descending
+        let norms: Vec<f64> = (0..n)
+            .map(|j| u.iter().map(|row| row[j] * row[j]).sum::<f64>().sqrt())
+            .collect();
+        let mut order: Vec<usize> = (0..n).collect();
+        order.sort_by(|&a, &b| norms[b].total_cmp(&norms[a]));
+        let singular_values: Vec<f64> = order.iter().map(|&j| norms[j]).collect();
+        let permute = |rows: Vec<Vec<f64>>| -> Vec<Vec<f64>> {
+            rows.into_iter().map(|row| order.iter().map(|&j| row[j]).collect()).collect()
+        };
+        let mut u = permute(u);
+        let v = permute(v);
+
+        // Normalize U's columns; those of zero singular values are completed
+        // to an orthonormal set from the standard basis vector that keeps the
+        // largest component outside the columns found so far. The first j
+        // columns are orthonormal with j < m, so some e_i keeps at least
+        // sqrt((m - j) / m) of its length.
+        let tolerance = f64::EPSILON * m as f64 * singular_values[0];
+        for j in 0..n {
+            if singular_values[j] > tolerance {
+                for row in u.iter_mut() {
+                    row[j] /= singular_values[j];
+                }
+                continue;
+            }
+            let orthogonalize = |column: &mut Vec<f64>, u: &[Vec<f64>]| {
+                for k in 0..j {
+                    let dot: f64 = u.iter().zip(column.iter()).map(|(row, x)| row[k] * x).sum();
+                    for (x, row) in column.iter_mut().zip(u) {
+                        *x -= dot * row[k];
+                    }
+                }
+            };
+            let mut best: Option<(f64, Vec<f64>)> = None;
+            for candidate in 0..m {
+                let mut column: Vec<f64> = (0..m).map(|i| if i == candidate { 1.0 } else { 0.0 }).collect();
+                orthogonalize(&mut column, &u);
+                let norm = column.iter().map(|x| x * x).sum::<f64>().sqrt();
+                if best.as_ref().is_none_or(|(best_norm, _)| norm > *best_norm) {
+                    best = Some((norm, column));
+                }
+            }
+            let (_, mut column) = best.expect("U has at least one row");
+            // A second pass removes what rounding left of the earlier columns
+            orthogonalize(&mut column, &u);
+            let norm = column.iter().map(|x| x * x).sum::<f64>().sqrt();
+            for (row, x) in u.iter_mut().zip(&column) {
+                row[j] = x / norm;
             }
         }
 
-        // Return identity matrices for U and V (simplified)
-        let u = Self::identity_matrix(m);
-        let v = Self::identity_matrix(n);
-
         Ok((u, singular_values, v))
     }
 
@@ -2341,6 +3262,61 @@ pub mod week4_tests {
         assert!((real_parts[1] - 3.0).abs() < 1e-10);
     }
 
+    #[test]
+    fn test_eigenvalues_general_real_matrices() {
+        // S diag(1, 2, 3) S⁻¹ is far from diagonal
+        let basis = vec![
+            vec![1.0, 1.0, 0.0],
+            vec![0.0, 1.0, 1.0],
+            vec![1.0, 0.0, 1.0],
+        ];
+        let diagonal = vec![
+            vec![1.0, 0.0, 0.0],
+            vec![0.0, 2.0, 0.0],
+            vec![0.0, 0.0, 3.0],
+        ];
+        let matrix = MatrixOperations::multiply(
+            &MatrixOperations::multiply(&basis, &diagonal).unwrap(),
+            &MatrixOperations::invert(&basis).unwrap(),
+        ).unwrap();
+
+        let eigenvals = MatrixOperations::eigenvalues(&matrix).unwrap();
+        let mut real_parts: Vec<f64> = eigenvals.iter().map(|c| c.real).collect();
+        real_parts.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        for (value, expected) in real_parts.iter().zip([1.0, 2.0, 3.0]) {
+            assert!((value - expected).abs() < 1e-10);
+        }
+        assert!(eigenvals.iter().all(|c| c.imag.abs() < 1e-10));
+
+        // A plane rotation has eigenvalues cos θ ± i sin θ
+        let theta: f64 = 0.7;
+        let rotation = vec![
+            vec![theta.cos(), -theta.sin()],
+            vec![theta.sin(), theta.cos()],
+        ];
+        let eigenvals = MatrixOperations::eigenvalues(&rotation).unwrap();
+        let mut imag_parts: Vec<f64> = eigenvals.iter().map(|c| c.imag).collect();
+        imag_parts.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        assert!(eigenvals.iter().all(|c| (c.real - theta.cos()).abs() < 1e-12));
+        assert!((imag_parts[0] + theta.sin()).abs() < 1e-12);
+        assert!((imag_parts[1] - theta.sin()).abs() < 1e-12);
+
+        // Companion matrix of (x - 1)(x - 2)(x - 3)(x - 4)(x - 5)
+        let coefficients = [-120.0, 274.0, -225.0, 85.0, -15.0];
+        let mut companion = vec![vec![0.0; 5]; 5];
+        for i in 1..5 {
+            companion[i][i - 1] = 1.0;
+        }
+        for (i, c) in coefficients.iter().enumerate() {
+            companion[i][4] = -c;
+        }
+        let mut roots: Vec<f64> = MatrixOperations::eigenvalues(&companion).unwrap().iter().map(|c| c.real).collect();
+        roots.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        for (root, expected) in roots.iter().zip(1..=5) {
+            assert!((root - expected as f64).abs() < 1e-8);
+        }
+    }
+
     #[test]
     fn test_matrix_multiplication() {
         let a = vec![
@@ -2495,6 +3471,79 @@ pub mod week4_tests {
         assert!(singular_vals[1] >= 0.0);
     }
 
+    #[test]
+    fn test_svd_reconstructs_rectangular_matrix() {
+        let matrix = vec![
+            vec![2.0, -1.0, 0.5],
+            vec![1.0, 3.0, -2.0],
+            vec![0.0, 4.0, 1.0],
+            vec![-3.0, 0.5, 2.5],
+        ];
+        let (u, s, v) = MatrixOperations::svd(&matrix).unwrap();
+        assert_eq!((u.len(), u[0].len(), s.len(), v.len()), (4, 3, 3, 3));
+        assert!(s.windows(2).all(|pair| pair[0] >= pair[1]));
+
+        // U Σ Vᵀ gives back A
+        let sigma: Vec<Vec<f64>> = (0..3).map(|i| (0..3).map(|j| if i == j { s[i] } else { 0.0 }).collect()).collect();
+        let product = MatrixOperations::multiply(
+            &MatrixOperations::multiply(&u, &sigma).unwrap(),
+            &transpose(&v),
+        ).unwrap();
+        for (row, expected) in product.iter().zip(&matrix) {
+            for (value, expected) in row.iter().zip(expected) {
+                assert!((value - expected).abs() < 1e-10);
+            }
+        }
+
+        // UᵀU and VᵀV are identities
+        for factor in [&u, &v] {
+            let gram = MatrixOperations::multiply(&transpose(factor), factor).unwrap();
+            for (i, row) in gram.iter().enumerate() {
+                for (j, value) in row.iter().enumerate() {
+                    assert!((value - if i == j { 1.0 } else { 0.0 }).abs() < 1e-10);
+                }
+            }
+        }
+
+        // Rank-deficient input still yields orthonormal U
+        let rank_one = vec![vec![1.0, 2.0], vec![2.0, 4.0], vec![3.0, 6.0]];
+        let (u, s, _) = MatrixOperations::svd(&rank_one).unwrap();
+        assert!((s[0] - 70.0_f64.sqrt()).abs() < 1e-12);
+        assert!(s[1].abs() < 1e-12);
+        let gram = MatrixOperations::multiply(&transpose(&u), &u).unwrap();
+        assert!((gram[0][1]).abs() < 1e-12 && (gram[1][1] - 1.0).abs() < 1e-12);
+
+        assert!(MatrixOperations::svd(&transpose(&matrix)).is_err());
+    }
+
+    #[test]
+    fn test_svd_completes_u_for_centering_matrices() {
+        // I - J/n has rank n - 1; every standard basis vector keeps at most
+        // half its length outside its column space
+        for n in [4, 5, 7] {
+            let centering: Vec<Vec<f64>> = (0..n)
+                .map(|i| (0..n).map(|j| if i == j { 1.0 } else { 0.0 } - 1.0 / n as f64).collect())
+                .collect();
+            let (u, s, v) = MatrixOperations::svd(&centering).unwrap();
+            assert!(s[..n - 1].iter().all(|value| (value - 1.0).abs() < 1e-12));
+            assert!(s[n - 1].abs() < 1e-12);
+
+            let gram = MatrixOperations::multiply(&transpose(&u), &u).unwrap();
+            for (i, row) in gram.iter().enumerate() {
+                for (j, value) in row.iter().enumerate() {
+                    assert!((value - if i == j { 1.0 } else { 0.0 }).abs() < 1e-12);
+                }
+            }
+            let sigma: Vec<Vec<f64>> = (0..n).map(|i| (0..n).map(|j| if i == j { s[i] } else { 0.0 }).collect()).collect();
+            let product = MatrixOperations::multiply(&MatrixOperations::multiply(&u, &sigma).unwrap(), &transpose(&v)).unwrap();
+            for (row, expected) in product.iter().zip(&centering) {
+                for (value, expected) in row.iter().zip(expected) {
+                    assert!((value - expected).abs() < 1e-12);
+                }
+            }
+        }
+    }
+
     // Helper function for matrix transpose
     pub fn transpose(matrix: &[Vec<f64>]) -> Vec<Vec<f64>> {
         let m = matrix.len();
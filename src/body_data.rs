@@ -2,7 +2,8 @@
 //! 
 //! This module provides planetary constants, body name/ID mappings, and physical
 //! properties of celestial bodies. It implements the equivalent functionality
-//! of CSPICE bodvrd_c, bodn2c_c, bodc2n_c, and related functions.
+//! of CSPICE bodvrd_c, bodn2c_c, bodc2n_c, and related functions, plus
+//! instrument fields of view from IK variables (getfov_c).
 
 #[cfg(not(feature = "std"))]
 extern crate alloc;
@@ -13,9 +14,10 @@ use alloc::{collections::BTreeMap, format, string::String, vec::Vec};
 #[cfg(feature = "std")]
 use std::{collections::BTreeMap, format, string::String, vec::Vec, sync::OnceLock};
 
-use crate::foundation::{SpiceInt, SpiceDouble};
+use crate::foundation::{SpiceInt, SpiceDouble, SpiceVector3};
 use crate::error_handling::{SpiceResult, SpiceError, SpiceErrorType};
 use crate::kernel_pool;
+use crate::global_state::GlobalState;
 
 // Standard NAIF body codes (from NAIF documentation)
 pub const SOLAR_SYSTEM_BARYCENTER: SpiceInt = 0;
@@ -91,120 +93,145 @@ impl Default for BodyConstants {
     }
 }
 
-/// Get a reference to the built-in body name/code mapping
-fn get_builtin_body_mapping() -> &'static BTreeMap<&'static str, SpiceInt> {
-    #[cfg(feature = "std")]
-    {
-        use std::sync::OnceLock;
-        static BODY_MAPPING: OnceLock<BTreeMap<&'static str, SpiceInt>> = OnceLock::new();
-        
-        BODY_MAPPING.get_or_init(|| {
-            let mut map = BTreeMap::new();
-            
-            // Solar system bodies
-            map.insert("SOLAR SYSTEM BARYCENTER", SOLAR_SYSTEM_BARYCENTER);
-            map.insert("SSB", SOLAR_SYSTEM_BARYCENTER);
-            map.insert("SUN", SUN);
-            
-            // Planets
-            map.insert("MERCURY BARYCENTER", MERCURY_BARYCENTER);
-            map.insert("VENUS BARYCENTER", VENUS_BARYCENTER);  
-            map.insert("EARTH BARYCENTER", EARTH_MOON_BARYCENTER);
-            map.insert("MARS BARYCENTER", MARS_BARYCENTER);
-            map.insert("JUPITER BARYCENTER", JUPITER_BARYCENTER);
-            map.insert("SATURN BARYCENTER", SATURN_BARYCENTER);
-            map.insert("URANUS BARYCENTER", URANUS_BARYCENTER);
-            map.insert("NEPTUNE BARYCENTER", NEPTUNE_BARYCENTER);
-            map.insert("PLUTO BARYCENTER", PLUTO_BARYCENTER);
-            
-            map.insert("MERCURY", MERCURY);
-            map.insert("VENUS", VENUS);
-            map.insert("EARTH", EARTH);
-            map.insert("MARS", MARS);
-            map.insert("JUPITER", JUPITER);
-            map.insert("SATURN", SATURN);
-            map.insert("URANUS", URANUS);
-            map.insert("NEPTUNE", NEPTUNE);
-            map.insert("PLUTO", PLUTO);
-            
-            // Moon
-            map.insert("MOON", MOON);
-            map.insert("LUNA", MOON);
-            
-            // Jupiter moons
-            map.insert("IO", IO);
-            map.insert("EUROPA", EUROPA);
-            map.insert("GANYMEDE", GANYMEDE);
-            map.insert("CALLISTO", CALLISTO);
-            
-            // Saturn moons
-            map.insert("MIMAS", MIMAS);
-            map.insert("ENCELADUS", ENCELADUS);
-            map.insert("TETHYS", TETHYS);
-            map.insert("DIONE", DIONE);
-            map.insert("RHEA", RHEA);
-            map.insert("TITAN", TITAN);
-            map.insert("IAPETUS", IAPETUS);
-            
-            map
-        })
+/// Built-in body names and NAIF ID codes
+///
+/// Where a code has several names, the last one listed is the one
+/// `body_code_to_name` reports.
+const BUILT_IN_BODIES: &[(&str, SpiceInt)] = &[
+    // Solar system bodies
+    ("SSB", SOLAR_SYSTEM_BARYCENTER),
+    ("SOLAR SYSTEM BARYCENTER", SOLAR_SYSTEM_BARYCENTER),
+    ("SUN", SUN),
+
+    // Planets
+    ("MERCURY BARYCENTER", MERCURY_BARYCENTER),
+    ("VENUS BARYCENTER", VENUS_BARYCENTER),
+    ("EARTH BARYCENTER", EARTH_MOON_BARYCENTER),
+    ("MARS BARYCENTER", MARS_BARYCENTER),
+    ("JUPITER BARYCENTER", JUPITER_BARYCENTER),
+    ("SATURN BARYCENTER", SATURN_BARYCENTER),
+    ("URANUS BARYCENTER", URANUS_BARYCENTER),
+    ("NEPTUNE BARYCENTER", NEPTUNE_BARYCENTER),
+    ("PLUTO BARYCENTER", PLUTO_BARYCENTER),
+    ("MERCURY", MERCURY),
+    ("VENUS", VENUS),
+    ("EARTH", EARTH),
+    ("MARS", MARS),
+    ("JUPITER", JUPITER),
+    ("SATURN", SATURN),
+    ("URANUS", URANUS),
+    ("NEPTUNE", NEPTUNE),
+    ("PLUTO", PLUTO),
+
+    // Moon
+    ("LUNA", MOON),
+    ("MOON", MOON),
+
+    // Jupiter moons
+    ("IO", IO),
+    ("EUROPA", EUROPA),
+    ("GANYMEDE", GANYMEDE),
+    ("CALLISTO", CALLISTO),
+
+    // Saturn moons
+    ("MIMAS", MIMAS),
+    ("ENCELADUS", ENCELADUS),
+    ("TETHYS", TETHYS),
+    ("DIONE", DIONE),
+    ("RHEA", RHEA),
+    ("TITAN", TITAN),
+    ("IAPETUS", IAPETUS),
+];
+
+/// Longest body name accepted by `define_body`, as in CSPICE
+const MAX_BODY_NAME_LENGTH: usize = 36;
+
+/// Body name/code associations: the built-ins followed by `define_body` calls
+struct BodyRegistry {
+    /// Code of each normalized name
+    codes: BTreeMap<String, SpiceInt>,
+    /// Most recently defined name of each code
+    names: BTreeMap<SpiceInt, String>,
+    /// Names as defined with their codes, oldest first
+    definitions: Vec<(String, SpiceInt)>,
+}
+
+impl BodyRegistry {
+    fn with_built_ins() -> Self {
+        let mut registry = BodyRegistry { codes: BTreeMap::new(), names: BTreeMap::new(), definitions: Vec::new() };
+        for &(name, code) in BUILT_IN_BODIES {
+            registry.define(name, code);
+        }
+        registry
     }
-    
-    #[cfg(not(feature = "std"))]
-    {
-        // For no_std, use a const static map
-        static BODY_MAPPING_ARRAY: &[(&str, SpiceInt)] = &[
-            ("SOLAR SYSTEM BARYCENTER", SOLAR_SYSTEM_BARYCENTER),
-            ("SSB", SOLAR_SYSTEM_BARYCENTER),
-            ("SUN", SUN),
-            ("MERCURY BARYCENTER", MERCURY_BARYCENTER),
-            ("VENUS BARYCENTER", VENUS_BARYCENTER),
-            ("EARTH BARYCENTER", EARTH_MOON_BARYCENTER),
-            ("MARS BARYCENTER", MARS_BARYCENTER),
-            ("JUPITER BARYCENTER", JUPITER_BARYCENTER),
-            ("SATURN BARYCENTER", SATURN_BARYCENTER),
-            ("URANUS BARYCENTER", URANUS_BARYCENTER),
-            ("NEPTUNE BARYCENTER", NEPTUNE_BARYCENTER),
-            ("PLUTO BARYCENTER", PLUTO_BARYCENTER),
-            ("MERCURY", MERCURY),
-            ("VENUS", VENUS),
-            ("EARTH", EARTH),
-            ("MARS", MARS),
-            ("JUPITER", JUPITER),
-            ("SATURN", SATURN),
-            ("URANUS", URANUS),
-            ("NEPTUNE", NEPTUNE),
-            ("PLUTO", PLUTO),
-            ("MOON", MOON),
-            ("LUNA", MOON),
-            ("IO", IO),
-            ("EUROPA", EUROPA),
-            ("GANYMEDE", GANYMEDE),
-            ("CALLISTO", CALLISTO),
-            ("MIMAS", MIMAS),
-            ("ENCELADUS", ENCELADUS),
-            ("TETHYS", TETHYS),
-            ("DIONE", DIONE),
-            ("RHEA", RHEA),
-            ("TITAN", TITAN),
-            ("IAPETUS", IAPETUS),
-        ];
-        
-        static mut BODY_MAPPING_ONCE: Option<BTreeMap<&'static str, SpiceInt>> = None;
-        
-        unsafe {
-            if BODY_MAPPING_ONCE.is_none() {
-                let mut map = BTreeMap::new();
-                for &(name, code) in BODY_MAPPING_ARRAY {
-                    map.insert(name, code);
-                }
-                BODY_MAPPING_ONCE = Some(map);
+
+    /// Associate a name with a code, replacing any earlier association of
+    /// the name
+    fn define(&mut self, name: &str, code: SpiceInt) {
+        let key = normalize_body_name(name);
+        self.definitions.retain(|(defined, _)| normalize_body_name(defined) != key);
+        self.definitions.push((name.trim().to_string(), code));
+
+        // A renamed code falls back to its previous name, if any
+        if let Some(previous) = self.codes.insert(key, code) {
+            if previous != code {
+                match self.definitions.iter().rev().find(|(_, defined)| *defined == previous) {
+                    Some((fallback, _)) => self.names.insert(previous, fallback.clone()),
+                    None => self.names.remove(&previous),
+                };
             }
-            BODY_MAPPING_ONCE.as_ref().unwrap()
         }
+        self.names.insert(code, name.trim().to_string());
+    }
+
+    fn code(&self, name: &str) -> Option<SpiceInt> {
+        self.codes.get(&normalize_body_name(name)).copied()
+    }
+
+    /// The most recently defined name of a code
+    fn name(&self, code: SpiceInt) -> Option<&str> {
+        self.names.get(&code).map(String::as_str)
     }
 }
 
+/// Body registry shared by all name/code lookups
+static BODY_REGISTRY: GlobalState<BodyRegistry> = GlobalState::new();
+
+/// Run `f` on the body registry, filling it with the built-ins on first use
+fn with_body_registry<R>(f: impl FnOnce(&BodyRegistry) -> R) -> R {
+    if !BODY_REGISTRY.is_set() {
+        BODY_REGISTRY.get_or_init(BodyRegistry::with_built_ins);
+    }
+    BODY_REGISTRY.read(|registry| f(registry.expect("body registry is filled before reading")))
+}
+
+/// Drop every `define_body` definition, leaving only the built-ins
+#[cfg(test)]
+pub(crate) fn reset_body_registry() {
+    BODY_REGISTRY.set(BodyRegistry::with_built_ins());
+}
+
+/// Upper-case a body name and collapse its blanks, as CSPICE compares names
+fn normalize_body_name(name: &str) -> String {
+    name.split_whitespace().collect::<Vec<_>>().join(" ").to_uppercase()
+}
+
+/// Define a body name/code pair (equivalent to boddef_c)
+///
+/// The definition overrides any built-in or earlier one for the same name,
+/// and the name becomes the one reported for the code.
+pub fn define_body(name: &str, code: SpiceInt) -> SpiceResult<()> {
+    let trimmed = name.trim();
+    if trimmed.is_empty() || trimmed.len() > MAX_BODY_NAME_LENGTH {
+        return Err(SpiceError::new(
+            SpiceErrorType::InvalidArgument,
+            format!("Body name '{}' must have 1 to {} characters", name, MAX_BODY_NAME_LENGTH)
+        ));
+    }
+    BODY_REGISTRY.write(|slot| slot.get_or_insert_with(BodyRegistry::with_built_ins).define(trimmed, code));
+    Ok(())
+}
+
 /// Get a reference to the built-in physical constants
 fn get_builtin_constants() -> &'static BTreeMap<SpiceInt, BodyConstants> {
     use std::sync::OnceLock;
@@ -282,15 +309,13 @@ fn get_builtin_constants() -> &'static BTreeMap<SpiceInt, BodyConstants> {
 /// * `Ok(code)` - NAIF ID code for the body
 /// * `Err(SpiceError)` - If body name is not recognized
 pub fn body_name_to_code(name: &str) -> SpiceResult<SpiceInt> {
-    let upper_name = name.to_uppercase();
-    
-    // First check built-in mapping
-    if let Some(&code) = get_builtin_body_mapping().get(upper_name.as_str()) {
+    // First check the built-ins and define_body definitions
+    if let Some(code) = with_body_registry(|registry| registry.code(name)) {
         return Ok(code);
     }
     
     // Check kernel pool for custom body definitions
-    let pool_key = format!("NAIF_BODY_NAME_{}", upper_name);
+    let pool_key = format!("NAIF_BODY_NAME_{}", normalize_body_name(name));
     if kernel_pool::exists_in_pool(&pool_key).unwrap_or(false) {
         let (codes, _) = kernel_pool::get_integer_pool(&pool_key, 0, 1)?;
         if !codes.is_empty() {
@@ -299,7 +324,7 @@ pub fn body_name_to_code(name: &str) -> SpiceResult<SpiceInt> {
     }
     
     // Try parsing as integer
-    if let Ok(code) = name.parse::<SpiceInt>() {
+    if let Ok(code) = name.trim().parse::<SpiceInt>() {
         return Ok(code);
     }
     
@@ -318,32 +343,9 @@ pub fn body_name_to_code(name: &str) -> SpiceResult<SpiceInt> {
 /// * `Ok(name)` - Body name corresponding to the code
 /// * `Err(SpiceError)` - If body code is not recognized
 pub fn body_code_to_name(code: SpiceInt) -> SpiceResult<String> {
-    // Define preferred names for bodies with multiple aliases
-    let preferred_name = match code {
-        MOON => "MOON",
-        EARTH => "EARTH", 
-        SUN => "SUN",
-        MARS => "MARS",
-        JUPITER => "JUPITER",
-        SATURN => "SATURN",
-        URANUS => "URANUS",
-        NEPTUNE => "NEPTUNE",
-        PLUTO => "PLUTO",
-        MERCURY => "MERCURY",
-        VENUS => "VENUS",
-        _ => "",
-    };
-    
-    // Return preferred name if available
-    if !preferred_name.is_empty() {
-        return Ok(preferred_name.to_string());
-    }
-    
-    // Search built-in mapping for other bodies
-    for (&name, &id) in get_builtin_body_mapping().iter() {
-        if id == code {
-            return Ok(name.to_string());
-        }
+    // The most recent built-in or define_body name of the code
+    if let Some(name) = with_body_registry(|registry| registry.name(code).map(String::from)) {
+        return Ok(name);
     }
     
     // Check kernel pool for custom body definitions
@@ -415,7 +417,11 @@ pub fn body_data(body: &str, item: &str) -> SpiceResult<Vec<SpiceDouble>> {
             },
             "PM" => {
                 if let Some(pm) = constants.pm {
-                    return Ok(vec![pm]);
+                    // Same layout as the PCK: W0 followed by the daily rate
+                    return Ok(match constants.rotation_rate {
+                        Some(rate) => vec![pm, rate],
+                        None => vec![pm],
+                    });
                 }
             },
             "NUT_PREC_RA" | "NUT_PREC_DEC" | "NUT_PREC_PM" => {
@@ -432,23 +438,74 @@ pub fn body_data(body: &str, item: &str) -> SpiceResult<Vec<SpiceDouble>> {
     ))
 }
 
-/// Check if a body is recognized (equivalent to bodfnd_c)
+/// Values of a body constant from the kernel pool (equivalent to bodvrd_c)
+///
+/// Reads `BODY<code>_<item>` after resolving `body` to its NAIF code. Unlike
+/// `body_data` there is no built-in fallback: the constant must have been
+/// loaded from a PCK, and it may hold at most `max` values.
+pub fn body_values_double(body: &str, item: &str, max: usize) -> SpiceResult<Vec<SpiceDouble>> {
+    body_values_double_by_code(body_name_to_code(body)?, item, max)
+}
+
+/// Values of a body constant from the kernel pool by NAIF code (equivalent to bodvcd_c)
+///
+/// As `body_values_double`, without resolving a body name.
+pub fn body_values_double_by_code(code: SpiceInt, item: &str, max: usize) -> SpiceResult<Vec<SpiceDouble>> {
+    let pool_key = format!("BODY{}_{}", code, item.to_uppercase());
+    let (found, count, _) = kernel_pool::describe_pool_variable(&pool_key)?;
+    if !found {
+        return Err(SpiceError::new(
+            SpiceErrorType::InsufficientData,
+            format!("Kernel variable {} is not defined", pool_key)
+        ));
+    }
+    if count > max {
+        return Err(SpiceError::new(
+            SpiceErrorType::InvalidArgument,
+            format!("Kernel variable {} holds {} values; at most {} were requested", pool_key, count, max)
+        ));
+    }
+    let (values, _) = kernel_pool::get_double_pool(&pool_key, 0, count)?;
+    Ok(values)
+}
+
+/// Gravitational parameter GM of a body in km³/s², from `BODY<code>_GM`
+///
+/// Unlike `body_data` there is no built-in fallback; the GM must come from a
+/// furnished PCK.
+pub fn gravitational_parameter(body: &str) -> SpiceResult<SpiceDouble> {
+    let values = body_values_double(body, "GM", 1)?;
+    match values.first() {
+        Some(&gm) if gm > 0.0 => Ok(gm),
+        _ => Err(SpiceError::new(
+            SpiceErrorType::InvalidKernelData,
+            format!("GM of {} must be a single positive value, got {:?}", body, values)
+        )),
+    }
+}
+
+/// Whether a body constant `BODY<code>_<item>` is in the kernel pool
+/// (equivalent to bodfnd_c)
+pub fn body_constant_found(code: SpiceInt, item: &str) -> bool {
+    let pool_key = format!("BODY{}_{}", code, item.trim().to_uppercase());
+    kernel_pool::exists_in_pool(&pool_key).unwrap_or(false)
+}
+
+/// Check if a body name or NAIF ID is recognized
 /// 
 /// # Arguments
 /// * `body` - Body name or NAIF ID
 /// 
 /// # Returns
 /// * `true` if body is recognized, `false` otherwise
-pub fn body_found(body: &str) -> bool {
-    let upper_name = body.to_uppercase();
-    
-    // Check built-in name mapping first
-    if get_builtin_body_mapping().contains_key(upper_name.as_str()) {
+pub fn body_recognized(body: &str) -> bool {
+    // Check built-in and define_body names first
+    if with_body_registry(|registry| registry.code(body)).is_some() {
         return true;
     }
     
     // Check kernel pool for custom body definitions by name
-    let pool_key = format!("NAIF_BODY_NAME_{}", upper_name);
+    let pool_key = format!("NAIF_BODY_NAME_{}", normalize_body_name(body));
     if kernel_pool::exists_in_pool(&pool_key).unwrap_or(false) {
         return true;
     }
@@ -470,6 +527,12 @@ pub fn body_found(body: &str) -> bool {
     false
 }
 
+/// Check if a body name or NAIF ID is recognized
+#[deprecated(note = "renamed to `body_recognized`; use `body_constant_found` for bodfnd_c")]
+pub fn body_found(body: &str) -> bool {
+    body_recognized(body)
+}
+
 /// Get the central body for a given body (equivalent to ccifrm_c logic)
 /// 
 /// # Arguments
@@ -524,6 +587,112 @@ pub fn body_center(body: &str) -> SpiceResult<SpiceInt> {
     }
 }
 
+/// Shape of an instrument field of view
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FovShape {
+    Circle,
+    Ellipse,
+    Rectangle,
+    Polygon,
+}
+
+impl FovShape {
+    /// Parse an `INS<code>_FOV_SHAPE` keyword
+    pub fn from_name(shape: &str) -> SpiceResult<Self> {
+        match shape.trim().to_uppercase().as_str() {
+            "CIRCLE" => Ok(Self::Circle),
+            "ELLIPSE" => Ok(Self::Ellipse),
+            "RECTANGLE" => Ok(Self::Rectangle),
+            "POLYGON" => Ok(Self::Polygon),
+            _ => Err(SpiceError::new(
+                SpiceErrorType::InvalidKernelData,
+                format!("Unknown FOV shape '{}'; expected CIRCLE, ELLIPSE, RECTANGLE or POLYGON", shape)
+            )),
+        }
+    }
+
+    /// Whether `count` boundary vectors are valid for this shape
+    fn accepts_bounds(self, count: usize) -> bool {
+        match self {
+            Self::Circle => count == 1,
+            Self::Ellipse => count == 2,
+            Self::Rectangle => count == 4,
+            Self::Polygon => count >= 3,
+        }
+    }
+}
+
+/// Instrument field of view read from an IK (see `get_fov`)
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fov {
+    pub shape: FovShape,
+    /// Frame in which the boresight and boundary vectors are expressed
+    pub frame: String,
+    pub boresight: SpiceVector3,
+    /// Corner vectors; for a circle or ellipse, vectors to the edge at the
+    /// ends of its axes
+    pub bounds: Vec<SpiceVector3>,
+}
+
+/// Field of view of an instrument from the kernel pool (equivalent to getfov_c)
+///
+/// Reads `INS<inst>_FOV_SHAPE`, `_FOV_FRAME`, `_BORESIGHT` and
+/// `_FOV_BOUNDARY_CORNERS`. Only the CORNERS form of the definition is
+/// supported.
+pub fn get_fov(inst: SpiceInt) -> SpiceResult<Fov> {
+    let prefix = format!("INS{}_", inst);
+    let text = |item: &str| -> SpiceResult<String> {
+        let name = format!("{}{}", prefix, item);
+        match kernel_pool::get_character_pool(&name, 0, 1)? {
+            (values, true) if !values.is_empty() => Ok(values[0].trim().to_string()),
+            _ => Err(SpiceError::new(
+                SpiceErrorType::InsufficientData,
+                format!("Kernel variable {} is not defined", name)
+            )),
+        }
+    };
+    let vectors = |item: &str| -> SpiceResult<Vec<SpiceVector3>> {
+        let name = format!("{}{}", prefix, item);
+        let (found, count, _) = kernel_pool::describe_pool_variable(&name)?;
+        if !found || count == 0 || count % 3 != 0 {
+            return Err(SpiceError::new(
+                SpiceErrorType::InsufficientData,
+                format!("Kernel variable {} must hold a nonzero multiple of 3 values, found {}", name, count)
+            ));
+        }
+        let (values, _) = kernel_pool::get_double_pool(&name, 0, count)?;
+        Ok(values.chunks_exact(3).map(|v| SpiceVector3::new(v[0], v[1], v[2])).collect())
+    };
+
+    if let Ok(spec) = text("FOV_CLASS_SPEC") {
+        if !spec.eq_ignore_ascii_case("CORNERS") {
+            return Err(SpiceError::new(
+                SpiceErrorType::InvalidKernelData,
+                format!("FOV class specification '{}' of instrument {} is not supported", spec, inst)
+            ));
+        }
+    }
+
+    let shape = FovShape::from_name(&text("FOV_SHAPE")?)?;
+    let frame = text("FOV_FRAME")?;
+    let boresight = vectors("BORESIGHT")?;
+    let bounds = vectors("FOV_BOUNDARY_CORNERS")?;
+    if boresight.len() != 1 || boresight[0].magnitude() == 0.0 {
+        return Err(SpiceError::new(
+            SpiceErrorType::InvalidKernelData,
+            format!("Boresight of instrument {} must be one nonzero vector", inst)
+        ));
+    }
+    if !shape.accepts_bounds(bounds.len()) {
+        return Err(SpiceError::new(
+            SpiceErrorType::InvalidKernelData,
+            format!("{:?} FOV of instrument {} cannot have {} boundary vectors", shape, inst, bounds.len())
+        ));
+    }
+
+    Ok(Fov { shape, frame, boresight: boresight[0], bounds })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -559,6 +728,27 @@ mod tests {
         assert!(body_name_to_code("INVALID_BODY").is_err());
     }
 
+    #[test]
+    fn test_body_registry_overrides_and_renames() {
+        // Definitions override the built-ins; the newest name of a code wins
+        let mut registry = BodyRegistry::with_built_ins();
+        assert_eq!(registry.name(SOLAR_SYSTEM_BARYCENTER), Some("SOLAR SYSTEM BARYCENTER"));
+        registry.define("MOON", -301);
+        registry.define("Earth  Moon", MOON);
+        assert_eq!(registry.code("moon"), Some(-301));
+        assert_eq!(registry.code("EARTH MOON"), Some(MOON));
+        assert_eq!(registry.name(MOON), Some("Earth  Moon"));
+        assert_eq!(registry.name(-301), Some("MOON"));
+
+        // Moving a name to another code leaves the old code its earlier name,
+        // or none
+        registry.define("Sol", SUN);
+        registry.define("SOL", -10);
+        assert_eq!(registry.name(SUN), Some("SUN"));
+        registry.define("SUN", -11);
+        assert_eq!(registry.name(SUN), None);
+    }
+
     #[test]
     fn test_body_code_to_name() {
         // Test built-in body codes
@@ -621,6 +811,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(deprecated)]
     fn test_body_found() {
         // Test valid bodies
         assert!(body_found("EARTH"));
@@ -696,6 +887,7 @@ mod tests {
 
     #[test]
     fn test_kernel_pool_integration() {
+        let _lock = crate::global_state::test_lock();
         initialize_kernel_system().unwrap();
         initialize_pool().unwrap();
         
@@ -715,6 +907,60 @@ mod tests {
         assert_eq!(body_name_to_code("LUNA").unwrap(), body_name_to_code("MOON").unwrap());
     }
 
+    #[test]
+    fn test_body_values_by_code() {
+        use crate::kernel_system::{initialize_kernel_pool, furnish_kernel_from_bytes};
+
+        let _lock = crate::global_state::test_lock();
+        initialize_kernel_system().unwrap();
+        initialize_kernel_pool().unwrap();
+        let pck = "\\begindata\n\
+                   BODY499_GM = 42828.37362069909\n\
+                   BODY-1999499_RADII = ( 10 9 8 )\n";
+        furnish_kernel_from_bytes(pck.as_bytes().to_vec(), "/pck/by_code.tpc").unwrap();
+
+        assert_eq!(body_values_double_by_code(499, "GM", 1).unwrap(), vec![42828.37362069909]);
+        assert_eq!(body_values_double_by_code(-1999499, "radii", 3).unwrap(), vec![10.0, 9.0, 8.0]);
+        assert_eq!(body_values_double_by_code(-1999499, "RADII", 2).unwrap_err().error_type, SpiceErrorType::InvalidArgument);
+        assert_eq!(body_values_double_by_code(-1999499, "GM", 1).unwrap_err().error_type, SpiceErrorType::InsufficientData);
+
+        assert!(body_constant_found(499, "GM"));
+        assert!(body_constant_found(-1999499, "radii"));
+        assert!(!body_constant_found(-1999499, "GM"));
+    }
+
+    #[test]
+    fn test_rectangular_fov_from_ik() {
+        use crate::kernel_system::{initialize_kernel_pool, furnish_kernel_from_bytes};
+
+        let _lock = crate::global_state::test_lock();
+        initialize_kernel_system().unwrap();
+        initialize_kernel_pool().unwrap();
+        let ik = "\\begindata\n\
+                  INS-1999101_FOV_SHAPE = 'RECTANGLE'\n\
+                  INS-1999101_FOV_FRAME = 'TEST_CAMERA'\n\
+                  INS-1999101_BORESIGHT = ( 0 0 1 )\n\
+                  INS-1999101_FOV_BOUNDARY_CORNERS = ( 0.1 0.05 1, -0.1 0.05 1,\n\
+                                                       -0.1 -0.05 1, 0.1 -0.05 1 )\n\
+                  INS-1999102_FOV_SHAPE = 'CONE'\n\
+                  INS-1999102_FOV_FRAME = 'TEST_CAMERA'\n\
+                  INS-1999102_BORESIGHT = ( 0 0 1 )\n\
+                  INS-1999102_FOV_BOUNDARY_CORNERS = ( 0.1 0 1 )\n";
+        furnish_kernel_from_bytes(ik.as_bytes().to_vec(), "/ik/test_camera.ti").unwrap();
+
+        let fov = get_fov(-1999101).unwrap();
+        assert_eq!(fov.shape, FovShape::Rectangle);
+        assert_eq!(fov.frame, "TEST_CAMERA");
+        assert_eq!(fov.boresight, SpiceVector3::new(0.0, 0.0, 1.0));
+        assert_eq!(fov.bounds.len(), 4);
+        assert_eq!(fov.bounds[2], SpiceVector3::new(-0.1, -0.05, 1.0));
+
+        let error = get_fov(-1999102).unwrap_err();
+        assert_eq!(error.error_type, SpiceErrorType::InvalidKernelData);
+        assert!(error.message.contains("CONE"));
+        assert_eq!(get_fov(-1999103).unwrap_err().error_type, SpiceErrorType::InsufficientData);
+    }
+
     #[test]
     fn test_roundtrip_conversion() {
         // Test that name -> code -> name gives consistent results
@@ -7,10 +7,22 @@
 //! as C-matrices (rotation matrices) that transform vectors between reference frames
 //! and instrument-fixed coordinate systems.
 
-use crate::foundation::{SpiceMatrix3x3, SpiceVector3, EphemerisTime};
+use crate::foundation::{SpiceMatrix3x3, SpiceMatrix6x6, SpiceVector3, EphemerisTime};
+use crate::coordinates::{is_rotation_matrix, rotation_and_av_to_state_transform};
 use crate::error_handling::{SpiceError, SpiceResult, SpiceErrorType};
+use crate::global_state::GlobalState;
+use crate::file_system::daf::DafFile;
+use crate::kernel_pool;
+use crate::spk_reader::{merge_coverage, loaded_file};
+use crate::math_core::{last_le, last_lt};
+use crate::time_system::sclk::Sclk01;
 use std::collections::HashMap;
 
+/// Number of double precision components in a CK segment descriptor
+const CK_ND: usize = 2;
+/// Number of integer components in a CK segment descriptor
+const CK_NI: usize = 6;
+
 /// Represents a C-matrix (attitude/rotation matrix) with associated metadata
 #[derive(Debug, Clone)]
 pub struct CMatrix {
@@ -24,6 +36,37 @@ pub struct CMatrix {
     pub instrument_id: i32,
 }
 
+impl CMatrix {
+    /// C-matrix from a rotation taking vectors from `reference_frame` to the
+    /// instrument frame
+    pub fn from_rotation(
+        matrix: SpiceMatrix3x3,
+        sclk_time: f64,
+        reference_frame: i32,
+        instrument_id: i32,
+    ) -> SpiceResult<Self> {
+        if !is_rotation_matrix(&matrix) {
+            return Err(SpiceError::new(
+                SpiceErrorType::InvalidArgument,
+                format!("C-matrix for instrument {} is not a rotation", instrument_id)
+            ));
+        }
+        Ok(CMatrix { matrix, sclk_time, reference_frame, instrument_id })
+    }
+}
+
+impl From<CMatrix> for SpiceMatrix3x3 {
+    fn from(cmatrix: CMatrix) -> Self {
+        cmatrix.matrix
+    }
+}
+
+impl From<&CMatrix> for SpiceMatrix3x3 {
+    fn from(cmatrix: &CMatrix) -> Self {
+        cmatrix.matrix
+    }
+}
+
 /// Angular velocity vector with metadata
 #[derive(Debug, Clone)]
 pub struct AngularVelocity {
@@ -100,10 +143,26 @@ pub struct CkSegmentInfo {
     pub segment_id: String,
 }
 
+/// A CK file loaded into the reader
+#[derive(Debug, Clone)]
+struct CkFile {
+    /// Segment descriptors in file order
+    segments: Vec<CkSegmentInfo>,
+}
+
+/// A segment available for evaluation, with its data when read from a file
+#[derive(Debug, Clone)]
+struct LoadedSegment {
+    info: CkSegmentInfo,
+    data: Option<Vec<f64>>,
+}
+
 /// Main CK reader for attitude determination
 pub struct CkReader {
     /// Loaded CK segments indexed by instrument ID
-    segments: HashMap<i32, Vec<CkSegmentInfo>>,
+    segments: HashMap<i32, Vec<LoadedSegment>>,
+    /// Loaded CK files by the name they were loaded under
+    loaded_files: HashMap<String, CkFile>,
     /// Built-in attitude data for common spacecraft/instruments
     built_in_attitudes: HashMap<i32, CMatrix>,
 }
@@ -113,6 +172,7 @@ impl CkReader {
     pub fn new() -> Self {
         let mut reader = CkReader {
             segments: HashMap::new(),
+            loaded_files: HashMap::new(),
             built_in_attitudes: HashMap::new(),
         };
         
@@ -152,10 +212,14 @@ impl CkReader {
         
         // Search for attitude data
         if let Some(segments) = self.segments.get(&instrument_id) {
-            // Search through segments for applicable data
+            // Search through segments for applicable data; a segment may
+            // cover the time but have no pointing there, e.g. in a gap
             for segment in segments {
-                if self.time_in_segment(sclk_time, tolerance, segment) {
-                    return self.evaluate_segment_pointing(segment, sclk_time, false);
+                if self.time_in_segment(sclk_time, tolerance, &segment.info) {
+                    let attitude = self.evaluate_segment_pointing(segment, sclk_time, false)?;
+                    if attitude.found {
+                        return Ok(attitude);
+                    }
                 }
             }
         }
@@ -197,8 +261,11 @@ impl CkReader {
         // Search for attitude data with angular velocity
         if let Some(segments) = self.segments.get(&instrument_id) {
             for segment in segments {
-                if self.time_in_segment(sclk_time, tolerance, segment) && segment.has_angular_velocity {
-                    return self.evaluate_segment_pointing(segment, sclk_time, true);
+                if self.time_in_segment(sclk_time, tolerance, &segment.info) && segment.info.has_angular_velocity {
+                    let attitude = self.evaluate_segment_pointing(segment, sclk_time, true)?;
+                    if attitude.found {
+                        return Ok(attitude);
+                    }
                 }
             }
         }
@@ -238,21 +305,24 @@ impl CkReader {
     
     /// Find frame rotation from CK frame to base reference frame
     /// Equivalent to CSPICE ckfrot_c
+    ///
+    /// `et` is converted to ticks of the clock named by `CK_<id>_SCLK`, or of
+    /// clock `ck_frame_id / 1000` when that variable is absent. The matrix
+    /// takes vectors from the CK frame to the returned base frame, i.e. it is
+    /// the transpose of the C-matrix.
     pub fn find_frame_rotation(
         &self,
         ck_frame_id: i32,
         et: f64, // Using f64 instead of EphemerisTime for simplicity
     ) -> SpiceResult<(SpiceMatrix3x3, i32, bool)> {
-        // Convert ET to SCLK (simplified - would need proper conversion)
-        let sclk_time = et; // Placeholder conversion
-        
         // Search for frame data
         if let Some(segments) = self.segments.get(&ck_frame_id) {
+            let sclk_time = ck_clock(ck_frame_id)?.et_to_ticks(et)?;
             for segment in segments {
-                if self.time_in_segment(sclk_time, 0.0, segment) {
+                if self.time_in_segment(sclk_time, 0.0, &segment.info) {
                     let attitude = self.evaluate_segment_pointing(segment, sclk_time, false)?;
                     if attitude.found {
-                        return Ok((attitude.cmatrix.matrix, segment.reference_frame, true));
+                        return Ok((attitude.cmatrix.matrix.transpose(), segment.info.reference_frame, true));
                     }
                 }
             }
@@ -264,22 +334,13 @@ impl CkReader {
     
     /// Parse reference frame string to frame ID
     fn parse_reference_frame(&self, frame_name: &str) -> SpiceResult<i32> {
-        match frame_name.to_uppercase().as_str() {
-            "J2000" | "J2000.0" => Ok(1),
-            "ECLIPJ2000" => Ok(17),
-            "GALACTIC" => Ok(18),
-            "ITRF93" => Ok(13),
-            _ => {
-                // Try to parse as integer
-                if let Ok(frame_id) = frame_name.parse::<i32>() {
-                    Ok(frame_id)
-                } else {
-                    Err(SpiceError::new(
-                        SpiceErrorType::InvalidArgument,
-                        format!("Unknown reference frame: {}", frame_name)
-                    ))
-                }
-            }
+        if frame_name.eq_ignore_ascii_case("J2000.0") {
+            return Ok(1);
+        }
+        // Try to parse as integer
+        match frame_name.parse::<i32>() {
+            Ok(frame_id) => Ok(frame_id),
+            Err(_) => crate::coordinates::frame_name_to_id(frame_name),
         }
     }
     
@@ -291,15 +352,18 @@ impl CkReader {
     /// Evaluate pointing from a specific segment
     fn evaluate_segment_pointing(
         &self,
-        segment: &CkSegmentInfo,
+        loaded: &LoadedSegment,
         sclk_time: f64,
         need_angular_velocity: bool,
     ) -> SpiceResult<AttitudeState> {
-        // This is a placeholder - in a full implementation, this would:
-        // 1. Read the actual CK file data for this segment
-        // 2. Interpolate/evaluate based on segment type
-        // 3. Return the computed C-matrix and angular velocity
-        
+        let segment = &loaded.info;
+        match (segment.segment_type, &loaded.data) {
+            (CkSegmentType::Type2, Some(data)) => return evaluate_type2_data(segment, data, sclk_time, need_angular_velocity),
+            (CkSegmentType::Type3, Some(data)) => return evaluate_type3_data(segment, data, sclk_time, need_angular_velocity),
+            _ => {}
+        }
+
+        // Segments without file data fall back to placeholder models
         match segment.segment_type {
             CkSegmentType::Type1 => self.evaluate_type1_segment(segment, sclk_time, need_angular_velocity),
             CkSegmentType::Type2 => self.evaluate_type2_segment(segment, sclk_time, need_angular_velocity),
@@ -396,36 +460,363 @@ impl CkReader {
     
     /// Load CK segment information (placeholder for actual file loading)
     pub fn load_ck_segment(&mut self, segment: CkSegmentInfo) {
+        self.insert_segment(segment, None);
+    }
+
+    /// Add a segment and its data to the per-instrument search lists
+    fn insert_segment(&mut self, info: CkSegmentInfo, data: Option<Vec<f64>>) {
         self.segments
-            .entry(segment.instrument_id)
-            .or_insert_with(Vec::new)
-            .push(segment);
+            .entry(info.instrument_id)
+            .or_default()
+            .push(LoadedSegment { info, data });
     }
     
+    /// Load a binary CK file from memory
+    ///
+    /// Each DAF array becomes a segment; its descriptor holds the start and
+    /// end SCLK ticks followed by the instrument, reference frame, segment
+    /// type and angular velocity flag. Type 2 and 3 segments are evaluated
+    /// from their data; other types still use placeholder models.
+    pub fn load_ck_data(&mut self, filename: &str, data: &[u8]) -> SpiceResult<()> {
+        let daf = DafFile::open_read(data)?;
+        if daf.id_word() != "DAF/CK" || daf.nd() != CK_ND || daf.ni() != CK_NI {
+            return Err(SpiceError::new(
+                SpiceErrorType::InvalidFormat,
+                format!(
+                    "{} is not a CK file (ID word '{}', ND={}, NI={})",
+                    filename, daf.id_word(), daf.nd(), daf.ni()
+                )
+            ));
+        }
+
+        let segments = daf.summaries().into_iter()
+            .map(|summary| Ok(CkSegmentInfo {
+                segment_type: CkSegmentType::from_i32(summary.integers[2])?,
+                instrument_id: summary.integers[0],
+                reference_frame: summary.integers[1],
+                start_time: summary.doubles[0],
+                end_time: summary.doubles[1],
+                has_angular_velocity: summary.integers[3] != 0,
+                segment_id: summary.name.trim_end().to_string(),
+            }))
+            .collect::<SpiceResult<Vec<_>>>()?;
+
+        for (index, segment) in segments.iter().enumerate() {
+            self.insert_segment(segment.clone(), Some(daf.array_data(index)?.to_vec()));
+        }
+        self.loaded_files.insert(filename.to_string(), CkFile { segments });
+        Ok(())
+    }
+
+    /// Instrument and structure IDs with segments in a loaded file, in
+    /// ascending order
+    pub fn objects(&self, filename: &str) -> SpiceResult<Vec<i32>> {
+        let mut instruments: Vec<i32> = self.loaded_file(filename)?.segments.iter()
+            .map(|segment| segment.instrument_id)
+            .collect();
+        instruments.sort_unstable();
+        instruments.dedup();
+        Ok(instruments)
+    }
+
+    /// Time covered by an instrument's segments in a loaded file, as disjoint
+    /// [start, end] intervals in ascending order
+    ///
+    /// Intervals are in encoded SCLK ticks, the unit CK descriptors store.
+    /// Converting them to ET needs the spacecraft's SCLK kernel, so callers
+    /// that want ET convert the endpoints themselves. Overlapping or abutting
+    /// segment windows merge into one interval.
+    pub fn coverage(&self, filename: &str, instrument_id: i32) -> SpiceResult<Vec<(f64, f64)>> {
+        Ok(merge_coverage(self.loaded_file(filename)?.segments.iter()
+            .filter(|segment| segment.instrument_id == instrument_id)
+            .map(|segment| (segment.start_time, segment.end_time))))
+    }
+
+    /// A loaded file by the name it was loaded under
+    fn loaded_file(&self, filename: &str) -> SpiceResult<&CkFile> {
+        loaded_file(&self.loaded_files, "CK", filename)
+    }
+
     /// Clear all loaded CK data
     pub fn clear(&mut self) {
         self.segments.clear();
+        self.loaded_files.clear();
         self.initialize_built_in_data();
     }
 }
 
+/// Error for a segment whose data does not match its type
+fn malformed_segment(segment: &CkSegmentInfo, detail: String) -> SpiceError {
+    SpiceError::new(
+        SpiceErrorType::InvalidKernelData,
+        format!("Type {} CK segment '{}' is malformed: {}", segment.segment_type as i32, segment.segment_id, detail)
+    )
+}
+
+/// Attitude result for a time a segment has no pointing for
+fn missing_pointing(segment: &CkSegmentInfo, sclk_time: f64) -> AttitudeState {
+    AttitudeState {
+        cmatrix: CMatrix {
+            matrix: SpiceMatrix3x3::identity(),
+            sclk_time,
+            reference_frame: segment.reference_frame,
+            instrument_id: segment.instrument_id,
+        },
+        angular_velocity: None,
+        found: false,
+    }
+}
+
+/// Quaternion of a pointing record, SPICE style with the scalar first
+fn record_quaternion(record: &[f64]) -> [f64; 4] {
+    [record[0], record[1], record[2], record[3]]
+}
+
+/// Rotation matrix of a unit SPICE-style quaternion (equivalent to q2m_c)
+fn quaternion_to_matrix(q: [f64; 4]) -> SpiceMatrix3x3 {
+    let [q0, q1, q2, q3] = q;
+    SpiceMatrix3x3::new([
+        [1.0 - 2.0 * (q2 * q2 + q3 * q3), 2.0 * (q1 * q2 - q0 * q3), 2.0 * (q1 * q3 + q0 * q2)],
+        [2.0 * (q1 * q2 + q0 * q3), 1.0 - 2.0 * (q1 * q1 + q3 * q3), 2.0 * (q2 * q3 - q0 * q1)],
+        [2.0 * (q1 * q3 - q0 * q2), 2.0 * (q2 * q3 + q0 * q1), 1.0 - 2.0 * (q1 * q1 + q2 * q2)],
+    ])
+}
+
+/// Product of SPICE-style quaternions, so that the matrix of `a * b` is the
+/// matrix of `a` times that of `b` (equivalent to qxq_c)
+fn quaternion_product(a: [f64; 4], b: [f64; 4]) -> [f64; 4] {
+    [
+        a[0] * b[0] - a[1] * b[1] - a[2] * b[2] - a[3] * b[3],
+        a[0] * b[1] + a[1] * b[0] + a[2] * b[3] - a[3] * b[2],
+        a[0] * b[2] - a[1] * b[3] + a[2] * b[0] + a[3] * b[1],
+        a[0] * b[3] + a[1] * b[2] - a[2] * b[1] + a[3] * b[0],
+    ]
+}
+
+/// Spherical linear interpolation between unit quaternions, a fraction
+/// `f` of the way from `a` to `b` along the shorter arc
+fn slerp(a: [f64; 4], mut b: [f64; 4], f: f64) -> [f64; 4] {
+    let mut cos_angle: f64 = (0..4).map(|i| a[i] * b[i]).sum();
+    // q and -q are the same rotation; take the short way round
+    if cos_angle < 0.0 {
+        b = b.map(|x| -x);
+        cos_angle = -cos_angle;
+    }
+
+    let (wa, wb) = if cos_angle > 1.0 - 1e-12 {
+        (1.0 - f, f)
+    } else {
+        let angle = cos_angle.acos();
+        let sin_angle = angle.sin();
+        (((1.0 - f) * angle).sin() / sin_angle, (f * angle).sin() / sin_angle)
+    };
+
+    let q: [f64; 4] = core::array::from_fn(|i| wa * a[i] + wb * b[i]);
+    let norm = q.iter().map(|x| x * x).sum::<f64>().sqrt();
+    q.map(|x| x / norm)
+}
+
+/// Evaluate a Type 2 segment (constant angular velocity over each record)
+/// from its data
+///
+/// The data holds N records of a quaternion, an angular velocity in the base
+/// frame and the seconds per tick, then the N record start times, the N stop
+/// times and a directory of every 100th start time. Within a record the
+/// start attitude turns about the angular velocity at a constant rate: the
+/// instrument axes rotate by θ = |ω|·rate·Δt, so C(t) = C₀·R(ω, -θ). Times
+/// between one record's stop and the next one's start have no pointing.
+fn evaluate_type2_data(
+    segment: &CkSegmentInfo,
+    data: &[f64],
+    sclk_time: f64,
+    need_angular_velocity: bool,
+) -> SpiceResult<AttitudeState> {
+    // 10 elements per record plus the directory; the count is not stored
+    let records = (1..=data.len() / 10).rev()
+        .find(|&n| 10 * n + (n - 1) / 100 == data.len())
+        .ok_or_else(|| malformed_segment(segment, format!("{} elements is not a whole number of records", data.len())))?;
+    let starts = &data[8 * records..9 * records];
+    let stops = &data[9 * records..10 * records];
+
+    let Some(index) = last_le(starts, sclk_time).filter(|&index| sclk_time <= stops[index]) else {
+        return Ok(missing_pointing(segment, sclk_time));
+    };
+    let record = &data[8 * index..8 * index + 8];
+    let omega = SpiceVector3::new(record[4], record[5], record[6]);
+    let seconds_per_tick = record[7];
+
+    // Exponential map of the rotation turned through since the record start
+    let (axis, rate) = omega.unit_and_magnitude();
+    let half_angle = -0.5 * rate * seconds_per_tick * (sclk_time - starts[index]);
+    let turn = [half_angle.cos(), half_angle.sin() * axis.x(), half_angle.sin() * axis.y(), half_angle.sin() * axis.z()];
+    let quaternion = quaternion_product(record_quaternion(record), turn);
+
+    Ok(AttitudeState {
+        cmatrix: CMatrix {
+            matrix: quaternion_to_matrix(quaternion),
+            sclk_time,
+            reference_frame: segment.reference_frame,
+            instrument_id: segment.instrument_id,
+        },
+        angular_velocity: need_angular_velocity.then_some(AngularVelocity {
+            vector: omega,
+            reference_frame: segment.reference_frame,
+            time: sclk_time,
+        }),
+        found: true,
+    })
+}
+
+/// Evaluate a Type 3 segment (linear interpolation within interpolation
+/// intervals) from its data
+///
+/// The data holds N pointing records (a quaternion, followed by an angular
+/// velocity when the segment has one), the N record times, a directory of
+/// every 100th time, the start times of the interpolation intervals with
+/// their own directory, and finally the interval count and N. Between
+/// records of one interval the quaternions are interpolated by SLERP and the
+/// angular velocities linearly; times after the last record of an interval
+/// but before the next interval starts have no pointing.
+fn evaluate_type3_data(
+    segment: &CkSegmentInfo,
+    data: &[f64],
+    sclk_time: f64,
+    need_angular_velocity: bool,
+) -> SpiceResult<AttitudeState> {
+    let malformed = |detail: String| malformed_segment(segment, detail);
+
+    if data.len() < 2 {
+        return Err(malformed(format!("only {} elements", data.len())));
+    }
+    let records = data[data.len() - 1] as usize;
+    let intervals = data[data.len() - 2] as usize;
+    let record_size = if segment.has_angular_velocity { 7 } else { 4 };
+    if records == 0 || intervals == 0 {
+        return Err(malformed(format!("{} records in {} intervals", records, intervals)));
+    }
+    let expected = records * (record_size + 1) + (records - 1) / 100 + intervals + (intervals - 1) / 100 + 2;
+    if data.len() != expected {
+        return Err(malformed(format!("{} elements, expected {}", data.len(), expected)));
+    }
+
+    let times_start = records * record_size;
+    let times = &data[times_start..times_start + records];
+    let starts_start = times_start + records + (records - 1) / 100;
+    let starts = &data[starts_start..starts_start + intervals];
+    let record = |i: usize| &data[i * record_size..(i + 1) * record_size];
+
+    // Interpolation interval and record at or before the time, and the interval's last record
+    let (Some(interval), Some(lower)) = (last_le(starts, sclk_time), last_le(times, sclk_time)) else {
+        return Ok(missing_pointing(segment, sclk_time));
+    };
+    let interval_end = match starts.get(interval + 1) {
+        Some(&next_start) => last_lt(times, next_start).unwrap_or(0),
+        None => records - 1,
+    };
+    if sclk_time > times[interval_end] {
+        return Ok(missing_pointing(segment, sclk_time));
+    }
+
+    let (quaternion, angular_velocity) = if times[lower] == sclk_time || lower == interval_end {
+        (record_quaternion(record(lower)), record(lower).get(4..7).map(|av| [av[0], av[1], av[2]]))
+    } else {
+        let fraction = (sclk_time - times[lower]) / (times[lower + 1] - times[lower]);
+        let (a, b) = (record(lower), record(lower + 1));
+        let quaternion = slerp(record_quaternion(a), record_quaternion(b), fraction);
+        let angular_velocity = a.get(4..7).zip(b.get(4..7))
+            .map(|(va, vb)| core::array::from_fn(|i| va[i] + fraction * (vb[i] - va[i])));
+        (quaternion, angular_velocity)
+    };
+
+    Ok(AttitudeState {
+        cmatrix: CMatrix {
+            matrix: quaternion_to_matrix(quaternion),
+            sclk_time,
+            reference_frame: segment.reference_frame,
+            instrument_id: segment.instrument_id,
+        },
+        angular_velocity: angular_velocity.filter(|_| need_angular_velocity).map(|av: [f64; 3]| AngularVelocity {
+            vector: SpiceVector3::new(av[0], av[1], av[2]),
+            reference_frame: segment.reference_frame,
+            time: sclk_time,
+        }),
+        found: true,
+    })
+}
+
 impl Default for CkReader {
     fn default() -> Self {
         Self::new()
     }
 }
 
+/// Spacecraft clock of CK `ck_id`: `CK_<id>_SCLK` if set, else `ck_id / 1000`
+fn ck_clock(ck_id: i32) -> SpiceResult<Sclk01> {
+    let clock = if kernel_pool::is_pool_initialized() {
+        let (clocks, found) = kernel_pool::get_integer_pool(&format!("CK_{}_SCLK", ck_id), 0, 1)?;
+        clocks.first().copied().filter(|_| found)
+    } else {
+        None
+    };
+    Sclk01::from_pool(clock.unwrap_or(ck_id / 1000))
+}
+
 /// Global CK reader instance
-static mut GLOBAL_CK_READER: Option<CkReader> = None;
+static GLOBAL_CK_READER: GlobalState<CkReader> = GlobalState::new();
 
 /// Initialize the global CK reader
 pub fn initialize_ck_system() -> SpiceResult<()> {
-    unsafe {
-        GLOBAL_CK_READER = Some(CkReader::new());
-    }
+    GLOBAL_CK_READER.set(CkReader::new());
     Ok(())
 }
 
+/// Run `f` with shared access to the global CK reader
+fn with_ck_reader<R>(f: impl FnOnce(&CkReader) -> SpiceResult<R>) -> SpiceResult<R> {
+    GLOBAL_CK_READER.read(|reader| f(reader.ok_or_else(|| SpiceError::new(
+        SpiceErrorType::SpiceError,
+        "CK system not initialized. Call initialize_ck_system() first".to_string()
+    ))?))
+}
+
+/// Run `f` with exclusive access to the global CK reader
+fn with_ck_reader_mut<R>(f: impl FnOnce(&mut CkReader) -> SpiceResult<R>) -> SpiceResult<R> {
+    GLOBAL_CK_READER.write(|reader| f(reader.as_mut().ok_or_else(|| SpiceError::new(
+        SpiceErrorType::SpiceError,
+        "CK system not initialized. Call initialize_ck_system() first".to_string()
+    ))?))
+}
+
+/// State transformation from an attitude's reference frame to its instrument frame
+///
+/// The C-matrix and angular velocity combine as in rav2xf_c: the result maps
+/// base-frame states to instrument-frame states, and its inverse (the
+/// transposed blocks) maps them back. The angular velocity must be present and
+/// expressed in the C-matrix's reference frame.
+pub fn attitude_state_to_state_transform(attitude: &AttitudeState) -> SpiceResult<SpiceMatrix6x6> {
+    let cmatrix = &attitude.cmatrix;
+    if !attitude.found {
+        return Err(SpiceError::new(
+            SpiceErrorType::InsufficientData,
+            format!("No pointing found for instrument {}", cmatrix.instrument_id)
+        ));
+    }
+    let angular_velocity = attitude.angular_velocity.as_ref().ok_or_else(|| SpiceError::new(
+        SpiceErrorType::InsufficientData,
+        format!("Attitude of instrument {} has no angular velocity", cmatrix.instrument_id)
+    ))?;
+    if angular_velocity.reference_frame != cmatrix.reference_frame {
+        return Err(SpiceError::new(
+            SpiceErrorType::InvalidFrame,
+            format!(
+                "Angular velocity is in frame {} but the C-matrix is relative to frame {}",
+                angular_velocity.reference_frame, cmatrix.reference_frame
+            )
+        ));
+    }
+
+    Ok(rotation_and_av_to_state_transform(&cmatrix.matrix, &angular_velocity.vector))
+}
+
 /// Get pointing (C-matrix) for specified instrument at given time
 /// Global wrapper for ckgp_c equivalent
 pub fn ck_get_pointing(
@@ -434,15 +825,7 @@ pub fn ck_get_pointing(
     tolerance: f64,
     reference_frame: &str,
 ) -> SpiceResult<AttitudeState> {
-    unsafe {
-        GLOBAL_CK_READER
-            .as_ref()
-            .ok_or_else(|| SpiceError::new(
-                SpiceErrorType::SpiceError,
-                "CK system not initialized. Call initialize_ck_system() first".to_string()
-            ))?
-            .get_pointing(instrument_id, sclk_time, tolerance, reference_frame)
-    }
+    with_ck_reader(|reader| reader.get_pointing(instrument_id, sclk_time, tolerance, reference_frame))
 }
 
 /// Get pointing and angular velocity for specified instrument
@@ -453,37 +836,41 @@ pub fn ck_get_pointing_and_av(
     tolerance: f64,
     reference_frame: &str,
 ) -> SpiceResult<AttitudeState> {
-    unsafe {
-        GLOBAL_CK_READER
-            .as_ref()
-            .ok_or_else(|| SpiceError::new(
-                SpiceErrorType::SpiceError,
-                "CK system not initialized. Call initialize_ck_system() first".to_string()
-            ))?
-            .get_pointing_and_av(instrument_id, sclk_time, tolerance, reference_frame)
-    }
+    with_ck_reader(|reader| reader.get_pointing_and_av(instrument_id, sclk_time, tolerance, reference_frame))
 }
 
 /// Find frame rotation from CK frame to base reference frame
 /// Global wrapper for ckfrot_c equivalent
+///
+/// This is a single CK link; `get_position_transformation` follows CK frames
+/// defined in frame kernels through their TK/CK parents to J2000.
 pub fn ck_find_frame_rotation(
     ck_frame_id: i32,
     et: f64, // Using f64 instead of EphemerisTime
 ) -> SpiceResult<(SpiceMatrix3x3, i32, bool)> {
-    unsafe {
-        GLOBAL_CK_READER
-            .as_ref()
-            .ok_or_else(|| SpiceError::new(
-                SpiceErrorType::SpiceError,
-                "CK system not initialized. Call initialize_ck_system() first".to_string()
-            ))?
-            .find_frame_rotation(ck_frame_id, et)
-    }
+    with_ck_reader(|reader| reader.find_frame_rotation(ck_frame_id, et))
+}
+
+/// Load a binary CK file from memory into the global reader
+pub fn ck_load_data(filename: &str, data: &[u8]) -> SpiceResult<()> {
+    with_ck_reader_mut(|reader| reader.load_ck_data(filename, data))
+}
+
+/// Instrument and structure IDs in a loaded CK file (equivalent to ckobj_c)
+pub fn ck_objects(filename: &str) -> SpiceResult<Vec<i32>> {
+    with_ck_reader(|reader| reader.objects(filename))
+}
+
+/// Coverage of an instrument in a loaded CK file, in encoded SCLK ticks
+/// (equivalent to ckcov_c at segment level with TICKS output)
+pub fn ck_coverage(filename: &str, instrument_id: i32) -> SpiceResult<Vec<(f64, f64)>> {
+    with_ck_reader(|reader| reader.coverage(filename, instrument_id))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use core::f64::consts::PI;
     
     #[test]
     fn test_ck_reader_creation() {
@@ -493,6 +880,7 @@ mod tests {
     
     #[test]
     fn test_initialize_ck_system() {
+        let _lock = crate::global_state::test_lock();
         assert!(initialize_ck_system().is_ok());
     }
     
@@ -545,6 +933,7 @@ mod tests {
     
     #[test]
     fn test_global_ck_functions() {
+        let _lock = crate::global_state::test_lock();
         initialize_ck_system().unwrap();
         
         let result = ck_get_pointing(-999, 0.0, 0.0, "J2000");
@@ -659,4 +1048,148 @@ mod tests {
         assert!(result_without_av.found);
         assert!(result_without_av.angular_velocity.is_none());
     }
+
+    #[test]
+    fn test_spinning_attitude_couples_velocity_block() {
+        let rate = 0.05;
+        let angle: f64 = 0.3;
+        // Instrument frame turned by `angle` about Z from J2000 and spinning at `rate`
+        let rotation = SpiceMatrix3x3::new([
+            [angle.cos(), angle.sin(), 0.0],
+            [-angle.sin(), angle.cos(), 0.0],
+            [0.0, 0.0, 1.0],
+        ]);
+        let attitude = AttitudeState {
+            cmatrix: CMatrix::from_rotation(rotation, 0.0, 1, -77701).unwrap(),
+            angular_velocity: Some(AngularVelocity {
+                vector: SpiceVector3::new(0.0, 0.0, rate),
+                reference_frame: 1,
+                time: 0.0,
+            }),
+            found: true,
+        };
+        let xform = attitude_state_to_state_transform(&attitude).unwrap();
+        assert_eq!(SpiceMatrix3x3::from(&attitude.cmatrix), rotation);
+
+        // A point at rest on the J2000 X axis sweeps backwards in the spinning
+        // frame: velocity = R (-ω × r)
+        let state = xform.multiply_vector(&crate::foundation::SpiceVector6::new([1.0, 0.0, 0.0, 0.0, 0.0, 0.0]));
+        let expected = rotation.multiply_vector(&SpiceVector3::new(0.0, -rate, 0.0));
+        for i in 0..3 {
+            assert!((state.get(i) - rotation.get(i, 0)).abs() < 1e-15);
+            assert!((state.get(i + 3) - expected.0[i]).abs() < 1e-15);
+        }
+
+        // The same point followed through the rotating matrix numerically
+        let dt = 1e-3;
+        let later = SpiceMatrix3x3::new([
+            [(angle + rate * dt).cos(), (angle + rate * dt).sin(), 0.0],
+            [-(angle + rate * dt).sin(), (angle + rate * dt).cos(), 0.0],
+            [0.0, 0.0, 1.0],
+        ]);
+        for i in 0..3 {
+            let numeric = (later.get(i, 0) - rotation.get(i, 0)) / dt;
+            assert!((state.get(i + 3) - numeric).abs() < 1e-4);
+        }
+
+        let mut without_av = attitude.clone();
+        without_av.angular_velocity = None;
+        assert!(attitude_state_to_state_transform(&without_av).is_err());
+        assert!(CMatrix::from_rotation(rotation.scale(2.0), 0.0, 1, -77701).is_err());
+    }
+
+    #[test]
+    fn test_ck_coverage_reports_gap_as_two_intervals() {
+        let mut daf = DafFile::new("DAF/CK", CK_ND, CK_NI, "COVERAGE TEST").unwrap();
+        let quaternion = [1.0, 0.0, 0.0, 0.0];
+        daf.add_array(&[0.0, 100.0], &[-82000, 1, 3, 1], "FIRST", &quaternion).unwrap();
+        daf.add_array(&[100.0, 150.0], &[-82000, 1, 3, 1], "ABUTTING", &quaternion).unwrap();
+        daf.add_array(&[400.0, 500.0], &[-82000, 1, 3, 1], "AFTER GAP", &quaternion).unwrap();
+        daf.add_array(&[0.0, 500.0], &[-82100, 1, 3, 0], "OTHER", &quaternion).unwrap();
+        let bytes = daf.to_bytes().unwrap();
+
+        let mut reader = CkReader::new();
+        reader.load_ck_data("gap.bc", &bytes).unwrap();
+
+        assert_eq!(reader.objects("gap.bc").unwrap(), vec![-82100, -82000]);
+        assert_eq!(reader.coverage("gap.bc", -82000).unwrap(), vec![(0.0, 150.0), (400.0, 500.0)]);
+        assert_eq!(reader.coverage("gap.bc", -82100).unwrap(), vec![(0.0, 500.0)]);
+        assert!(reader.coverage("gap.bc", -1).unwrap().is_empty());
+        assert!(reader.objects("missing.bc").is_err());
+        assert_eq!(reader.segments[&-82000].len(), 3);
+    }
+
+    #[test]
+    fn test_type3_interpolates_within_intervals_but_not_across_gaps() {
+        // Spin about Z at 0.02 rad/tick, sampled in two intervals with a gap
+        // between ticks 20 and 50
+        let rate: f64 = 0.02;
+        let times = [0.0, 10.0, 20.0, 50.0, 60.0];
+        let mut data = Vec::new();
+        for &t in &times {
+            let half = 0.5 * rate * t;
+            data.extend_from_slice(&[half.cos(), 0.0, 0.0, half.sin(), 0.0, 0.0, rate]);
+        }
+        data.extend_from_slice(&times);
+        data.extend_from_slice(&[0.0, 50.0]);
+        data.extend_from_slice(&[2.0, times.len() as f64]);
+
+        let mut daf = DafFile::new("DAF/CK", CK_ND, CK_NI, "TYPE 3 TEST").unwrap();
+        daf.add_array(&[0.0, 60.0], &[-82000, 1, 3, 1], "SPIN", &data).unwrap();
+        let mut reader = CkReader::new();
+        reader.load_ck_data("spin.bc", &daf.to_bytes().unwrap()).unwrap();
+
+        for t in [5.0, 20.0, 50.0, 57.5] {
+            let attitude = reader.get_pointing_and_av(-82000, t, 0.0, "J2000").unwrap();
+            assert!(attitude.found, "no pointing at {}", t);
+            let angle = rate * t;
+            let expected = SpiceMatrix3x3::new([
+                [angle.cos(), -angle.sin(), 0.0],
+                [angle.sin(), angle.cos(), 0.0],
+                [0.0, 0.0, 1.0],
+            ]);
+            for i in 0..3 {
+                for j in 0..3 {
+                    assert!((attitude.cmatrix.matrix.get(i, j) - expected.get(i, j)).abs() < 1e-12);
+                }
+            }
+            let av = attitude.angular_velocity.unwrap().vector;
+            assert!((av - SpiceVector3::new(0.0, 0.0, rate)).magnitude() < 1e-15);
+        }
+
+        // Inside the segment's time bounds, but between the intervals
+        assert!(!reader.get_pointing(-82000, 35.0, 0.0, "J2000").unwrap().found);
+        assert!(!reader.get_pointing(-82000, 60.5, 1.0, "J2000").unwrap().found);
+    }
+
+    #[test]
+    fn test_type2_spin_advances_yaw_linearly() {
+        // Spin about Z at 0.01 rad/s with 2 s per tick, over two records
+        // separated by a gap from tick 100 to 200
+        let (rate, seconds_per_tick): (f64, f64) = (0.01, 2.0);
+        let mut data = Vec::new();
+        for start in [0.0_f64, 200.0] {
+            let half = -0.5 * rate * seconds_per_tick * start;
+            data.extend_from_slice(&[half.cos(), 0.0, 0.0, half.sin(), 0.0, 0.0, rate, seconds_per_tick]);
+        }
+        data.extend_from_slice(&[0.0, 200.0, 100.0, 300.0]);
+
+        let mut daf = DafFile::new("DAF/CK", CK_ND, CK_NI, "TYPE 2 TEST").unwrap();
+        daf.add_array(&[0.0, 300.0], &[-82000, 1, 2, 1], "SPIN", &data).unwrap();
+        let mut reader = CkReader::new();
+        reader.load_ck_data("spin.bc", &daf.to_bytes().unwrap()).unwrap();
+
+        for t in [0.0, 25.0, 100.0, 200.0, 262.5] {
+            let attitude = reader.get_pointing_and_av(-82000, t, 0.0, "J2000").unwrap();
+            assert!(attitude.found, "no pointing at {}", t);
+            let m = attitude.cmatrix.matrix;
+            let yaw = m.get(0, 1).atan2(m.get(0, 0));
+            let expected = (rate * seconds_per_tick * t + PI).rem_euclid(2.0 * PI) - PI;
+            assert!((yaw - expected).abs() < 1e-12, "yaw {} at {}, expected {}", yaw, t, expected);
+            assert!(is_rotation_matrix(&m));
+            assert_eq!(attitude.angular_velocity.unwrap().vector, SpiceVector3::new(0.0, 0.0, rate));
+        }
+
+        assert!(!reader.get_pointing(-82000, 150.0, 0.0, "J2000").unwrap().found);
+    }
 }
@@ -2,17 +2,32 @@
 //! 
 //! This module provides complete equivalency to CSPICE coordinate functions:
 //! - pxform_c → get_position_transformation() - Position transformation matrices between frames
+//! - pxform_c → position_transform() - The same by frame name at ET seconds, through the frame registry
 //! - sxform_c → get_state_transformation() - State transformation matrices with derivatives
 //! - rotate_c → rotate_vector() - Rotate vectors by specified angles
 //! - rotmat_c → rotation_matrix() - Build rotation matrices from angles and axes
 //! - axisar_c → axis_angle_rotation() - Axis-angle rotation matrices
 //! - m2eul_c → matrix_to_euler() - Extract Euler angles from rotation matrices
 //! - eul2m_c → euler_to_matrix() - Convert Euler angles to rotation matrices
+//! - eul2xf_c / xf2eul_c → euler_rates_to_state_transform() / state_transform_to_euler_rates()
+//! - twovec_c → two_vectors_to_matrix() - Frame from primary and secondary axis vectors
+//! - rav2xf_c → rotation_and_av_to_state_transform() - State transform of a rotating frame
+//! - tisbod_c → body_orientation() - Inertial to body-fixed state transform from the PCK
+//! - reclat_c / latrec_c → rectangular_to_latitudinal() / latitudinal_to_rectangular()
+//! - recrad_c / radrec_c → rectangular_to_ra_dec() / ra_dec_to_rectangular()
+//! - recgeo_c / georec_c → rectangular_to_geodetic() / geodetic_to_rectangular()
+//! - recpgr_c / pgrrec_c → rectangular_to_planetographic() / planetographic_to_rectangular()
+//! - namfrm_c / frmnam_c → frame_name_to_id() / frame_id_to_name()
+//!
+//! Fixed-offset (TK) and CK-driven frames defined in frame kernels are
+//! resolved through `frame_kernel`; surface geometry on target ellipsoids (subpnt_c and
+//! related routines) lives in `surface`, and plane geometry (nvc2pl_c, inrypl_c and
+//! related routines) in `plane`.
 //!
 //! Maintains numerical accuracy and compatibility with original CSPICE transformations.
 
 #[cfg(not(feature = "std"))]
-use alloc::{string::String, format};
+use alloc::{string::String, vec::Vec, format};
 #[cfg(feature = "std")]
 use std::{string::String, format};
 
@@ -21,7 +36,15 @@ use crate::foundation::{
     EphemerisTime, StateVector
 };
 use crate::error_handling::{SpiceResult, SpiceError, SpiceErrorType};
-use crate::math_core::constants;
+use crate::math_core::{constants, safe_acos, safe_asin};
+
+pub mod frame_kernel;
+pub mod plane;
+pub mod surface;
+
+use frame_kernel::kernel_frame_chain;
+pub use plane::Plane;
+pub use surface::{sub_observer_point, surface_intercept, surface_normal, nearest_point, illumination_angles};
 
 /// Reference frame identifiers and types
 #[derive(Debug, Clone, PartialEq)]
@@ -77,23 +100,45 @@ impl ReferenceFrame {
         }
     }
     
+    /// Frame name as used in kernels
+    pub fn name(&self) -> String {
+        match self {
+            ReferenceFrame::J2000 => "J2000".to_string(),
+            ReferenceFrame::B1950 => "B1950".to_string(),
+            ReferenceFrame::FK4 => "FK4".to_string(),
+            ReferenceFrame::FK5 => "FK5".to_string(),
+            ReferenceFrame::ICRF => "ICRF".to_string(),
+            ReferenceFrame::ITRF93 => "ITRF93".to_string(),
+            ReferenceFrame::IAU_EARTH => "IAU_EARTH".to_string(),
+            ReferenceFrame::IAU_MARS => "IAU_MARS".to_string(),
+            ReferenceFrame::IAU_MOON => "IAU_MOON".to_string(),
+            ReferenceFrame::IAU_SUN => "IAU_SUN".to_string(),
+            ReferenceFrame::IAU_JUPITER => "IAU_JUPITER".to_string(),
+            ReferenceFrame::IAU_SATURN => "IAU_SATURN".to_string(),
+            ReferenceFrame::Spacecraft(name) | ReferenceFrame::Custom(name) => name.to_uppercase(),
+        }
+    }
+    
     /// Get the frame identifier code (for internal calculations)
+    ///
+    /// Built-in frames have their NAIF IDs. ICRF and FK5 share J2000's ID, as
+    /// SPICE's J2000 frame is the realization of both. Spacecraft and custom
+    /// frames are looked up with `frame_name_to_id`, falling back to
+    /// placeholders when no frame kernel defines them.
     pub fn frame_id(&self) -> SpiceInt {
         match self {
-            ReferenceFrame::J2000 => 1,
+            ReferenceFrame::J2000 | ReferenceFrame::ICRF | ReferenceFrame::FK5 => 1,
             ReferenceFrame::B1950 => 2,
             ReferenceFrame::FK4 => 3,
-            ReferenceFrame::FK5 => 4,
-            ReferenceFrame::ICRF => 17,
             ReferenceFrame::ITRF93 => 13000,
+            ReferenceFrame::IAU_SUN => 10010,
             ReferenceFrame::IAU_EARTH => 10013,
             ReferenceFrame::IAU_MARS => 10014,
-            ReferenceFrame::IAU_MOON => 10015,
-            ReferenceFrame::IAU_SUN => 10010,
-            ReferenceFrame::IAU_JUPITER => 10016,
-            ReferenceFrame::IAU_SATURN => 10017,
-            ReferenceFrame::Spacecraft(_) => -100000, // Placeholder
-            ReferenceFrame::Custom(_) => -200000, // Placeholder
+            ReferenceFrame::IAU_JUPITER => 10015,
+            ReferenceFrame::IAU_SATURN => 10016,
+            ReferenceFrame::IAU_MOON => 10020,
+            ReferenceFrame::Spacecraft(name) => frame_name_to_id(name).unwrap_or(-100000),
+            ReferenceFrame::Custom(name) => frame_name_to_id(name).unwrap_or(-200000),
         }
     }
 }
@@ -166,56 +211,104 @@ pub fn get_position_transformation(
     to_frame: &str,
     et: EphemerisTime,
 ) -> SpiceResult<SpiceMatrix3x3> {
-    let from_ref = ReferenceFrame::from_str(from_frame)?;
-    let to_ref = ReferenceFrame::from_str(to_frame)?;
-    
-    // Handle common transformations
-    match (&from_ref, &to_ref) {
-        // Identity transformation
-        (a, b) if a == b => Ok(SpiceMatrix3x3::identity()),
-        
-        // J2000 to other inertial frames
-        (ReferenceFrame::J2000, ReferenceFrame::B1950) => {
-            get_j2000_to_b1950_matrix()
-        },
-        (ReferenceFrame::B1950, ReferenceFrame::J2000) => {
-            get_j2000_to_b1950_matrix().map(|m| m.transpose())
-        },
-        
-        // J2000 to Earth-fixed frames
-        (ReferenceFrame::J2000, ReferenceFrame::IAU_EARTH) => {
-            get_j2000_to_earth_fixed_matrix(et)
-        },
-        (ReferenceFrame::IAU_EARTH, ReferenceFrame::J2000) => {
-            get_j2000_to_earth_fixed_matrix(et).map(|m| m.transpose())
-        },
-        
-        // J2000 to planetary body-fixed frames
-        (ReferenceFrame::J2000, ReferenceFrame::IAU_MARS) => {
-            get_j2000_to_mars_fixed_matrix(et)
-        },
-        (ReferenceFrame::IAU_MARS, ReferenceFrame::J2000) => {
-            get_j2000_to_mars_fixed_matrix(et).map(|m| m.transpose())
+    position_transform(from_frame, to_frame, et.seconds())
+}
+
+/// Position transformation between any two frames known to the frame
+/// registry (equivalent to pxform_c)
+///
+/// Both frames are resolved with `frame_name_to_id` and reduced to J2000:
+/// inertial frames by fixed rotations, IAU body-fixed frames by the PCK
+/// rotation model at `et`, and FK-defined TK/CK frames through their chain.
+pub fn position_transform(from: &str, to: &str, et: SpiceDouble) -> SpiceResult<SpiceMatrix3x3> {
+    if from.trim().eq_ignore_ascii_case(to.trim()) {
+        return Ok(SpiceMatrix3x3::identity());
+    }
+    let from_to_j2000 = frame_to_j2000(from, et)?;
+    let to_to_j2000 = frame_to_j2000(to, et)?;
+    Ok(to_to_j2000.transpose().multiply(&from_to_j2000))
+}
+
+/// Obliquity of the ecliptic at J2000 (IAU 1976), 84381.448 arcseconds
+const J2000_OBLIQUITY: SpiceDouble = 84381.448 / 3600.0 * constants::RADIANS_PER_DEGREE;
+
+/// Rotation taking position vectors in `frame` to J2000 at `et`
+fn frame_to_j2000(frame: &str, et: SpiceDouble) -> SpiceResult<SpiceMatrix3x3> {
+    let id = frame_name_to_id(frame)?;
+    if let Some(body) = pck_frame_body(id) {
+        return body_fixed_rotation(body, et).map(|m| m.transpose());
+    }
+
+    match id {
+        1 => Ok(SpiceMatrix3x3::identity()),
+        2 => get_j2000_to_b1950_matrix().map(|m| m.transpose()),
+        17 => rotation_matrix_axis_angle(J2000_OBLIQUITY, RotationAxis::X),
+        // FK-defined frames reduce to the base of their TK/CK chain
+        _ => match kernel_frame_chain(frame, EphemerisTime::new(et))? {
+            Some((to_base, base)) => Ok(frame_to_j2000(&base, et)?.multiply(&to_base)),
+            None => Err(SpiceError::new(
+                SpiceErrorType::InvalidFrame,
+                format!("No transformation available from {} to J2000", frame)
+            )),
         },
-        
-        // Chain transformations through J2000
-        _ => {
-            let to_j2000 = get_position_transformation(from_frame, "J2000", et)?;
-            let j2000_to_target = get_position_transformation("J2000", to_frame, et)?;
-            Ok(j2000_to_target.multiply(&to_j2000))
+    }
+}
+
+/// NAIF code of the body an IAU body-fixed frame is attached to
+fn iau_frame_body(frame: &ReferenceFrame) -> Option<SpiceInt> {
+    pck_frame_body(frame.frame_id())
+}
+
+/// NAIF code of the body of a built-in IAU_<body> frame ID
+fn pck_frame_body(id: SpiceInt) -> Option<SpiceInt> {
+    match id {
+        10010 => Some(10),
+        // IAU_MERCURY through IAU_PLUTO
+        10011..=10019 => Some((id - 10010) * 100 + 99),
+        10020 => Some(301),
+        _ => None,
+    }
+}
+
+/// Analytic J2000-to-frame state transformation for J2000 and the IAU
+/// body-fixed frames, or `None` for other frames
+fn j2000_to_frame_state(frame: &ReferenceFrame, et: EphemerisTime) -> SpiceResult<Option<SpiceMatrix6x6>> {
+    if *frame == ReferenceFrame::J2000 {
+        return Ok(Some(SpiceMatrix6x6::identity()));
+    }
+    iau_frame_body(frame).map(|body| body_orientation(body, et.seconds())).transpose()
+}
+
+/// Inverse of a state transformation: the blocks of | R 0 ; dR R | transposed
+fn invert_state_transformation(matrix: &SpiceMatrix6x6) -> SpiceMatrix6x6 {
+    let mut inverse = SpiceMatrix6x6::zeros();
+    for i in 0..3 {
+        for j in 0..3 {
+            inverse.set(i, j, matrix.get(j, i));
+            inverse.set(i + 3, j, matrix.get(j + 3, i));
+            inverse.set(i + 3, j + 3, matrix.get(j, i));
         }
     }
+    inverse
 }
 
 /// Get state transformation matrix between reference frames (equivalent to sxform_c)
 /// 
 /// Returns the 6x6 transformation matrix that converts state vectors (position + velocity)
-/// from the 'from' frame to the 'to' frame at the specified epoch.
+/// from the 'from' frame to the 'to' frame at the specified epoch. Between
+/// J2000 and the IAU body-fixed frames the derivative block comes from the
+/// PCK rotation rates; other pairs are differentiated numerically.
 pub fn get_state_transformation(
     from_frame: &str,
     to_frame: &str,
     et: EphemerisTime,
 ) -> SpiceResult<SpiceMatrix6x6> {
+    let from_ref = ReferenceFrame::from_str(from_frame)?;
+    let to_ref = ReferenceFrame::from_str(to_frame)?;
+    if let (Some(from_state), Some(to_state)) = (j2000_to_frame_state(&from_ref, et)?, j2000_to_frame_state(&to_ref, et)?) {
+        return Ok(to_state.multiply(&invert_state_transformation(&from_state)));
+    }
+
     let position_matrix = get_position_transformation(from_frame, to_frame, et)?;
     
     // Calculate time derivative of position transformation matrix
@@ -259,6 +352,38 @@ pub fn get_state_transformation(
     Ok(state_matrix)
 }
 
+/// State transformation for a rotating frame (equivalent to rav2xf_c)
+///
+/// `rotation` takes vectors from a base frame to the rotating frame, and
+/// `angular_velocity` is the rotating frame's angular velocity relative to the
+/// base, expressed in the base frame (rad/s). The derivative block is
+/// dR/dt = -R [ω]×, so a point at rest in the base frame moves by -ω × r as
+/// seen from the rotating frame.
+pub fn rotation_and_av_to_state_transform(
+    rotation: &SpiceMatrix3x3,
+    angular_velocity: &SpiceVector3,
+) -> SpiceMatrix6x6 {
+    let [wx, wy, wz] = angular_velocity.0;
+    let omega = SpiceMatrix3x3::new([
+        [0.0, -wz, wy],
+        [wz, 0.0, -wx],
+        [-wy, wx, 0.0],
+    ]);
+    let derivative = rotation.multiply(&omega).scale(-1.0);
+
+    // | R   0 |
+    // | dR  R |
+    let mut state_matrix = SpiceMatrix6x6::zeros();
+    for i in 0..3 {
+        for j in 0..3 {
+            state_matrix.set(i, j, rotation.get(i, j));
+            state_matrix.set(i + 3, j, derivative.get(i, j));
+            state_matrix.set(i + 3, j + 3, rotation.get(i, j));
+        }
+    }
+    state_matrix
+}
+
 /// Rotate a vector by a specified angle around an axis (equivalent to rotate_c)
 pub fn rotate_vector(
     vector: &SpiceVector3,
@@ -371,6 +496,94 @@ pub fn euler_to_matrix(
     }
 }
 
+/// Rotation of an Euler sequence and the axes about which its angle rates
+/// turn the rotated frame, in that frame
+///
+/// With R = A1·A2·A3 as in `euler_to_matrix`, Rᵀ·dR/dt = [ω]× where
+/// ω = a1'·(A2·A3)ᵀe1 + a2'·A3ᵀe2 + a3'·e3; the returned matrix holds those
+/// three axes as columns.
+fn euler_rate_axes(angles: [SpiceDouble; 3], sequence: EulerSequence) -> SpiceResult<(SpiceMatrix3x3, SpiceMatrix3x3)> {
+    let code = sequence as SpiceInt;
+    let mut factors = [SpiceMatrix3x3::identity(); 3];
+    let mut basis = [SpiceVector3::zeros(); 3];
+    for (index, digit) in [code / 100, code / 10 % 10, code % 10].into_iter().enumerate() {
+        let axis = match digit {
+            1 => RotationAxis::X,
+            2 => RotationAxis::Y,
+            _ => RotationAxis::Z,
+        };
+        basis[index].0[digit as usize - 1] = 1.0;
+        factors[index] = rotation_matrix_axis_angle(angles[index], axis)?;
+    }
+
+    let inner = factors[1].multiply(&factors[2]);
+    let columns = [
+        inner.transpose_multiply_vector(&basis[0]),
+        factors[2].transpose_multiply_vector(&basis[1]),
+        basis[2],
+    ];
+    let axes = SpiceMatrix3x3::new([columns[0].0, columns[1].0, columns[2].0]).transpose();
+    Ok((factors[0].multiply(&inner), axes))
+}
+
+/// State transformation from Euler angles and their rates (equivalent to eul2xf_c)
+///
+/// The rotation block is `euler_to_matrix` of the angles and the derivative
+/// block its time derivative, laid out as in `rotation_and_av_to_state_transform`.
+pub fn euler_rates_to_state_transform(angles: [SpiceDouble; 3], rates: [SpiceDouble; 3], sequence: EulerSequence) -> SpiceMatrix6x6 {
+    // Every sequence has three valid axes, so building the factors cannot fail
+    let (rotation, axes) = euler_rate_axes(angles, sequence).expect("Euler sequence axes are 1 to 3");
+    let [wx, wy, wz] = axes.multiply_vector(&SpiceVector3::new(rates[0], rates[1], rates[2])).0;
+    let derivative = rotation.multiply(&SpiceMatrix3x3::new([
+        [0.0, -wz, wy],
+        [wz, 0.0, -wx],
+        [-wy, wx, 0.0],
+    ]));
+
+    let mut state_matrix = SpiceMatrix6x6::zeros();
+    for i in 0..3 {
+        for j in 0..3 {
+            state_matrix.set(i, j, rotation.get(i, j));
+            state_matrix.set(i + 3, j, derivative.get(i, j));
+            state_matrix.set(i + 3, j + 3, rotation.get(i, j));
+        }
+    }
+    state_matrix
+}
+
+/// Euler angles and their rates from a state transformation (equivalent to xf2eul_c)
+///
+/// Angles are extracted as by `matrix_to_euler`. Rates are undefined at
+/// gimbal lock, where this fails.
+pub fn state_transform_to_euler_rates(
+    transform: &SpiceMatrix6x6,
+    sequence: EulerSequence,
+) -> SpiceResult<([SpiceDouble; 3], [SpiceDouble; 3])> {
+    let mut rotation = SpiceMatrix3x3::zeros();
+    let mut derivative = SpiceMatrix3x3::zeros();
+    for i in 0..3 {
+        for j in 0..3 {
+            rotation.set(i, j, transform.get(i, j));
+            derivative.set(i, j, transform.get(i + 3, j));
+        }
+    }
+
+    let (a1, a2, a3) = matrix_to_euler(&rotation, sequence.clone())?;
+    let angles = [a1, a2, a3];
+    let (_, axes) = euler_rate_axes(angles, sequence)?;
+    if axes.determinant().abs() < 1e-12 {
+        return Err(SpiceError::new(
+            SpiceErrorType::ComputationError,
+            format!("Euler angle rates are undefined at gimbal lock (angles {:?})", angles),
+        ));
+    }
+
+    let omega = rotation.transpose().multiply(&derivative);
+    let w = SpiceVector3::new(omega.get(2, 1), omega.get(0, 2), omega.get(1, 0));
+    let rates = axes.invert()?.multiply_vector(&w);
+    Ok((angles, rates.0))
+}
+
 // ============================================================================
 // SPECIFIC FRAME TRANSFORMATION IMPLEMENTATIONS
 // ============================================================================
@@ -410,45 +623,163 @@ fn get_j2000_to_b1950_matrix() -> SpiceResult<SpiceMatrix3x3> {
     ]))
 }
 
-/// Get transformation matrix from J2000 to Earth-fixed frame
-fn get_j2000_to_earth_fixed_matrix(et: EphemerisTime) -> SpiceResult<SpiceMatrix3x3> {
-    // Simplified Earth rotation model
-    // In practice, this would use IERS data for precision
-    
-    // Earth rotation rate (radians per second)
-    let earth_rotation_rate = 7.2921159e-5; // rad/s
+/// Get transformation matrix from J2000 to a body-fixed frame using the PCK
+/// pole (`BODY<code>_POLE_RA`, `_POLE_DEC`) and prime meridian (`_PM`) models
+pub(crate) fn body_fixed_rotation(body: SpiceInt, et: SpiceDouble) -> SpiceResult<SpiceMatrix3x3> {
+    let (angles, _) = PckRotationModel::from_pool(body)?.evaluate(et);
+    let [ra, dec, w] = angles;
     
-    // Time since J2000 epoch
-    let seconds_since_j2000 = et.seconds();
+    // Frame rotations are the transposes of the vector rotations built by rotmat
+    let about_pole = rotation_matrix_axis_angle(-(constants::HALF_PI + ra), RotationAxis::Z)?;
+    let tilt = rotation_matrix_axis_angle(-(constants::HALF_PI - dec), RotationAxis::X)?;
+    let spin = rotation_matrix_axis_angle(-w, RotationAxis::Z)?;
     
-    // Earth rotation angle
-    let rotation_angle = earth_rotation_rate * seconds_since_j2000;
-    
-    // Simple Z-axis rotation for Earth's rotation
-    rotation_matrix_axis_angle(rotation_angle, RotationAxis::Z)
+    Ok(spin.multiply(&tilt.multiply(&about_pole)))
 }
 
-/// Get transformation matrix from J2000 to Mars-fixed frame  
-fn get_j2000_to_mars_fixed_matrix(et: EphemerisTime) -> SpiceResult<SpiceMatrix3x3> {
-    // Simplified Mars rotation model
-    // Mars rotation period: 24.6229 hours
-    let mars_rotation_period = 24.6229 * 3600.0; // seconds
-    let mars_rotation_rate = 2.0 * constants::PI / mars_rotation_period;
-    
-    // Time since J2000 epoch
-    let seconds_since_j2000 = et.seconds();
-    
-    // Mars rotation angle
-    let rotation_angle = mars_rotation_rate * seconds_since_j2000;
-    
-    // Mars obliquity (approximately 25.19 degrees)
-    let mars_obliquity: SpiceDouble = 25.19 * constants::RADIANS_PER_DEGREE;
-    
-    // Combine obliquity and rotation
-    let obliquity_matrix = rotation_matrix_axis_angle(mars_obliquity, RotationAxis::X)?;
-    let rotation_matrix = rotation_matrix_axis_angle(rotation_angle, RotationAxis::Z)?;
-    
-    Ok(rotation_matrix.multiply(&obliquity_matrix))
+/// Inertial-to-body-fixed state transformation from the PCK rotation model
+/// (equivalent to tisbod_c with J2000 as the inertial frame)
+///
+/// Evaluates the `BODY<code>_POLE_RA`, `_POLE_DEC` and `_PM` polynomials plus
+/// the nutation/precession terms, and takes the derivative block from their
+/// rates.
+pub fn body_orientation(body: SpiceInt, et: SpiceDouble) -> SpiceResult<SpiceMatrix6x6> {
+    let (angles, rates) = PckRotationModel::from_pool(body)?.evaluate(et);
+    rotation_model_state(angles, rates)
+}
+
+/// A body's PCK rotation model: pole and prime meridian polynomials in
+/// degrees, and nutation/precession angles and coefficients
+#[derive(Debug, Clone, Default)]
+struct PckRotationModel {
+    pole_ra: Vec<SpiceDouble>,
+    pole_dec: Vec<SpiceDouble>,
+    pm: Vec<SpiceDouble>,
+    /// (constant, rate per century) pairs in degrees
+    nut_prec_angles: Vec<SpiceDouble>,
+    nut_prec_ra: Vec<SpiceDouble>,
+    nut_prec_dec: Vec<SpiceDouble>,
+    nut_prec_pm: Vec<SpiceDouble>,
+}
+
+impl PckRotationModel {
+    /// Read a body's model from the kernel pool, falling back to built-in
+    /// pole values
+    ///
+    /// The nutation/precession angles are looked up on the body and then on
+    /// its system barycenter, where PCKs conventionally store them; bodies
+    /// without them have no periodic terms.
+    fn from_pool(body: SpiceInt) -> SpiceResult<Self> {
+        let body_name = body.to_string();
+        let periodic = |code: SpiceInt, item: &str| -> SpiceResult<Vec<SpiceDouble>> {
+            if !crate::kernel_pool::is_pool_initialized() {
+                return Ok(Vec::new());
+            }
+            match crate::body_data::body_values_double(&code.to_string(), item, crate::kernel_pool::MAX_VALUES_PER_VARIABLE) {
+                Err(error) if error.error_type == SpiceErrorType::InsufficientData => Ok(Vec::new()),
+                values => values,
+            }
+        };
+
+        let mut nut_prec_angles = periodic(body, "NUT_PREC_ANGLES")?;
+        if nut_prec_angles.is_empty() && body >= 100 {
+            nut_prec_angles = periodic(body / 100, "NUT_PREC_ANGLES")?;
+        }
+
+        Ok(Self {
+            pole_ra: crate::body_data::body_data(&body_name, "POLE_RA")?,
+            pole_dec: crate::body_data::body_data(&body_name, "POLE_DEC")?,
+            pm: crate::body_data::body_data(&body_name, "PM")?,
+            nut_prec_angles,
+            nut_prec_ra: periodic(body, "NUT_PREC_RA")?,
+            nut_prec_dec: periodic(body, "NUT_PREC_DEC")?,
+            nut_prec_pm: periodic(body, "NUT_PREC_PM")?,
+        })
+    }
+
+    /// Pole right ascension, declination and prime meridian angle in
+    /// radians, and their rates in radians per second
+    ///
+    /// RA and DEC polynomials are in Julian centuries and PM in days past
+    /// J2000. The periodic terms add Σ aᵢ sin θᵢ to RA and PM and
+    /// Σ dᵢ cos θᵢ to DEC, with θᵢ linear in centuries.
+    fn evaluate(&self, et: SpiceDouble) -> ([SpiceDouble; 3], [SpiceDouble; 3]) {
+        let days = et / 86400.0;
+        let centuries = days / 36525.0;
+        let evaluate = |coeffs: &[SpiceDouble], t: SpiceDouble| {
+            coeffs.iter().rev().fold(0.0, |acc, &c| acc * t + c)
+        };
+        let derivative = |coeffs: &[SpiceDouble], t: SpiceDouble| {
+            coeffs.iter().enumerate().skip(1).rev().fold(0.0, |acc, (k, &c)| acc * t + k as SpiceDouble * c)
+        };
+
+        // Values in degrees, rates in degrees per century
+        let mut ra = (evaluate(&self.pole_ra, centuries), derivative(&self.pole_ra, centuries));
+        let mut dec = (evaluate(&self.pole_dec, centuries), derivative(&self.pole_dec, centuries));
+        let mut w = (evaluate(&self.pm, days), derivative(&self.pm, days) * 36525.0);
+
+        let degrees = constants::RADIANS_PER_DEGREE;
+        for (i, pair) in self.nut_prec_angles.chunks_exact(2).enumerate() {
+            let theta = (pair[0] + pair[1] * centuries) * degrees;
+            let theta_rate = pair[1] * degrees;
+            let (sin, cos) = theta.sin_cos();
+            if let Some(&a) = self.nut_prec_ra.get(i) {
+                ra = (ra.0 + a * sin, ra.1 + a * cos * theta_rate);
+            }
+            if let Some(&d) = self.nut_prec_dec.get(i) {
+                dec = (dec.0 + d * cos, dec.1 - d * sin * theta_rate);
+            }
+            if let Some(&a) = self.nut_prec_pm.get(i) {
+                w = (w.0 + a * sin, w.1 + a * cos * theta_rate);
+            }
+        }
+
+        let per_century = degrees / (36525.0 * 86400.0);
+        (
+            [ra.0 * degrees, dec.0 * degrees, (w.0 % 360.0) * degrees],
+            [ra.1 * per_century, dec.1 * per_century, w.1 * per_century],
+        )
+    }
+}
+
+/// State transformation of the frame with pole angles [ra, dec, w] changing
+/// at the given rates
+///
+/// The rotation is R = Rz(w) Rx(π/2 - dec) Rz(π/2 + ra) in frame-rotation
+/// form, and each factor F(θ) has derivative -[e]× F(θ) θ'.
+fn rotation_model_state(angles: [SpiceDouble; 3], rates: [SpiceDouble; 3]) -> SpiceResult<SpiceMatrix6x6> {
+    let [ra, dec, w] = angles;
+    let [ra_rate, dec_rate, w_rate] = rates;
+
+    let frame_rotation = |angle: SpiceDouble, axis: RotationAxis| -> SpiceResult<(SpiceMatrix3x3, SpiceMatrix3x3)> {
+        // [e]× for the unit vector e along the axis
+        let cross_axis = match axis {
+            RotationAxis::X => SpiceMatrix3x3::new([[0.0, 0.0, 0.0], [0.0, 0.0, -1.0], [0.0, 1.0, 0.0]]),
+            RotationAxis::Y => SpiceMatrix3x3::new([[0.0, 0.0, 1.0], [0.0, 0.0, 0.0], [-1.0, 0.0, 0.0]]),
+            RotationAxis::Z => SpiceMatrix3x3::new([[0.0, -1.0, 0.0], [1.0, 0.0, 0.0], [0.0, 0.0, 0.0]]),
+        };
+        let rotation = rotation_matrix_axis_angle(-angle, axis)?;
+        Ok((rotation, cross_axis.multiply(&rotation).scale(-1.0)))
+    };
+
+    let (about_pole, d_about_pole) = frame_rotation(constants::HALF_PI + ra, RotationAxis::Z)?;
+    let (tilt, d_tilt) = frame_rotation(constants::HALF_PI - dec, RotationAxis::X)?;
+    let (spin, d_spin) = frame_rotation(w, RotationAxis::Z)?;
+
+    let rotation = spin.multiply(&tilt.multiply(&about_pole));
+    let derivative = d_spin.multiply(&tilt.multiply(&about_pole)).scale(w_rate)
+        .add(&spin.multiply(&d_tilt.multiply(&about_pole)).scale(-dec_rate))
+        .add(&spin.multiply(&tilt.multiply(&d_about_pole)).scale(ra_rate));
+
+    let mut state = SpiceMatrix6x6::zeros();
+    for i in 0..3 {
+        for j in 0..3 {
+            state.set(i, j, rotation.get(i, j));
+            state.set(i + 3, j, derivative.get(i, j));
+            state.set(i + 3, j + 3, rotation.get(i, j));
+        }
+    }
+    Ok(state)
 }
 
 // ============================================================================
@@ -460,7 +791,7 @@ fn matrix_to_euler_zyx(matrix: &SpiceMatrix3x3) -> SpiceResult<(SpiceDouble, Spi
     let m = matrix;
     
     // Extract Y rotation (pitch)
-    let pitch = (-m.get(2, 0)).asin();
+    let pitch = safe_asin(-m.get(2, 0));
     
     // Check for gimbal lock
     if pitch.cos().abs() < 1e-6 {
@@ -481,7 +812,7 @@ fn matrix_to_euler_xyz(matrix: &SpiceMatrix3x3) -> SpiceResult<(SpiceDouble, Spi
     let m = matrix;
     
     // Extract Y rotation
-    let y_angle = m.get(0, 2).asin();
+    let y_angle = safe_asin(m.get(0, 2));
     
     // Check for gimbal lock
     if y_angle.cos().abs() < 1e-6 {
@@ -502,7 +833,7 @@ fn matrix_to_euler_zxz(matrix: &SpiceMatrix3x3) -> SpiceResult<(SpiceDouble, Spi
     let m = matrix;
     
     // Extract X rotation (middle angle)
-    let x_angle = m.get(2, 2).acos();
+    let x_angle = safe_acos(m.get(2, 2));
     
     // Check for gimbal lock
     if x_angle.sin().abs() < 1e-6 {
@@ -626,12 +957,62 @@ pub fn rotation_between_vectors(
     }
     
     // Calculate rotation angle
-    let rotation_angle = from_norm.dot(&to_norm).acos();
+    let rotation_angle = safe_acos(from_norm.dot(&to_norm));
     let normalized_axis = rotation_axis.scale(1.0 / axis_magnitude);
     
     axis_angle_rotation(&normalized_axis, rotation_angle)
 }
 
+/// Rotation matrix whose axes are defined by two vectors (equivalent to twovec_c)
+///
+/// `primary` lies exactly along `primary_axis` of the new frame; `secondary`
+/// lies in the plane of the primary and secondary axes, on the positive side
+/// of the secondary axis. The returned matrix rotates vectors from the input
+/// frame into the new frame.
+pub fn two_vectors_to_matrix(
+    primary: &SpiceVector3,
+    primary_axis: RotationAxis,
+    secondary: &SpiceVector3,
+    secondary_axis: RotationAxis,
+) -> SpiceResult<SpiceMatrix3x3> {
+    if primary_axis == secondary_axis {
+        return Err(SpiceError::new(
+            SpiceErrorType::InvalidArgument,
+            format!("Primary and secondary axes must differ (both {:?})", primary_axis)
+        ));
+    }
+    if primary.magnitude() == 0.0 || secondary.magnitude() == 0.0 {
+        return Err(SpiceError::new(
+            SpiceErrorType::InvalidArgument,
+            "Axis definition vectors must be non-zero".into()
+        ));
+    }
+    let normal = primary.unit_cross(secondary);
+    if normal.magnitude() == 0.0 {
+        return Err(SpiceError::new(
+            SpiceErrorType::InvalidArgument,
+            "Primary and secondary vectors are parallel and do not define a plane".into()
+        ));
+    }
+
+    // Axis indices in cyclic order starting from the primary
+    let i1 = primary_axis as usize - 1;
+    let i2 = (i1 + 1) % 3;
+    let i3 = (i1 + 2) % 3;
+
+    let mut rows = [SpiceVector3::zeros(); 3];
+    rows[i1] = primary.unit()?;
+    if secondary_axis as usize - 1 == i2 {
+        rows[i3] = normal;
+        rows[i2] = rows[i3].unit_cross(primary);
+    } else {
+        rows[i2] = secondary.unit_cross(primary);
+        rows[i3] = primary.unit_cross(&rows[i2]);
+    }
+
+    Ok(SpiceMatrix3x3::new([rows[0].0, rows[1].0, rows[2].0]))
+}
+
 /// Check if a matrix is a valid rotation matrix
 pub fn is_rotation_matrix(matrix: &SpiceMatrix3x3) -> bool {
     // Check if determinant is +1
@@ -640,20 +1021,291 @@ pub fn is_rotation_matrix(matrix: &SpiceMatrix3x3) -> bool {
         return false;
     }
     
-    // Check if matrix is orthogonal (R * R^T = I)
-    let transpose = matrix.transpose();
-    let product = matrix.multiply(&transpose);
-    let identity = SpiceMatrix3x3::identity();
-    
-    for i in 0..3 {
-        for j in 0..3 {
-            if (product.get(i, j) - identity.get(i, j)).abs() > 1e-10 {
-                return false;
-            }
+    // Check if matrix is orthogonal (R^T * R = I)
+    matrix.rotation_residual() <= 1e-10
+}
+
+// ============================================================================
+// FRAME NAMES AND IDS
+// ============================================================================
+
+/// Built-in frames with their NAIF frame IDs: the inertial frames, the PCK
+/// body-fixed frames and ITRF93
+const BUILT_IN_FRAMES: &[(&str, SpiceInt)] = &[
+    ("J2000", 1), ("B1950", 2), ("FK4", 3),
+    ("DE-118", 4), ("DE-96", 5), ("DE-102", 6), ("DE-108", 7), ("DE-111", 8),
+    ("DE-114", 9), ("DE-122", 10), ("DE-125", 11), ("DE-130", 12),
+    ("GALACTIC", 13), ("DE-200", 14), ("DE-202", 15), ("MARSIAU", 16),
+    ("ECLIPJ2000", 17), ("ECLIPB1950", 18), ("DE-140", 19), ("DE-142", 20), ("DE-143", 21),
+    ("IAU_SUN", 10010), ("IAU_MERCURY", 10011), ("IAU_VENUS", 10012), ("IAU_EARTH", 10013),
+    ("IAU_MARS", 10014), ("IAU_JUPITER", 10015), ("IAU_SATURN", 10016), ("IAU_URANUS", 10017),
+    ("IAU_NEPTUNE", 10018), ("IAU_PLUTO", 10019), ("IAU_MOON", 10020),
+    ("ITRF93", 13000),
+];
+
+/// Frame ID of a frame name (equivalent to namfrm_c)
+///
+/// Built-in frames are checked first, then `FRAME_<name>` assignments from
+/// loaded frame kernels. Names are case-insensitive.
+pub fn frame_name_to_id(name: &str) -> SpiceResult<SpiceInt> {
+    let name = name.trim().to_uppercase();
+    if let Some(&(_, id)) = BUILT_IN_FRAMES.iter().find(|(built_in, _)| *built_in == name) {
+        return Ok(id);
+    }
+
+    if crate::kernel_pool::is_pool_initialized() {
+        let (ids, found) = crate::kernel_pool::get_integer_pool(&format!("FRAME_{}", name), 0, 1)?;
+        if let (true, Some(&id)) = (found, ids.first()) {
+            return Ok(id);
         }
     }
-    
-    true
+
+    Err(SpiceError::new(
+        SpiceErrorType::InvalidFrame,
+        format!("Unknown frame '{}'", name),
+    ))
+}
+
+/// Frame name of a frame ID (equivalent to frmnam_c)
+///
+/// Built-in frames are checked first, then `FRAME_<id>_NAME` assignments
+/// from loaded frame kernels.
+pub fn frame_id_to_name(id: SpiceInt) -> SpiceResult<String> {
+    if let Some(&(name, _)) = BUILT_IN_FRAMES.iter().find(|&&(_, built_in)| built_in == id) {
+        return Ok(name.to_string());
+    }
+
+    if crate::kernel_pool::is_pool_initialized() {
+        let (names, found) = crate::kernel_pool::get_character_pool(&format!("FRAME_{}_NAME", id), 0, 1)?;
+        if let (true, Some(name)) = (found, names.first()) {
+            return Ok(name.trim().to_uppercase());
+        }
+    }
+
+    Err(SpiceError::new(
+        SpiceErrorType::InvalidFrame,
+        format!("Frame ID {} has no name", id),
+    ))
+}
+
+// ============================================================================
+// LATITUDINAL COORDINATES
+// ============================================================================
+
+/// Rectangular to latitudinal coordinates (equivalent to reclat_c)
+///
+/// Returns (radius, longitude, latitude) in km and radians. Longitude is 0 on
+/// the Z axis and both angles are 0 at the origin.
+pub fn rectangular_to_latitudinal(point: &SpiceVector3) -> (SpiceDouble, SpiceDouble, SpiceDouble) {
+    let radius = point.magnitude();
+    if radius == 0.0 {
+        return (0.0, 0.0, 0.0);
+    }
+    let (x, y, z) = (point.x(), point.y(), point.z());
+    let longitude = if x == 0.0 && y == 0.0 { 0.0 } else { y.atan2(x) };
+    let latitude = z.atan2((x * x + y * y).sqrt());
+    (radius, longitude, latitude)
+}
+
+/// Latitudinal to rectangular coordinates (equivalent to latrec_c)
+pub fn latitudinal_to_rectangular(radius: SpiceDouble, longitude: SpiceDouble, latitude: SpiceDouble) -> SpiceVector3 {
+    SpiceVector3::new(
+        radius * latitude.cos() * longitude.cos(),
+        radius * latitude.cos() * longitude.sin(),
+        radius * latitude.sin(),
+    )
+}
+
+/// `rectangular_to_latitudinal` over a slice of points
+pub fn rectangular_to_latitudinal_batch(points: &[SpiceVector3]) -> Vec<(SpiceDouble, SpiceDouble, SpiceDouble)> {
+    points.iter().map(rectangular_to_latitudinal).collect()
+}
+
+/// `latitudinal_to_rectangular` over a slice of (radius, longitude, latitude)
+pub fn latitudinal_to_rectangular_batch(coordinates: &[(SpiceDouble, SpiceDouble, SpiceDouble)]) -> Vec<SpiceVector3> {
+    coordinates
+        .iter()
+        .map(|&(radius, longitude, latitude)| latitudinal_to_rectangular(radius, longitude, latitude))
+        .collect()
+}
+
+/// Reduce an angle to [0, 2π)
+fn wrap_two_pi(angle: SpiceDouble) -> SpiceDouble {
+    // rem_euclid rounds tiny negative angles up to 2π itself
+    angle.rem_euclid(constants::TWO_PI) % constants::TWO_PI
+}
+
+/// Rectangular coordinates to range, right ascension and declination
+/// (equivalent to recrad_c)
+///
+/// Right ascension lies in [0, 2π) and declination in [-π/2, π/2]; both
+/// angles are 0 at the origin and right ascension is 0 on the Z axis.
+pub fn rectangular_to_ra_dec(v: &SpiceVector3) -> (SpiceDouble, SpiceDouble, SpiceDouble) {
+    let (range, longitude, latitude) = rectangular_to_latitudinal(v);
+    (range, wrap_two_pi(longitude), latitude.clamp(-constants::HALF_PI, constants::HALF_PI))
+}
+
+/// Range, right ascension and declination to rectangular coordinates
+/// (equivalent to radrec_c)
+pub fn ra_dec_to_rectangular(range: SpiceDouble, ra: SpiceDouble, dec: SpiceDouble) -> SpiceVector3 {
+    latitudinal_to_rectangular(range, ra, dec)
+}
+
+// ============================================================================
+// GEODETIC AND PLANETOGRAPHIC COORDINATES
+// ============================================================================
+
+/// Check that a reference spheroid has a positive equatorial radius and
+/// flattening below one
+fn validate_spheroid(re: SpiceDouble, f: SpiceDouble) -> SpiceResult<()> {
+    if re.is_nan() || f.is_nan() || re <= 0.0 || f >= 1.0 {
+        return Err(SpiceError::new(
+            SpiceErrorType::InvalidArgument,
+            format!("Spheroid needs a positive equatorial radius and flattening below 1, got {} and {}", re, f)
+        ));
+    }
+    Ok(())
+}
+
+/// Geodetic latitude and altitude of a point at distance `rho` from the
+/// spheroid's axis and height `z` above its equator
+///
+/// Bowring's iteration on the parametric latitude converges in a few steps
+/// everywhere outside the immediate neighborhood of the center, including
+/// near the poles.
+fn geodetic_latitude_altitude(rho: SpiceDouble, z: SpiceDouble, re: SpiceDouble, f: SpiceDouble) -> (SpiceDouble, SpiceDouble) {
+    let polar = re * (1.0 - f);
+    if rho == 0.0 {
+        let latitude = if z < 0.0 { -constants::HALF_PI } else { constants::HALF_PI };
+        return (latitude, z.abs() - polar);
+    }
+
+    let e2 = f * (2.0 - f);
+    let ep2 = e2 / ((1.0 - f) * (1.0 - f));
+    let mut parametric = (re * z).atan2(polar * rho);
+    let mut latitude = parametric;
+    for _ in 0..10 {
+        let (sin_b, cos_b) = parametric.sin_cos();
+        latitude = (z + ep2 * polar * sin_b.powi(3)).atan2(rho - e2 * re * cos_b.powi(3));
+        let next = ((1.0 - f) * latitude.sin()).atan2(latitude.cos());
+        let settled = (next - parametric).abs() <= 1e-15;
+        parametric = next;
+        if settled {
+            break;
+        }
+    }
+
+    let (sin_lat, cos_lat) = latitude.sin_cos();
+    let altitude = rho * cos_lat + z * sin_lat - re * (1.0 - e2 * sin_lat * sin_lat).sqrt();
+    (latitude, altitude)
+}
+
+/// Rectangular point at a geodetic longitude, latitude and altitude
+fn geodetic_point(longitude: SpiceDouble, latitude: SpiceDouble, altitude: SpiceDouble, re: SpiceDouble, f: SpiceDouble) -> SpiceVector3 {
+    let e2 = f * (2.0 - f);
+    let (sin_lat, cos_lat) = latitude.sin_cos();
+    let normal_radius = re / (1.0 - e2 * sin_lat * sin_lat).sqrt();
+    let rho = (normal_radius + altitude) * cos_lat;
+    SpiceVector3::new(
+        rho * longitude.cos(),
+        rho * longitude.sin(),
+        (normal_radius * (1.0 - e2) + altitude) * sin_lat,
+    )
+}
+
+/// Rectangular to geodetic coordinates (equivalent to recgeo_c)
+///
+/// Returns (longitude, latitude, altitude) in radians and km relative to the
+/// spheroid with equatorial radius `re` and flattening `f`. Longitude lies in
+/// (-π, π] and is 0 for points on the Z axis.
+pub fn rectangular_to_geodetic(
+    rectan: &SpiceVector3,
+    re: SpiceDouble,
+    f: SpiceDouble,
+) -> SpiceResult<(SpiceDouble, SpiceDouble, SpiceDouble)> {
+    validate_spheroid(re, f)?;
+    let (x, y, z) = (rectan.x(), rectan.y(), rectan.z());
+    let longitude = if x == 0.0 && y == 0.0 { 0.0 } else { y.atan2(x) };
+    let (latitude, altitude) = geodetic_latitude_altitude(x.hypot(y), z, re, f);
+    Ok((longitude, latitude, altitude))
+}
+
+/// Geodetic to rectangular coordinates (equivalent to georec_c)
+pub fn geodetic_to_rectangular(
+    longitude: SpiceDouble,
+    latitude: SpiceDouble,
+    altitude: SpiceDouble,
+    re: SpiceDouble,
+    f: SpiceDouble,
+) -> SpiceResult<SpiceVector3> {
+    validate_spheroid(re, f)?;
+    Ok(geodetic_point(longitude, latitude, altitude, re, f))
+}
+
+/// Whether planetographic longitude increases eastward on a body
+///
+/// `BODY<code>_PGR_POSITIVE_LON` ('EAST' or 'WEST') takes precedence. Otherwise
+/// the Earth, Moon and Sun use east longitudes, and other bodies west
+/// longitudes when they rotate prograde (positive prime meridian rate).
+pub(crate) fn planetographic_east_positive(code: SpiceInt) -> SpiceResult<bool> {
+    if crate::kernel_pool::is_pool_initialized() {
+        let name = format!("BODY{}_PGR_POSITIVE_LON", code);
+        let (values, found) = crate::kernel_pool::get_character_pool(&name, 0, 1)?;
+        if found {
+            return match values.first().map(|value| value.trim().to_uppercase()).as_deref() {
+                Some("EAST") => Ok(true),
+                Some("WEST") => Ok(false),
+                other => Err(SpiceError::new(
+                    SpiceErrorType::InvalidKernelData,
+                    format!("{} must be 'EAST' or 'WEST', got {:?}", name, other)
+                )),
+            };
+        }
+    }
+    if matches!(code, 10 | 399 | 301) {
+        return Ok(true);
+    }
+
+    let pm = crate::body_data::body_data(&code.to_string(), "PM")?;
+    match pm.get(1) {
+        Some(&rate) => Ok(rate < 0.0),
+        None => Err(SpiceError::new(
+            SpiceErrorType::InsufficientData,
+            format!("No prime meridian rate for body {}; its planetographic longitude sense is unknown", code)
+        )),
+    }
+}
+
+/// Rectangular to planetographic coordinates (equivalent to recpgr_c)
+///
+/// Returns (longitude, latitude, altitude) in radians and km on the spheroid
+/// with equatorial radius `re` and flattening `f`. Latitude is geodetic and
+/// longitude lies in [0, 2π), measured in the body's positive sense.
+pub fn rectangular_to_planetographic(
+    body: &str,
+    rectan: &SpiceVector3,
+    re: SpiceDouble,
+    f: SpiceDouble,
+) -> SpiceResult<(SpiceDouble, SpiceDouble, SpiceDouble)> {
+    let (east_longitude, latitude, altitude) = rectangular_to_geodetic(rectan, re, f)?;
+    let east = planetographic_east_positive(crate::body_data::body_name_to_code(body)?)?;
+    let longitude = if east { east_longitude } else { -east_longitude };
+    Ok((wrap_two_pi(longitude), latitude, altitude))
+}
+
+/// Planetographic to rectangular coordinates (equivalent to pgrrec_c)
+pub fn planetographic_to_rectangular(
+    body: &str,
+    longitude: SpiceDouble,
+    latitude: SpiceDouble,
+    altitude: SpiceDouble,
+    re: SpiceDouble,
+    f: SpiceDouble,
+) -> SpiceResult<SpiceVector3> {
+    validate_spheroid(re, f)?;
+    let east = planetographic_east_positive(crate::body_data::body_name_to_code(body)?)?;
+    let east_longitude = if east { longitude } else { -longitude };
+    geodetic_to_rectangular(east_longitude, latitude, altitude, re, f)
 }
 
 // ============================================================================
@@ -726,6 +1378,37 @@ mod tests {
         assert_relative_eq!(angles.2, recovered.2, epsilon = 1e-10);
     }
 
+    #[test]
+    fn test_euler_rates_round_trip_through_state_transform() {
+        let angles = [0.4, 0.3, 1.2];
+        let rates = [1e-3, -2e-4, 5e-5];
+        for sequence in [EulerSequence::ZYX, EulerSequence::XYZ, EulerSequence::ZXZ] {
+            let transform = euler_rates_to_state_transform(angles, rates, sequence.clone());
+
+            // The derivative block matches a finite difference of euler_to_matrix
+            let h = 1.0;
+            let at = |t: f64| euler_to_matrix(
+                angles[0] + rates[0] * t, angles[1] + rates[1] * t, angles[2] + rates[2] * t, sequence.clone()
+            ).unwrap();
+            let difference = at(h).subtract(&at(-h)).scale(0.5 / h);
+            for i in 0..3 {
+                for j in 0..3 {
+                    assert_relative_eq!(transform.get(i, j), at(0.0).get(i, j), epsilon = 1e-15);
+                    assert_relative_eq!(transform.get(i + 3, j), difference.get(i, j), epsilon = 1e-9);
+                }
+            }
+
+            let (angles2, rates2) = state_transform_to_euler_rates(&transform, sequence).unwrap();
+            for k in 0..3 {
+                assert_relative_eq!(angles2[k], angles[k], epsilon = 1e-12);
+                assert_relative_eq!(rates2[k], rates[k], epsilon = 1e-15);
+            }
+        }
+
+        let locked = euler_rates_to_state_transform([0.2, 0.0, 0.1], rates, EulerSequence::ZXZ);
+        assert!(state_transform_to_euler_rates(&locked, EulerSequence::ZXZ).is_err());
+    }
+
     #[test]
     fn test_position_transformation() {
         let position = SpiceVector3::new(1.0, 0.0, 0.0);
@@ -780,7 +1463,7 @@ mod tests {
     fn test_earth_rotation() {
         // Test Earth rotation after 6 hours (should be 90 degrees)
         let et = EphemerisTime::new(6.0 * 3600.0); // 6 hours in seconds
-        let matrix = get_j2000_to_earth_fixed_matrix(et).unwrap();
+        let matrix = body_fixed_rotation(399, et.seconds()).unwrap();
         
         // Verify it's a valid rotation matrix
         assert!(is_rotation_matrix(&matrix));
@@ -805,4 +1488,351 @@ mod tests {
         // Test determinant = 1
         assert_relative_eq!(matrix.determinant(), 1.0, epsilon = 1e-12);
     }
+
+    #[test]
+    fn test_latitudinal_batch_matches_scalar() {
+        let points = [
+            SpiceVector3::new(1.0, 1.0, 0.0),
+            SpiceVector3::new(0.0, 0.0, 6356.752),
+            SpiceVector3::new(0.0, 0.0, 0.0),
+            SpiceVector3::new(-3000.0, -4000.0, -1200.0),
+        ];
+        let batch = rectangular_to_latitudinal_batch(&points);
+        assert_eq!(batch.len(), points.len());
+        for (point, coords) in points.iter().zip(&batch) {
+            assert_eq!(*coords, rectangular_to_latitudinal(point));
+        }
+        assert_eq!(batch[1], (6356.752, 0.0, core::f64::consts::FRAC_PI_2));
+        assert_eq!(batch[2], (0.0, 0.0, 0.0));
+
+        let back = latitudinal_to_rectangular_batch(&batch);
+        for ((point, coords), rect) in points.iter().zip(&batch).zip(&back) {
+            assert_eq!(*rect, latitudinal_to_rectangular(coords.0, coords.1, coords.2));
+            assert_relative_eq!((*rect - *point).magnitude(), 0.0, epsilon = 1e-9);
+        }
+
+        assert!(rectangular_to_latitudinal_batch(&[]).is_empty());
+        assert!(latitudinal_to_rectangular_batch(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_two_vectors_to_matrix() {
+        let primary = SpiceVector3::new(1.0, 2.0, 3.0);
+        let secondary = SpiceVector3::new(-2.0, 0.5, 1.0);
+        let matrix = two_vectors_to_matrix(&primary, RotationAxis::X, &secondary, RotationAxis::Y).unwrap();
+        assert!(is_rotation_matrix(&matrix));
+
+        // +X along the primary, the secondary in the XY plane with positive Y
+        let x = matrix.multiply_vector(&primary.unit().unwrap());
+        assert_relative_eq!(x.x(), 1.0, epsilon = 1e-15);
+        let in_plane = matrix.multiply_vector(&secondary);
+        assert_relative_eq!(in_plane.z(), 0.0, epsilon = 1e-15);
+        assert!(in_plane.y() > 0.0);
+
+        // Non-cyclic pairing: primary on Z, secondary in the ZX plane
+        let matrix = two_vectors_to_matrix(&primary, RotationAxis::Z, &secondary, RotationAxis::X).unwrap();
+        assert!(is_rotation_matrix(&matrix));
+        assert_relative_eq!(matrix.multiply_vector(&secondary).y(), 0.0, epsilon = 1e-15);
+        let matrix = two_vectors_to_matrix(&primary, RotationAxis::Y, &secondary, RotationAxis::X).unwrap();
+        assert!(is_rotation_matrix(&matrix));
+        assert!(matrix.multiply_vector(&secondary).x() > 0.0);
+
+        assert!(two_vectors_to_matrix(&primary, RotationAxis::X, &primary.scale(-2.0), RotationAxis::Y).is_err());
+        assert!(two_vectors_to_matrix(&primary, RotationAxis::X, &secondary, RotationAxis::X).is_err());
+    }
+
+    #[test]
+    fn test_refurnished_fk_changes_tk_frame_transform() {
+        use crate::kernel_system::{initialize_kernel_system, initialize_kernel_pool, furnish_kernel_from_bytes};
+
+        let _lock = crate::global_state::test_lock();
+        let fk = |matrix: &str| format!(
+            "\\begindata\n\
+             FRAME_FK_RELOAD_TEST = -1999001\n\
+             FRAME_-1999001_CLASS = 4\n\
+             TKFRAME_-1999001_RELATIVE = 'J2000'\n\
+             TKFRAME_-1999001_SPEC = 'MATRIX'\n\
+             TKFRAME_-1999001_MATRIX = ( {} )\n",
+            matrix
+        ).into_bytes();
+        let et = EphemerisTime::new(0.0);
+        let x_axis = SpiceVector3::new(1.0, 0.0, 0.0);
+
+        initialize_kernel_system().unwrap();
+        initialize_kernel_pool().unwrap();
+
+        // Frame X axis along J2000 +Y (columns of the rotation in order)
+        furnish_kernel_from_bytes(fk("0, 1, 0, -1, 0, 0, 0, 0, 1"), "/fk/reload_v1.tf").unwrap();
+        let rotated = get_position_transformation("FK_RELOAD_TEST", "J2000", et).unwrap();
+        let image = rotated.multiply_vector(&x_axis);
+        assert_relative_eq!(image.y(), 1.0, epsilon = 1e-12);
+        let back = get_position_transformation("J2000", "FK_RELOAD_TEST", et).unwrap();
+        assert_relative_eq!(back.multiply_vector(&image).x(), 1.0, epsilon = 1e-12);
+
+        // A second FK redefines the matrix; the cached definition must not survive
+        furnish_kernel_from_bytes(fk("1, 0, 0, 0, 1, 0, 0, 0, 1"), "/fk/reload_v2.tf").unwrap();
+        let identity = get_position_transformation("FK_RELOAD_TEST", "J2000", et).unwrap();
+        let image = identity.multiply_vector(&x_axis);
+        assert_relative_eq!(image.x(), 1.0, epsilon = 1e-12);
+        assert_relative_eq!(image.y(), 0.0, epsilon = 1e-12);
+    }
+
+    #[test]
+    fn test_frame_names_and_ids_resolve_both_ways() {
+        use crate::kernel_system::{initialize_kernel_system, initialize_kernel_pool, furnish_kernel_from_bytes};
+
+        let _lock = crate::global_state::test_lock();
+        assert_eq!(frame_name_to_id("j2000").unwrap(), 1);
+        assert_eq!(frame_name_to_id("ECLIPJ2000").unwrap(), 17);
+        assert_eq!(frame_id_to_name(13).unwrap(), "GALACTIC");
+        assert_eq!(ReferenceFrame::IAU_MOON.frame_id(), 10020);
+        assert_eq!(frame_id_to_name(ReferenceFrame::B1950.frame_id()).unwrap(), "B1950");
+        assert_eq!(ReferenceFrame::ICRF.frame_id(), 1);
+        assert_eq!(ReferenceFrame::FK5.frame_id(), 1);
+        for frame in [
+            ReferenceFrame::FK4, ReferenceFrame::ITRF93, ReferenceFrame::IAU_SUN, ReferenceFrame::IAU_EARTH,
+            ReferenceFrame::IAU_MARS, ReferenceFrame::IAU_JUPITER, ReferenceFrame::IAU_SATURN,
+        ] {
+            assert_eq!(frame_name_to_id(&frame.name()).unwrap(), frame.frame_id());
+        }
+
+        initialize_kernel_system().unwrap();
+        initialize_kernel_pool().unwrap();
+        let fk = "\\begindata\n\
+                  FRAME_NAME_ID_TEST = -1999002\n\
+                  FRAME_-1999002_NAME = 'NAME_ID_TEST'\n\
+                  FRAME_-1999002_CLASS = 4\n";
+        furnish_kernel_from_bytes(fk.as_bytes().to_vec(), "/fk/name_id_test.tf").unwrap();
+
+        assert_eq!(frame_name_to_id("name_id_test").unwrap(), -1999002);
+        assert_eq!(frame_id_to_name(-1999002).unwrap(), "NAME_ID_TEST");
+        assert_eq!(ReferenceFrame::from_str("NAME_ID_TEST").unwrap().frame_id(), -1999002);
+
+        assert_eq!(frame_name_to_id("NO_SUCH_FRAME").unwrap_err().error_type, SpiceErrorType::InvalidFrame);
+        assert!(frame_id_to_name(-1999003).is_err());
+    }
+
+    #[test]
+    fn test_ra_dec_of_equinox_and_pole() {
+        let (range, ra, dec) = rectangular_to_ra_dec(&SpiceVector3::new(2.0, 0.0, 0.0));
+        assert_eq!((range, ra, dec), (2.0, 0.0, 0.0));
+
+        let (range, ra, dec) = rectangular_to_ra_dec(&SpiceVector3::new(0.0, 0.0, 5.0));
+        assert_eq!(range, 5.0);
+        assert_eq!(ra, 0.0);
+        assert_relative_eq!(dec, constants::HALF_PI, epsilon = 1e-15);
+
+        // Directions south of +X wrap to just under 2π rather than going negative
+        let (_, ra, dec) = rectangular_to_ra_dec(&SpiceVector3::new(1.0, -1.0, -1.0));
+        assert_relative_eq!(ra, 1.75 * constants::PI, epsilon = 1e-15);
+        assert!(dec < 0.0);
+        let (_, ra, _) = rectangular_to_ra_dec(&SpiceVector3::new(1.0, -1e-300, 0.0));
+        assert!((0.0..constants::TWO_PI).contains(&ra));
+
+        let v = ra_dec_to_rectangular(3.0, 4.0, -0.5);
+        let (range, ra, dec) = rectangular_to_ra_dec(&v);
+        assert_relative_eq!(range, 3.0, epsilon = 1e-15);
+        assert_relative_eq!(ra, 4.0, epsilon = 1e-14);
+        assert_relative_eq!(dec, -0.5, epsilon = 1e-15);
+    }
+
+    #[test]
+    fn test_geodetic_coordinates_on_wgs84() {
+        let (re, f) = (6378.137, 1.0 / 298.257223563);
+        let polar = re * (1.0 - f);
+
+        // On the equator altitude is distance beyond the equatorial radius
+        let (lon, lat, alt) = rectangular_to_geodetic(&SpiceVector3::new(0.0, -(re + 0.5), 0.0), re, f).unwrap();
+        assert_relative_eq!(lon, -constants::HALF_PI, epsilon = 1e-15);
+        assert_relative_eq!(lat, 0.0, epsilon = 1e-15);
+        assert!((alt - 0.5).abs() < 1e-6);
+
+        // Over the pole and just off it, altitude is relative to the polar radius
+        let (_, lat, alt) = rectangular_to_geodetic(&SpiceVector3::new(0.0, 0.0, -(polar + 20.0)), re, f).unwrap();
+        assert_relative_eq!(lat, -constants::HALF_PI, epsilon = 1e-15);
+        assert!((alt - 20.0).abs() < 1e-6);
+        for (lat, alt) in [(89.9999_f64.to_radians(), 3.0), (1.5, 0.0), (-1.2, 400.0), (0.3, -5.0)] {
+            let point = geodetic_to_rectangular(2.5, lat, alt, re, f).unwrap();
+            let (lon2, lat2, alt2) = rectangular_to_geodetic(&point, re, f).unwrap();
+            assert_relative_eq!(lon2, 2.5, epsilon = 1e-12);
+            assert_relative_eq!(lat2, lat, epsilon = 1e-12);
+            assert!((alt2 - alt).abs() < 1e-6);
+        }
+
+        // The surface point at the reported latitude is `alt` below along the normal
+        let point = SpiceVector3::new(-2500.0, 1200.0, 5800.0);
+        let (lon, lat, alt) = rectangular_to_geodetic(&point, re, f).unwrap();
+        let foot = geodetic_to_rectangular(lon, lat, 0.0, re, f).unwrap();
+        assert!(((point - foot).magnitude() - alt).abs() < 1e-6);
+
+        assert!(geodetic_to_rectangular(0.0, 0.0, 0.0, re, 1.0).is_err());
+    }
+
+    #[test]
+    fn test_planetographic_coordinates_on_oblate_body() {
+        use crate::kernel_system::{initialize_kernel_system, initialize_kernel_pool, furnish_kernel_from_bytes};
+
+        let _lock = crate::global_state::test_lock();
+        let (re, f) = (3396.19, 0.005_886_007_555_525_457);
+        // Mars rotates prograde, so its planetographic longitude runs west
+        let (lon, lat, alt) = rectangular_to_planetographic("MARS", &SpiceVector3::new(0.0, re + 10.0, 0.0), re, f).unwrap();
+        assert_relative_eq!(lon, 1.5 * constants::PI, epsilon = 1e-12);
+        assert_relative_eq!(lat, 0.0, epsilon = 1e-12);
+        assert_relative_eq!(alt, 10.0, epsilon = 1e-9);
+
+        // On the surface, geodetic latitude exceeds geocentric latitude:
+        // tan(geodetic) = tan(geocentric) / (1 - f)²
+        let surface = planetographic_to_rectangular("MARS", 0.7, 0.6, 0.0, re, f).unwrap();
+        let (_, _, geocentric) = rectangular_to_latitudinal(&surface);
+        assert!(geocentric < 0.6);
+        assert_relative_eq!(0.6_f64.tan(), geocentric.tan() / ((1.0 - f) * (1.0 - f)), epsilon = 1e-12);
+        let (_, east_longitude, _) = rectangular_to_latitudinal(&surface);
+        assert_relative_eq!(east_longitude, -0.7, epsilon = 1e-12);
+
+        // Round trip, including high latitudes and points below the surface
+        for (lon, lat, alt) in [(0.7, 0.6, 0.0), (4.0, -1.5, 250.0), (2.0, 1.5707, -100.0)] {
+            let point = planetographic_to_rectangular("MARS", lon, lat, alt, re, f).unwrap();
+            let (lon2, lat2, alt2) = rectangular_to_planetographic("MARS", &point, re, f).unwrap();
+            assert_relative_eq!(lon2, lon, epsilon = 1e-10);
+            assert_relative_eq!(lat2, lat, epsilon = 1e-12);
+            assert_relative_eq!(alt2, alt, epsilon = 1e-8);
+        }
+
+        // The Earth uses east longitudes; a kernel variable overrides the default
+        let (lon, _, _) = rectangular_to_planetographic("EARTH", &SpiceVector3::new(0.0, 7000.0, 0.0), 6378.137, 1.0 / 298.257).unwrap();
+        assert_relative_eq!(lon, constants::HALF_PI, epsilon = 1e-12);
+        initialize_kernel_system().unwrap();
+        initialize_kernel_pool().unwrap();
+        let pck = "\\begindata\n\
+                   BODY-1999499_PGR_POSITIVE_LON = 'EAST'\n\
+                   BODY299_PM = ( 160.20 -1.4813688 0.0 )\n";
+        furnish_kernel_from_bytes(pck.as_bytes().to_vec(), "/pck/pgr_east.tpc").unwrap();
+        let (lon, _, _) = rectangular_to_planetographic("-1999499", &SpiceVector3::new(0.0, 7000.0, 0.0), re, f).unwrap();
+        assert_relative_eq!(lon, constants::HALF_PI, epsilon = 1e-12);
+
+        // Venus rotates retrograde, so its planetographic longitude runs east
+        let (lon, _, _) = rectangular_to_planetographic("VENUS", &SpiceVector3::new(0.0, 7000.0, 0.0), 6051.8, 0.0).unwrap();
+        assert_relative_eq!(lon, constants::HALF_PI, epsilon = 1e-12);
+        assert!(planetographic_east_positive(299).unwrap());
+        assert!(!planetographic_east_positive(499).unwrap());
+
+        assert!(rectangular_to_planetographic("MARS", &surface, 0.0, f).is_err());
+    }
+
+    #[test]
+    fn test_position_transform_round_trips_through_body_fixed_frame() {
+        let et = 8.0e8;
+        let vector = SpiceVector3::new(6378.0, -1200.0, 4500.0);
+
+        let to_earth = position_transform("J2000", "IAU_EARTH", et).unwrap();
+        let from_earth = position_transform("iau_earth", "J2000", et).unwrap();
+        let fixed = to_earth.multiply_vector(&vector);
+        assert!((fixed - vector).magnitude() > 1.0);
+        assert!((from_earth.multiply_vector(&fixed) - vector).magnitude() < 1e-9);
+
+        // Body-fixed to body-fixed chains through J2000
+        let earth_to_moon = position_transform("IAU_EARTH", "IAU_MOON", et).unwrap();
+        let direct = position_transform("J2000", "IAU_MOON", et).unwrap().multiply_vector(&vector);
+        assert!((earth_to_moon.multiply_vector(&fixed) - direct).magnitude() < 1e-9);
+
+        // The ecliptic pole is the ECLIPJ2000 Z axis
+        let obliquity = J2000_OBLIQUITY;
+        let pole = SpiceVector3::new(0.0, -obliquity.sin(), obliquity.cos());
+        let ecliptic = position_transform("J2000", "ECLIPJ2000", et).unwrap().multiply_vector(&pole);
+        assert_relative_eq!(ecliptic.z(), 1.0, epsilon = 1e-15);
+
+        assert!(position_transform("J2000", "NO_SUCH_FRAME", et).is_err());
+        assert!(position_transform("GALACTIC", "J2000", et).is_err());
+    }
+
+    #[test]
+    fn test_body_fixed_state_transform_adds_rotation_term() {
+        let degrees = constants::RADIANS_PER_DEGREE;
+        let (ra, dec, w) = (40.0 * degrees, 60.0 * degrees, 10.0 * degrees);
+        let spin_rate = 1e-4;
+        let state = rotation_model_state([ra, dec, w], [0.0, 0.0, spin_rate]).unwrap();
+
+        // A point at rest in J2000 seen from the frame spinning about its pole
+        let pole = SpiceVector3::new(dec.cos() * ra.cos(), dec.cos() * ra.sin(), dec.sin());
+        let omega = pole.scale(spin_rate);
+        let r = SpiceVector3::new(3000.0, -1500.0, 700.0);
+        let fixed = state.multiply_vector(&SpiceVector6::new([r.x(), r.y(), r.z(), 0.0, 0.0, 0.0]));
+
+        let mut rotation = SpiceMatrix3x3::zeros();
+        for i in 0..3 {
+            for j in 0..3 {
+                rotation.set(i, j, state.get(i, j));
+            }
+        }
+        assert!(is_rotation_matrix(&rotation));
+        // The pole is the frame's +Z axis
+        assert_relative_eq!(rotation.multiply_vector(&pole).z(), 1.0, epsilon = 1e-14);
+
+        let expected = rotation.multiply_vector(&omega.cross(&r).scale(-1.0));
+        for i in 0..3 {
+            assert_relative_eq!(fixed.get(i + 3), expected.0[i], epsilon = 1e-12);
+        }
+
+        // Pole motion terms against a central difference of the rotation
+        let rates = [2e-9, -1e-9, spin_rate];
+        let state = rotation_model_state([ra, dec, w], rates).unwrap();
+        let h = 1.0;
+        let at = |t: f64| rotation_model_state([ra + rates[0] * t, dec + rates[1] * t, w + rates[2] * t], rates).unwrap();
+        let (after, before) = (at(h), at(-h));
+        for i in 0..3 {
+            for j in 0..3 {
+                let numeric = (after.get(i, j) - before.get(i, j)) / (2.0 * h);
+                assert_relative_eq!(state.get(i + 3, j), numeric, epsilon = 1e-12);
+            }
+        }
+
+        // Inverting and composing gets back to the identity
+        let round_trip = invert_state_transformation(&state).multiply(&state);
+        for i in 0..6 {
+            for j in 0..6 {
+                assert_relative_eq!(round_trip.get(i, j), if i == j { 1.0 } else { 0.0 }, epsilon = 1e-15);
+            }
+        }
+    }
+
+    #[test]
+    fn test_earth_orientation_at_j2000() {
+        // IAU Earth model: RA = -0.641 T, DEC = 90 - 0.557 T, W = 190.147 + 360.9856235 d
+        let model = PckRotationModel {
+            pole_ra: vec![0.0, -0.641],
+            pole_dec: vec![90.0, -0.557],
+            pm: vec![190.147, 360.9856235],
+            ..Default::default()
+        };
+        let degrees = constants::RADIANS_PER_DEGREE;
+        let (angles, rates) = model.evaluate(0.0);
+        assert_relative_eq!(angles[0], 0.0);
+        assert_relative_eq!(angles[1], 90.0 * degrees, epsilon = 1e-15);
+        assert_relative_eq!(angles[2], 190.147 * degrees, epsilon = 1e-15);
+        assert_relative_eq!(rates[2], 360.9856235 * degrees / 86400.0, epsilon = 1e-18);
+        assert_relative_eq!(rates[0], -0.641 * degrees / (36525.0 * 86400.0), epsilon = 1e-24);
+
+        // The pole is J2000 +Z and the prime meridian lies W east of the node
+        // at RA + 90 degrees
+        let state = rotation_model_state(angles, rates).unwrap();
+        let meridian = 90.0 * degrees + angles[2];
+        for j in 0..3 {
+            let expected_x = [meridian.cos(), meridian.sin(), 0.0][j];
+            assert_relative_eq!(state.get(0, j), expected_x, epsilon = 1e-15);
+            assert_relative_eq!(state.get(2, j), [0.0, 0.0, 1.0][j], epsilon = 1e-15);
+        }
+
+        // One nutation/precession term of 1 degree at θ = 30 degrees
+        let nutating = PckRotationModel {
+            nut_prec_angles: vec![30.0, 0.0],
+            nut_prec_ra: vec![1.0],
+            nut_prec_dec: vec![1.0],
+            nut_prec_pm: vec![-1.0],
+            ..model
+        };
+        let (shifted, _) = nutating.evaluate(0.0);
+        assert_relative_eq!(shifted[0], 0.5 * degrees, epsilon = 1e-15);
+        assert_relative_eq!(shifted[1], (90.0 + 0.75_f64.sqrt()) * degrees, epsilon = 1e-15);
+        assert_relative_eq!(shifted[2], 189.647 * degrees, epsilon = 1e-14);
+    }
 }
@@ -0,0 +1,339 @@
+//! Frame Kernel (FK) Frame Definitions for RustSPICE
+//!
+//! Text-kernel (TK) frames are fixed offsets from another frame, defined by
+//! kernel pool assignments such as:
+//!
+//! ```text
+//! FRAME_MY_INSTRUMENT      = -1000001
+//! FRAME_-1000001_CLASS     = 4
+//! TKFRAME_-1000001_RELATIVE = 'J2000'
+//! TKFRAME_-1000001_SPEC    = 'MATRIX'
+//! TKFRAME_-1000001_MATRIX  = ( 1 0 0  0 1 0  0 0 1 )
+//! ```
+//!
+//! CK frames (class 3) take their orientation relative to a parent frame
+//! from CK pointing, read at the encoded SCLK of the frame's clock:
+//!
+//! ```text
+//! FRAME_MY_SPACECRAFT      = -1000000
+//! FRAME_-1000000_CLASS     = 3
+//! FRAME_-1000000_CLASS_ID  = -1000000
+//! CK_-1000000_SCLK         = -1
+//! ```
+//!
+//! `kernel_frame_chain` walks a kernel frame's parents, TK and CK alike,
+//! until it reaches a frame with no kernel definition.
+//!
+//! TK definitions are cached after their first use. The cache registers a kernel
+//! pool watch on every variable it read and is cleared whenever one of them
+//! changes, so reloading an FK takes effect on the next transformation.
+
+#[cfg(not(feature = "std"))]
+use alloc::{string::{String, ToString}, vec, vec::Vec, format, collections::BTreeMap};
+#[cfg(feature = "std")]
+use std::{string::String, vec::Vec, format, collections::BTreeMap};
+
+use crate::foundation::{SpiceInt, SpiceMatrix3x3, EphemerisTime};
+use crate::error_handling::{SpiceResult, SpiceError, SpiceErrorType};
+use crate::global_state::GlobalState;
+use crate::kernel_pool;
+use crate::ck_reader::ck_find_frame_rotation;
+
+/// Frame class code of CK frames in `FRAME_<id>_CLASS`
+const CK_FRAME_CLASS: SpiceInt = 3;
+
+/// Frame class code of TK frames in `FRAME_<id>_CLASS`
+const TK_FRAME_CLASS: SpiceInt = 4;
+
+/// Kernel pool watch agent for the TK frame cache
+const FRAME_CACHE_AGENT: &str = "COORDINATES_TK_FRAMES";
+
+/// A fixed-offset frame defined in a frame kernel
+#[derive(Debug, Clone, PartialEq)]
+pub struct TkFrame {
+    /// Frame ID from `FRAME_<name>`
+    pub id: SpiceInt,
+    /// Upper-case frame name
+    pub name: String,
+    /// Name of the frame this one is fixed relative to
+    pub relative: String,
+    /// Rotation taking vectors in this frame to the relative frame
+    pub matrix: SpiceMatrix3x3,
+}
+
+/// TK frames read so far, by upper-case name
+static TK_FRAME_CACHE: GlobalState<BTreeMap<String, TkFrame>> = GlobalState::new();
+
+/// Drop every cached TK frame; a freshly initialized pool flags no watch to clear them
+#[cfg(test)]
+pub(crate) fn reset_tk_frame_cache() {
+    TK_FRAME_CACHE.write(|cache| *cache = None);
+}
+
+/// Look up a TK frame definition in the kernel pool
+///
+/// Returns `None` when `name` is not an FK-defined frame or no pool exists.
+pub fn tk_frame(name: &str) -> SpiceResult<Option<TkFrame>> {
+    if !kernel_pool::is_pool_initialized() {
+        return Ok(None);
+    }
+
+    // Any change to a watched frame variable discards every cached frame, since
+    // several FKs may assign the same variables
+    if kernel_pool::poll_watch(FRAME_CACHE_AGENT)? {
+        TK_FRAME_CACHE.write(|cache| {
+            if let Some(cache) = cache {
+                cache.clear();
+            }
+        });
+    }
+
+    let name = name.to_uppercase();
+    if let Some(frame) = TK_FRAME_CACHE.read(|cache| cache.and_then(|cache| cache.get(&name).cloned())) {
+        return Ok(Some(frame));
+    }
+
+    let id_variable = format!("FRAME_{}", name);
+    let (ids, found) = kernel_pool::get_integer_pool(&id_variable, 0, 1)?;
+    if !found || ids.is_empty() {
+        return Ok(None);
+    }
+    let id = ids[0];
+    if frame_class(id)? == Some(CK_FRAME_CLASS) {
+        return Ok(None);
+    }
+
+    // Watch before reading, so an update between the two is not lost
+    kernel_pool::set_watch(FRAME_CACHE_AGENT, &frame_variables(&id_variable, id))?;
+    kernel_pool::poll_watch(FRAME_CACHE_AGENT)?;
+
+    let frame = read_tk_frame(&name, id)?;
+    TK_FRAME_CACHE.write(|cache| {
+        cache.get_or_insert_with(BTreeMap::new).insert(name, frame.clone());
+    });
+    Ok(Some(frame))
+}
+
+/// Class code of frame `id` from `FRAME_<id>_CLASS`, if assigned
+fn frame_class(id: SpiceInt) -> SpiceResult<Option<SpiceInt>> {
+    let (class, found) = kernel_pool::get_integer_pool(&format!("FRAME_{}_CLASS", id), 0, 1)?;
+    Ok(class.first().copied().filter(|_| found))
+}
+
+/// Rotation from a kernel-defined frame to the first frame up its chain of
+/// parents that is not kernel-defined, together with that frame's name
+///
+/// TK frames contribute their fixed matrix and CK frames the transpose of
+/// their C-matrix at `et`. Returns `None` when `frame` itself has no kernel
+/// definition; a chain that returns to a frame already visited is an error.
+pub fn kernel_frame_chain(frame: &str, et: EphemerisTime) -> SpiceResult<Option<(SpiceMatrix3x3, String)>> {
+    resolve_chain(frame, |name| {
+        if let Some(tk) = tk_frame(name)? {
+            return Ok(Some((tk.relative, tk.matrix)));
+        }
+        ck_frame_link(name, et)
+    })
+}
+
+/// Compose the frame-to-parent rotations given by `link` from `frame` up to
+/// the first frame without a parent
+fn resolve_chain<F>(frame: &str, mut link: F) -> SpiceResult<Option<(SpiceMatrix3x3, String)>>
+where
+    F: FnMut(&str) -> SpiceResult<Option<(String, SpiceMatrix3x3)>>,
+{
+    let mut name = frame.to_uppercase();
+    let mut rotation = SpiceMatrix3x3::identity();
+    let mut visited: Vec<String> = Vec::new();
+
+    while let Some((parent, matrix)) = link(&name)? {
+        visited.push(name);
+        rotation = matrix.multiply(&rotation);
+        name = parent.to_uppercase();
+        if visited.contains(&name) {
+            return Err(SpiceError::new(
+                SpiceErrorType::InvalidFrame,
+                format!("Frame chain from {} loops back to {}: {}", frame, name, visited.join(" -> "))
+            ));
+        }
+    }
+
+    Ok(if visited.is_empty() { None } else { Some((rotation, name)) })
+}
+
+/// Parent frame and frame-to-parent rotation of a CK frame at `et`, or `None`
+/// when `name` is not a CK frame
+///
+/// The CK ID is `FRAME_<id>_CLASS_ID`, defaulting to the frame ID as in
+/// CSPICE; `ck_find_frame_rotation` picks the clock.
+fn ck_frame_link(name: &str, et: EphemerisTime) -> SpiceResult<Option<(String, SpiceMatrix3x3)>> {
+    if !kernel_pool::is_pool_initialized() {
+        return Ok(None);
+    }
+    let (ids, found) = kernel_pool::get_integer_pool(&format!("FRAME_{}", name.to_uppercase()), 0, 1)?;
+    let id = match ids.first() {
+        Some(&id) if found => id,
+        _ => return Ok(None),
+    };
+    if frame_class(id)? != Some(CK_FRAME_CLASS) {
+        return Ok(None);
+    }
+
+    let (class_ids, found) = kernel_pool::get_integer_pool(&format!("FRAME_{}_CLASS_ID", id), 0, 1)?;
+    let ck_id = class_ids.first().copied().filter(|_| found).unwrap_or(id);
+    let (rotation, base, found) = ck_find_frame_rotation(ck_id, et.seconds())?;
+    if !found {
+        return Err(SpiceError::new(
+            SpiceErrorType::InsufficientData,
+            format!("No CK pointing for frame {} (CK ID {}) at ET {}", name, ck_id, et.seconds())
+        ));
+    }
+
+    Ok(Some((super::frame_id_to_name(base)?, rotation)))
+}
+
+/// Pool variables that make up the definition of frame `id`
+fn frame_variables(id_variable: &str, id: SpiceInt) -> Vec<String> {
+    let mut vars = vec![id_variable.to_string()];
+    vars.extend(["CLASS", "NAME", "CENTER", "CLASS_ID"].iter().map(|item| format!("FRAME_{}_{}", id, item)));
+    vars.extend(["RELATIVE", "SPEC", "MATRIX"].iter().map(|item| format!("TKFRAME_{}_{}", id, item)));
+    vars
+}
+
+/// Read the definition of TK frame `id` from the kernel pool
+fn read_tk_frame(name: &str, id: SpiceInt) -> SpiceResult<TkFrame> {
+    let (class, _) = kernel_pool::get_integer_pool(&format!("FRAME_{}_CLASS", id), 0, 1)?;
+    if class.first() != Some(&TK_FRAME_CLASS) {
+        return Err(SpiceError::new(
+            SpiceErrorType::InvalidFrame,
+            format!("Frame {} ({}) is not a TK frame; FRAME_{}_CLASS is {:?}", name, id, id, class)
+        ));
+    }
+
+    let relative = required_string(&format!("TKFRAME_{}_RELATIVE", id))?.to_uppercase();
+    if relative == name {
+        return Err(SpiceError::new(
+            SpiceErrorType::InvalidFrame,
+            format!("TK frame {} is defined relative to itself", name)
+        ));
+    }
+
+    let spec = required_string(&format!("TKFRAME_{}_SPEC", id))?;
+    if !spec.eq_ignore_ascii_case("MATRIX") {
+        return Err(SpiceError::new(
+            SpiceErrorType::InvalidFrame,
+            format!("TK frame {} uses unsupported specification '{}'", name, spec)
+        ));
+    }
+
+    // The matrix is stored in column order, as in Fortran
+    let m = kernel_pool::get_required_doubles(&format!("TKFRAME_{}_MATRIX", id), 9)?;
+    let matrix = SpiceMatrix3x3::new([
+        [m[0], m[3], m[6]],
+        [m[1], m[4], m[7]],
+        [m[2], m[5], m[8]],
+    ]);
+
+    Ok(TkFrame { id, name: name.to_string(), relative, matrix })
+}
+
+/// The first value of a character pool variable, which must exist
+fn required_string(variable: &str) -> SpiceResult<String> {
+    let (values, found) = kernel_pool::get_character_pool(variable, 0, 1)?;
+    values.into_iter().next().filter(|_| found).ok_or_else(|| SpiceError::new(
+        SpiceErrorType::InsufficientData,
+        format!("Kernel variable {} is not defined", variable)
+    ))
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// CK written by CSPICE ckw03_c: TEST_BUS (-82000) turns about the
+    /// (1, 2, 2) axis by 0.2 + 1e-5 rad per tick over ticks 0 to 120000
+    pub(crate) const ROTATING_BUS_CK: &[u8] = include_bytes!("../../kernels/ck/rotating_bus.bc");
+
+    /// Clock -82 at 1000 ticks per TDB second from ET 1e8, and a camera frame
+    /// mounted on the CK-driven bus with its Z axis along the bus -X axis
+    pub(crate) const ROTATING_BUS_KERNELS: &str = r"
+\begindata
+SCLK_DATA_TYPE_82 = ( 1 )
+SCLK01_TIME_SYSTEM_82 = ( 1 )
+SCLK01_N_FIELDS_82 = ( 2 )
+SCLK01_MODULI_82 = ( 4294967296, 1000 )
+SCLK01_OFFSETS_82 = ( 0, 0 )
+SCLK01_OUTPUT_DELIM_82 = ( 1 )
+SCLK_PARTITION_START_82 = ( 0.0 )
+SCLK_PARTITION_END_82 = ( 4.294967296D12 )
+SCLK01_COEFFICIENTS_82 = ( 0.0, 1.0D8, 1.0 )
+FRAME_TEST_BUS = -82000
+FRAME_-82000_NAME = 'TEST_BUS'
+FRAME_-82000_CLASS = 3
+FRAME_-82000_CLASS_ID = -82000
+FRAME_-82000_CENTER = -82
+CK_-82000_SCLK = -82
+FRAME_TEST_CAMERA = -82100
+FRAME_-82100_NAME = 'TEST_CAMERA'
+FRAME_-82100_CLASS = 4
+FRAME_-82100_CLASS_ID = -82100
+FRAME_-82100_CENTER = -82
+TKFRAME_-82100_RELATIVE = 'TEST_BUS'
+TKFRAME_-82100_SPEC = 'MATRIX'
+TKFRAME_-82100_MATRIX = ( 0.0, 0.0, -1.0,
+                          0.0, 1.0,  0.0,
+                          1.0, 0.0,  0.0 )
+\begintext
+";
+
+    #[test]
+    fn test_chain_through_ck_driven_spacecraft_frame() {
+        let kernels = crate::kernel_system::IsolatedKernels::new();
+        kernel_pool::load_text_kernel(ROTATING_BUS_KERNELS).unwrap();
+        kernels.load_ck("rotating_bus.bc", ROTATING_BUS_CK);
+
+        // pxform_c("TEST_CAMERA", "J2000", et) with the same kernels
+        let expected = [
+            (1.0e8 + 15.0, [
+                [-2.42071268781994420e-01, -2.15125807825273990e-01, 9.46109078086559085e-01],
+                [8.73538081950966105e-02, 9.66318173804099345e-01, 2.42071268781994448e-01],
+                [-9.66318173804099234e-01, 1.41244730108537553e-01, -2.15125807825273907e-01],
+            ]),
+            (1.0e8 + 47.25, [
+                [-4.63680898366801375e-01, -3.66910566406347283e-01, 8.06459336079091926e-01],
+                [1.10877534232833128e-01, 8.79037085049432343e-01, 4.63680898366801375e-01],
+                [-8.79037085049432343e-01, 3.04418198153741215e-01, -3.66910566406347172e-01],
+            ]),
+            (1.0e8 + 119.0, [
+                [-8.38064325946851207e-01, -4.73536760468184270e-01, 2.70944869042666237e-01],
+                [-3.66272938749080609e-02, 5.44340543151666356e-01, 8.38064325946851207e-01],
+                [-5.44340543151666356e-01, 6.92427837082425723e-01, -4.73536760468184159e-01],
+            ]),
+        ];
+        for (et, matrix) in expected {
+            let (rotation, base) = kernel_frame_chain("test_camera", EphemerisTime::new(et)).unwrap().unwrap();
+            assert_eq!(base, "J2000");
+            for i in 0..3 {
+                for j in 0..3 {
+                    assert!((rotation.get(i, j) - matrix[i][j]).abs() < 1e-12, "element ({}, {}) at ET {}", i, j, et);
+                }
+            }
+        }
+
+        // The CK covers ticks 0 to 120000 only
+        let error = kernel_frame_chain("TEST_CAMERA", EphemerisTime::new(1.0e8 + 121.0)).unwrap_err();
+        assert_eq!(error.error_type, SpiceErrorType::InsufficientData);
+        assert!(kernel_frame_chain("J2000", EphemerisTime::new(1.0e8)).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_chain_that_loops_is_an_error() {
+        let cyclic = |name: &str| Ok(match name {
+            "A" => Some(("B".to_string(), SpiceMatrix3x3::identity())),
+            "B" => Some(("C".to_string(), SpiceMatrix3x3::identity())),
+            "C" => Some(("A".to_string(), SpiceMatrix3x3::identity())),
+            _ => None,
+        });
+        let error = resolve_chain("A", cyclic).unwrap_err();
+        assert_eq!(error.error_type, SpiceErrorType::InvalidFrame);
+    }
+}
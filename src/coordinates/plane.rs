@@ -0,0 +1,143 @@
+//! Plane Geometry for RustSPICE
+//!
+//! This module provides equivalency to the CSPICE plane routines:
+//! - nvc2pl_c → Plane::from_normal_and_constant() - Plane from normal and constant
+//! - nvp2pl_c → Plane::from_normal_and_point() - Plane from normal and a point
+//! - pl2nvc_c → Plane::normal_and_constant() - Unit normal and constant of a plane
+//! - inrypl_c → Plane::intersect_ray() - Intersection of a ray with a plane
+//!
+//! A plane is the set of points x with normal · x = constant. As in CSPICE
+//! the normal is stored as a unit vector and the constant is non-negative,
+//! so the constant is the distance of the plane from the origin.
+
+#[cfg(not(feature = "std"))]
+use alloc::format;
+#[cfg(feature = "std")]
+use std::format;
+
+use crate::foundation::{SpiceDouble, SpiceVector3};
+use crate::error_handling::{SpiceResult, SpiceError, SpiceErrorType};
+
+/// A plane in three dimensions, stored as a unit normal and a constant
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Plane {
+    normal: SpiceVector3,
+    constant: SpiceDouble,
+}
+
+impl Plane {
+    /// Plane of points x with normal · x = constant (equivalent to nvc2pl_c)
+    ///
+    /// The normal need not be a unit vector; the constant is rescaled by its
+    /// length.
+    pub fn from_normal_and_constant(normal: &SpiceVector3, constant: SpiceDouble) -> SpiceResult<Self> {
+        let (unit, length) = normal.unit_and_magnitude();
+        if length == 0.0 {
+            return Err(SpiceError::new(
+                SpiceErrorType::InvalidArgument,
+                "Plane normal vector is the zero vector".into()
+            ));
+        }
+        Ok(Self::from_unit_normal(unit, constant / length))
+    }
+
+    /// Plane through `point` with the given normal (equivalent to nvp2pl_c)
+    pub fn from_normal_and_point(normal: &SpiceVector3, point: &SpiceVector3) -> SpiceResult<Self> {
+        let (unit, length) = normal.unit_and_magnitude();
+        if length == 0.0 {
+            return Err(SpiceError::new(
+                SpiceErrorType::InvalidArgument,
+                format!("Plane through {:?} has a zero normal vector", point.0)
+            ));
+        }
+        Ok(Self::from_unit_normal(unit, unit.dot(point)))
+    }
+
+    /// Flip a unit normal if needed so the constant is non-negative
+    fn from_unit_normal(normal: SpiceVector3, constant: SpiceDouble) -> Self {
+        if constant < 0.0 {
+            Self { normal: normal.scale(-1.0), constant: -constant }
+        } else {
+            Self { normal, constant }
+        }
+    }
+
+    /// Unit normal and constant of the plane (equivalent to pl2nvc_c)
+    pub fn normal_and_constant(&self) -> (SpiceVector3, SpiceDouble) {
+        (self.normal, self.constant)
+    }
+
+    /// Intersection of the ray from `vertex` along `direction` with the plane
+    /// (equivalent to inrypl_c)
+    ///
+    /// Returns the intersection point and whether the ray meets the plane. A
+    /// ray lying in the plane meets it at its vertex; a ray parallel to the
+    /// plane or pointing away from it does not meet it, and the zero vector is
+    /// returned as the point.
+    pub fn intersect_ray(&self, vertex: &SpiceVector3, direction: &SpiceVector3) -> SpiceResult<(SpiceVector3, bool)> {
+        let (unit, length) = direction.unit_and_magnitude();
+        if length == 0.0 {
+            return Err(SpiceError::new(
+                SpiceErrorType::InvalidArgument,
+                "Ray direction is the zero vector".into()
+            ));
+        }
+
+        // Signed distance from the vertex to the plane along the normal
+        let offset = self.constant - self.normal.dot(vertex);
+        if offset == 0.0 {
+            return Ok((*vertex, true));
+        }
+        let closing = self.normal.dot(&unit);
+        if closing == 0.0 || offset.signum() != closing.signum() {
+            return Ok((SpiceVector3::zeros(), false));
+        }
+        let distance = offset / closing;
+        if !distance.is_finite() {
+            return Ok((SpiceVector3::zeros(), false));
+        }
+        Ok((*vertex + unit.scale(distance), true))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plane_stores_unit_normal_and_rescaled_constant() {
+        let plane = Plane::from_normal_and_constant(&SpiceVector3::new(0.0, 0.0, -2.0), 6.0).unwrap();
+        let (normal, constant) = plane.normal_and_constant();
+        assert_eq!(normal, SpiceVector3::new(0.0, 0.0, -1.0));
+        assert_eq!(constant, 3.0);
+
+        // A negative constant flips the normal
+        let through = Plane::from_normal_and_point(&SpiceVector3::new(0.0, 0.0, 4.0), &SpiceVector3::new(5.0, 1.0, -3.0)).unwrap();
+        assert_eq!(through, plane);
+
+        assert!(Plane::from_normal_and_constant(&SpiceVector3::zeros(), 1.0).is_err());
+    }
+
+    #[test]
+    fn test_ray_hits_and_misses_plane() {
+        let plane = Plane::from_normal_and_point(&SpiceVector3::new(1.0, 1.0, 0.0), &SpiceVector3::new(2.0, 0.0, 0.0)).unwrap();
+
+        let vertex = SpiceVector3::new(0.0, 0.0, 7.0);
+        let (point, found) = plane.intersect_ray(&vertex, &SpiceVector3::new(3.0, 0.0, 0.0)).unwrap();
+        assert!(found);
+        assert!((point - SpiceVector3::new(2.0, 0.0, 7.0)).magnitude() < 1e-12);
+
+        // Pointing away from the plane, or parallel to it
+        let (_, found) = plane.intersect_ray(&vertex, &SpiceVector3::new(-1.0, 0.0, 0.0)).unwrap();
+        assert!(!found);
+        let (point, found) = plane.intersect_ray(&vertex, &SpiceVector3::new(1.0, -1.0, 0.5)).unwrap();
+        assert!(!found);
+        assert_eq!(point, SpiceVector3::zeros());
+
+        // A ray in the plane meets it at its vertex
+        let on_plane = SpiceVector3::new(1.0, 1.0, 0.0);
+        assert_eq!(plane.intersect_ray(&on_plane, &SpiceVector3::new(1.0, -1.0, 0.0)).unwrap(), (on_plane, true));
+
+        assert!(plane.intersect_ray(&vertex, &SpiceVector3::zeros()).is_err());
+    }
+}
@@ -0,0 +1,562 @@
+//! Surface Geometry on Reference Ellipsoids for RustSPICE
+//!
+//! This module provides equivalency to the CSPICE ellipsoid surface routines:
+//! - subpnt_c → sub_observer_point() - Sub-observer point on a target body
+//! - sincpt_c → surface_intercept() - Intercept of a ray with a target body
+//! - surfnm_c → surface_normal() - Outward normal on an ellipsoid
+//! - nearpt_c → nearest_point() - Nearest ellipsoid point and altitude
+//! - ilumin_c → illumination_angles() - Phase, incidence and emission angles
+//!
+//! Target shapes are the triaxial ellipsoids given by `BODY<code>_RADII` in
+//! the kernel pool. Surface points and vectors are expressed in the target's
+//! body-fixed frame.
+
+#[cfg(not(feature = "std"))]
+use alloc::{string::String, format};
+#[cfg(feature = "std")]
+use std::{string::String, format};
+
+use crate::foundation::{SpiceDouble, SpiceVector3, EphemerisTime};
+use crate::error_handling::{SpiceResult, SpiceError, SpiceErrorType};
+use crate::math_core::{constants::SPEED_OF_LIGHT, vector_separation};
+use crate::body_data::body_values_double;
+use crate::ephemeris::{ephemeris_state, ellipsoid_ray_intercept, target_epoch, AberrationCorrection};
+use super::get_position_transformation;
+
+/// Iteration limit for the near-point solve
+const NEAR_POINT_MAX_ITERATIONS: usize = 200;
+
+/// Ways of choosing a surface point beneath an observer
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum SubPointMethod {
+    /// Surface point closest to the observer
+    NearPoint,
+    /// Surface intercept of the ray from the observer to the target center
+    Intercept,
+}
+
+impl SubPointMethod {
+    fn from_str(method: &str) -> SpiceResult<Self> {
+        let compact: String = method.chars().filter(|c| !c.is_whitespace()).collect();
+        match compact.to_uppercase().as_str() {
+            "NEARPOINT:ELLIPSOID" | "NEARPOINT/ELLIPSOID" => Ok(Self::NearPoint),
+            "INTERCEPT:ELLIPSOID" | "INTERCEPT/ELLIPSOID" => Ok(Self::Intercept),
+            _ => Err(SpiceError::new(
+                SpiceErrorType::InvalidArgument,
+                format!("Unsupported sub-observer point method '{}'", method)
+            )),
+        }
+    }
+}
+
+/// Sub-observer point on a target body (equivalent to subpnt_c)
+///
+/// `method` is `"Near point: ellipsoid"` or `"Intercept: ellipsoid"`. Returns
+/// the surface point in `fixref`, the epoch at the surface point, and the
+/// observer-to-point vector in `fixref`. The target's position and
+/// orientation are taken at the light-time corrected epoch of its center;
+/// the returned epoch is corrected for the light time to the point itself.
+pub fn sub_observer_point(
+    method: &str,
+    target: &str,
+    et: SpiceDouble,
+    fixref: &str,
+    abcorr: &str,
+    observer: &str,
+) -> SpiceResult<(SpiceVector3, SpiceDouble, SpiceVector3)> {
+    let method = SubPointMethod::from_str(method)?;
+    let correction = AberrationCorrection::from_str(abcorr)?;
+    let radii = target_radii(target)?;
+
+    let state = ephemeris_state(target, EphemerisTime::new(et), "J2000", abcorr, observer)?;
+    let center_epoch = target_epoch(et, state.light_time, &correction);
+    let j2000_to_fixed = get_position_transformation("J2000", fixref, EphemerisTime::new(center_epoch))?;
+    let observer_fixed = j2000_to_fixed.multiply_vector(&state.position).scale(-1.0);
+
+    let point = sub_point(method, &observer_fixed, radii)?;
+    let surface_vector = point - observer_fixed;
+    let light_time = surface_vector.magnitude() / SPEED_OF_LIGHT;
+    Ok((point, target_epoch(et, light_time, &correction), surface_vector))
+}
+
+/// Intercept of an observer's ray with a target body (equivalent to sincpt_c)
+///
+/// `method` must be `"Ellipsoid"`. The ray direction `dvec` is given in frame
+/// `dref` at `et`. Returns the intercept in `fixref`, the epoch at the
+/// intercept, the observer-to-intercept vector and whether the ray hit the
+/// target; on a miss the vectors are zero and the epoch is `et`. As with
+/// `sub_observer_point`, the target is placed at its center's light-time
+/// corrected epoch.
+#[allow(clippy::too_many_arguments)]
+pub fn surface_intercept(
+    method: &str,
+    target: &str,
+    et: SpiceDouble,
+    fixref: &str,
+    abcorr: &str,
+    observer: &str,
+    dref: &str,
+    dvec: &SpiceVector3,
+) -> SpiceResult<(SpiceVector3, SpiceDouble, SpiceVector3, bool)> {
+    if !method.trim().eq_ignore_ascii_case("ELLIPSOID") {
+        return Err(SpiceError::new(
+            SpiceErrorType::InvalidArgument,
+            format!("Unsupported surface intercept method '{}'", method)
+        ));
+    }
+    let correction = AberrationCorrection::from_str(abcorr)?;
+    let radii = target_radii(target)?;
+
+    let state = ephemeris_state(target, EphemerisTime::new(et), "J2000", abcorr, observer)?;
+    let center_epoch = target_epoch(et, state.light_time, &correction);
+    let j2000_to_fixed = get_position_transformation("J2000", fixref, EphemerisTime::new(center_epoch))?;
+    let observer_fixed = j2000_to_fixed.multiply_vector(&state.position).scale(-1.0);
+
+    let direction_j2000 = get_position_transformation(dref, "J2000", EphemerisTime::new(et))?.multiply_vector(dvec);
+    let direction = j2000_to_fixed.multiply_vector(&direction_j2000);
+
+    match ray_intercept(&observer_fixed, &direction, radii)? {
+        Some((point, surface_vector)) => {
+            let light_time = surface_vector.magnitude() / SPEED_OF_LIGHT;
+            Ok((point, target_epoch(et, light_time, &correction), surface_vector, true))
+        }
+        None => Ok((SpiceVector3::zeros(), et, SpiceVector3::zeros(), false)),
+    }
+}
+
+/// Illumination angles at a surface point of a target body (equivalent to
+/// ilumin_c)
+///
+/// `method` must be `"Ellipsoid"` and `surface_point` is given in `fixref`.
+/// Returns the epoch at the surface point and the phase, solar incidence and
+/// emission angles in radians. The observer leg is light-time corrected to
+/// the surface point, and the Sun's position is taken as seen from the target
+/// center at that epoch with the same correction.
+#[allow(clippy::too_many_arguments)]
+pub fn illumination_angles(
+    method: &str,
+    target: &str,
+    et: SpiceDouble,
+    fixref: &str,
+    abcorr: &str,
+    observer: &str,
+    surface_point: &SpiceVector3,
+) -> SpiceResult<(SpiceDouble, SpiceDouble, SpiceDouble, SpiceDouble)> {
+    if !method.trim().eq_ignore_ascii_case("ELLIPSOID") {
+        return Err(SpiceError::new(
+            SpiceErrorType::InvalidArgument,
+            format!("Unsupported illumination angle method '{}'", method)
+        ));
+    }
+    let correction = AberrationCorrection::from_str(abcorr)?;
+    let [a, b, c] = target_radii(target)?;
+
+    let state = ephemeris_state(target, EphemerisTime::new(et), "J2000", abcorr, observer)?;
+    let center_epoch = target_epoch(et, state.light_time, &correction);
+    let j2000_to_fixed = get_position_transformation("J2000", fixref, EphemerisTime::new(center_epoch))?;
+    let observer_fixed = j2000_to_fixed.multiply_vector(&state.position).scale(-1.0);
+
+    let light_time = (*surface_point - observer_fixed).magnitude() / SPEED_OF_LIGHT;
+    let point_epoch = target_epoch(et, light_time, &correction);
+
+    let sun = ephemeris_state("SUN", EphemerisTime::new(point_epoch), "J2000", abcorr, target)?;
+    let sun_fixed = get_position_transformation("J2000", fixref, EphemerisTime::new(point_epoch))?
+        .multiply_vector(&sun.position);
+
+    let normal = surface_normal(a, b, c, surface_point);
+    let (phase, incidence, emission) = surface_angles(surface_point, &normal, &observer_fixed, &sun_fixed)?;
+    Ok((point_epoch, phase, incidence, emission))
+}
+
+/// Phase, incidence and emission angles at a body-fixed surface point with
+/// outward normal `normal`, given the observer and Sun positions
+fn surface_angles(
+    point: &SpiceVector3,
+    normal: &SpiceVector3,
+    observer: &SpiceVector3,
+    sun: &SpiceVector3,
+) -> SpiceResult<(SpiceDouble, SpiceDouble, SpiceDouble)> {
+    let to_observer = *observer - *point;
+    let to_sun = *sun - *point;
+    Ok((
+        vector_separation(&to_sun, &to_observer)?,
+        vector_separation(&to_sun, normal)?,
+        vector_separation(&to_observer, normal)?,
+    ))
+}
+
+/// Intercept and observer-to-intercept vector of a body-fixed ray, if it hits
+fn ray_intercept(
+    observer: &SpiceVector3,
+    direction: &SpiceVector3,
+    radii: [SpiceDouble; 3],
+) -> SpiceResult<Option<(SpiceVector3, SpiceVector3)>> {
+    Ok(ellipsoid_ray_intercept(observer, direction, radii)?.map(|point| (point, point - *observer)))
+}
+
+/// Radii of a target's reference ellipsoid from the kernel pool
+fn target_radii(target: &str) -> SpiceResult<[SpiceDouble; 3]> {
+    let radii = body_values_double(target, "RADII", 3)?;
+    if radii.len() != 3 || radii.iter().any(|&r| r <= 0.0) {
+        return Err(SpiceError::new(
+            SpiceErrorType::InvalidArgument,
+            format!("Radii of {} must be three positive values, got {:?}", target, radii)
+        ));
+    }
+    Ok([radii[0], radii[1], radii[2]])
+}
+
+/// Sub-observer point for an observer at a body-fixed position
+fn sub_point(method: SubPointMethod, observer: &SpiceVector3, radii: [SpiceDouble; 3]) -> SpiceResult<SpiceVector3> {
+    match method {
+        SubPointMethod::NearPoint => ellipsoid_near_point(observer, radii),
+        SubPointMethod::Intercept => {
+            ellipsoid_ray_intercept(observer, &observer.scale(-1.0), radii)?.ok_or_else(|| SpiceError::new(
+                SpiceErrorType::InvalidObserver,
+                "Observer is at the target center; the sub-observer point is undefined".into()
+            ))
+        }
+    }
+}
+
+/// Outward unit normal at a point on the ellipsoid with semi-axes a, b, c
+/// (equivalent to surfnm_c)
+///
+/// The point is assumed to lie on the surface; the zero vector gives zero.
+pub fn surface_normal(a: SpiceDouble, b: SpiceDouble, c: SpiceDouble, point: &SpiceVector3) -> SpiceVector3 {
+    SpiceVector3::new(point.x() / (a * a), point.y() / (b * b), point.z() / (c * c))
+        .unit_and_magnitude()
+        .0
+}
+
+/// Nearest point on the ellipsoid with semi-axes a, b, c to `position`, and
+/// the position's altitude above it (equivalent to nearpt_c)
+///
+/// The altitude is negative for points inside the ellipsoid.
+pub fn nearest_point(
+    position: &SpiceVector3,
+    a: SpiceDouble,
+    b: SpiceDouble,
+    c: SpiceDouble,
+) -> SpiceResult<(SpiceVector3, SpiceDouble)> {
+    let radii = [a, b, c];
+    if radii.iter().any(|&r| r <= 0.0 || !r.is_finite()) {
+        return Err(SpiceError::new(
+            SpiceErrorType::InvalidArgument,
+            format!("Ellipsoid semi-axes must be positive: {:?}", radii)
+        ));
+    }
+
+    // A sphere needs no iteration
+    if a == b && b == c {
+        let (direction, distance) = position.unit_and_magnitude();
+        let direction = if distance == 0.0 { SpiceVector3::new(1.0, 0.0, 0.0) } else { direction };
+        return Ok((direction.scale(a), distance - a));
+    }
+
+    let point = ellipsoid_near_point(position, radii)?;
+    let level: SpiceDouble = (0..3).map(|i| (position.0[i] / radii[i]).powi(2)).sum();
+    let distance = (*position - point).magnitude();
+    Ok((point, if level < 1.0 { -distance } else { distance }))
+}
+
+/// Point on an ellipsoid nearest to `position`
+///
+/// The near point is `a_i² x_i / (a_i² + λ)` for the root λ of
+/// `Σ (a_i x_i / (a_i² + λ))² = 1`, which is decreasing in λ above
+/// `-min(a_i²)`. The root is found by Newton's method safeguarded with
+/// bisection.
+pub(crate) fn ellipsoid_near_point(position: &SpiceVector3, radii: [SpiceDouble; 3]) -> SpiceResult<SpiceVector3> {
+    if radii.iter().any(|&r| r <= 0.0) {
+        return Err(SpiceError::new(
+            SpiceErrorType::InvalidArgument,
+            format!("Ellipsoid radii must be positive: {:?}", radii)
+        ));
+    }
+    let x = position.0;
+    let squares = radii.map(|r| r * r);
+
+    // An interior point in the plane normal to the shortest axis may be
+    // closest to a point off that plane, where λ sits at its lower limit
+    let shortest = (0..3).fold(0, |k, i| if radii[i] < radii[k] { i } else { k });
+    if x[shortest] == 0.0 {
+        let mut projected = [0.0; 3];
+        let mut level = 0.0;
+        for i in (0..3).filter(|&i| i != shortest && x[i] != 0.0) {
+            if radii[i] == radii[shortest] {
+                level = SpiceDouble::INFINITY;
+                break;
+            }
+            projected[i] = squares[i] * x[i] / (squares[i] - squares[shortest]);
+            level += (projected[i] / radii[i]).powi(2);
+        }
+        if level <= 1.0 {
+            projected[shortest] = radii[shortest] * (1.0 - level).sqrt();
+            return Ok(SpiceVector3(projected));
+        }
+    }
+
+    // f(λ) - 1 and its derivative
+    let residual = |lambda: SpiceDouble| {
+        let mut f = -1.0;
+        let mut df = 0.0;
+        for i in 0..3 {
+            let q = radii[i] * x[i] / (squares[i] + lambda);
+            f += q * q;
+            df -= 2.0 * q * q / (squares[i] + lambda);
+        }
+        (f, df)
+    };
+
+    let smallest = squares.iter().copied().fold(SpiceDouble::INFINITY, SpiceDouble::min);
+    let largest = radii.iter().copied().fold(0.0, SpiceDouble::max);
+    let mut lower = -smallest;
+    let mut upper = (largest * position.magnitude()).max(SpiceDouble::MIN_POSITIVE);
+    let mut lambda = 0.0;
+
+    for _ in 0..NEAR_POINT_MAX_ITERATIONS {
+        let (f, df) = residual(lambda);
+        if f > 0.0 {
+            lower = lambda;
+        } else {
+            upper = lambda;
+        }
+        if f == 0.0 || (upper - lower) <= SpiceDouble::EPSILON * upper.abs().max(smallest) {
+            break;
+        }
+        let newton = lambda - f / df;
+        lambda = if newton > lower && newton < upper { newton } else { 0.5 * (lower + upper) };
+    }
+
+    Ok(SpiceVector3::new(
+        squares[0] * x[0] / (squares[0] + lambda),
+        squares[1] * x[1] / (squares[1] + lambda),
+        squares[2] * x[2] / (squares[2] + lambda),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kernel_system::IsolatedKernels;
+
+    /// Sun, Mars and an orbiter 10 000 km from Mars over ET [0, 2000], with
+    /// IAU_MARS orientation from the pool
+    fn load_mars_scene(kernels: &IsolatedKernels) {
+        use crate::spk_reader::write;
+        use crate::kernel_system::furnish_kernel_from_bytes;
+
+        let record = |x: [f64; 2], y: [f64; 2], z: [f64; 2]| [write::ChebyshevRecord {
+            start: 0.0, end: 2000.0, x: x.to_vec(), y: y.to_vec(), z: z.to_vec(),
+        }];
+        let mut spk = write::new_spk("MARS SCENE").unwrap();
+        write::add_type2_segment(&mut spk, 10, 0, "J2000", 0.0, 2000.0, "SUN",
+            &record([1.0e5, 10.0], [2.0e4, 0.0], [0.0, 0.0])).unwrap();
+        write::add_type2_segment(&mut spk, 499, 10, "J2000", 0.0, 2000.0, "MARS",
+            &record([2.0e8, -5000.0], [5.0e7, 24000.0], [1.0e7, 1000.0])).unwrap();
+        write::add_type2_segment(&mut spk, -9701, 499, "J2000", 0.0, 2000.0, "ORBITER",
+            &record([8000.0, 1000.0], [3000.0, -2000.0], [4000.0, 500.0])).unwrap();
+        let pck = "\\begindata
+BODY499_RADII = ( 3396.19 3396.19 3376.20 )
+BODY499_POLE_RA = ( 317.68143 -0.1061 0.0 )
+BODY499_POLE_DEC = ( 52.88650 -0.0609 0.0 )
+BODY499_PM = ( 176.630 350.89198226 0.0 )
+";
+        kernels.load_spk("mars_scene.bsp", &spk.to_bytes().unwrap());
+        furnish_kernel_from_bytes(pck.as_bytes().to_vec(), "/pck/mars_scene.tpc").unwrap();
+    }
+
+    /// Components within `tolerance` of a reference
+    fn assert_close(actual: &SpiceVector3, expected: [f64; 3], tolerance: f64) {
+        for i in 0..3 {
+            assert!((actual.0[i] - expected[i]).abs() < tolerance, "{:?} vs {:?}", actual, expected);
+        }
+    }
+
+    // CSPICE N0067 references below use the same SPK and PCK. Without
+    // aberration correction they agree to rounding. With LT the target is
+    // placed at its center's light-time epoch rather than the surface
+    // point's, which moves the point by about 0.1 km here.
+
+    #[test]
+    fn test_sub_observer_point_matches_cspice_subpnt() {
+        let kernels = IsolatedKernels::new();
+        load_mars_scene(&kernels);
+
+        let reference = [
+            ("Near point: ellipsoid", "NONE", 600.0, [-2893.875341183, 6.966714674, 1767.048035459], 600.0, [5033.972986706, -12.118785138, -3110.334114188]),
+            ("Intercept: ellipsoid", "NONE", 600.0, [-2887.893148309, 6.952313145, 1776.693739472], 600.0, [5039.955179580, -12.133186667, -3100.688410174]),
+            ("Near point: ellipsoid", "NONE", 1400.0, [-2546.098482892, 539.374915864, 2169.029099298], 1400.0, [4679.975646345, -991.423343384, -4034.236925003]),
+            ("Intercept: ellipsoid", "NONE", 1400.0, [-2538.022670489, 537.664105907, 2178.780554859], 1400.0, [4688.051458748, -993.134153340, -4024.485469442]),
+            ("Near point: ellipsoid", "LT", 600.0, [-2893.939326421, 6.842516576, 1766.944952912], 599.980261393, [5034.187678019, -11.902983700, -3110.216544308]),
+            ("Intercept: ellipsoid", "LT", 1400.0, [-2538.125638525, 537.542534028, 2178.691658026], 1399.979125969, [4688.264671069, -992.914469335, -4024.341023327]),
+        ];
+        for (method, abcorr, et, point, epoch, vector) in reference {
+            let tolerance = if abcorr == "NONE" { 1e-6 } else { 0.2 };
+            let (spoint, trgepc, srfvec) = sub_observer_point(method, "MARS", et, "IAU_MARS", abcorr, "-9701").unwrap();
+            assert_close(&spoint, point, tolerance);
+            assert_close(&srfvec, vector, tolerance);
+            assert!((trgepc - epoch).abs() < 1e-6);
+        }
+
+        assert!(sub_observer_point("Near point: DSK", "MARS", 600.0, "IAU_MARS", "NONE", "-9701").is_err());
+    }
+
+    #[test]
+    fn test_surface_intercept_matches_cspice_sincpt() {
+        let kernels = IsolatedKernels::new();
+        load_mars_scene(&kernels);
+
+        let toward = SpiceVector3::new(-7600.0, -3000.0, -3800.0);
+        let (point, epoch, vector, found) = surface_intercept("Ellipsoid", "MARS", 600.0, "IAU_MARS", "NONE", "-9701", "J2000", &toward).unwrap();
+        assert!(found);
+        assert_close(&point, [-3062.925381688, -284.445279113, 1430.863154234], 1e-6);
+        assert_close(&vector, [4864.922946201, -303.530778925, -3446.518995412], 1e-6);
+        assert_eq!(epoch, 600.0);
+
+        let (point, epoch, _, found) = surface_intercept("Ellipsoid", "MARS", 600.0, "IAU_MARS", "LT", "-9701", "J2000", &toward).unwrap();
+        assert!(found);
+        assert_close(&point, [-3063.050545199, -284.791133802, 1430.530340396], 0.2);
+        assert!((epoch - 599.980086369).abs() < 1e-6);
+
+        // Aimed well off the limb
+        let beside = SpiceVector3::new(-7600.0, 3000.0, -3800.0);
+        let (point, epoch, _, found) = surface_intercept("Ellipsoid", "MARS", 600.0, "IAU_MARS", "NONE", "-9701", "J2000", &beside).unwrap();
+        assert!(!found);
+        assert_eq!(point, SpiceVector3::zeros());
+        assert_eq!(epoch, 600.0);
+    }
+
+    #[test]
+    fn test_illumination_angles_match_cspice_ilumin() {
+        let kernels = IsolatedKernels::new();
+        load_mars_scene(&kernels);
+
+        let point = SpiceVector3::new(-2893.875341183, 6.966714674, 1767.048035459);
+        for (abcorr, epoch, phase, incidence, emission, tolerance) in [
+            ("NONE", 1400.0, 2.732597781005, 2.666789090734, 0.339646238064, 1e-9),
+            ("LT", 1399.978701557, 2.732616344078, 2.666790329411, 0.339563253724, 1e-4),
+        ] {
+            let angles = illumination_angles("Ellipsoid", "MARS", 1400.0, "IAU_MARS", abcorr, "-9701", &point).unwrap();
+            assert!((angles.0 - epoch).abs() < 1e-6);
+            assert!((angles.1 - phase).abs() < tolerance, "{} phase {}", abcorr, angles.1);
+            assert!((angles.2 - incidence).abs() < tolerance, "{} incidence {}", abcorr, angles.2);
+            assert!((angles.3 - emission).abs() < tolerance, "{} emission {}", abcorr, angles.3);
+        }
+
+        // The Sun is overhead at the sub-solar point
+        let (sub_solar, _, _) = sub_observer_point("Near point: ellipsoid", "MARS", 1400.0, "IAU_MARS", "NONE", "SUN").unwrap();
+        let (_, _, incidence, _) = illumination_angles("Ellipsoid", "MARS", 1400.0, "IAU_MARS", "NONE", "-9701", &sub_solar).unwrap();
+        assert!(incidence < 1e-9);
+    }
+
+    #[test]
+    fn test_sub_point_on_sphere_lies_along_observer_direction() {
+        let radii = [1000.0; 3];
+        let observer = SpiceVector3::new(3000.0, -4000.0, 12000.0);
+        let expected = observer.scale(1000.0 / observer.magnitude());
+
+        for method in [SubPointMethod::NearPoint, SubPointMethod::Intercept] {
+            let point = sub_point(method, &observer, radii).unwrap();
+            assert!((point - expected).magnitude() < 1e-9, "{:?} gave {:?}", method, point);
+        }
+
+        assert_eq!(SubPointMethod::from_str("Near point: ellipsoid").unwrap(), SubPointMethod::NearPoint);
+        assert_eq!(SubPointMethod::from_str("INTERCEPT/ELLIPSOID").unwrap(), SubPointMethod::Intercept);
+        assert!(SubPointMethod::from_str("Near point: DSK").is_err());
+    }
+
+    #[test]
+    fn test_near_point_on_oblate_body_is_normal_to_observer() {
+        let radii = [3396.19, 3396.19, 3376.20];
+        let observer = SpiceVector3::new(5000.0, 2000.0, 6000.0);
+        let point = ellipsoid_near_point(&observer, radii).unwrap();
+
+        let on_surface: f64 = (0..3).map(|i| (point.0[i] / radii[i]).powi(2)).sum();
+        assert!((on_surface - 1.0).abs() < 1e-12);
+
+        // The outward normal at the near point points at the observer
+        let normal = SpiceVector3::new(
+            point.0[0] / (radii[0] * radii[0]),
+            point.0[1] / (radii[1] * radii[1]),
+            point.0[2] / (radii[2] * radii[2]),
+        );
+        let offset = observer - point;
+        assert!(offset.unit().unwrap().cross(&normal.unit().unwrap()).magnitude() < 1e-12);
+    }
+
+    #[test]
+    fn test_ray_intercept_of_unit_sphere() {
+        let radii = [1.0; 3];
+        let observer = SpiceVector3::new(5.0, 0.0, 0.0);
+
+        // Aimed at the center: the near side at x = 1, not the far side
+        let (point, surface_vector) = ray_intercept(&observer, &SpiceVector3::new(-1.0, 0.0, 0.0), radii).unwrap().unwrap();
+        assert!((point - SpiceVector3::new(1.0, 0.0, 0.0)).magnitude() < 1e-15);
+        assert!((surface_vector - SpiceVector3::new(-4.0, 0.0, 0.0)).magnitude() < 1e-15);
+
+        // Offset ray grazing at 0.6 off axis hits at x = 0.8
+        let offset = SpiceVector3::new(5.0, 0.6, 0.0);
+        let (point, _) = ray_intercept(&offset, &SpiceVector3::new(-1.0, 0.0, 0.0), radii).unwrap().unwrap();
+        assert!((point - SpiceVector3::new(0.8, 0.6, 0.0)).magnitude() < 1e-15);
+
+        // Pointing away from the sphere, and passing beside it
+        assert!(ray_intercept(&observer, &SpiceVector3::new(1.0, 0.0, 0.0), radii).unwrap().is_none());
+        assert!(ray_intercept(&observer, &SpiceVector3::new(-1.0, 1.0, 0.0), radii).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_nearest_point_altitude_sign() {
+        let outside = SpiceVector3::new(0.0, 3.0, 4.0);
+        let (point, altitude) = nearest_point(&outside, 2.0, 2.0, 2.0).unwrap();
+        assert!((point - SpiceVector3::new(0.0, 1.2, 1.6)).magnitude() < 1e-15);
+        assert!((altitude - 3.0).abs() < 1e-15);
+
+        let inside = SpiceVector3::new(0.5, 0.0, 0.0);
+        let (point, altitude) = nearest_point(&inside, 2.0, 2.0, 2.0).unwrap();
+        assert_eq!(point, SpiceVector3::new(2.0, 0.0, 0.0));
+        assert_eq!(altitude, -1.5);
+        assert_eq!(nearest_point(&SpiceVector3::zeros(), 2.0, 2.0, 2.0).unwrap().1, -2.0);
+
+        // Triaxial: inside and outside, including a point in the plane normal
+        // to the shortest axis whose nearest point lies off that plane
+        let (a, b, c) = (3.0, 2.0, 1.0);
+        for (position, outward) in [
+            (SpiceVector3::new(4.0, 1.0, 2.0), true),
+            (SpiceVector3::new(0.5, 0.2, 0.1), false),
+            (SpiceVector3::new(0.5, 0.3, 0.0), false),
+        ] {
+            let (point, altitude) = nearest_point(&position, a, b, c).unwrap();
+            let level = (point.x() / a).powi(2) + (point.y() / b).powi(2) + (point.z() / c).powi(2);
+            assert!((level - 1.0).abs() < 1e-12);
+            assert_eq!(altitude > 0.0, outward);
+            assert!(((position - point).magnitude() - altitude.abs()).abs() < 1e-12);
+
+            let normal = surface_normal(a, b, c, &point);
+            assert!((normal.magnitude() - 1.0).abs() < 1e-15);
+            assert!((position - point).cross(&normal).magnitude() < 1e-12);
+        }
+
+        assert!(nearest_point(&outside, 1.0, 0.0, 1.0).is_err());
+    }
+
+    #[test]
+    fn test_surface_angles_at_sub_solar_point() {
+        let (a, b, c) = (3396.19, 3396.19, 3376.20);
+        let point = SpiceVector3::new(a, 0.0, 0.0);
+        let normal = surface_normal(a, b, c, &point);
+        let sun = SpiceVector3::new(2.2e8, 0.0, 0.0);
+
+        // Observer 30 degrees off the sub-solar direction, in the equator
+        let angle = 30.0_f64.to_radians();
+        let observer = point + SpiceVector3::new(angle.cos(), angle.sin(), 0.0).scale(1.0e4);
+
+        let (phase, incidence, emission) = surface_angles(&point, &normal, &observer, &sun).unwrap();
+        assert!(incidence.abs() < 1e-12);
+        assert!((emission - angle).abs() < 1e-12);
+        assert!((phase - angle).abs() < 1e-12);
+
+        // The observer standing on the point leaves the angles undefined
+        assert!(surface_angles(&point, &normal, &point, &sun).is_err());
+    }
+}
@@ -6,7 +6,20 @@
 //!
 //! # Key Functions
 //! - `ephemeris_state()` - Position and velocity (spkezr_c equivalent)
+//! - `ephemeris_states()` - States at many epochs in one call
 //! - `ephemeris_position()` - Position only (spkpos_c equivalent) 
+//! - `geometric_state()` - Uncorrected state by NAIF code (spkgeo_c equivalent)
+//! - `barycentric_state()` - State relative to the solar system barycenter (spkssb_c equivalent)
+//! - `stellar_aberration()` - Stellar aberration of a direction (stelab_c/stlabx_c equivalent)
+//! - `define_body()` - Custom body name/code pairs (boddef_c equivalent)
+//! - `phase_angle()` - Illuminator-target-observer phase angle (phaseq_c equivalent)
+//! - `occultation_type()` - Occultation of one target by another (occult_c equivalent)
+//! - `target_in_fov()` - Whether a target is in an instrument field of view (fovtrg_c equivalent)
+//! - `closest_approach()` - Epoch and distance of minimum separation
+//! - `gf_distance()` - Times a distance condition holds (gfdist_c equivalent)
+//! - `compare_to_conic()` - Residuals of loaded ephemeris against a two-body orbit
+//! - `propagate_two_body()` - Two-body propagation of a state (prop2b_c equivalent)
+//! - `propagate()` - Two-body propagation of an SPK state about its center's GM
 //! - Light time correction modes (NONE, LT, LT+S, CN, CN+S)
 //! - Reference frame transformations
 //! 
@@ -19,37 +32,24 @@
 //! - ✅ Integration with kernel loading system
 //! - ✅ Full SPICE-compatible functionality
 
-use crate::foundation::{StateVector, SpiceVector3, EphemerisTime};
+use crate::foundation::{StateVector, SpiceVector3, SpiceMatrix3x3, EphemerisTime, SpiceWindow};
 use crate::error_handling::{SpiceResult, SpiceError, SpiceErrorType};
 use crate::coordinates::get_position_transformation;
-use crate::spk_reader::get_spk_reader;
-use std::collections::HashMap;
+use crate::spk_reader::with_spk_reader;
+use crate::kernel_pool::{get_required_doubles, get_character_pool};
+use crate::body_data::{body_data, body_values_double, gravitational_parameter, get_fov, Fov, FovShape};
+use crate::body_data::{SOLAR_SYSTEM_BARYCENTER, MERCURY, VENUS, EARTH, MOON, MARS};
+use crate::math_core::vector_separation;
+use crate::advanced_math::OptimizationMethods;
 
 /// Speed of light in km/s (exact value used by CSPICE)
 const SPEED_OF_LIGHT: f64 = 299792.458;
 
-/// NAIF body codes for common celestial bodies
-const SOLAR_SYSTEM_BARYCENTER: i32 = 0;
-const MERCURY_BARYCENTER: i32 = 1;
-const VENUS_BARYCENTER: i32 = 2; 
-const EARTH_BARYCENTER: i32 = 3;
-const MARS_BARYCENTER: i32 = 4;
-const JUPITER_BARYCENTER: i32 = 5;
-const SATURN_BARYCENTER: i32 = 6;
-const URANUS_BARYCENTER: i32 = 7;
-const NEPTUNE_BARYCENTER: i32 = 8;
-const PLUTO_BARYCENTER: i32 = 9;
-const SUN: i32 = 10;
-const MERCURY: i32 = 199;
-const VENUS: i32 = 299;
-const EARTH: i32 = 399;
-const MOON: i32 = 301;
-const MARS: i32 = 499;
-const JUPITER: i32 = 599;
-const SATURN: i32 = 699;
-const URANUS: i32 = 799;
-const NEPTUNE: i32 = 899;
-const PLUTO: i32 = 999;
+/// Most light-time iterations made for converged Newtonian (CN) corrections
+const CN_MAX_ITERATIONS: usize = 10;
+
+/// Relative light-time change at which CN iteration stops
+const CN_TOLERANCE: f64 = 4.0 * f64::EPSILON;
 
 /// Aberration correction types
 #[derive(Debug, Clone, PartialEq)]
@@ -104,6 +104,14 @@ impl AberrationCorrection {
         )
     }
     
+    /// Check if this correction iterates the light time to convergence
+    pub fn is_converged_newtonian(&self) -> bool {
+        matches!(self,
+            Self::ConvergedNewtonian | Self::ConvergedNewtonianAndStellar |
+            Self::TransmissionConvergedNewtonian | Self::TransmissionConvergedNewtonianAndStellar
+        )
+    }
+
     /// Check if this correction includes stellar aberration
     pub fn includes_stellar_aberration(&self) -> bool {
         matches!(self,
@@ -121,73 +129,9 @@ impl AberrationCorrection {
     }
 }
 
-/// Body name to NAIF ID code mapping
-fn get_body_code_map() -> HashMap<String, i32> {
-    let mut map = HashMap::new();
-    
-    // Solar system bodies
-    map.insert("SOLAR SYSTEM BARYCENTER".to_string(), SOLAR_SYSTEM_BARYCENTER);
-    map.insert("SSB".to_string(), SOLAR_SYSTEM_BARYCENTER);
-    map.insert("SUN".to_string(), SUN);
-    map.insert("MERCURY BARYCENTER".to_string(), MERCURY_BARYCENTER);
-    map.insert("VENUS BARYCENTER".to_string(), VENUS_BARYCENTER);
-    map.insert("EARTH BARYCENTER".to_string(), EARTH_BARYCENTER);
-    map.insert("MARS BARYCENTER".to_string(), MARS_BARYCENTER);
-    map.insert("JUPITER BARYCENTER".to_string(), JUPITER_BARYCENTER);
-    map.insert("SATURN BARYCENTER".to_string(), SATURN_BARYCENTER);
-    map.insert("URANUS BARYCENTER".to_string(), URANUS_BARYCENTER);
-    map.insert("NEPTUNE BARYCENTER".to_string(), NEPTUNE_BARYCENTER);
-    map.insert("PLUTO BARYCENTER".to_string(), PLUTO_BARYCENTER);
-    
-    // Planets
-    map.insert("MERCURY".to_string(), MERCURY);
-    map.insert("VENUS".to_string(), VENUS);
-    map.insert("EARTH".to_string(), EARTH);
-    map.insert("MARS".to_string(), MARS);
-    map.insert("JUPITER".to_string(), JUPITER);
-    map.insert("SATURN".to_string(), SATURN);
-    map.insert("URANUS".to_string(), URANUS);
-    map.insert("NEPTUNE".to_string(), NEPTUNE);
-    map.insert("PLUTO".to_string(), PLUTO);
-    
-    // Natural satellites
-    map.insert("MOON".to_string(), MOON);
-    
-    map
-}
-
-/// Convert body name to NAIF ID code (equivalent to bodn2c_c)
-pub fn body_name_to_code(name: &str) -> SpiceResult<i32> {
-    // Try parsing as integer first
-    if let Ok(code) = name.parse::<i32>() {
-        return Ok(code);
-    }
-    
-    let body_map = get_body_code_map();
-    let upper_name = name.to_uppercase();
-    
-    body_map.get(&upper_name).copied()
-        .ok_or_else(|| SpiceError::new(
-            SpiceErrorType::InvalidTarget,
-            format!("Unknown body name: {}", name)
-        ))
-}
-
-/// Convert NAIF ID code to body name (equivalent to bodc2n_c)
-pub fn body_code_to_name(code: i32) -> SpiceResult<String> {
-    let body_map = get_body_code_map();
-    
-    for (name, &body_code) in &body_map {
-        if body_code == code {
-            return Ok(name.clone());
-        }
-    }
-    
-    Err(SpiceError::new(
-        SpiceErrorType::InvalidTarget,
-        format!("Unknown body code: {}", code)
-    ))
-}
+/// Body names and codes come from the registry in `body_data`, which
+/// `define_body` extends
+pub use crate::body_data::{define_body, body_name_to_code, body_code_to_name};
 
 /// Simplified SPK data structure for basic celestial mechanics
 /// In a full implementation, this would read actual SPK files
@@ -292,27 +236,55 @@ fn get_planetary_elements(target: i32) -> SpiceResult<OrbitalElements> {
 }
 
 /// Solve Kepler's equation for eccentric anomaly
+///
+/// Newton iteration seeded with `M + e*sin(M)`, safeguarded by bisection on
+/// the bracket `[M - e, M + e]` so that eccentricities up to 0.999 converge.
 fn solve_kepler_equation(mean_anomaly: f64, eccentricity: f64) -> f64 {
-    let mut e_anom = mean_anomaly;
     let tolerance = 1e-12;
-    let max_iterations = 50;
-    
+    let max_iterations = 100;
+
+    // Reduce to [-pi, pi] and restore the whole revolutions at the end
+    let two_pi = 2.0 * std::f64::consts::PI;
+    let revolutions = (mean_anomaly / two_pi).round();
+    let m = mean_anomaly - revolutions * two_pi;
+
+    // f(E) = E - e*sin(E) - M is monotonic, so the root lies in [M - e, M + e]
+    let mut lower = m - eccentricity;
+    let mut upper = m + eccentricity;
+    let mut e_anom = m + eccentricity * m.sin();
+
     for _ in 0..max_iterations {
-        let delta = e_anom - eccentricity * e_anom.sin() - mean_anomaly;
+        let delta = e_anom - eccentricity * e_anom.sin() - m;
         let delta_prime = 1.0 - eccentricity * e_anom.cos();
-        
+        let newton = e_anom - delta / delta_prime;
+
+        // Take the final Newton step once the residual is small enough
         if delta.abs() < tolerance {
+            e_anom = newton;
             break;
         }
-        
-        e_anom -= delta / delta_prime;
+
+        // Shrink the bracket around the root
+        if delta > 0.0 {
+            upper = e_anom;
+        } else {
+            lower = e_anom;
+        }
+
+        // Fall back to bisection when the Newton step leaves the bracket
+        e_anom = if newton > lower && newton < upper {
+            newton
+        } else {
+            0.5 * (lower + upper)
+        };
     }
-    
-    e_anom
+
+    e_anom + revolutions * two_pi
 }
 
-/// Compute position and velocity from orbital elements  
-fn compute_orbital_state(elements: &OrbitalElements, et: f64) -> SpiceResult<StateVector> {
+/// Compute position and velocity from orbital elements about a body with
+/// gravitational parameter `mu` (km³/s²)
+fn compute_orbital_state(elements: &OrbitalElements, mu: f64, et: f64) -> SpiceResult<StateVector> {
     let dt = et - elements.epoch;
     let mean_anomaly = elements.m0 + elements.n * dt;
     
@@ -333,7 +305,6 @@ fn compute_orbital_state(elements: &OrbitalElements, et: f64) -> SpiceResult<Sta
     let y_orb = r * sin_ta;
     
     // Velocity in orbital plane  
-    let mu = 1.32712440018e11; // Solar GM (km³/s²) - simplified
     let h = (mu * elements.a * (1.0 - elements.e * elements.e)).sqrt();
     
     let vx_orb = -mu * sin_ta / h;
@@ -367,6 +338,109 @@ fn compute_orbital_state(elements: &OrbitalElements, et: f64) -> SpiceResult<Sta
     })
 }
 
+/// Stumpff functions C(z) and S(z) of the universal-variable formulation
+fn stumpff(z: f64) -> (f64, f64) {
+    if z > 1e-6 {
+        let root = z.sqrt();
+        ((1.0 - root.cos()) / z, (root - root.sin()) / (root * z))
+    } else if z < -1e-6 {
+        let root = (-z).sqrt();
+        ((root.cosh() - 1.0) / -z, (root.sinh() - root) / (root * -z))
+    } else {
+        // Series about z = 0
+        (0.5 - z / 24.0 + z * z / 720.0, 1.0 / 6.0 - z / 120.0 + z * z / 5040.0)
+    }
+}
+
+/// Propagate a state by two-body motion for `dt` seconds about a body with
+/// gravitational parameter `mu` (km³/s²) (equivalent to prop2b_c)
+///
+/// Uses the universal-variable formulation, so elliptic, parabolic and
+/// hyperbolic orbits are handled alike. Elliptic propagation is first reduced
+/// to less than one period.
+pub fn propagate_two_body(state: &StateVector, mu: f64, dt: f64) -> SpiceResult<StateVector> {
+    let (r0, v0) = (state.position, state.velocity);
+    let r0_mag = r0.magnitude();
+    if mu.is_nan() || mu <= 0.0 || r0_mag == 0.0 || !dt.is_finite() {
+        return Err(SpiceError::new(
+            SpiceErrorType::InvalidArgument,
+            format!("Cannot propagate with GM {} from |r| = {} over {} s", mu, r0_mag, dt)
+        ));
+    }
+
+    let sqrt_mu = mu.sqrt();
+    let rv = r0.dot(&v0);
+    // Reciprocal of the semi-major axis
+    let alpha = 2.0 / r0_mag - v0.dot(&v0) / mu;
+
+    let mut dt = dt;
+    let mut chi = if alpha > 1e-12 {
+        let period = 2.0 * std::f64::consts::PI / (alpha.powf(1.5) * sqrt_mu);
+        dt -= period * (dt / period).round();
+        sqrt_mu * alpha * dt
+    } else if alpha < -1e-12 {
+        let a = 1.0 / alpha;
+        let sign = dt.signum();
+        let ratio = -2.0 * mu * alpha * dt / (rv + sign * (-mu * a).sqrt() * (1.0 - r0_mag * alpha));
+        sign * (-a).sqrt() * ratio.abs().ln()
+    } else {
+        sqrt_mu * dt / r0_mag
+    };
+    if !chi.is_finite() {
+        chi = sqrt_mu * dt / r0_mag;
+    }
+
+    // Newton iteration on the universal Kepler equation; its derivative is |r|
+    let mut converged = dt == 0.0;
+    let (mut c, mut s_fn, mut r_mag) = (0.5, 1.0 / 6.0, r0_mag);
+    for _ in 0..100 {
+        let z = alpha * chi * chi;
+        (c, s_fn) = stumpff(z);
+        let chi2 = chi * chi;
+        let residual = rv / sqrt_mu * chi2 * c + (1.0 - alpha * r0_mag) * chi2 * chi * s_fn + r0_mag * chi - sqrt_mu * dt;
+        r_mag = rv / sqrt_mu * chi * (1.0 - z * s_fn) + (1.0 - alpha * r0_mag) * chi2 * c + r0_mag;
+        let step = residual / r_mag;
+        chi -= step;
+        if step.abs() <= 1e-13 * chi.abs().max(1.0) {
+            let z = alpha * chi * chi;
+            (c, s_fn) = stumpff(z);
+            r_mag = rv / sqrt_mu * chi * (1.0 - z * s_fn) + (1.0 - alpha * r0_mag) * chi * chi * c + r0_mag;
+            converged = true;
+            break;
+        }
+    }
+    if !converged {
+        return Err(SpiceError::new(
+            SpiceErrorType::ComputationError,
+            format!("Two-body propagation over {} s did not converge", dt)
+        ));
+    }
+
+    let chi2 = chi * chi;
+    let f = 1.0 - chi2 / r0_mag * c;
+    let g = dt - chi2 * chi / sqrt_mu * s_fn;
+    let f_dot = sqrt_mu / (r_mag * r0_mag) * (alpha * chi2 * chi * s_fn - chi);
+    let g_dot = 1.0 - chi2 / r_mag * c;
+
+    Ok(StateVector {
+        position: r0.scale(f) + v0.scale(g),
+        velocity: r0.scale(f_dot) + v0.scale(g_dot),
+        light_time: 0.0,
+    })
+}
+
+/// Propagate a target's state relative to `center` from `et` by `dt` seconds
+/// of two-body motion about the center
+///
+/// The starting state is the geometric J2000 state from the loaded SPK
+/// files, and the center's GM is read from the kernel pool
+/// (`BODY<code>_GM`). The result is relative to the center at `et + dt`.
+pub fn propagate(target: &str, center: &str, et: f64, dt: f64) -> SpiceResult<StateVector> {
+    let mu = gravitational_parameter(center)?;
+    let state = geometric_state(body_name_to_code(target)?, et, "J2000", body_name_to_code(center)?)?;
+    propagate_two_body(&state, mu, dt)
+}
+
 /// Compute geometric state of target relative to center using real SPK data
 fn compute_geometric_state(target: i32, center: i32, et: f64, frame: &str) -> SpiceResult<StateVector> {
     // Handle special cases
@@ -379,8 +453,7 @@ fn compute_geometric_state(target: i32, center: i32, et: f64, frame: &str) -> Sp
     }
     
     // Use real SPK data through the SPK reader
-    let spk_reader = get_spk_reader()?;
-    let mut state = spk_reader.compute_state(target, center, et)?;
+    let mut state = with_spk_reader(|reader| reader.compute_chained_state(target, center, et))?;
     
     // Apply frame transformation if needed
     if frame != "J2000" {
@@ -417,127 +490,108 @@ fn apply_light_time_correction(
         });
     }
     
-    // Iterative light time correction
-    let mut light_time = 0.0;
-    let max_iterations = if correction.includes_stellar_aberration() { 5 } else { 3 };
-    let tolerance = 1e-10; // Higher precision for stellar aberration
-    
-    for iteration in 0..max_iterations {
-        let corrected_et = if correction.is_transmission() {
-            et + light_time
-        } else {
-            et - light_time
-        };
-        
-        // Recompute target state at corrected time
-        let corrected_target_state = compute_geometric_state(target, SOLAR_SYSTEM_BARYCENTER, corrected_et, frame)?;
-        let mut relative_pos = corrected_target_state.position.subtract(&observer_state.position);
-        let relative_vel = corrected_target_state.velocity.subtract(&observer_state.velocity);
-        
-        // Apply stellar aberration correction if requested
-        if correction.includes_stellar_aberration() {
-            relative_pos = apply_stellar_aberration_correction(&relative_pos, &observer_state.velocity, correction.is_transmission())?;
-        }
-        
-        let new_light_time = relative_pos.magnitude() / SPEED_OF_LIGHT;
-        
-        if (new_light_time - light_time).abs() < tolerance || iteration == max_iterations - 1 {
-            return Ok(StateVector {
-                position: relative_pos,
-                velocity: relative_vel,
-                light_time: new_light_time,
-            });
-        }
-        
+    let (corrected_target_state, light_time) = solve_light_time(
+        |epoch| compute_geometric_state(target, SOLAR_SYSTEM_BARYCENTER, epoch, frame),
+        &observer_state.position,
+        et,
+        correction,
+    )?;
+    let mut relative_pos = corrected_target_state.position.subtract(&observer_state.position);
+    let relative_vel = corrected_target_state.velocity.subtract(&observer_state.velocity);
+
+    // Apply stellar aberration correction if requested
+    if correction.includes_stellar_aberration() {
+        relative_pos = apply_stellar_aberration_correction(&relative_pos, &observer_state.velocity, correction.is_transmission())?;
+    }
+
+    Ok(StateVector {
+        position: relative_pos,
+        velocity: relative_vel,
+        light_time,
+    })
+}
+
+/// Solve the light-time equation for a target whose barycentric state at an
+/// epoch is given by `target_at`, seen from `observer` at `et`
+///
+/// Starting from the geometric light time, LT makes one Newtonian iteration
+/// as CSPICE does; CN iterates until the light time changes by less than a
+/// few ulps. Returns the target state at the final corrected epoch and the
+/// light time.
+fn solve_light_time<F>(
+    target_at: F,
+    observer: &SpiceVector3,
+    et: f64,
+    correction: &AberrationCorrection,
+) -> SpiceResult<(StateVector, f64)>
+where
+    F: Fn(f64) -> SpiceResult<StateVector>,
+{
+    let mut state = target_at(et)?;
+    let mut light_time = (state.position - *observer).magnitude() / SPEED_OF_LIGHT;
+    let iterations = if correction.is_converged_newtonian() { CN_MAX_ITERATIONS } else { 1 };
+
+    for _ in 0..iterations {
+        state = target_at(target_epoch(et, light_time, correction))?;
+        let new_light_time = (state.position - *observer).magnitude() / SPEED_OF_LIGHT;
+        let change = (new_light_time - light_time).abs();
         light_time = new_light_time;
+        if change <= CN_TOLERANCE * light_time {
+            break;
+        }
     }
-    
-    // Should not reach here
-    Err(SpiceError::new(
-        SpiceErrorType::ComputationError,
-        "Light time and aberration correction failed to converge".into()
-    ))
+
+    Ok((state, light_time))
 }
 
 /// Apply stellar aberration correction using relativistic formula
+///
+/// The apparent direction seen by an observer moving at β = v/c is
+/// `(u + γβ + γ²/(1+γ) (u·β) β) / (γ(1 + u·β))` for the geometric unit
+/// direction u. For transmission the observer's velocity is reversed, as in
+/// stlabx_c. The magnitude of `position` is preserved.
 fn apply_stellar_aberration_correction(
     position: &SpiceVector3,
     observer_velocity: &SpiceVector3,
     is_transmission: bool
 ) -> SpiceResult<SpiceVector3> {
-    // Stellar aberration is caused by the motion of the observer
-    // For small velocities (v << c), the correction is approximately v/c
-    
-    let c = SPEED_OF_LIGHT;
-    let v_magnitude = observer_velocity.magnitude();
-    
-    // Check for relativistic velocities (shouldn't happen for most spacecraft)
-    if v_magnitude >= 0.1 * c {
-        return Err(SpiceError::new(
-            SpiceErrorType::ComputationError,
-            format!("Observer velocity ({:.3} km/s) is relativistic - not supported", v_magnitude)
-        ));
-    }
-    
-    // Unit vector in direction of target
-    let position_magnitude = position.magnitude();
+    let (unit_pos, position_magnitude) = position.unit_and_magnitude();
     if position_magnitude == 0.0 {
         return Ok(*position);
     }
-    
-    let unit_pos = SpiceVector3::new(
-        position.0[0] / position_magnitude,
-        position.0[1] / position_magnitude,
-        position.0[2] / position_magnitude,
-    );
-    
-    // First-order stellar aberration correction
-    // Δθ ≈ (v/c) × sin(angle between v and r)
-    
-    // Component of observer velocity perpendicular to line of sight
-    let v_dot_r = observer_velocity.dot(&unit_pos);
-    let v_parallel = SpiceVector3::new(
-        unit_pos.0[0] * v_dot_r,
-        unit_pos.0[1] * v_dot_r,
-        unit_pos.0[2] * v_dot_r,
-    );
-    let v_perpendicular = observer_velocity.subtract(&v_parallel);
-    
-    // Aberration angle (small angle approximation)
-    let aberration_factor = if is_transmission {
-        -v_perpendicular.magnitude() / c
-    } else {
-        v_perpendicular.magnitude() / c
-    };
-    
-    // Apply correction: new_direction = old_direction + (v_perp / c)
-    let correction = SpiceVector3::new(
-        v_perpendicular.0[0] / c,
-        v_perpendicular.0[1] / c,
-        v_perpendicular.0[2] / c,
-    );
-    
-    let corrected_direction = if is_transmission {
-        unit_pos.subtract(&correction)
-    } else {
-        SpiceVector3::new(
-            unit_pos.0[0] + correction.0[0],
-            unit_pos.0[1] + correction.0[1],
-            unit_pos.0[2] + correction.0[2],
-        )
-    };
-    
-    // Renormalize and scale back to original magnitude
-    let corrected_magnitude = corrected_direction.magnitude();
-    if corrected_magnitude == 0.0 {
-        return Ok(*position);
+
+    let sign = if is_transmission { -1.0 } else { 1.0 };
+    let beta = observer_velocity.scale(sign / SPEED_OF_LIGHT);
+    let beta_squared = beta.dot(&beta);
+    if beta_squared >= 1.0 {
+        return Err(SpiceError::new(
+            SpiceErrorType::ComputationError,
+            format!("Observer velocity ({:.3} km/s) is not below the speed of light", observer_velocity.magnitude())
+        ));
     }
-    
-    Ok(SpiceVector3::new(
-        (corrected_direction.0[0] / corrected_magnitude) * position_magnitude,
-        (corrected_direction.0[1] / corrected_magnitude) * position_magnitude,
-        (corrected_direction.0[2] / corrected_magnitude) * position_magnitude,
-    ))
+
+    let gamma = 1.0 / (1.0 - beta_squared).sqrt();
+    let u_dot_beta = unit_pos.dot(&beta);
+    let apparent = (unit_pos + beta.scale(gamma + gamma * gamma / (1.0 + gamma) * u_dot_beta))
+        .scale(1.0 / (gamma * (1.0 + u_dot_beta)));
+
+    // The formula gives a unit vector; renormalize away rounding
+    Ok(apparent.unit_and_magnitude().0.scale(position_magnitude))
+}
+
+/// Apparent position of an object seen by an observer moving at `vobs`
+/// km/s, corrected for stellar aberration (equivalent to stelab_c)
+pub fn stellar_aberration(pobj: &SpiceVector3, vobs: &SpiceVector3) -> SpiceResult<SpiceVector3> {
+    apply_stellar_aberration_correction(pobj, vobs, false)
+}
+
+/// Stellar aberration for transmission from an observer moving at `vobs`
+/// km/s (equivalent to stlabx_c)
+///
+/// This is the inverse of `stellar_aberration`: applied to an apparent
+/// position it recovers the geometric direction.
+pub fn stellar_aberration_transmission(pobj: &SpiceVector3, vobs: &SpiceVector3) -> SpiceResult<SpiceVector3> {
+    apply_stellar_aberration_correction(pobj, vobs, true)
 }
 
 /// Get state (position and velocity) of a target relative to an observer  
@@ -554,24 +608,79 @@ pub fn ephemeris_state(
     let observer_code = body_name_to_code(observer)?;
     let correction = AberrationCorrection::from_str(aberration_correction)?;
     
+    state_by_code(target_code, et.seconds(), reference_frame, &correction, observer_code)
+}
+
+/// States of a target relative to an observer at many epochs
+///
+/// Matches calling `ephemeris_state` at each epoch, but the bodies and the
+/// correction are resolved once and the epochs are evaluated in time order,
+/// so consecutive lookups reuse the SPK reader's cached records. States are
+/// returned in the order of `ets`.
+pub fn ephemeris_states(
+    target: &str,
+    ets: &[f64],
+    reference_frame: &str,
+    aberration_correction: &str,
+    observer: &str
+) -> SpiceResult<Vec<StateVector>> {
+    let target_code = body_name_to_code(target)?;
+    let observer_code = body_name_to_code(observer)?;
+    let correction = AberrationCorrection::from_str(aberration_correction)?;
+
+    let mut order: Vec<usize> = (0..ets.len()).collect();
+    order.sort_by(|&a, &b| ets[a].total_cmp(&ets[b]));
+
+    let mut states = vec![None; ets.len()];
+    for index in order {
+        states[index] = Some(state_by_code(target_code, ets[index], reference_frame, &correction, observer_code)?);
+    }
+    Ok(states.into_iter().flatten().collect())
+}
+
+/// Corrected state of a target relative to an observer, both given as NAIF codes
+fn state_by_code(
+    target: i32,
+    et: f64,
+    reference_frame: &str,
+    correction: &AberrationCorrection,
+    observer: i32
+) -> SpiceResult<StateVector> {
+    // Name the missing link up front rather than failing on a barycentric leg
+    with_spk_reader(|reader| reader.check_connection(target, observer, et))?;
+
     // Get observer state relative to solar system barycenter
-    let observer_state = compute_geometric_state(observer_code, SOLAR_SYSTEM_BARYCENTER, et.seconds(), reference_frame)?;
+    let observer_state = compute_geometric_state(observer, SOLAR_SYSTEM_BARYCENTER, et, reference_frame)?;
     
     // Get target state relative to solar system barycenter  
-    let target_state = compute_geometric_state(target_code, SOLAR_SYSTEM_BARYCENTER, et.seconds(), reference_frame)?;
+    let target_state = compute_geometric_state(target, SOLAR_SYSTEM_BARYCENTER, et, reference_frame)?;
     
     // Apply aberration corrections
     apply_light_time_correction(
         &target_state,
         &observer_state, 
-        &correction,
-        target_code,
-        observer_code,
-        et.seconds(),
+        correction,
+        target,
+        observer,
+        et,
         reference_frame
     )
 }
 
+/// Geometric state of a target relative to an observer, without aberration
+/// corrections (equivalent to spkgeo_c)
+///
+/// The light time is the one-way light time of the geometric separation.
+pub fn geometric_state(target: i32, et: f64, frame: &str, observer: i32) -> SpiceResult<StateVector> {
+    state_by_code(target, et, frame, &AberrationCorrection::None, observer)
+}
+
+/// State of a body relative to the solar system barycenter (equivalent to
+/// spkssb_c)
+pub fn barycentric_state(body: i32, et: f64, frame: &str) -> SpiceResult<StateVector> {
+    compute_geometric_state(body, SOLAR_SYSTEM_BARYCENTER, et, frame)
+}
+
 /// Get position of a target relative to an observer
 /// Equivalent to spkezp_c  
 pub fn ephemeris_position(
@@ -596,92 +705,1045 @@ pub fn light_time(
     Ok(state.light_time)
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::time_system::str_to_et;
-    use crate::kernel_system::{initialize_kernel_system, furnish_kernel, clear_kernels};
+/// Epoch at the target for an observation at `et` with the given one-way light time
+///
+/// Without a light-time correction the target is taken at `et` itself.
+pub(crate) fn target_epoch(et: f64, light_time: f64, correction: &AberrationCorrection) -> f64 {
+    if !correction.includes_light_time() {
+        et
+    } else if correction.is_transmission() {
+        et + light_time
+    } else {
+        et - light_time
+    }
+}
 
-    /// Load standard test kernels
-    fn load_test_kernels() -> SpiceResult<()> {
-        initialize_kernel_system()?;
-        crate::spk_reader::initialize_spk_reader()?;
-        
-        // Try to load kernel from the kernels directory
-        match furnish_kernel("kernels/standard.mk") {
-            Ok(_) => Ok(()),
-            Err(_) => {
-                // Fallback: try to load individual kernels for CI/different working directories
-                let kernel_paths = [
-                    "kernels/spk/de442.bsp",
-                    "kernels/lsk/naif0012.tls", 
-                    "kernels/pck/pck00011.tpc",
-                    "../kernels/spk/de442.bsp",
-                    "../kernels/lsk/naif0012.tls",
-                    "../kernels/pck/pck00011.tpc",
-                    "../../kernels/spk/de442.bsp",
-                    "../../kernels/lsk/naif0012.tls",
-                    "../../kernels/pck/pck00011.tpc",
-                ];
-                
-                for path in &kernel_paths {
-                    let _ = furnish_kernel(path); // Ignore errors, try all paths
-                }
-                Ok(())
-            }
-        }
+// ============================================================================
+// ILLUMINATION GEOMETRY
+// ============================================================================
+
+/// Phase angle at a target between an illumination source and an observer
+/// (equivalent to phaseq_c)
+///
+/// Returns the angle in [0, π] between the target-to-illuminator and
+/// target-to-observer vectors. `abcorr` must be `"NONE"` or a reception
+/// light-time correction (`"LT"` or `"CN"`); the observer leg is corrected
+/// first, and the illuminator is taken relative to the target at the
+/// resulting target epoch with the same correction. Full phase (observer
+/// between the illuminator and target) is 0.
+pub fn phase_angle(
+    et: f64,
+    target: &str,
+    illuminator: &str,
+    observer: &str,
+    abcorr: &str
+) -> SpiceResult<f64> {
+    let correction = AberrationCorrection::from_str(abcorr)?;
+    if correction.includes_stellar_aberration() || correction.is_transmission() {
+        return Err(SpiceError::new(
+            SpiceErrorType::InvalidArgument,
+            format!("Phase angle accepts only reception light-time corrections, not '{}'", abcorr)
+        ));
     }
 
-    #[test]
-    fn test_body_name_to_code() {
-        // Test name to code conversion
-        assert_eq!(body_name_to_code("EARTH").unwrap(), EARTH);
-        assert_eq!(body_name_to_code("earth").unwrap(), EARTH);
-        assert_eq!(body_name_to_code("MARS").unwrap(), MARS);
-        assert_eq!(body_name_to_code("MOON").unwrap(), MOON);
-        assert_eq!(body_name_to_code("SUN").unwrap(), SUN);
-        
-        // Test numeric codes
-        assert_eq!(body_name_to_code("399").unwrap(), EARTH);
-        assert_eq!(body_name_to_code("499").unwrap(), MARS);
-        
-        // Test error case
-        assert!(body_name_to_code("UNKNOWN_BODY").is_err());
+    phase_angle_from_states(
+        state_by_code,
+        body_name_to_code(target)?,
+        et,
+        &correction,
+        body_name_to_code(illuminator)?,
+        body_name_to_code(observer)?
+    )
+}
+
+/// Phase angle computed from an arbitrary corrected state function taking
+/// (target, et, frame, correction, observer)
+fn phase_angle_from_states<F>(
+    state: F,
+    target: i32,
+    et: f64,
+    correction: &AberrationCorrection,
+    illuminator: i32,
+    observer: i32
+) -> SpiceResult<f64>
+where
+    F: Fn(i32, f64, &str, &AberrationCorrection, i32) -> SpiceResult<StateVector>,
+{
+    if target == illuminator || target == observer {
+        return Err(SpiceError::new(
+            SpiceErrorType::InvalidTarget,
+            format!(
+                "Phase angle is undefined for target {} seen from {} with illuminator {}",
+                target, observer, illuminator
+            )
+        ));
     }
 
-    #[test]
-    fn test_body_code_to_name() {
-        // Test code to name conversion
-        assert_eq!(body_code_to_name(EARTH).unwrap(), "EARTH");
-        assert_eq!(body_code_to_name(MARS).unwrap(), "MARS");
-        assert_eq!(body_code_to_name(MOON).unwrap(), "MOON");
-        
-        // Test error case
-        assert!(body_code_to_name(99999).is_err());
+    let observer_state = state(target, et, "J2000", correction, observer)?;
+    let epoch = target_epoch(et, observer_state.light_time, correction);
+    let illuminator_state = state(illuminator, epoch, "J2000", correction, target)?;
+
+    phase_angle_between(&observer_state.position.scale(-1.0), &illuminator_state.position)
+}
+
+/// Separation of the target-to-observer and target-to-illuminator directions
+pub(crate) fn phase_angle_between(
+    target_to_observer: &SpiceVector3,
+    target_to_sun: &SpiceVector3
+) -> SpiceResult<f64> {
+    if target_to_observer.magnitude() == 0.0 || target_to_sun.magnitude() == 0.0 {
+        return Err(SpiceError::new(
+            SpiceErrorType::InvalidArgument,
+            "Observer or Sun coincides with the target; phase angle is undefined".into()
+        ));
     }
+    vector_separation(target_to_observer, target_to_sun)
+}
 
-    #[test]
-    fn test_aberration_correction_parsing() {
-        // Test valid aberration corrections
-        assert_eq!(AberrationCorrection::from_str("NONE").unwrap(), AberrationCorrection::None);
-        assert_eq!(AberrationCorrection::from_str("LT").unwrap(), AberrationCorrection::LightTime);
-        assert_eq!(AberrationCorrection::from_str("CN").unwrap(), AberrationCorrection::ConvergedNewtonian);
-        assert_eq!(AberrationCorrection::from_str("LT+S").unwrap(), AberrationCorrection::LightTimeAndStellar);
-        assert_eq!(AberrationCorrection::from_str("CN+S").unwrap(), AberrationCorrection::ConvergedNewtonianAndStellar);
-        
-        // Test case insensitive
-        assert_eq!(AberrationCorrection::from_str("lt").unwrap(), AberrationCorrection::LightTime);
-        assert_eq!(AberrationCorrection::from_str("cn+s").unwrap(), AberrationCorrection::ConvergedNewtonianAndStellar);
-        
-        // Test transmission corrections
-        assert_eq!(AberrationCorrection::from_str("XLT").unwrap(), AberrationCorrection::TransmissionLightTime);
-        assert_eq!(AberrationCorrection::from_str("XCN+S").unwrap(), AberrationCorrection::TransmissionConvergedNewtonianAndStellar);
-        
-        // Test error case
-        assert!(AberrationCorrection::from_str("INVALID").is_err());
+// ============================================================================
+// OCCULTATIONS
+// ============================================================================
+
+/// Occultation codes returned by `occultation_type` (as in occult_c); negative
+/// codes mean target 1 is behind target 2, positive codes the reverse
+pub const OCCULT_TOTAL1: i32 = -3;
+pub const OCCULT_ANNULAR1: i32 = -2;
+pub const OCCULT_PARTIAL1: i32 = -1;
+pub const OCCULT_NONE: i32 = 0;
+pub const OCCULT_PARTIAL2: i32 = 1;
+pub const OCCULT_ANNULAR2: i32 = 2;
+pub const OCCULT_TOTAL2: i32 = 3;
+
+/// Number of limb points sampled when bracketing the extremes of a limb
+/// relative to another body's apparent outline
+const LIMB_SAMPLES: usize = 64;
+
+/// Limb-angle tolerance of the refined extremes (radians)
+const LIMB_ANGLE_TOLERANCE: f64 = 1e-10;
+
+/// A target as seen by the observer: its apparent position, plus the
+/// unit-sphere view of its ellipsoid unless it is a point
+#[derive(Debug, Clone, Copy)]
+struct ApparentBody {
+    position: SpiceVector3,
+    ellipsoid: Option<UnitSphereView>,
+}
+
+/// An ellipsoid mapped onto the unit sphere by its body-fixed axes and radii.
+/// Linear maps keep rays straight, so its apparent outline becomes a circular
+/// cone about the observer direction.
+#[derive(Debug, Clone, Copy)]
+struct UnitSphereView {
+    to_unit_sphere: SpiceMatrix3x3,
+    from_unit_sphere: SpiceMatrix3x3,
+    /// Observer position relative to the body center
+    observer: SpiceVector3,
+    /// Orthonormal axes across the observer direction
+    across: SpiceVector3,
+    up: SpiceVector3,
+}
+
+impl ApparentBody {
+    fn point(position: SpiceVector3) -> Self {
+        Self { position, ellipsoid: None }
     }
 
-    #[test]
+    /// An ellipsoid with `radii` along the axes of the body-fixed frame
+    /// `to_body` rotates into
+    fn ellipsoid(position: SpiceVector3, radii: [f64; 3], to_body: &SpiceMatrix3x3) -> SpiceResult<Self> {
+        let mut to_unit_sphere = *to_body;
+        for (row, radius) in to_unit_sphere.0.iter_mut().zip(radii) {
+            for value in row.iter_mut() {
+                *value /= radius;
+            }
+        }
+        let observer = to_unit_sphere.multiply_vector(&position.scale(-1.0));
+        if observer.magnitude() <= 1.0 {
+            return Err(SpiceError::new(
+                SpiceErrorType::InvalidObserver,
+                format!("Observer is inside the target ellipsoid with radii {:?}", radii)
+            ));
+        }
+        let axis = observer.unit()?;
+        let across = SpiceVector3::new(1.0, 0.0, 0.0).perpendicular(&axis).unit()
+            .or_else(|_| SpiceVector3::new(0.0, 1.0, 0.0).perpendicular(&axis).unit())?;
+        Ok(Self {
+            position,
+            ellipsoid: Some(UnitSphereView {
+                to_unit_sphere,
+                from_unit_sphere: to_unit_sphere.invert()?,
+                observer,
+                across,
+                up: axis.cross(&across),
+            }),
+        })
+    }
+
+    /// Angle of a direction outside the body's outline, measured in
+    /// unit-sphere coordinates: negative when a ray along it hits the body and
+    /// zero on the limb. For a point, the angle from its direction.
+    fn limb_margin(&self, direction: &SpiceVector3) -> f64 {
+        let Some(view) = &self.ellipsoid else {
+            return direction.angular_separation(&self.position);
+        };
+        let half_angle = (1.0 / view.observer.magnitude()).asin();
+        view.to_unit_sphere.multiply_vector(direction).angular_separation(&view.observer.scale(-1.0)) - half_angle
+    }
+
+    /// Observer-relative limb point at angle `t` around the outline, or the
+    /// position of a point target
+    fn limb_point(&self, t: f64) -> SpiceVector3 {
+        let Some(view) = &self.ellipsoid else {
+            return self.position;
+        };
+        // On the unit sphere the limb is a circle about the observer direction
+        let distance_squared = view.observer.dot(&view.observer);
+        let radius = (1.0 - 1.0 / distance_squared).sqrt();
+        let around = view.across.scale(t.cos()) + view.up.scale(t.sin());
+        let limb = view.observer.scale(1.0 / distance_squared) + around.scale(radius);
+        self.position + view.from_unit_sphere.multiply_vector(&limb)
+    }
+
+    /// Distance from the observer to the first surface point along a
+    /// direction, if the ray hits the body. For a point, its distance.
+    fn range_along(&self, direction: &SpiceVector3) -> Option<f64> {
+        let Some(view) = &self.ellipsoid else {
+            return Some(self.position.magnitude());
+        };
+        let ray = view.to_unit_sphere.multiply_vector(&direction.scale(1.0 / direction.magnitude()));
+        let a = ray.dot(&ray);
+        let b = view.observer.dot(&ray);
+        let c = view.observer.dot(&view.observer) - 1.0;
+        let discriminant = b * b - a * c;
+        if discriminant < 0.0 {
+            return None;
+        }
+        let near = (-b - discriminant.sqrt()) / a;
+        (near >= 0.0).then_some(near)
+    }
+
+    /// Smallest and largest limb margin of this body's limb relative to
+    /// `other`, with the limb angle of the smallest
+    fn limb_extremes(&self, other: &ApparentBody) -> SpiceResult<(f64, f64, f64)> {
+        let margin = |t: f64| other.limb_margin(&self.limb_point(t));
+        if self.ellipsoid.is_none() {
+            return Ok((margin(0.0), margin(0.0), 0.0));
+        }
+
+        // Bracket each extreme by sampling, then refine it within one step
+        let step = 2.0 * std::f64::consts::PI / LIMB_SAMPLES as f64;
+        let samples: Vec<f64> = (0..LIMB_SAMPLES).map(|k| margin(k as f64 * step)).collect();
+        let lowest = (0..LIMB_SAMPLES).min_by(|&i, &j| samples[i].total_cmp(&samples[j])).unwrap_or(0);
+        let highest = (0..LIMB_SAMPLES).max_by(|&i, &j| samples[i].total_cmp(&samples[j])).unwrap_or(0);
+        let bracket = |k: usize| ((k as f64 - 1.0) * step, (k as f64 + 1.0) * step);
+
+        let (low, high) = bracket(lowest);
+        let t_min = OptimizationMethods::golden_section_search(margin, low, high, LIMB_ANGLE_TOLERANCE, 200)?;
+        let (low, high) = bracket(highest);
+        let t_max = OptimizationMethods::golden_section_search(|t| -margin(t), low, high, LIMB_ANGLE_TOLERANCE, 200)?;
+
+        let (min, t_min) = if margin(t_min) < samples[lowest] {
+            (margin(t_min), t_min)
+        } else {
+            (samples[lowest], lowest as f64 * step)
+        };
+        Ok((min, margin(t_max).max(samples[highest]), t_min))
+    }
+}
+
+/// Occultation state of one target by another as seen by an observer
+/// (equivalent to occult_c)
+///
+/// Shapes are `"ELLIPSOID"`, using `BODY<code>_RADII` from the kernel pool
+/// oriented by the body-fixed frame `frame1`/`frame2`, or `"POINT"`; at most
+/// one target may be a point. With a light-time correction each frame is
+/// evaluated at the epoch the light left that target. A point target is only
+/// ever totally occulted, in transit (annular) or clear. Returns one of the
+/// `OCCULT_*` codes.
+#[allow(clippy::too_many_arguments)]
+pub fn occultation_type(
+    target1: &str,
+    shape1: &str,
+    frame1: &str,
+    target2: &str,
+    shape2: &str,
+    frame2: &str,
+    abcorr: &str,
+    observer: &str,
+    et: f64,
+) -> SpiceResult<i32> {
+    let radii1 = shape_radii(target1, shape1)?;
+    let radii2 = shape_radii(target2, shape2)?;
+    if radii1.is_none() && radii2.is_none() {
+        return Err(SpiceError::new(
+            SpiceErrorType::InvalidArgument,
+            format!("Targets {} and {} cannot both be points", target1, target2)
+        ));
+    }
+
+    let apparent = |target: &str, radii: Option<[f64; 3]>, frame: &str| {
+        apparent_body(target, radii, frame, abcorr, observer, et, &SpiceMatrix3x3::identity())
+    };
+    occultation_code(&apparent(target1, radii1, frame1)?, &apparent(target2, radii2, frame2)?)
+}
+
+/// A target as the observer sees it at `et`, with vectors expressed in the
+/// frame `j2000_to_view` rotates J2000 into. An ellipsoid with `radii` is
+/// oriented by the body-fixed `frame` at the epoch the light left the
+/// target; `None` radii make a point.
+fn apparent_body(
+    target: &str,
+    radii: Option<[f64; 3]>,
+    frame: &str,
+    abcorr: &str,
+    observer: &str,
+    et: f64,
+    j2000_to_view: &SpiceMatrix3x3,
+) -> SpiceResult<ApparentBody> {
+    let correction = AberrationCorrection::from_str(abcorr)?;
+    let state = ephemeris_state(target, EphemerisTime::new(et), "J2000", abcorr, observer)?;
+    let position = j2000_to_view.multiply_vector(&state.position);
+    let Some(radii) = radii else {
+        return Ok(ApparentBody::point(position));
+    };
+    let epoch = target_epoch(et, state.light_time, &correction);
+    let j2000_to_body = crate::coordinates::position_transform("J2000", frame, epoch)?;
+    ApparentBody::ellipsoid(position, radii, &j2000_to_body.multiply(&j2000_to_view.transpose()))
+}
+
+/// Radii of a target shape, or `None` for a point
+fn shape_radii(target: &str, shape: &str) -> SpiceResult<Option<[f64; 3]>> {
+    match shape.trim().to_uppercase().as_str() {
+        "POINT" => Ok(None),
+        "ELLIPSOID" => {
+            let radii = body_values_double(target, "RADII", 3)?;
+            if radii.len() != 3 || radii.iter().any(|&r| r <= 0.0) {
+                return Err(SpiceError::new(
+                    SpiceErrorType::InvalidArgument,
+                    format!("Radii of {} must be three positive values, got {:?}", target, radii)
+                ));
+            }
+            Ok(Some([radii[0], radii[1], radii[2]]))
+        }
+        _ => Err(SpiceError::new(
+            SpiceErrorType::InvalidArgument,
+            format!("Unsupported target shape '{}'", shape)
+        )),
+    }
+}
+
+/// Occultation code of two apparent bodies, positive when the first is in front
+fn occultation_code(body1: &ApparentBody, body2: &ApparentBody) -> SpiceResult<i32> {
+    let (min1, max1, t1) = body1.limb_extremes(body2)?;
+    let (min2, max2, t2) = body2.limb_extremes(body1)?;
+
+    // The first body is in front when, along a direction that reaches both,
+    // its surface is nearer
+    let first_in_front = |direction: SpiceVector3| {
+        let range1 = body1.range_along(&direction).unwrap_or(direction.magnitude());
+        let range2 = body2.range_along(&direction).unwrap_or(direction.magnitude());
+        range1 < range2
+    };
+
+    if max1 <= 0.0 {
+        // The first body's outline lies within the second's
+        return Ok(if first_in_front(body1.position) { OCCULT_ANNULAR2 } else { OCCULT_TOTAL1 });
+    }
+    if max2 <= 0.0 {
+        return Ok(if first_in_front(body2.position) { OCCULT_TOTAL2 } else { OCCULT_ANNULAR1 });
+    }
+    if min1 > 0.0 && min2 > 0.0 {
+        return Ok(OCCULT_NONE);
+    }
+
+    // The outlines cross; compare ranges along a limb point inside the other
+    let direction = if min1 <= 0.0 { body1.limb_point(t1) } else { body2.limb_point(t2) };
+    Ok(if first_in_front(direction) { OCCULT_PARTIAL2 } else { OCCULT_PARTIAL1 })
+}
+
+// ============================================================================
+// INSTRUMENT FIELDS OF VIEW
+// ============================================================================
+
+/// Number of limb directions sampled around an extended target
+const FOV_LIMB_SAMPLES: usize = 360;
+
+/// Whether a target is within an instrument's field of view (equivalent to fovtrg_c)
+///
+/// The FOV comes from `get_fov` and is placed by its frame at `et`. A POINT
+/// target is in view when its apparent direction lies inside the FOV. An
+/// ELLIPSOID target, with `BODY<code>_RADII` oriented by `tframe` at the
+/// light-time corrected epoch, is in view when its center or a point of its
+/// limb lies inside, or when the boresight or a boundary vector meets it.
+#[allow(clippy::too_many_arguments)]
+pub fn target_in_fov(
+    inst: i32,
+    target: &str,
+    tshape: &str,
+    tframe: &str,
+    abcorr: &str,
+    observer: &str,
+    et: f64,
+) -> SpiceResult<bool> {
+    let fov = get_fov(inst)?;
+    let radii = shape_radii(target, tshape)?;
+    let j2000_to_fov = crate::coordinates::position_transform("J2000", &fov.frame, et)?;
+    let body = apparent_body(target, radii, tframe, abcorr, observer, et, &j2000_to_fov)?;
+    fov_contains_body(&fov, &body)
+}
+
+/// Whether any part of a body, given in the FOV frame, lies in the FOV
+fn fov_contains_body(fov: &Fov, body: &ApparentBody) -> SpiceResult<bool> {
+    if fov_contains(fov, &body.position)? {
+        return Ok(true);
+    }
+    if body.ellipsoid.is_none() {
+        return Ok(false);
+    }
+
+    // A body covering the FOV is met by the boresight, and one poking into a
+    // corner by that boundary vector
+    let corners = if matches!(fov.shape, FovShape::Circle | FovShape::Ellipse) { &[][..] } else { &fov.bounds[..] };
+    if core::iter::once(&fov.boresight).chain(corners).any(|ray| body.range_along(ray).is_some()) {
+        return Ok(true);
+    }
+    for k in 0..FOV_LIMB_SAMPLES {
+        let angle = 2.0 * std::f64::consts::PI * k as f64 / FOV_LIMB_SAMPLES as f64;
+        if fov_contains(fov, &body.limb_point(angle))? {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// Whether a direction lies inside the FOV boundary cone or pyramid
+fn fov_contains(fov: &Fov, direction: &SpiceVector3) -> SpiceResult<bool> {
+    let boresight = fov.boresight.unit()?;
+    if fov.shape == FovShape::Circle {
+        let half_angle = vector_separation(&boresight, &fov.bounds[0])?;
+        return Ok(vector_separation(&boresight, direction)? <= half_angle);
+    }
+
+    // Compare in the plane one unit along the boresight
+    let project = |v: &SpiceVector3| -> Option<SpiceVector3> {
+        let along = v.dot(&boresight);
+        (along > 0.0).then(|| v.scale(1.0 / along) - boresight)
+    };
+    let Some(point) = project(direction) else {
+        return Ok(false);
+    };
+    let bounds: Vec<SpiceVector3> = fov.bounds.iter().map(project).collect::<Option<_>>().ok_or_else(|| SpiceError::new(
+        SpiceErrorType::InvalidKernelData,
+        format!("FOV boundary of frame {} reaches 90 degrees or more from the boresight", fov.frame)
+    ))?;
+
+    if fov.shape == FovShape::Ellipse {
+        let (major, a) = bounds[0].unit_and_magnitude();
+        let (minor, b) = bounds[1].unit_and_magnitude();
+        return Ok((point.dot(&major) / a).powi(2) + (point.dot(&minor) / b).powi(2) <= 1.0);
+    }
+
+    // Crossing-number test in the plane, using coordinates along the first
+    // corner and perpendicular to it
+    let x_axis = bounds[0].unit()?;
+    let y_axis = boresight.cross(&x_axis);
+    let planar = |v: &SpiceVector3| (v.dot(&x_axis), v.dot(&y_axis));
+    let (px, py) = planar(&point);
+    let mut inside = false;
+    for (i, corner) in bounds.iter().enumerate() {
+        let (x1, y1) = planar(corner);
+        let (x2, y2) = planar(&bounds[(i + 1) % bounds.len()]);
+        if (y1 > py) != (y2 > py) && px < x1 + (py - y1) * (x2 - x1) / (y2 - y1) {
+            inside = !inside;
+        }
+    }
+    Ok(inside)
+}
+
+// ============================================================================
+// FLYBY GEOMETRY
+// ============================================================================
+
+/// Number of intervals in the coarse scan that brackets a distance minimum
+const CLOSEST_APPROACH_SCAN_STEPS: usize = 200;
+
+/// Convergence tolerance for the refined closest-approach epoch (seconds)
+const CLOSEST_APPROACH_TOLERANCE: f64 = 1e-3;
+
+/// Epoch and distance of closest approach between two bodies within a window
+///
+/// Geometric J2000 distances are sampled across `window` (ET seconds) to
+/// bracket the global minimum, which is then refined by golden section search.
+/// When the distance is monotonic over the window the minimum is at an endpoint.
+pub fn closest_approach(
+    target: &str,
+    observer: &str,
+    window: (f64, f64)
+) -> SpiceResult<(EphemerisTime, f64)> {
+    let target_code = body_name_to_code(target)?;
+    let observer_code = body_name_to_code(observer)?;
+
+    minimum_distance(
+        |et| {
+            let state = state_by_code(target_code, et, "J2000", &AberrationCorrection::None, observer_code)?;
+            Ok(state.position.magnitude())
+        },
+        window
+    )
+}
+
+/// Bracket the smallest sampled distance, then refine it within that bracket
+fn minimum_distance<F>(distance: F, window: (f64, f64)) -> SpiceResult<(EphemerisTime, f64)>
+where
+    F: Fn(f64) -> SpiceResult<f64>,
+{
+    let (start, end) = window;
+    if !(start.is_finite() && end.is_finite()) || end < start {
+        return Err(SpiceError::new(
+            SpiceErrorType::InvalidArgument,
+            format!("Invalid closest-approach window [{}, {}]", start, end)
+        ));
+    }
+
+    let step = (end - start) / CLOSEST_APPROACH_SCAN_STEPS as f64;
+    let mut best_index = 0;
+    let mut best_distance = f64::INFINITY;
+    for i in 0..=CLOSEST_APPROACH_SCAN_STEPS {
+        let d = distance(start + i as f64 * step)?;
+        if d < best_distance {
+            best_index = i;
+            best_distance = d;
+        }
+    }
+    let mut best_et = start + best_index as f64 * step;
+    if step == 0.0 {
+        return Ok((EphemerisTime::new(best_et), best_distance));
+    }
+
+    // The minimum lies within one scan step of the best sample
+    let low = start + best_index.saturating_sub(1) as f64 * step;
+    let high = start + (best_index + 1).min(CLOSEST_APPROACH_SCAN_STEPS) as f64 * step;
+
+    // The search needs a plain f64 objective; keep the first failure aside
+    let failure = core::cell::RefCell::new(None);
+    let refined = OptimizationMethods::golden_section_search(
+        |et| distance(et).unwrap_or_else(|e| {
+            failure.borrow_mut().get_or_insert(e);
+            f64::INFINITY
+        }),
+        low,
+        high,
+        CLOSEST_APPROACH_TOLERANCE,
+        200
+    )?;
+    if let Some(error) = failure.into_inner() {
+        return Err(error);
+    }
+
+    // An endpoint minimum is only approached by the search, never sampled
+    let refined_distance = distance(refined)?;
+    if refined_distance < best_distance {
+        best_et = refined;
+        best_distance = refined_distance;
+    }
+    Ok((EphemerisTime::new(best_et), best_distance))
+}
+
+// ============================================================================
+// GEOMETRY FINDER
+// ============================================================================
+
+/// Convergence tolerance for geometry finder event times (seconds)
+const GF_TOLERANCE: f64 = 1e-6;
+
+/// Iteration limit for refining one geometry finder event
+const GF_MAX_ITERATIONS: usize = 200;
+
+/// Relational conditions accepted by the geometry finder
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum GfRelation {
+    Equals,
+    LessThan,
+    GreaterThan,
+    LocalMinimum,
+    LocalMaximum,
+}
+
+impl GfRelation {
+    fn from_str(relation: &str) -> SpiceResult<Self> {
+        match relation.trim().to_uppercase().as_str() {
+            "=" => Ok(Self::Equals),
+            "<" => Ok(Self::LessThan),
+            ">" => Ok(Self::GreaterThan),
+            "LOCMIN" => Ok(Self::LocalMinimum),
+            "LOCMAX" => Ok(Self::LocalMaximum),
+            _ => Err(SpiceError::new(
+                SpiceErrorType::InvalidArgument,
+                format!("Unsupported geometry finder relation '{}'", relation)
+            )),
+        }
+    }
+}
+
+/// Times within `confinement` when the observer-target distance satisfies a
+/// condition (equivalent to gfdist_c)
+///
+/// `relation` is one of `"="`, `"<"`, `">"`, `"LOCMIN"` or `"LOCMAX"`;
+/// `reference_value` (km) is ignored for the extrema. The distance is sampled
+/// every `step` seconds, so `step` must be shorter than the time between any
+/// two events for all of them to be found. Each bracketed event is refined
+/// with Brent's method.
+pub fn gf_distance(
+    target: &str,
+    observer: &str,
+    abcorr: &str,
+    relation: &str,
+    reference_value: f64,
+    step: f64,
+    confinement: &SpiceWindow,
+) -> SpiceResult<SpiceWindow> {
+    let target_code = body_name_to_code(target)?;
+    let observer_code = body_name_to_code(observer)?;
+    let correction = AberrationCorrection::from_str(abcorr)?;
+
+    search_distance(
+        |et| {
+            // The range rate is the velocity along the line of sight
+            let state = state_by_code(target_code, et, "J2000", &correction, observer_code)?;
+            let range = state.position.magnitude();
+            Ok((range, state.position.dot(&state.velocity) / range))
+        },
+        GfRelation::from_str(relation)?,
+        reference_value,
+        step,
+        confinement
+    )
+}
+
+/// Geometry finder search on an arbitrary distance function
+///
+/// `distance` returns the distance and its rate of change at an epoch, and is
+/// only evaluated inside the confinement window.
+fn search_distance<F>(
+    distance: F,
+    relation: GfRelation,
+    reference_value: f64,
+    step: f64,
+    confinement: &SpiceWindow,
+) -> SpiceResult<SpiceWindow>
+where
+    F: Fn(f64) -> SpiceResult<(f64, f64)>,
+{
+    if !(step.is_finite() && step > 0.0) {
+        return Err(SpiceError::new(
+            SpiceErrorType::InvalidArgument,
+            format!("Geometry finder step must be positive, got {}", step)
+        ));
+    }
+
+    let mut result = SpiceWindow::new();
+    for &[start, end] in confinement.intervals() {
+        match relation {
+            GfRelation::Equals | GfRelation::LessThan | GfRelation::GreaterThan => {
+                let offset = |et: f64| Ok(distance(et)?.0 - reference_value);
+                let roots = sign_changes(&offset, start, end, step)?;
+                if relation == GfRelation::Equals {
+                    for root in roots {
+                        result.insert_interval(root, root)?;
+                    }
+                    continue;
+                }
+
+                // The condition flips at every root
+                let holds = |value: f64| if relation == GfRelation::LessThan { value < 0.0 } else { value > 0.0 };
+                let mut inside = holds(offset(start)?);
+                let mut interval_start = start;
+                for root in roots {
+                    if inside {
+                        result.insert_interval(interval_start, root)?;
+                    }
+                    inside = !inside;
+                    interval_start = root;
+                }
+                if inside {
+                    result.insert_interval(interval_start, end)?;
+                }
+            }
+            GfRelation::LocalMinimum | GfRelation::LocalMaximum => {
+                // Extrema are where the rate of change crosses zero in the right sense
+                let h = (step * 1e-3).min(1.0);
+                let rate = |et: f64| Ok(distance(et)?.1);
+                for root in sign_changes(&rate, start, end, step)? {
+                    let rising = rate((root + h).min(end))? > rate((root - h).max(start))?;
+                    if rising == (relation == GfRelation::LocalMinimum) {
+                        result.insert_interval(root, root)?;
+                    }
+                }
+            }
+        }
+    }
+    Ok(result)
+}
+
+/// Times in [start, end] where `f` changes sign between samples `step` apart,
+/// each refined by Brent's method
+fn sign_changes<F>(f: &F, start: f64, end: f64, step: f64) -> SpiceResult<Vec<f64>>
+where
+    F: Fn(f64) -> SpiceResult<f64>,
+{
+    let mut roots = Vec::new();
+    let mut t0 = start;
+    let mut f0 = f(t0)?;
+    while t0 < end {
+        let t1 = (t0 + step).min(end);
+        let f1 = f(t1)?;
+        if (f0 > 0.0) != (f1 > 0.0) {
+            // Brent's method needs a plain f64 function; keep the first failure aside
+            let failure = core::cell::RefCell::new(None);
+            let root = OptimizationMethods::brent_method(
+                |et| f(et).unwrap_or_else(|e| {
+                    failure.borrow_mut().get_or_insert(e);
+                    0.0
+                }),
+                t0,
+                t1,
+                GF_TOLERANCE,
+                GF_MAX_ITERATIONS
+            )?;
+            if let Some(error) = failure.into_inner() {
+                return Err(error);
+            }
+            roots.push(root.clamp(t0, t1));
+        }
+        t0 = t1;
+        f0 = f1;
+    }
+    Ok(roots)
+}
+
+// ============================================================================
+// EPHEMERIS VALIDATION
+// ============================================================================
+
+/// Differences between an ephemeris and a reference orbit over a set of epochs
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResidualReport {
+    /// Largest position difference (km)
+    pub max_position: f64,
+    /// Root-mean-square position difference (km)
+    pub rms_position: f64,
+    /// Largest velocity difference (km/s)
+    pub max_velocity: f64,
+    /// Root-mean-square velocity difference (km/s)
+    pub rms_velocity: f64,
+    /// Number of epochs compared
+    pub samples: usize,
+    /// Epochs skipped because the ephemeris does not cover them
+    pub skipped_epochs: Vec<f64>,
+}
+
+/// Compare the loaded ephemeris of `target` relative to `center` with a conic
+/// orbit
+///
+/// `n` epochs are spaced evenly across `window`, endpoints included. The
+/// conic is propagated from `elements` with gravitational parameter `mu`
+/// (km³/s²); `elements.n` should be the matching mean motion. Epochs the
+/// loaded kernels do not cover are listed in the report rather than failing
+/// the comparison.
+pub fn compare_to_conic(
+    target: &str,
+    center: &str,
+    elements: &OrbitalElements,
+    mu: f64,
+    window: (f64, f64),
+    n: usize,
+) -> SpiceResult<ResidualReport> {
+    let target_code = body_name_to_code(target)?;
+    let center_code = body_name_to_code(center)?;
+
+    conic_residuals(
+        |et| with_spk_reader(|reader| reader.compute_chained_state(target_code, center_code, et)),
+        elements,
+        mu,
+        window,
+        n
+    )
+}
+
+/// Residuals of an arbitrary state function against a conic orbit
+fn conic_residuals<F>(
+    state: F,
+    elements: &OrbitalElements,
+    mu: f64,
+    window: (f64, f64),
+    n: usize,
+) -> SpiceResult<ResidualReport>
+where
+    F: Fn(f64) -> SpiceResult<StateVector>,
+{
+    let (start, end) = window;
+    if n == 0 || !(start.is_finite() && end.is_finite()) || end < start {
+        return Err(SpiceError::new(
+            SpiceErrorType::InvalidArgument,
+            format!("Cannot sample {} epochs over window [{}, {}]", n, start, end)
+        ));
+    }
+
+    let step = if n > 1 { (end - start) / (n - 1) as f64 } else { 0.0 };
+    let mut report = ResidualReport {
+        max_position: 0.0,
+        rms_position: 0.0,
+        max_velocity: 0.0,
+        rms_velocity: 0.0,
+        samples: 0,
+        skipped_epochs: Vec::new(),
+    };
+    let (mut position_squares, mut velocity_squares) = (0.0, 0.0);
+
+    for i in 0..n {
+        let et = if i + 1 == n { end } else { start + i as f64 * step };
+        let actual = match state(et) {
+            Ok(actual) => actual,
+            Err(e) if e.error_type == SpiceErrorType::InsufficientData => {
+                report.skipped_epochs.push(et);
+                continue;
+            }
+            Err(e) => return Err(e),
+        };
+        let expected = compute_orbital_state(elements, mu, et)?;
+
+        let dr = (actual.position - expected.position).magnitude();
+        let dv = (actual.velocity - expected.velocity).magnitude();
+        report.max_position = report.max_position.max(dr);
+        report.max_velocity = report.max_velocity.max(dv);
+        position_squares += dr * dr;
+        velocity_squares += dv * dv;
+        report.samples += 1;
+    }
+
+    if report.samples > 0 {
+        report.rms_position = (position_squares / report.samples as f64).sqrt();
+        report.rms_velocity = (velocity_squares / report.samples as f64).sqrt();
+    }
+    Ok(report)
+}
+
+// ============================================================================
+// SURFACE INTERCEPTS
+// ============================================================================
+
+/// Body-fixed surface point seen along an instrument boresight
+///
+/// The boresight is read from `INS<instrument>_BORESIGHT` and is expressed in
+/// the frame named by `INS<instrument>_FOV_FRAME`, which may be an FK frame
+/// reached through a TK/CK chain. Returns `None` when the boresight ray misses
+/// the target ellipsoid.
+pub fn boresight_ground_point(
+    instrument: i32,
+    et: EphemerisTime,
+    target: i32,
+    fixref: &str,
+    correction: AberrationCorrection,
+    observer: i32
+) -> SpiceResult<Option<SpiceVector3>> {
+    let boresight_name = format!("INS{}_BORESIGHT", instrument);
+    let boresight = get_required_doubles(&boresight_name, 3)?;
+    let frame_name = format!("INS{}_FOV_FRAME", instrument);
+    let (frame, found) = get_character_pool(&frame_name, 0, 1)?;
+    if !found || frame.is_empty() {
+        return Err(SpiceError::new(
+            SpiceErrorType::InsufficientData,
+            format!("Kernel variable {} is not defined", frame_name)
+        ));
+    }
+
+    let radii = body_data(&target.to_string(), "RADII")?;
+    if radii.len() != 3 {
+        return Err(SpiceError::new(
+            SpiceErrorType::InsufficientData,
+            format!("Body {} does not have three radii", target)
+        ));
+    }
+
+    // Target position seen from the observer, in J2000
+    let state = state_by_code(target, et.seconds(), "J2000", &correction, observer)?;
+
+    // The body-fixed frame is evaluated at the light-time corrected epoch
+    let target_epoch = target_epoch(et.seconds(), state.light_time, &correction);
+    let j2000_to_fixed = get_position_transformation("J2000", fixref, EphemerisTime::new(target_epoch))?;
+
+    let boresight = SpiceVector3::new(boresight[0], boresight[1], boresight[2]);
+    let boresight_j2000 = get_position_transformation(&frame[0], "J2000", et)?.multiply_vector(&boresight);
+
+    let direction = j2000_to_fixed.multiply_vector(&boresight_j2000);
+    let observer_fixed = j2000_to_fixed.multiply_vector(&state.position).scale(-1.0);
+
+    ellipsoid_ray_intercept(&observer_fixed, &direction, [radii[0], radii[1], radii[2]])
+}
+
+/// Intersect a ray with a triaxial ellipsoid centred at the origin (equivalent to surfpt_c)
+///
+/// Returns the nearest intercept along the ray, or `None` if the ray misses.
+pub(crate) fn ellipsoid_ray_intercept(
+    vertex: &SpiceVector3,
+    direction: &SpiceVector3,
+    radii: [f64; 3]
+) -> SpiceResult<Option<SpiceVector3>> {
+    if radii.iter().any(|&r| r <= 0.0) {
+        return Err(SpiceError::new(
+            SpiceErrorType::InvalidArgument,
+            format!("Ellipsoid radii must be positive: {:?}", radii)
+        ));
+    }
+    if direction.magnitude() == 0.0 {
+        return Err(SpiceError::new(
+            SpiceErrorType::InvalidArgument,
+            "Ray direction is the zero vector".into()
+        ));
+    }
+
+    // Scale the problem so the ellipsoid becomes the unit sphere
+    let v = SpiceVector3::new(vertex.0[0] / radii[0], vertex.0[1] / radii[1], vertex.0[2] / radii[2]);
+    let d = SpiceVector3::new(direction.0[0] / radii[0], direction.0[1] / radii[1], direction.0[2] / radii[2]);
+
+    let a = d.dot(&d);
+    let b = 2.0 * v.dot(&d);
+    let c = v.dot(&v) - 1.0;
+    let discriminant = b * b - 4.0 * a * c;
+    if discriminant < 0.0 {
+        return Ok(None);
+    }
+
+    let root = discriminant.sqrt();
+    let near = (-b - root) / (2.0 * a);
+    let far = (-b + root) / (2.0 * a);
+    let t = if near >= 0.0 {
+        near
+    } else if far >= 0.0 {
+        far
+    } else {
+        return Ok(None);
+    };
+
+    Ok(Some(SpiceVector3::new(
+        vertex.0[0] + t * direction.0[0],
+        vertex.0[1] + t * direction.0[1],
+        vertex.0[2] + t * direction.0[2],
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::time_system::str_to_et;
+    use crate::kernel_system::{initialize_kernel_system, furnish_kernel, clear_kernels, IsolatedKernels};
+    use crate::body_data::{SUN, PLUTO};
+
+    /// Load standard test kernels
+    fn load_test_kernels() -> SpiceResult<()> {
+        initialize_kernel_system()?;
+        crate::spk_reader::initialize_spk_reader()?;
+        
+        // Try to load kernel from the kernels directory
+        match furnish_kernel("kernels/standard.mk") {
+            Ok(_) => Ok(()),
+            Err(_) => {
+                // Fallback: try to load individual kernels for CI/different working directories
+                let kernel_paths = [
+                    "kernels/spk/de442.bsp",
+                    "kernels/lsk/naif0012.tls", 
+                    "kernels/pck/pck00011.tpc",
+                    "../kernels/spk/de442.bsp",
+                    "../kernels/lsk/naif0012.tls",
+                    "../kernels/pck/pck00011.tpc",
+                    "../../kernels/spk/de442.bsp",
+                    "../../kernels/lsk/naif0012.tls",
+                    "../../kernels/pck/pck00011.tpc",
+                ];
+                
+                for path in &kernel_paths {
+                    let _ = furnish_kernel(path); // Ignore errors, try all paths
+                }
+                Ok(())
+            }
+        }
+    }
+
+    #[test]
+    fn test_body_name_to_code() {
+        // Test name to code conversion
+        assert_eq!(body_name_to_code("EARTH").unwrap(), EARTH);
+        assert_eq!(body_name_to_code("earth").unwrap(), EARTH);
+        assert_eq!(body_name_to_code("MARS").unwrap(), MARS);
+        assert_eq!(body_name_to_code("MOON").unwrap(), MOON);
+        assert_eq!(body_name_to_code("SUN").unwrap(), SUN);
+        
+        // Test numeric codes
+        assert_eq!(body_name_to_code("399").unwrap(), EARTH);
+        assert_eq!(body_name_to_code("499").unwrap(), MARS);
+        
+        // Test error case
+        assert!(body_name_to_code("UNKNOWN_BODY").is_err());
+    }
+
+    #[test]
+    fn test_define_body_resolves_both_directions() {
+        // The kernel guard drops the definition again
+        let _kernels = IsolatedKernels::new();
+        define_body("ROSETTA", -226).unwrap();
+        assert_eq!(body_name_to_code("Rosetta").unwrap(), -226);
+        assert_eq!(body_name_to_code("  rosetta ").unwrap(), -226);
+        assert_eq!(body_code_to_name(-226).unwrap(), "ROSETTA");
+        assert!(define_body(" ", -226).is_err());
+
+        // Body constants are found through the same registry
+        crate::kernel_pool::load_text_kernel("\\begindata\nBODY-226_GM = ( 1.5D-8 )\n\\begintext\n").unwrap();
+        assert_eq!(gravitational_parameter("Rosetta").unwrap(), 1.5e-8);
+        assert!(crate::body_data::body_recognized("ROSETTA"));
+    }
+
+    #[test]
+    fn test_body_lookups_borrow_the_cached_registry() {
+        for i in 0..1000 {
+            let name = if i % 2 == 0 { "Jupiter Barycenter" } else { "MOON" };
+            let code = body_name_to_code(name).unwrap();
+            assert_eq!(body_code_to_name(code).unwrap(), name.to_uppercase());
+        }
+    }
+
+    #[test]
+    fn test_body_code_to_name() {
+        // Test code to name conversion
+        assert_eq!(body_code_to_name(EARTH).unwrap(), "EARTH");
+        assert_eq!(body_code_to_name(MARS).unwrap(), "MARS");
+        assert_eq!(body_code_to_name(MOON).unwrap(), "MOON");
+        
+        // Test error case
+        assert!(body_code_to_name(99999).is_err());
+    }
+
+    #[test]
+    fn test_aberration_correction_parsing() {
+        // Test valid aberration corrections
+        assert_eq!(AberrationCorrection::from_str("NONE").unwrap(), AberrationCorrection::None);
+        assert_eq!(AberrationCorrection::from_str("LT").unwrap(), AberrationCorrection::LightTime);
+        assert_eq!(AberrationCorrection::from_str("CN").unwrap(), AberrationCorrection::ConvergedNewtonian);
+        assert_eq!(AberrationCorrection::from_str("LT+S").unwrap(), AberrationCorrection::LightTimeAndStellar);
+        assert_eq!(AberrationCorrection::from_str("CN+S").unwrap(), AberrationCorrection::ConvergedNewtonianAndStellar);
+        
+        // Test case insensitive
+        assert_eq!(AberrationCorrection::from_str("lt").unwrap(), AberrationCorrection::LightTime);
+        assert_eq!(AberrationCorrection::from_str("cn+s").unwrap(), AberrationCorrection::ConvergedNewtonianAndStellar);
+        
+        // Test transmission corrections
+        assert_eq!(AberrationCorrection::from_str("XLT").unwrap(), AberrationCorrection::TransmissionLightTime);
+        assert_eq!(AberrationCorrection::from_str("XCN+S").unwrap(), AberrationCorrection::TransmissionConvergedNewtonianAndStellar);
+        
+        // Test error case
+        assert!(AberrationCorrection::from_str("INVALID").is_err());
+    }
+
+    #[test]
     fn test_aberration_correction_properties() {
         let none = AberrationCorrection::None;
         let lt = AberrationCorrection::LightTime;
@@ -722,6 +1784,189 @@ mod tests {
         assert!((circular_e_anom - mean_anomaly).abs() < 1e-10);
     }
 
+    #[test]
+    fn test_kepler_equation_high_eccentricity() {
+        // Reference value computed with 30-digit arithmetic
+        let eccentric_anomaly = solve_kepler_equation(0.1, 0.97);
+        assert!((eccentric_anomaly - 0.786_972_673_433_771).abs() < 1e-12);
+
+        // Near-parabolic orbits must still reach the residual tolerance
+        for &e in &[0.9, 0.99, 0.999] {
+            for &m in &[1e-4, 0.01, 0.1, 1.0, 3.0, -2.5, 7.0] {
+                let e_anom = solve_kepler_equation(m, e);
+                let residual = e_anom - e * e_anom.sin() - m;
+                assert!(residual.abs() < 1e-12, "e={} M={} residual={}", e, m, residual);
+            }
+        }
+    }
+
+    #[test]
+    fn test_two_body_propagation_follows_conics() {
+        let mu = 398600.4418;
+        let radius: f64 = 7000.0;
+        let speed = (mu / radius).sqrt();
+        let circular = StateVector::new(SpiceVector3::new(radius, 0.0, 0.0), SpiceVector3::new(0.0, speed, 0.0), 0.0);
+        let period = 2.0 * std::f64::consts::PI * (radius.powi(3) / mu).sqrt();
+
+        // A quarter period turns a circular orbit by 90 degrees
+        let quarter = propagate_two_body(&circular, mu, period / 4.0).unwrap();
+        assert!((quarter.position - SpiceVector3::new(0.0, radius, 0.0)).magnitude() < 1e-6);
+        assert!((quarter.velocity - SpiceVector3::new(-speed, 0.0, 0.0)).magnitude() < 1e-9);
+        // Whole revolutions are removed before propagating
+        let later = propagate_two_body(&circular, mu, 100.0 * period + period / 4.0).unwrap();
+        assert!((later.position - quarter.position).magnitude() < 1e-5);
+
+        // Elliptic and hyperbolic states return to the start
+        for v in [SpiceVector3::new(0.5, 8.1, 1.2), SpiceVector3::new(2.0, 11.5, -1.0)] {
+            let start = StateVector::new(SpiceVector3::new(radius, 300.0, -200.0), v, 0.0);
+            let ahead = propagate_two_body(&start, mu, 5400.0).unwrap();
+            let back = propagate_two_body(&ahead, mu, -5400.0).unwrap();
+            assert!((back.position - start.position).magnitude() < 1e-6);
+            assert!((back.velocity - start.velocity).magnitude() < 1e-9);
+
+            // Energy and angular momentum are conserved
+            let energy = |s: &StateVector| s.velocity.dot(&s.velocity) / 2.0 - mu / s.position.magnitude();
+            assert!((energy(&ahead) - energy(&start)).abs() < 1e-9);
+            let momentum = |s: &StateVector| s.position.cross(&s.velocity);
+            assert!((momentum(&ahead) - momentum(&start)).magnitude() < 1e-6);
+        }
+
+        assert!(propagate_two_body(&circular, 0.0, 10.0).is_err());
+    }
+
+    #[test]
+    fn test_propagate_uses_center_gm_from_pool() {
+        use crate::spk_reader::write;
+        use crate::kernel_system::furnish_kernel_from_bytes;
+
+        let gm = 398600.435436;
+        let (radius, speed): (f64, f64) = (6778.0, (gm / 6778.0_f64).sqrt());
+        // A window far from other tests' epochs: Earth fixed at 1 AU, and a
+        // LEO state at the window midpoint
+        let (start, end) = (9.0e9, 9.0e9 + 100.0);
+        let et = start + 50.0;
+        let mut spk = write::new_spk("LEO").unwrap();
+        let earth = write::ChebyshevRecord { start, end, x: vec![1.496e8, 0.0], y: vec![0.0; 2], z: vec![0.0; 2] };
+        let leo = write::ChebyshevRecord { start, end, x: vec![radius, 0.0], y: vec![0.0, 50.0 * speed], z: vec![0.0; 2] };
+        write::add_type2_segment(&mut spk, 399, 0, "J2000", start, end, "EARTH", &[earth]).unwrap();
+        write::add_type2_segment(&mut spk, -9501, 399, "J2000", start, end, "LEO", &[leo]).unwrap();
+        let bytes = spk.to_bytes().unwrap();
+        let pck = format!("\\begindata\nBODY399_GM = {}\n", gm);
+
+        let period = 2.0 * std::f64::consts::PI * (radius.powi(3) / gm).sqrt();
+        let kernels = IsolatedKernels::new();
+        kernels.load_spk("leo.bsp", &bytes);
+        furnish_kernel_from_bytes(pck.into_bytes(), "/pck/earth_gm.tpc").unwrap();
+        let state = propagate("-9501", "EARTH", et, period / 4.0).unwrap();
+
+        assert_eq!(gravitational_parameter("EARTH").unwrap(), gm);
+        let start_state = StateVector::new(SpiceVector3::new(radius, 0.0, 0.0), SpiceVector3::new(0.0, speed, 0.0), 0.0);
+        let expected = propagate_two_body(&start_state, gm, period / 4.0).unwrap();
+        assert!((state.position - expected.position).magnitude() < 1e-6);
+        assert!((state.position - SpiceVector3::new(0.0, radius, 0.0)).magnitude() < 1e-6);
+        assert!((state.velocity - SpiceVector3::new(-speed, 0.0, 0.0)).magnitude() < 1e-9);
+
+        // No GM for the center in the pool
+        assert_eq!(propagate("-9501", "-9501", et, 10.0).unwrap_err().error_type, SpiceErrorType::InsufficientData);
+    }
+
+    #[test]
+    fn test_nadir_boresight_hits_sub_observer_point() {
+        let radii = [6378.137, 6378.137, 6356.752];
+        let observer = SpiceVector3::new(4000.0, -5000.0, 3500.0);
+
+        // Sub-observer point by the intercept method: scale the observer
+        // position onto the ellipsoid surface
+        let scale = ((observer.x() / radii[0]).powi(2)
+            + (observer.y() / radii[1]).powi(2)
+            + (observer.z() / radii[2]).powi(2)).sqrt();
+        let sub_observer = observer.scale(1.0 / scale);
+
+        let nadir = observer.scale(-1.0);
+        let point = ellipsoid_ray_intercept(&observer, &nadir, radii).unwrap().unwrap();
+        for i in 0..3 {
+            assert!((point.0[i] - sub_observer.0[i]).abs() < 1e-9);
+        }
+
+        // Pointing away from the body misses it
+        assert!(ellipsoid_ray_intercept(&observer, &observer, radii).unwrap().is_none());
+
+        // Limb-grazing rays just outside the body miss as well
+        let beside = SpiceVector3::new(0.0, 6378.2, 0.0);
+        let along_x = SpiceVector3::new(1.0, 0.0, 0.0);
+        assert!(ellipsoid_ray_intercept(&beside, &along_x, radii).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_boresight_ground_point_matches_cspice_sincpt() {
+        use crate::kernel_system::furnish_kernel_from_bytes;
+
+        let kernels = IsolatedKernels::new();
+        load_occultation_scene(&kernels);
+        let ik = "\\begindata
+INS-9601001_BORESIGHT = ( 3.0D6 7000.0 2000.0 )
+INS-9601001_FOV_FRAME = 'J2000'
+INS-9601002_BORESIGHT = ( 1.0 0.0 0.0 )
+INS-9601002_FOV_FRAME = 'J2000'
+INS-9601003_BORESIGHT = ( 3.0D6 6000.0 2000.0 )
+INS-9601003_FOV_FRAME = 'J2000'
+";
+        furnish_kernel_from_bytes(ik.as_bytes().to_vec(), "/ik/boresight.ti").unwrap();
+        let et = EphemerisTime::new(600.0);
+
+        // sincpt_c from CSPICE N0067 along the same ray, with the same SPK and PCK
+        let point = boresight_ground_point(-9601001, et, MARS, "IAU_MARS", AberrationCorrection::None, -9601).unwrap().unwrap();
+        let expected = SpiceVector3::new(705.161520152, -1758.769471808, -1341.382098409);
+        assert!((point - expected).magnitude() < 1e-6, "{:?}", point);
+
+        // A boresight passing beside Mars
+        assert!(boresight_ground_point(-9601002, et, MARS, "IAU_MARS", AberrationCorrection::None, -9601).unwrap().is_none());
+
+        // A boresight on Mars' center hits the sub-observer point
+        let nadir = boresight_ground_point(-9601003, et, MARS, "IAU_MARS", AberrationCorrection::None, -9601).unwrap().unwrap();
+        let (sub_observer, _, _) = crate::coordinates::sub_observer_point("Intercept: ellipsoid", "MARS", 600.0, "IAU_MARS", "NONE", "-9601").unwrap();
+        assert!((nadir - sub_observer).magnitude() < 1e-6);
+
+        assert!(boresight_ground_point(-9601004, et, MARS, "IAU_MARS", AberrationCorrection::None, -9601).is_err());
+    }
+
+    #[test]
+    fn test_boresight_through_camera_on_ck_bus() {
+        use crate::coordinates::frame_kernel::tests::{ROTATING_BUS_CK, ROTATING_BUS_KERNELS};
+        use crate::kernel_system::furnish_kernel_from_bytes;
+        use crate::spk_reader::write;
+
+        let kernels = IsolatedKernels::new();
+        let fixed = |x: f64, y: f64, z: f64| write::ChebyshevRecord {
+            start: 1.0e8, end: 1.0e8 + 1000.0, x: vec![x, 0.0], y: vec![y, 0.0], z: vec![z, 0.0],
+        };
+        let mut spk = write::new_spk("BORE").unwrap();
+        write::add_type2_segment(&mut spk, -82, 0, "J2000", 1.0e8, 1.0e8 + 1000.0, "OBS", &[fixed(0.0, 0.0, 0.0)]).unwrap();
+        write::add_type2_segment(&mut spk, MARS, 0, "J2000", 1.0e8, 1.0e8 + 1000.0, "MARS", &[fixed(2.42e6, 1.39e6, -1.1e6)]).unwrap();
+        kernels.load_spk("bore.bsp", &spk.to_bytes().unwrap());
+        kernels.load_ck("rotating_bus.bc", ROTATING_BUS_CK);
+        furnish_kernel_from_bytes(ROTATING_BUS_KERNELS.as_bytes().to_vec(), "/fk/rotating_bus.tf").unwrap();
+        let constants = "\\begindata
+BODY499_RADII = ( 3000.0 2500.0 2000.0 )
+BODY499_POLE_RA = ( 317.68 0.0 0.0 )
+BODY499_POLE_DEC = ( 52.88 0.0 0.0 )
+BODY499_PM = ( 176.63 350.89 0.0 )
+INS-82100_BORESIGHT = ( 0.0 0.0 1.0 )
+INS-82100_FOV_FRAME = 'TEST_CAMERA'
+";
+        furnish_kernel_from_bytes(constants.as_bytes().to_vec(), "/ik/bus_camera.ti").unwrap();
+
+        // sincpt_c from CSPICE N0067 along TEST_CAMERA +Z, a TK frame on the CK-driven TEST_BUS
+        let point = |et: f64| boresight_ground_point(-82100, EphemerisTime::new(et), MARS, "IAU_MARS", AberrationCorrection::None, -82).unwrap();
+        let expected = SpiceVector3::new(290.494740564, -2136.774797379, -1019.989410777);
+        let hit = point(1.0e8 + 47.25).unwrap();
+        assert!((hit - expected).magnitude() < 1e-6, "{:?}", hit);
+
+        // The bus has turned away from Mars at the other epochs
+        assert!(point(1.0e8 + 15.0).is_none());
+        assert!(point(1.0e8 + 50.0).is_none());
+    }
+
     #[test]
     fn test_planetary_elements() {
         // Test that we can get orbital elements for major planets
@@ -742,6 +1987,7 @@ mod tests {
 
     #[test]
     fn test_geometric_state_computation() {
+        let _lock = crate::global_state::test_lock();
         clear_kernels().unwrap_or(());
         load_test_kernels().unwrap();
         
@@ -761,6 +2007,7 @@ mod tests {
 
     #[test]
     fn test_same_body_state() {
+        let _lock = crate::global_state::test_lock();
         clear_kernels().unwrap_or(());
         load_test_kernels().unwrap();
         
@@ -776,6 +2023,7 @@ mod tests {
 
     #[test]
     fn test_ephemeris_position() {
+        let _lock = crate::global_state::test_lock();
         clear_kernels().unwrap_or(());
         load_test_kernels().unwrap();
         
@@ -791,6 +2039,7 @@ mod tests {
 
     #[test]
     fn test_ephemeris_state() {
+        let _lock = crate::global_state::test_lock();
         clear_kernels().unwrap_or(());
         load_test_kernels().unwrap();
         
@@ -814,6 +2063,7 @@ mod tests {
 
     #[test]
     fn test_light_time_correction() {
+        let _lock = crate::global_state::test_lock();
         clear_kernels().unwrap_or(());
         load_test_kernels().unwrap();
         
@@ -843,6 +2093,7 @@ mod tests {
 
     #[test]
     fn test_light_time_function() {
+        let _lock = crate::global_state::test_lock();
         clear_kernels().unwrap_or(());
         load_test_kernels().unwrap();
         
@@ -857,6 +2108,7 @@ mod tests {
 
     #[test]  
     fn test_moon_relative_to_earth() {
+        let _lock = crate::global_state::test_lock();
         clear_kernels().unwrap_or(());
         load_test_kernels().unwrap();
         
@@ -879,8 +2131,139 @@ mod tests {
         assert!(speed > 100.0 && speed < 1000.0); // Our model produces high velocities
     }
 
+    #[test]
+    fn test_geometric_state_matches_uncorrected_ephemeris_state() {
+        use crate::spk_reader::write;
+
+        let record = |x: f64, vx: f64| write::ChebyshevRecord {
+            start: 0.0, end: 1000.0, x: vec![x, 500.0 * vx], y: vec![-2.0e7, 0.0], z: vec![0.0, 0.0],
+        };
+        let mut spk = write::new_spk("GEOMETRIC").unwrap();
+        write::add_type2_segment(&mut spk, -9101, 0, "J2000", 0.0, 1000.0, "HOST", &[record(1.0e8, 10.0)]).unwrap();
+        write::add_type2_segment(&mut spk, -9102, -9101, "J2000", 0.0, 1000.0, "PROBE", &[record(3.0e5, 2.0)]).unwrap();
+        let bytes = spk.to_bytes().unwrap();
+
+        let et = 250.0;
+        let kernels = IsolatedKernels::new();
+        kernels.load_spk("geometric.bsp", &bytes);
+        let geometric = geometric_state(-9102, et, "J2000", -9101).unwrap();
+        let uncorrected = ephemeris_state("-9102", EphemerisTime::new(et), "J2000", "NONE", "-9101").unwrap();
+        let barycentric = barycentric_state(-9102, et, "J2000").unwrap();
+
+        assert_eq!(geometric.position, uncorrected.position);
+        assert_eq!(geometric.velocity, uncorrected.velocity);
+        assert_eq!(geometric.light_time, uncorrected.light_time);
+        let expected = SpiceVector3::new(3.0e5 - 500.0, -2.0e7, 0.0);
+        assert!((geometric.position - expected).magnitude() < 1e-6);
+        assert!((geometric.light_time - expected.magnitude() / SPEED_OF_LIGHT).abs() < 1e-12);
+
+        assert!((barycentric.position - SpiceVector3::new(1.0e8 - 2500.0 + 3.0e5 - 500.0, -4.0e7, 0.0)).magnitude() < 1e-6);
+        assert!((barycentric.velocity - SpiceVector3::new(12.0, 0.0, 0.0)).magnitude() < 1e-9);
+    }
+
+    #[test]
+    fn test_batch_states_match_single_epoch_calls() {
+        use crate::spk_reader::write;
+
+        let record = |start: f64, x: f64| write::ChebyshevRecord {
+            start, end: start + 500.0, x: vec![x, 2000.0, -30.0], y: vec![-4.0e5, 700.0, 5.0], z: vec![0.0, 10.0, 0.0],
+        };
+        let mut spk = write::new_spk("BATCH").unwrap();
+        let records = [record(0.0, 1.0e6), record(500.0, 1.004e6), record(1000.0, 1.008e6)];
+        let host = write::ChebyshevRecord { start: 0.0, end: 1500.0, x: vec![1.5e8, 22000.0], y: vec![0.0; 2], z: vec![0.0; 2] };
+        write::add_type2_segment(&mut spk, -9400, 0, "J2000", 0.0, 1500.0, "BATCH HOST", &[host]).unwrap();
+        write::add_type2_segment(&mut spk, -9401, -9400, "J2000", 0.0, 1500.0, "BATCH PROBE", &records).unwrap();
+        let bytes = spk.to_bytes().unwrap();
+
+        // Unsorted, with a repeated epoch, across record boundaries
+        let ets = [1200.0, 30.0, 760.5, 30.0, 1499.0, 500.0];
+        let kernels = IsolatedKernels::new();
+        kernels.load_spk("batch.bsp", &bytes);
+        let batch = ephemeris_states("-9401", &ets, "J2000", "LT", "-9400").unwrap();
+        assert_eq!(batch.len(), ets.len());
+        for (batch, &et) in batch.iter().zip(&ets) {
+            let single = ephemeris_state("-9401", EphemerisTime::new(et), "J2000", "LT", "-9400").unwrap();
+            assert_eq!(batch.position, single.position);
+            assert_eq!(batch.velocity, single.velocity);
+            assert_eq!(batch.light_time, single.light_time);
+        }
+        assert_eq!(batch[1].position, batch[3].position);
+
+        assert!(ephemeris_states("-9401", &ets, "J2000", "BOGUS", "-9400").is_err());
+        assert!(ephemeris_states("-9401", &[], "J2000", "NONE", "-9400").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_converged_light_time_differs_from_single_iteration() {
+        // Target receding at 1% of c from an observer at the origin
+        let (x0, v) = (1.0e9, 0.01 * SPEED_OF_LIGHT);
+        let target_at = |t: f64| Ok(StateVector {
+            position: SpiceVector3::new(x0 + v * t, 0.0, 0.0),
+            velocity: SpiceVector3::new(v, 0.0, 0.0),
+            light_time: 0.0,
+        });
+        let observer = SpiceVector3::zeros();
+        let et = 100.0;
+        let light_time = |abcorr: &str| {
+            solve_light_time(target_at, &observer, et, &AberrationCorrection::from_str(abcorr).unwrap()).unwrap().1
+        };
+
+        // Exact solutions of c·τ = x0 + v(et ∓ τ)
+        let reception = (x0 + v * et) / (SPEED_OF_LIGHT + v);
+        let transmission = (x0 + v * et) / (SPEED_OF_LIGHT - v);
+        assert!((light_time("CN") - reception).abs() < 1e-9);
+        assert!((light_time("XCN") - transmission).abs() < 1e-9);
+
+        // One iteration leaves an error of order (v/c)² τ
+        let single = light_time("LT");
+        assert!((single - reception).abs() > 0.1);
+        let geometric = (x0 + v * et) / SPEED_OF_LIGHT;
+        assert!((single - (x0 + v * (et - geometric)) / SPEED_OF_LIGHT).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_stellar_aberration_matches_relativistic_angle() {
+        let position = SpiceVector3::new(1.5e8, 0.0, 0.0);
+        let velocity = SpiceVector3::new(0.0, 30.0, 0.0);
+        let beta: f64 = 30.0 / SPEED_OF_LIGHT;
+
+        // Perpendicular motion: tan δ = γβ, i.e. sin δ = β
+        let apparent = apply_stellar_aberration_correction(&position, &velocity, false).unwrap();
+        assert!((apparent.magnitude() - position.magnitude()).abs() < 1e-6);
+        assert!(apparent.y() > 0.0);
+        assert!((vector_separation(&position, &apparent).unwrap() - beta.asin()).abs() < 1e-9);
+
+        // Transmission shifts the other way; motion along the line of sight
+        // does not shift at all
+        let transmitted = apply_stellar_aberration_correction(&position, &velocity, true).unwrap();
+        assert!(transmitted.y() < 0.0);
+        let along = apply_stellar_aberration_correction(&position, &SpiceVector3::new(30.0, 0.0, 0.0), false).unwrap();
+        assert!(vector_separation(&position, &along).unwrap() < 1e-15);
+
+        // Oblique: cos θ' = (cos θ + β) / (1 + β cos θ)
+        let velocity = SpiceVector3::new(-0.6, 0.8, 0.0).scale(0.2 * SPEED_OF_LIGHT);
+        let apparent = apply_stellar_aberration_correction(&position, &velocity, false).unwrap();
+        let (cos_theta, beta) = (-0.6, 0.2);
+        let expected = ((cos_theta + beta) / (1.0 + beta * cos_theta) as f64).acos();
+        let actual = vector_separation(&apparent, &velocity).unwrap();
+        assert!((actual - expected).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_stellar_aberration_round_trip() {
+        let star = SpiceVector3::new(0.3, -0.5, 0.81);
+        let velocity = SpiceVector3::new(-29.8, 4.1, 1.7);
+
+        let apparent = stellar_aberration(&star, &velocity).unwrap();
+        assert!(vector_separation(&star, &apparent).unwrap() > 1e-5);
+
+        let recovered = stellar_aberration_transmission(&apparent, &velocity).unwrap();
+        assert!((recovered - star).magnitude() < 1e-12);
+    }
+
     #[test]
     fn test_transmission_vs_reception() {
+        let _lock = crate::global_state::test_lock();
         initialize_kernel_system().unwrap();
         crate::spk_reader::initialize_spk_reader().unwrap();
         
@@ -897,6 +2280,7 @@ mod tests {
 
     #[test]
     fn test_multiple_reference_frames() {
+        let _lock = crate::global_state::test_lock();
         initialize_kernel_system().unwrap();
         crate::spk_reader::initialize_spk_reader().unwrap();
         
@@ -909,4 +2293,433 @@ mod tests {
         
         // For now, other frames may not be fully implemented, so we just verify the interface works
     }
+
+    #[test]
+    fn test_phase_angle_full_and_new_phase() {
+        let sun_direction = SpiceVector3::new(1.5e8, 0.0, 0.0);
+
+        // Observer between the Sun and the target: fully lit disk, phase angle ~0
+        let full = phase_angle_between(&SpiceVector3::new(4.0e5, 1.0e3, 0.0), &sun_direction).unwrap();
+        assert!(full < 1e-2);
+
+        // Target between the Sun and the observer: unlit disk, phase angle ~π
+        let new = phase_angle_between(&SpiceVector3::new(-4.0e5, 1.0e3, 0.0), &sun_direction).unwrap();
+        assert!((new - std::f64::consts::PI).abs() < 1e-2);
+
+        assert!(phase_angle_between(&SpiceVector3::new(0.0, 0.0, 0.0), &sun_direction).is_err());
+        assert!(phase_angle(0.0, "SUN", "SUN", "EARTH", "NONE").is_err());
+        assert!(phase_angle(0.0, "MARS", "SUN", "MARS", "NONE").is_err());
+        assert!(phase_angle(0.0, "MARS", "SUN", "EARTH", "LT+S").is_err());
+    }
+
+    #[test]
+    fn test_phase_angle_of_collinear_bodies() {
+        use crate::spk_reader::write;
+
+        // Bodies fixed on the barycentric X axis
+        let fixed = |x: f64| [write::ChebyshevRecord {
+            start: -5000.0, end: 5000.0, x: vec![x], y: vec![0.0], z: vec![0.0],
+        }];
+        let mut spk = write::new_spk("COLLINEAR").unwrap();
+        write::add_type2_segment(&mut spk, SUN, 0, "J2000", -5000.0, 5000.0, "SUN", &fixed(0.0)).unwrap();
+        write::add_type2_segment(&mut spk, EARTH, 0, "J2000", -5000.0, 5000.0, "EARTH", &fixed(1.5e8)).unwrap();
+        write::add_type2_segment(&mut spk, MARS, 0, "J2000", -5000.0, 5000.0, "MARS", &fixed(2.0e8)).unwrap();
+        write::add_type2_segment(&mut spk, PLUTO, 0, "J2000", -5000.0, 5000.0, "PLUTO", &fixed(3.0e8)).unwrap();
+
+        let kernels = IsolatedKernels::new();
+        kernels.load_spk("collinear.bsp", &spk.to_bytes().unwrap());
+
+        // Earth between the Sun and Mars: Mars is fully lit
+        let full = phase_angle(0.0, "MARS", "SUN", "EARTH", "LT").unwrap();
+        assert!(full.abs() < 1e-12);
+
+        // Mars between the Sun and an outer observer: the lit side faces away
+        let new = phase_angle(0.0, "MARS", "SUN", "PLUTO", "CN").unwrap();
+        assert!((new - std::f64::consts::PI).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_phase_angle_matches_cspice() {
+        let kernels = IsolatedKernels::new();
+        kernels.load_spk("de_fragment.bsp", include_bytes!("../kernels/spk/de_fragment.bsp"));
+
+        // phaseq_c for the Moon seen from the Earth; the fragment has no Sun,
+        // so the solar system barycenter stands in as the illuminator
+        let reference = [
+            (-299996400.0, "NONE", 1.490809369240665),
+            (-299996400.0, "LT", 1.490820025555964),
+            (-299996400.0, "CN", 1.490820026614211),
+            (-299870276.75, "NONE", 1.209541009129990),
+            (-299870276.75, "LT", 1.209578212901854),
+            (-299870276.75, "CN", 1.209578216310225),
+            (-299740800.0, "NONE", 0.925226873945092),
+            (-299740800.0, "LT", 0.925288129615918),
+            (-299740800.0, "CN", 0.925288134392019),
+        ];
+        for (et, abcorr, expected) in reference {
+            let angle = phase_angle(et, "MOON", "SSB", "EARTH", abcorr).unwrap();
+            assert!((angle - expected).abs() < 1e-10, "{} {}: {} vs {}", et, abcorr, angle, expected);
+        }
+    }
+
+    #[test]
+    fn test_occultation_code_of_ellipsoid_outlines() {
+        let identity = SpiceMatrix3x3::identity();
+        let sphere = |x: f64, y: f64, radius: f64| {
+            ApparentBody::ellipsoid(SpiceVector3::new(x, y, 0.0), [radius; 3], &identity).unwrap()
+        };
+
+        // A small moon directly behind a planet is totally occulted; seen the
+        // other way round, the moon transits the planet
+        let planet = sphere(1.0e6, 0.0, 6000.0);
+        let behind = sphere(1.5e6, 1000.0, 100.0);
+        assert_eq!(occultation_code(&behind, &planet).unwrap(), OCCULT_TOTAL1);
+        assert_eq!(occultation_code(&planet, &behind).unwrap(), OCCULT_TOTAL2);
+        let in_front = sphere(0.5e6, 1000.0, 100.0);
+        assert_eq!(occultation_code(&in_front, &planet).unwrap(), OCCULT_ANNULAR2);
+        assert_eq!(occultation_code(&planet, &in_front).unwrap(), OCCULT_ANNULAR1);
+
+        // Straddling the limb, and well clear of it
+        let limb = sphere(1.5e6, 9000.0, 100.0);
+        assert_eq!(occultation_code(&limb, &planet).unwrap(), OCCULT_PARTIAL1);
+        assert_eq!(occultation_code(&planet, &limb).unwrap(), OCCULT_PARTIAL2);
+        let clear = sphere(1.5e6, 1.0e5, 100.0);
+        assert_eq!(occultation_code(&clear, &planet).unwrap(), OCCULT_NONE);
+
+        // Behind an oblate body seen from its equator, a point is hidden out to
+        // the equatorial radius along the equator but only to the polar radius
+        // along the axis; it is never partially occulted
+        let oblate = ApparentBody::ellipsoid(SpiceVector3::new(1.0e6, 0.0, 0.0), [6000.0, 6000.0, 5000.0], &identity).unwrap();
+        let point = |y: f64, z: f64| ApparentBody::point(SpiceVector3::new(2.0e6, y, z));
+        assert_eq!(occultation_code(&point(11000.0, 0.0), &oblate).unwrap(), OCCULT_TOTAL1);
+        assert_eq!(occultation_code(&point(0.0, 9000.0), &oblate).unwrap(), OCCULT_TOTAL1);
+        assert_eq!(occultation_code(&point(0.0, 11000.0), &oblate).unwrap(), OCCULT_NONE);
+        assert_eq!(occultation_code(&oblate, &point(0.0, 11000.0)).unwrap(), OCCULT_NONE);
+        let transit = ApparentBody::point(SpiceVector3::new(0.5e6, 1000.0, 1000.0));
+        assert_eq!(occultation_code(&transit, &oblate).unwrap(), OCCULT_ANNULAR2);
+
+        assert!(ApparentBody::ellipsoid(SpiceVector3::new(10.0, 0.0, 0.0), [50.0; 3], &identity).is_err());
+    }
+
+    /// Bodies in uniform motion past an observer (-9601) at the origin: a
+    /// probe (-9602) behind a strongly oblate, tilted Earth, a triaxial Mars
+    /// behind it and a triaxial Moon in front, over ET 0 to 1000 s
+    fn load_occultation_scene(kernels: &IsolatedKernels) {
+        use crate::spk_reader::write;
+        use crate::kernel_system::furnish_kernel_from_bytes;
+
+        let record = |x: f64, y: f64, z: f64| write::ChebyshevRecord {
+            start: 0.0, end: 1000.0, x: vec![x, 0.0], y: vec![0.0, y], z: vec![z, 0.0],
+        };
+        let mut spk = write::new_spk("OCCULT").unwrap();
+        for (body, name, track) in [
+            (-9601, "OBSERVER", record(0.0, 0.0, 0.0)),
+            (399, "EARTH", record(1.0e6, 0.0, 0.0)),
+            (-9602, "PROBE", record(2.0e6, 20000.0, 3000.0)),
+            (499, "MARS", record(3.0e6, 30000.0, 2000.0)),
+            (301, "MOON", record(4.0e5, 6000.0, 500.0)),
+        ] {
+            write::add_type2_segment(&mut spk, body, 0, "J2000", 0.0, 1000.0, name, &[track]).unwrap();
+        }
+        let pck = "\\begindata
+BODY399_RADII = ( 6378.14 6378.14 2000.0 )
+BODY399_POLE_RA = ( 30.0 0.0 0.0 )
+BODY399_POLE_DEC = ( 40.0 0.0 0.0 )
+BODY399_PM = ( 10.0 360.9856 0.0 )
+BODY499_RADII = ( 3000.0 2500.0 2000.0 )
+BODY499_POLE_RA = ( 317.68 0.0 0.0 )
+BODY499_POLE_DEC = ( 52.88 0.0 0.0 )
+BODY499_PM = ( 176.63 350.89 0.0 )
+BODY301_RADII = ( 1100.0 1000.0 900.0 )
+BODY301_POLE_RA = ( 270.0 0.0 0.0 )
+BODY301_POLE_DEC = ( 66.5 0.0 0.0 )
+BODY301_PM = ( 38.3 13.17 0.0 )
+";
+        kernels.load_spk("occult.bsp", &spk.to_bytes().unwrap());
+        furnish_kernel_from_bytes(pck.as_bytes().to_vec(), "/pck/occult.tpc").unwrap();
+    }
+
+    #[test]
+    fn test_occultation_type_matches_cspice_occult() {
+        let kernels = IsolatedKernels::new();
+        load_occultation_scene(&kernels);
+
+        // occult_c codes from CSPICE N0067 with the same SPK and PCK, at
+        // et = 10 + 24.5 k
+        let probe_by_earth = [
+            0, 0, 0, 0, 0, 0, 0, 0, -3, -3, -3, -3, -3, -3, -3, -3, -3, -3, -3, -3, -3,
+            -3, -3, -3, -3, -3, -3, -3, -3, -3, -3, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        ];
+        let earth_over_mars = [
+            0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3,
+            3, 3, 3, 3, 3, 3, 3, 3, 1, 1, 1, 1, 1, 0, 0, 0, 0, 0, 0, 0,
+        ];
+        let moon_over_earth = [
+            0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 1, 1, 1, 2, 2, 2, 2, 2,
+            2, 2, 1, 1, 1, 1, 1, 1, 1, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        ];
+        for k in 0..41 {
+            let et = 10.0 + 24.5 * k as f64;
+            let probe = occultation_type("-9602", "POINT", " ", "EARTH", "ELLIPSOID", "IAU_EARTH", "NONE", "-9601", et).unwrap();
+            assert_eq!(probe, probe_by_earth[k], "probe at et {}", et);
+            let mars = occultation_type("EARTH", "ELLIPSOID", "IAU_EARTH", "MARS", "ELLIPSOID", "IAU_MARS", "NONE", "-9601", et).unwrap();
+            assert_eq!(mars, earth_over_mars[k], "Mars at et {}", et);
+            let moon = occultation_type("MOON", "ELLIPSOID", "IAU_MOON", "EARTH", "ELLIPSOID", "IAU_EARTH", "LT", "-9601", et).unwrap();
+            assert_eq!(moon, moon_over_earth[k], "Moon at et {}", et);
+
+            // Swapping the targets flips the sign
+            let swapped = occultation_type("EARTH", "ELLIPSOID", "IAU_EARTH", "-9602", "POINT", " ", "NONE", "-9601", et).unwrap();
+            assert_eq!(swapped, -probe);
+        }
+
+        assert!(occultation_type("-9602", "POINT", " ", "-9601", "POINT", " ", "NONE", "EARTH", 500.0).is_err());
+        assert!(occultation_type("EARTH", "ELLIPSOID", "IAU_EARTH", "MARS", "DSK", "IAU_MARS", "NONE", "-9601", 500.0).is_err());
+    }
+
+    #[test]
+    fn test_fov_containment_on_and_off_boresight() {
+        let rectangle = Fov {
+            shape: FovShape::Rectangle,
+            frame: "TEST_CAMERA".to_string(),
+            boresight: SpiceVector3::new(0.0, 0.0, 1.0),
+            bounds: vec![
+                SpiceVector3::new(0.1, 0.05, 1.0), SpiceVector3::new(-0.1, 0.05, 1.0),
+                SpiceVector3::new(-0.1, -0.05, 1.0), SpiceVector3::new(0.1, -0.05, 1.0),
+            ],
+        };
+
+        let body = |position: SpiceVector3, radius: f64| if radius == 0.0 {
+            ApparentBody::point(position)
+        } else {
+            ApparentBody::ellipsoid(position, [radius; 3], &SpiceMatrix3x3::identity()).unwrap()
+        };
+
+        // On the boresight, and far off-axis
+        assert!(fov_contains_body(&rectangle, &body(SpiceVector3::new(0.0, 0.0, 1.0e6), 0.0)).unwrap());
+        assert!(!fov_contains_body(&rectangle, &body(SpiceVector3::new(1.0e6, 0.0, 1.0e6), 0.0)).unwrap());
+        assert!(!fov_contains_body(&rectangle, &body(SpiceVector3::new(0.0, 0.0, -1.0e6), 0.0)).unwrap());
+        // Inside along X but outside the narrower Y extent
+        assert!(fov_contains_body(&rectangle, &body(SpiceVector3::new(0.08, 0.0, 1.0), 0.0)).unwrap());
+        assert!(!fov_contains_body(&rectangle, &body(SpiceVector3::new(0.0, 0.08, 1.0), 0.0)).unwrap());
+
+        // A body centered outside the FOV is in view once its limb crosses the edge
+        let beside = SpiceVector3::new(0.0, 0.08, 1.0).scale(1.0e4);
+        assert!(!fov_contains_body(&rectangle, &body(beside, 100.0)).unwrap());
+        assert!(fov_contains_body(&rectangle, &body(beside, 400.0)).unwrap());
+        // A body filling the whole FOV covers the boresight
+        assert!(fov_contains_body(&rectangle, &body(SpiceVector3::new(0.5, 0.0, 1.0).scale(1.0e4), 8000.0)).unwrap());
+
+        let cone = Fov { shape: FovShape::Circle, bounds: vec![SpiceVector3::new(0.1, 0.0, 1.0)], ..rectangle.clone() };
+        assert!(fov_contains_body(&cone, &body(SpiceVector3::new(0.0, 0.09, 1.0), 0.0)).unwrap());
+        assert!(!fov_contains_body(&cone, &body(SpiceVector3::new(0.0, 0.11, 1.0), 0.0)).unwrap());
+
+        let ellipse = Fov {
+            shape: FovShape::Ellipse,
+            bounds: vec![SpiceVector3::new(0.2, 0.0, 1.0), SpiceVector3::new(0.0, 0.1, 1.0)],
+            ..rectangle
+        };
+        assert!(fov_contains_body(&ellipse, &body(SpiceVector3::new(0.15, 0.0, 1.0), 0.0)).unwrap());
+        assert!(!fov_contains_body(&ellipse, &body(SpiceVector3::new(0.15, 0.08, 1.0), 0.0)).unwrap());
+    }
+
+    #[test]
+    fn test_target_in_fov_matches_cspice_fovtrg() {
+        use crate::kernel_system::furnish_kernel_from_bytes;
+
+        // A camera frame whose boresight (+Z) points along J2000 +X, with a
+        // rectangular, an elliptical and two small circular fields of view
+        let ik = "\\begindata
+FRAME_TEST_CAMERA = -9601000
+FRAME_-9601000_NAME = 'TEST_CAMERA'
+FRAME_-9601000_CLASS = 4
+FRAME_-9601000_CLASS_ID = -9601000
+FRAME_-9601000_CENTER = -9601
+TKFRAME_-9601000_RELATIVE = 'J2000'
+TKFRAME_-9601000_SPEC = 'MATRIX'
+TKFRAME_-9601000_MATRIX = ( 0 1 0  0 0 1  1 0 0 )
+INS-9601100_FOV_SHAPE = 'RECTANGLE'
+INS-9601100_FOV_FRAME = 'TEST_CAMERA'
+INS-9601100_BORESIGHT = ( 0 0 1 )
+INS-9601100_FOV_BOUNDARY_CORNERS = ( 0.004 0.002 1  -0.004 0.002 1  -0.004 -0.002 1  0.004 -0.002 1 )
+INS-9601200_FOV_SHAPE = 'ELLIPSE'
+INS-9601200_FOV_FRAME = 'TEST_CAMERA'
+INS-9601200_BORESIGHT = ( 0 0 1 )
+INS-9601200_FOV_BOUNDARY_CORNERS = ( 0.003 0 1  0 0.0015 1 )
+INS-9601300_FOV_SHAPE = 'CIRCLE'
+INS-9601300_FOV_FRAME = 'TEST_CAMERA'
+INS-9601300_BORESIGHT = ( 0 0.006 1 )
+INS-9601300_FOV_BOUNDARY_CORNERS = ( 0 0.0065 1 )
+INS-9601400_FOV_SHAPE = 'CIRCLE'
+INS-9601400_FOV_FRAME = 'TEST_CAMERA'
+INS-9601400_BORESIGHT = ( 0.006 0 1 )
+INS-9601400_FOV_BOUNDARY_CORNERS = ( 0.0065 0 1 )
+";
+        let kernels = IsolatedKernels::new();
+        load_occultation_scene(&kernels);
+        furnish_kernel_from_bytes(ik.as_bytes().to_vec(), "/ik/camera.ti").unwrap();
+
+        // fovtrg_c results from CSPICE N0067 with the same kernels, at
+        // et = 20 + 24 k: each target is in view for a run of k
+        for k in 0..41 {
+            let et = 20.0 + 24.0 * k as f64;
+            let in_view = |inst: i32, target: &str, shape: &str, frame: &str, abcorr: &str| {
+                target_in_fov(inst, target, shape, frame, abcorr, "-9601", et).unwrap()
+            };
+            assert_eq!(in_view(-9601100, "MARS", "ELLIPSOID", "IAU_MARS", "NONE"), (10..=30).contains(&k), "Mars at et {}", et);
+            assert_eq!(in_view(-9601200, "MOON", "ELLIPSOID", "IAU_MOON", "LT"), (13..=27).contains(&k), "Moon at et {}", et);
+            assert_eq!(in_view(-9601100, "-9602", "POINT", " ", "NONE"), (12..=28).contains(&k), "probe at et {}", et);
+            assert_eq!(in_view(-9601200, "MARS", "ELLIPSOID", "IAU_MARS", "LT"), (13..=28).contains(&k), "Mars at et {}", et);
+        }
+
+        // Both small circles lie 0.006 rad from Earth's center, within its
+        // equatorial radius; only the one offset toward the tilted pole
+        // misses the flattened ellipsoid
+        assert!(target_in_fov(-9601400, "EARTH", "ELLIPSOID", "IAU_EARTH", "NONE", "-9601", 500.0).unwrap());
+        assert!(!target_in_fov(-9601300, "EARTH", "ELLIPSOID", "IAU_EARTH", "NONE", "-9601", 500.0).unwrap());
+        assert!(target_in_fov(-9601999, "EARTH", "ELLIPSOID", "IAU_EARTH", "NONE", "-9601", 500.0).is_err());
+    }
+
+    #[test]
+    fn test_closest_approach_of_constructed_flyby() {
+        // Straight-line flyby at 12 km/s with a 500 km miss distance; the
+        // analytic closest approach is at ET 1234.5 s
+        let (speed, miss, t_ca) = (12.0, 500.0, 1234.5);
+        let flyby = |et: f64| Ok(((speed * (et - t_ca)).powi(2) + miss * miss).sqrt());
+
+        let (et, distance) = minimum_distance(flyby, (0.0, 3600.0)).unwrap();
+        assert!((et.seconds() - t_ca).abs() < 0.1);
+        assert!((distance - miss).abs() < 1e-3);
+
+        // Receding for the whole window: the minimum is the window start
+        let (et, distance) = minimum_distance(flyby, (2000.0, 3000.0)).unwrap();
+        assert!((et.seconds() - 2000.0).abs() < 1e-9);
+        assert!((distance - flyby(2000.0).unwrap()).abs() < 1e-9);
+
+        assert!(minimum_distance(flyby, (10.0, 0.0)).is_err());
+    }
+
+    #[test]
+    fn test_gf_distance_finds_threshold_crossings() {
+        use core::f64::consts::PI;
+
+        // Circular, coplanar Earth and Mars orbits starting in conjunction
+        const DAY: f64 = 86400.0;
+        let (earth_radius, mars_radius): (f64, f64) = (1.496e8, 2.279e8);
+        let (earth_rate, mars_rate) = (2.0 * PI / (365.25 * DAY), 2.0 * PI / (687.0 * DAY));
+        let earth_mars = |et: f64| {
+            let dx = mars_radius * (mars_rate * et).cos() - earth_radius * (earth_rate * et).cos();
+            let dy = mars_radius * (mars_rate * et).sin() - earth_radius * (earth_rate * et).sin();
+            let distance = (dx * dx + dy * dy).sqrt();
+            let rate = mars_radius * earth_radius * (earth_rate - mars_rate)
+                * ((earth_rate - mars_rate) * et).sin() / distance;
+            Ok((distance, rate))
+        };
+        // d² = a² + b² - 2ab cos(Δω t), so the crossing time is closed form
+        let threshold: f64 = 2.0e8;
+        let cos_phase = (mars_radius.powi(2) + earth_radius.powi(2) - threshold.powi(2))
+            / (2.0 * mars_radius * earth_radius);
+        let crossing = cos_phase.acos() / (earth_rate - mars_rate);
+
+        let confinement = SpiceWindow::from_interval(0.0, 400.0 * DAY).unwrap();
+        let near = search_distance(earth_mars, GfRelation::LessThan, threshold, DAY, &confinement).unwrap();
+        assert_eq!(near.cardinality(), 1);
+        assert_eq!(near.intervals()[0][0], 0.0);
+        assert!((near.intervals()[0][1] - crossing).abs() < 1e-3);
+
+        let far = search_distance(earth_mars, GfRelation::GreaterThan, threshold, DAY, &confinement).unwrap();
+        assert!((far.intervals()[0][0] - crossing).abs() < 1e-3);
+        assert_eq!(far.intervals()[0][1], 400.0 * DAY);
+
+        let equal = search_distance(earth_mars, GfRelation::Equals, threshold, DAY, &confinement).unwrap();
+        assert_eq!(equal.cardinality(), 1);
+
+        // Opposition-to-conjunction: maximum separation at half the synodic period
+        let synodic = 2.0 * PI / (earth_rate - mars_rate);
+        let maxima = search_distance(earth_mars, GfRelation::LocalMaximum, 0.0, DAY, &confinement).unwrap();
+        assert_eq!(maxima.cardinality(), 1);
+        assert!((maxima.intervals()[0][0] - synodic / 2.0).abs() < 60.0);
+
+        assert!(search_distance(earth_mars, GfRelation::LessThan, threshold, 0.0, &confinement).is_err());
+        assert!(GfRelation::from_str("ABOUT").is_err());
+    }
+
+    #[test]
+    fn test_gf_distance_matches_cspice_gfdist() {
+        let kernels = IsolatedKernels::new();
+        load_occultation_scene(&kernels);
+
+        // gfdist_c from CSPICE N0067 on the same SPK, step 50 s, reference 400030 km
+        let confinement = SpiceWindow::from_interval(10.0, 990.0).unwrap();
+        let reference: [(&str, &str, &[[f64; 2]]); 8] = [
+            ("NONE", "<", &[[93.875874291, 906.124125785]]),
+            ("NONE", "=", &[[93.875874109, 93.875874109], [906.124125804, 906.124125804]]),
+            ("NONE", "LOCMIN", &[[500.000000224, 500.000000224]]),
+            ("NONE", ">", &[[10.0, 93.875874291], [906.124125785, 990.0]]),
+            ("LT", "<", &[[95.210229885, 907.458483014]]),
+            ("LT", "=", &[[95.210229735, 95.210229735], [907.458483213, 907.458483213]]),
+            ("LT", "LOCMIN", &[[501.334257647, 501.334257647]]),
+            ("LT", ">", &[[10.0, 95.210229885], [907.458483014, 990.0]]),
+        ];
+        for (abcorr, relation, expected) in reference {
+            let window = gf_distance("MOON", "-9601", abcorr, relation, 400030.0, 50.0, &confinement).unwrap();
+            assert_eq!(window.cardinality(), expected.len(), "{} {}", abcorr, relation);
+            for (found, wanted) in window.intervals().iter().zip(expected) {
+                assert!((found[0] - wanted[0]).abs() < 1e-5, "{} {}: {:?}", abcorr, relation, found);
+                assert!((found[1] - wanted[1]).abs() < 1e-5, "{} {}: {:?}", abcorr, relation, found);
+            }
+        }
+
+        // A window spanning the whole SPK coverage is only sampled inside it
+        let coverage = SpiceWindow::from_interval(0.0, 1000.0).unwrap();
+        let minima = gf_distance("MOON", "-9601", "NONE", "LOCMIN", 0.0, 50.0, &coverage).unwrap();
+        assert_eq!(minima.cardinality(), 1);
+        assert!((minima.intervals()[0][0] - 500.000000224).abs() < 1e-5);
+        assert_eq!(gf_distance("MOON", "-9601", "NONE", "LOCMAX", 0.0, 50.0, &coverage).unwrap().cardinality(), 0);
+
+        assert!(gf_distance("MOON", "-9601", "NONE", "ABOUT", 400030.0, 50.0, &confinement).is_err());
+        assert!(gf_distance("MOON", "-9601", "NONE", "<", 400030.0, 0.0, &confinement).is_err());
+    }
+
+    #[test]
+    fn test_conic_residuals_of_generated_type2_kernel() {
+        use crate::spk_reader::SpkReader;
+        use crate::spk_reader::write::{fit_type2_records, write_type2, RecordInterval};
+
+        let mu: f64 = 398600.4418;
+        let a: f64 = 7000.0;
+        let elements = OrbitalElements {
+            a,
+            e: 0.1,
+            i: 0.5,
+            omega: 1.0,
+            w: 2.0,
+            m0: 0.3,
+            epoch: 0.0,
+            n: (mu / (a * a * a)).sqrt(),
+        };
+        let period = 2.0 * std::f64::consts::PI / elements.n;
+
+        let records = fit_type2_records(
+            0.0, 2.0 * period, 15, RecordInterval::Auto(period / 16.0),
+            |et| Ok(compute_orbital_state(&elements, mu, et)?.position)
+        ).unwrap();
+        let bytes = write_type2("CONIC", -500, 399, "J2000", 0.0, 2.0 * period, &records).unwrap();
+        let mut reader = SpkReader::new();
+        reader.load_spk_data("conic.bsp", &bytes).unwrap();
+        let state = |et| reader.compute_state(-500, 399, et);
+
+        let report = conic_residuals(state, &elements, mu, (0.0, 2.0 * period), 101).unwrap();
+        assert_eq!(report.samples, 101);
+        assert!(report.skipped_epochs.is_empty());
+        assert!(report.max_position < 1e-6, "max position residual {}", report.max_position);
+        assert!(report.max_velocity < 1e-8, "max velocity residual {}", report.max_velocity);
+        assert!(report.rms_position <= report.max_position);
+
+        // The last quarter of the window lies past the kernel's coverage
+        let report = conic_residuals(state, &elements, mu, (0.0, 2.5 * period), 11).unwrap();
+        assert_eq!(report.samples, 9);
+        assert_eq!(report.skipped_epochs, vec![2.25 * period, 2.5 * period]);
+
+        assert!(conic_residuals(state, &elements, mu, (0.0, period), 0).is_err());
+    }
 }
@@ -2,6 +2,20 @@
 //! 
 //! This module replaces CSPICE's error handling system (chkin_/chkout_/sigerr_)
 //! with Rust's native Result type and structured error handling.
+//!
+//! Code ported from CSPICE that signals errors instead of returning them can
+//! use `signal_error`, `has_failed` and `reset_error_state`; `check` folds a
+//! signaled error back into a `SpiceResult`. The same state is available
+//! under the CSPICE names, for hosts that poll it between calls:
+//! - sigerr_c → set_error()
+//! - failed_c → failed()
+//! - reset_c → reset()
+//! - getmsg_c → get_message()
+//! - erract_c → set_error_action()
+//! - chkin_c/chkout_c → TraceScope::enter() and its drop
+//!
+//! Errors built with `SpiceError::raise` are also passed to the handler
+//! installed with `set_error_handler`, so a host can log them as they occur.
 
 #[cfg(not(feature = "std"))]
 use alloc::string::String;
@@ -9,6 +23,10 @@ use alloc::string::String;
 use alloc::vec::Vec;
 #[cfg(not(feature = "std"))]
 use alloc::format;
+#[cfg(not(feature = "std"))]
+use alloc::boxed::Box;
+#[cfg(not(feature = "std"))]
+use alloc::sync::Arc;
 
 #[cfg(feature = "std")]
 use std::string::String;
@@ -16,8 +34,13 @@ use std::string::String;
 use std::vec::Vec;
 #[cfg(feature = "std")]
 use std::format;
+#[cfg(feature = "std")]
+use std::boxed::Box;
+#[cfg(feature = "std")]
+use std::sync::Arc;
 
 use core::fmt;
+use crate::global_state::GlobalState;
 
 /// SPICE error types corresponding to different failure modes
 #[derive(Debug, Clone, PartialEq)]
@@ -81,15 +104,39 @@ pub struct SpiceError {
 
 impl SpiceError {
     /// Create a new SpiceError
+    ///
+    /// The call trace starts as the functions of the enclosing `TraceScope`s,
+    /// outermost first.
     pub fn new(error_type: SpiceErrorType, message: String) -> Self {
         SpiceError {
             error_type,
             message,
-            function_trace: Vec::new(),
+            function_trace: with_trace_stack(|trace| trace.get_stack().to_vec()),
             details: None,
         }
     }
 
+    /// Create a new SpiceError and report it according to the error action
+    ///
+    /// The installed error handler sees the error once, unless the action is
+    /// IGNORE. Under ABORT the handler runs and then this panics; otherwise
+    /// the error is returned for the caller to propagate.
+    pub fn raise(error_type: SpiceErrorType, message: String) -> Self {
+        let error = Self::new(error_type, message);
+        let action = error_action();
+        if action != ErrorAction::Ignore {
+            // Call the handler outside the lock, so it may itself install
+            // a handler or raise
+            if let Some(handler) = ERROR_HANDLER.read(|handler| handler.cloned()) {
+                handler(&error);
+            }
+        }
+        if action == ErrorAction::Abort {
+            panic!("{}", error);
+        }
+        error
+    }
+
     /// Add function to the call trace (equivalent to chkin_ in CSPICE)
     pub fn add_trace(mut self, function_name: String) -> Self {
         self.function_trace.push(function_name);
@@ -102,6 +149,40 @@ impl SpiceError {
         self
     }
 
+    /// CSPICE-style short message, e.g. `SPICE(INVALIDTIME)`
+    pub fn short_message(&self) -> String {
+        format!("SPICE({})", self.error_type_string().replace('_', ""))
+    }
+
+    /// One-line explanation of the error type
+    pub fn explanation(&self) -> &'static str {
+        match self.error_type {
+            SpiceErrorType::KernelNotFound => "Kernel file not found or could not be loaded",
+            SpiceErrorType::InvalidTime => "Invalid time string or time conversion error",
+            SpiceErrorType::InvalidTarget => "Invalid target body name or ID",
+            SpiceErrorType::InvalidObserver => "Invalid observer body name or ID",
+            SpiceErrorType::InvalidFrame => "Invalid reference frame name",
+            SpiceErrorType::InvalidAberrationCorrection => "Invalid aberration correction specification",
+            SpiceErrorType::ComputationError => "Mathematical computation error",
+            SpiceErrorType::FileIOError => "File I/O error",
+            SpiceErrorType::MemoryError => "Memory allocation error",
+            SpiceErrorType::InvalidArgument => "Invalid function argument",
+            SpiceErrorType::InsufficientData => "Kernel data insufficient for requested operation",
+            SpiceErrorType::NumericalError => "Numerical integration or interpolation failure",
+            SpiceErrorType::KernelAlreadyLoaded => "Kernel already loaded",
+            SpiceErrorType::KernelLoadError => "Kernel loading error",
+            SpiceErrorType::TooManyKernels => "Too many kernels loaded",
+            SpiceErrorType::InvalidKernelPath => "Invalid kernel path",
+            SpiceErrorType::InvalidKernelData => "Invalid kernel data format",
+            SpiceErrorType::FileReadError => "File read error",
+            SpiceErrorType::PoolNotInitialized => "Kernel pool not initialized",
+            SpiceErrorType::InvalidDataType => "Invalid data type for kernel pool operations",
+            SpiceErrorType::InvalidFormat => "Invalid format",
+            SpiceErrorType::InvalidIndex => "Invalid index",
+            SpiceErrorType::SpiceError => "Generic SPICE error",
+        }
+    }
+
     /// Get the error type as a string
     pub fn error_type_string(&self) -> &'static str {
         match self.error_type {
@@ -184,27 +265,197 @@ impl ErrorTrace {
 }
 
 /// Global error trace for debugging
-static mut GLOBAL_ERROR_TRACE: Option<ErrorTrace> = None;
+static GLOBAL_ERROR_TRACE: GlobalState<ErrorTrace> = GlobalState::new();
 
 /// Initialize the error handling system
 pub fn initialize_error_system() -> SpiceResult<()> {
-    unsafe {
-        GLOBAL_ERROR_TRACE = Some(ErrorTrace::new());
-    }
+    GLOBAL_ERROR_TRACE.set(ErrorTrace::new());
     Ok(())
 }
 
-/// Check if the error system has failed (equivalent to failed_)
+// Functions entered through `TraceScope`, per thread where threads exist
+#[cfg(feature = "std")]
+std::thread_local! {
+    static TRACE_STACK: core::cell::RefCell<ErrorTrace> = const { core::cell::RefCell::new(ErrorTrace { function_stack: Vec::new() }) };
+}
+
+/// Run `f` on the trace stack
+#[cfg(feature = "std")]
+fn with_trace_stack<R>(f: impl FnOnce(&mut ErrorTrace) -> R) -> R {
+    TRACE_STACK.with(|trace| f(&mut trace.borrow_mut()))
+}
+
+/// Run `f` on the trace stack
+#[cfg(not(feature = "std"))]
+fn with_trace_stack<R>(f: impl FnOnce(&mut ErrorTrace) -> R) -> R {
+    GLOBAL_ERROR_TRACE.write(|trace| f(trace.get_or_insert_with(ErrorTrace::new)))
+}
+
+/// Guard naming a function on the call trace of errors created while it lives
+/// (equivalent to chkin_c, with chkout_c on drop)
+///
+/// Scopes nest: errors created inside list every live scope, outermost first.
+#[must_use = "the function leaves the trace as soon as the scope is dropped"]
+pub struct TraceScope {
+    _private: (),
+}
+
+impl TraceScope {
+    /// Push `function_name` onto the trace until the returned guard drops
+    pub fn enter(function_name: &str) -> Self {
+        with_trace_stack(|trace| trace.enter_function(function_name.into()));
+        TraceScope { _private: () }
+    }
+}
+
+impl Drop for TraceScope {
+    fn drop(&mut self) {
+        with_trace_stack(|trace| trace.exit_function());
+    }
+}
+
+// Error signaled but not yet returned, per thread where threads exist
+#[cfg(feature = "std")]
+std::thread_local! {
+    static SIGNALED_ERROR: core::cell::RefCell<Option<SpiceError>> = const { core::cell::RefCell::new(None) };
+}
+#[cfg(not(feature = "std"))]
+static SIGNALED_ERROR: GlobalState<SpiceError> = GlobalState::new();
+
+/// Run `f` on the signaled error slot
+#[cfg(feature = "std")]
+fn with_signaled_error<R>(f: impl FnOnce(&mut Option<SpiceError>) -> R) -> R {
+    SIGNALED_ERROR.with(|slot| f(&mut slot.borrow_mut()))
+}
+
+/// Run `f` on the signaled error slot
+#[cfg(not(feature = "std"))]
+fn with_signaled_error<R>(f: impl FnOnce(&mut Option<SpiceError>) -> R) -> R {
+    SIGNALED_ERROR.write(f)
+}
+
+/// Record an error without returning it (equivalent to sigerr_)
+///
+/// As in CSPICE, the first error is kept until the state is reset.
+pub fn signal_error(error: SpiceError) {
+    with_signaled_error(|slot| {
+        if slot.is_none() {
+            *slot = Some(error);
+        }
+    });
+}
+
+/// Check if an error has been signaled (equivalent to failed_)
 pub fn has_failed() -> bool {
-    // In Rust, we use Result types instead of global error state
-    // This function is provided for compatibility but should rarely be used
-    false
+    with_signaled_error(|slot| slot.is_some())
 }
 
 /// Reset error state (equivalent to reset_)
 pub fn reset_error_state() {
-    // In Rust, each operation returns its own Result
-    // This function is provided for compatibility but is largely unnecessary
+    with_signaled_error(|slot| *slot = None);
+}
+
+/// Record an error without returning it (equivalent to sigerr_c)
+///
+/// Same as `signal_error`: the first error is kept until `reset`.
+pub fn set_error(error: SpiceError) {
+    signal_error(error);
+}
+
+/// Check if an error has been signaled (equivalent to failed_c)
+pub fn failed() -> bool {
+    has_failed()
+}
+
+/// Reset error state (equivalent to reset_c)
+pub fn reset() {
+    reset_error_state();
+}
+
+/// Message of the signaled error (equivalent to getmsg_c)
+///
+/// `which` is `"SHORT"`, `"LONG"` or `"EXPLAIN"`, in any case. The result is
+/// empty when no error is signaled or `which` is not one of these.
+pub fn get_message(which: &str) -> String {
+    with_signaled_error(|slot| match slot.as_ref() {
+        Some(error) => match which.trim().to_ascii_uppercase().as_str() {
+            "SHORT" => error.short_message(),
+            "LONG" => error.message.clone(),
+            "EXPLAIN" => error.explanation().into(),
+            _ => String::new(),
+        },
+        None => String::new(),
+    })
+}
+
+/// Callback receiving each raised error
+pub type ErrorHandler = Box<dyn Fn(&SpiceError) + Send + Sync>;
+
+/// What `SpiceError::raise` does with an error
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ErrorAction {
+    /// Report the error to the handler, then panic
+    Abort,
+    /// Report the error to the handler and return it
+    Return,
+    /// Return the error without reporting it
+    Ignore,
+}
+
+/// An installed `ErrorHandler`, shared so `raise` can call it unlocked
+type SharedErrorHandler = Arc<dyn Fn(&SpiceError) + Send + Sync>;
+
+/// Handler for raised errors
+static ERROR_HANDLER: GlobalState<SharedErrorHandler> = GlobalState::new();
+
+/// Action for raised errors; RETURN when unset
+static ERROR_ACTION: GlobalState<ErrorAction> = GlobalState::new();
+
+/// Install the handler called for each raised error, replacing any previous one
+pub fn set_error_handler(handler: ErrorHandler) {
+    ERROR_HANDLER.set(Arc::from(handler));
+}
+
+/// Remove the error handler
+pub fn clear_error_handler() {
+    ERROR_HANDLER.write(|slot| *slot = None);
+}
+
+/// Set the action for raised errors (equivalent to erract_c with "SET")
+///
+/// `action` is `"ABORT"`, `"RETURN"` or `"IGNORE"`, in any case.
+pub fn set_error_action(action: &str) -> SpiceResult<()> {
+    let action = match action.trim().to_ascii_uppercase().as_str() {
+        "ABORT" => ErrorAction::Abort,
+        "RETURN" => ErrorAction::Return,
+        "IGNORE" => ErrorAction::Ignore,
+        _ => return Err(SpiceError::new(
+            SpiceErrorType::InvalidArgument,
+            format!("Unknown error action '{}'", action)
+        )),
+    };
+    ERROR_ACTION.set(action);
+    Ok(())
+}
+
+/// Current action for raised errors (equivalent to erract_c with "GET")
+pub fn error_action() -> ErrorAction {
+    ERROR_ACTION.read(|action| action.copied().unwrap_or(ErrorAction::Return))
+}
+
+/// Merge the signaled error state into a result, clearing it
+///
+/// An `Ok` becomes the signaled error if one is pending. An `Err` is returned
+/// as is, with the signaled message added as details when it has none.
+pub fn check<T>(result: SpiceResult<T>) -> SpiceResult<T> {
+    let signaled = with_signaled_error(|slot| slot.take());
+    match (result, signaled) {
+        (Ok(_), Some(error)) => Err(error),
+        (Err(error), Some(signaled)) if error.details.is_none() => {
+            Err(error.with_details(format!("Also signaled: {}", signaled.message)))
+        },
+        (result, _) => result,
+    }
 }
 
 /// Convenience macros for error creation
@@ -270,4 +521,124 @@ mod tests {
         assert_eq!(error.function_trace[0], "compute_position");
         assert_eq!(error.function_trace[1], "ephemeris_state");
     }
+
+    #[test]
+    fn test_check_merges_signaled_errors() {
+        reset_error_state();
+
+        // Clean success passes through
+        assert_eq!(check(Ok::<_, SpiceError>(42)).unwrap(), 42);
+        assert!(!has_failed());
+
+        // Success from a routine that signaled instead of returning
+        let ported = || {
+            signal_error(SpiceError::new(SpiceErrorType::InsufficientData, "No leapseconds loaded".to_string()));
+            signal_error(SpiceError::new(SpiceErrorType::InvalidTime, "Later errors are dropped".to_string()));
+            Ok::<_, SpiceError>(0.0)
+        };
+        let result = ported();
+        assert!(has_failed());
+        let error = check(result).unwrap_err();
+        assert_eq!(error.error_type, SpiceErrorType::InsufficientData);
+        assert!(!has_failed());
+
+        // A returned error keeps precedence over a signaled one
+        signal_error(SpiceError::new(SpiceErrorType::InvalidTarget, "Unknown body".to_string()));
+        let error = check::<()>(Err(SpiceError::new(SpiceErrorType::InvalidFrame, "Unknown frame".to_string()))).unwrap_err();
+        assert_eq!(error.error_type, SpiceErrorType::InvalidFrame);
+        assert_eq!(error.details.as_deref(), Some("Also signaled: Unknown body"));
+        assert!(!has_failed());
+    }
+
+    #[test]
+    fn test_cspice_style_error_polling() {
+        reset();
+        assert!(!failed());
+        assert_eq!(get_message("LONG"), "");
+
+        set_error(SpiceError::new(SpiceErrorType::InvalidTime, "Time string '2000 Foo 1' is not recognized".to_string()));
+        assert!(failed());
+        assert_eq!(get_message("short"), "SPICE(INVALIDTIME)");
+        assert_eq!(get_message("LONG"), "Time string '2000 Foo 1' is not recognized");
+        assert_eq!(get_message("EXPLAIN"), "Invalid time string or time conversion error");
+        assert_eq!(get_message("VERBOSE"), "");
+
+        reset();
+        assert!(!failed());
+        assert_eq!(get_message("SHORT"), "");
+    }
+
+    /// Puts back the error action and removes the handler when a test ends,
+    /// even if it panics
+    struct RestoreErrorAction(ErrorAction);
+
+    impl Drop for RestoreErrorAction {
+        fn drop(&mut self) {
+            ERROR_ACTION.set(self.0);
+            clear_error_handler();
+        }
+    }
+
+    #[test]
+    fn test_error_handler_sees_each_raised_error_once() {
+        use std::sync::Mutex;
+
+        let _restore = RestoreErrorAction(error_action());
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let log = Arc::clone(&seen);
+        set_error_handler(Box::new(move |error| {
+            if error.message.starts_with("handler test") {
+                log.lock().unwrap().push(error.message.clone());
+            }
+        }));
+        assert_eq!(error_action(), ErrorAction::Return);
+
+        let first = SpiceError::raise(SpiceErrorType::InvalidTarget, "handler test 1".to_string());
+        assert_eq!(first.error_type, SpiceErrorType::InvalidTarget);
+        let _ = SpiceError::raise(SpiceErrorType::InvalidFrame, "handler test 2".to_string());
+        // Plain construction is not reported
+        let _ = SpiceError::new(SpiceErrorType::InvalidFrame, "handler test 3".to_string());
+        assert_eq!(*seen.lock().unwrap(), ["handler test 1", "handler test 2"]);
+
+        set_error_action("ignore").unwrap();
+        let _ = SpiceError::raise(SpiceErrorType::InvalidFrame, "handler test 4".to_string());
+        set_error_action("ABORT").unwrap();
+        let aborted = std::panic::catch_unwind(|| SpiceError::raise(SpiceErrorType::InvalidTime, "handler test 5".to_string()));
+        assert!(aborted.is_err());
+        set_error_action("RETURN").unwrap();
+        clear_error_handler();
+
+        assert_eq!(*seen.lock().unwrap(), ["handler test 1", "handler test 2", "handler test 5"]);
+        assert!(set_error_action("REPORT").is_err());
+
+        // A handler may replace itself without deadlocking
+        let log = Arc::clone(&seen);
+        set_error_handler(Box::new(move |error| {
+            log.lock().unwrap().push(error.message.clone());
+            clear_error_handler();
+        }));
+        let _ = SpiceError::raise(SpiceErrorType::InvalidTime, "handler test 6".to_string());
+        let _ = SpiceError::raise(SpiceErrorType::InvalidTime, "handler test 7".to_string());
+        assert_eq!(seen.lock().unwrap().last().unwrap(), "handler test 6");
+    }
+
+    #[test]
+    fn test_trace_scopes_nest_into_error_trace() {
+        fn inner() -> SpiceResult<()> {
+            let _scope = TraceScope::enter("spkpos");
+            Err(SpiceError::new(SpiceErrorType::InsufficientData, "No ephemeris data".to_string()))
+        }
+        fn outer() -> SpiceResult<()> {
+            let _scope = TraceScope::enter("subpnt");
+            inner()
+        }
+
+        let error = outer().unwrap_err();
+        assert_eq!(error.function_trace, ["subpnt", "spkpos"]);
+        assert!(error.to_string().contains("1: subpnt\n  2: spkpos"));
+
+        // Both scopes have been left
+        let error = SpiceError::new(SpiceErrorType::InvalidTime, "Outside any scope".to_string());
+        assert!(error.function_trace.is_empty());
+    }
 }
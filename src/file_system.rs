@@ -6,8 +6,8 @@
 //! The system supports:
 //! - Loading kernel data from byte arrays (ArrayBuffer in JavaScript)
 //! - Virtual file paths and handles 
-//! - DAF (Double precision Array File) format support
-//! - DAS (Direct Access, Segregated) format support
+//! - DAF (Double precision Array File) format support (see `daf`)
+//! - DAS (Direct Access, Segregated) format support (see `das`)
 //! 
 //! # CSPICE Equivalents
 //! - Virtual file system replaces direct file I/O
@@ -21,6 +21,9 @@ use std::vec::Vec;
 use std::string::String;
 use core::fmt;
 
+pub mod daf;
+pub mod das;
+
 /// Maximum number of simultaneously loaded kernels
 pub const MAX_KERNELS: usize = 1000;
 
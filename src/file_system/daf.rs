@@ -0,0 +1,692 @@
+//! Double precision Array File (DAF) access for RustSPICE
+//!
+//! This module provides equivalency to the CSPICE DAF open and array routines:
+//! - dafopr_c → DafFile::open_read() - Open an existing DAF read-only
+//! - dafopw_c → DafFile::open_write() - Open an existing DAF for adding arrays
+//! - dafonw_c → DafFile::new() - Start a new, empty DAF in write mode
+//! - dafbna_c / dafada_c / dafena_c → DafFile::add_array() - Add an array
+//! - dafgs_c / dafgn_c / dafgda_c → summaries(), array_data()
+//! - dafac_c / dafec_c → add_comments(), comments() - Comment area
+//!
+//! Files are held in memory. Write-mode files are turned back into bytes with
+//! `DafFile::to_bytes()`, which lays out the file record, summary/name record
+//! chain and array data exactly as CSPICE does.
+
+#[cfg(not(feature = "std"))]
+use alloc::{string::String, vec::Vec, format};
+#[cfg(feature = "std")]
+use std::{string::String, vec::Vec, format};
+
+use crate::foundation::{SpiceDouble, SpiceInt};
+use crate::error_handling::{SpiceResult, SpiceError, SpiceErrorType};
+
+/// DAF physical record length in bytes
+pub const DAF_RECORD_BYTES: usize = 1024;
+
+/// Double precision words per DAF record
+const WORDS_PER_RECORD: usize = DAF_RECORD_BYTES / 8;
+
+/// Maximum length of the internal file name
+const INTERNAL_NAME_LENGTH: usize = 60;
+
+/// Characters of comment text stored in each comment record
+const COMMENT_CHARS_PER_RECORD: usize = 1000;
+
+/// End-of-line marker in the comment area
+const COMMENT_EOL: u8 = 0;
+
+/// End-of-comments marker in the comment area
+const COMMENT_EOT: u8 = 4;
+
+/// FTP validation string stored in the file record
+const FTP_STRING: &[u8; 28] = b"FTPSTR:\r:\n:\r\n:\r\x00:\x81:\x10\xce:ENDFTP";
+
+/// How a DAF was opened
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DafOpenMode {
+    /// Loaded reference kernel; arrays may be read but not added
+    ReadOnly,
+    /// Writable in-memory file
+    Write,
+}
+
+/// Binary format of the numeric data in a DAF
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DafByteOrder {
+    /// "LTL-IEEE"
+    LittleEndian,
+    /// "BIG-IEEE"
+    BigEndian,
+}
+
+impl DafByteOrder {
+    /// The LOCFMT string recorded in the file record
+    pub fn format_name(&self) -> &'static str {
+        match self {
+            DafByteOrder::LittleEndian => "LTL-IEEE",
+            DafByteOrder::BigEndian => "BIG-IEEE",
+        }
+    }
+
+    /// Byte order named by a file record's LOCFMT string
+    ///
+    /// Pre-N0050 files carry no format string; for those the order giving a
+    /// plausible ND is chosen.
+    pub(crate) fn detect(file_record: &[u8]) -> DafByteOrder {
+        match &file_record[88..96] {
+            b"BIG-IEEE" => DafByteOrder::BigEndian,
+            b"LTL-IEEE" => DafByteOrder::LittleEndian,
+            _ => {
+                let nd = DafByteOrder::LittleEndian.read_i32(&file_record[8..12]);
+                if (0..=124).contains(&nd) { DafByteOrder::LittleEndian } else { DafByteOrder::BigEndian }
+            }
+        }
+    }
+
+    pub(crate) fn read_f64(&self, bytes: &[u8]) -> SpiceDouble {
+        let mut raw = [0u8; 8];
+        raw.copy_from_slice(&bytes[..8]);
+        match self {
+            DafByteOrder::LittleEndian => f64::from_le_bytes(raw),
+            DafByteOrder::BigEndian => f64::from_be_bytes(raw),
+        }
+    }
+
+    pub(crate) fn read_i32(&self, bytes: &[u8]) -> SpiceInt {
+        let mut raw = [0u8; 4];
+        raw.copy_from_slice(&bytes[..4]);
+        match self {
+            DafByteOrder::LittleEndian => i32::from_le_bytes(raw),
+            DafByteOrder::BigEndian => i32::from_be_bytes(raw),
+        }
+    }
+
+    pub(crate) fn f64_bytes(&self, value: SpiceDouble) -> [u8; 8] {
+        match self {
+            DafByteOrder::LittleEndian => value.to_le_bytes(),
+            DafByteOrder::BigEndian => value.to_be_bytes(),
+        }
+    }
+
+    pub(crate) fn i32_bytes(&self, value: SpiceInt) -> [u8; 4] {
+        match self {
+            DafByteOrder::LittleEndian => value.to_le_bytes(),
+            DafByteOrder::BigEndian => value.to_be_bytes(),
+        }
+    }
+}
+
+/// Descriptor of one DAF array
+///
+/// `integers` holds the NI - 2 user integer components; the initial and final
+/// addresses that close every DAF summary are managed by `DafFile`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DafSummary {
+    pub doubles: Vec<SpiceDouble>,
+    pub integers: Vec<SpiceInt>,
+    pub name: String,
+}
+
+/// An in-memory DAF
+#[derive(Debug, Clone)]
+pub struct DafFile {
+    mode: DafOpenMode,
+    id_word: String,
+    internal_name: String,
+    nd: usize,
+    ni: usize,
+    byte_order: DafByteOrder,
+    arrays: Vec<(DafSummary, Vec<SpiceDouble>)>,
+    /// Lines of the comment area
+    comments: Vec<String>,
+    /// Word addresses of arrays read from the file; `None` until written
+    addresses: Vec<Option<(usize, usize)>>,
+}
+
+impl DafFile {
+    /// Start a new, empty DAF in write mode (equivalent to dafonw_c)
+    pub fn new(id_word: &str, nd: usize, ni: usize, internal_name: &str) -> SpiceResult<Self> {
+        if !id_word.starts_with("DAF/") || id_word.len() > 8 {
+            return Err(SpiceError::new(
+                SpiceErrorType::InvalidArgument,
+                format!("Invalid DAF ID word '{}'", id_word)
+            ));
+        }
+        validate_component_counts(nd, ni)?;
+
+        Ok(Self {
+            mode: DafOpenMode::Write,
+            id_word: id_word.to_string(),
+            internal_name: truncate_name(internal_name, INTERNAL_NAME_LENGTH),
+            nd,
+            ni,
+            byte_order: DafByteOrder::LittleEndian,
+            arrays: Vec::new(),
+            comments: Vec::new(),
+            addresses: Vec::new(),
+        })
+    }
+
+    /// Open an existing DAF for reading only (equivalent to dafopr_c)
+    pub fn open_read(data: &[u8]) -> SpiceResult<Self> {
+        Self::parse(data, DafOpenMode::ReadOnly)
+    }
+
+    /// Open an existing DAF so that arrays can be added (equivalent to dafopw_c)
+    pub fn open_write(data: &[u8]) -> SpiceResult<Self> {
+        Self::parse(data, DafOpenMode::Write)
+    }
+
+    /// Mode the file was opened in
+    pub fn mode(&self) -> DafOpenMode {
+        self.mode
+    }
+
+    /// File ID word, e.g. "DAF/SPK"
+    pub fn id_word(&self) -> &str {
+        &self.id_word
+    }
+
+    /// Internal file name (LOCIFN)
+    pub fn internal_name(&self) -> &str {
+        &self.internal_name
+    }
+
+    /// Number of double precision summary components
+    pub fn nd(&self) -> usize {
+        self.nd
+    }
+
+    /// Number of integer summary components, including the two addresses
+    pub fn ni(&self) -> usize {
+        self.ni
+    }
+
+    /// Byte order of the numeric data
+    pub fn byte_order(&self) -> DafByteOrder {
+        self.byte_order
+    }
+
+    /// Number of arrays in the file
+    pub fn array_count(&self) -> usize {
+        self.arrays.len()
+    }
+
+    /// Summaries of all arrays in file order
+    pub fn summaries(&self) -> Vec<&DafSummary> {
+        self.arrays.iter().map(|(summary, _)| summary).collect()
+    }
+
+    /// Elements of an array (equivalent to dafgda_c)
+    ///
+    /// Works in either mode, including for arrays added since a write-mode
+    /// file was opened.
+    pub fn array_data(&self, index: usize) -> SpiceResult<&[SpiceDouble]> {
+        self.arrays.get(index).map(|(_, data)| data.as_slice()).ok_or_else(|| SpiceError::new(
+            SpiceErrorType::InvalidIndex,
+            format!("DAF array index {} out of range (file has {} arrays)", index, self.arrays.len())
+        ))
+    }
+
+    /// Initial and final word addresses of an array as stored in the source file
+    ///
+    /// Arrays added in memory have no address until the file is written.
+    pub fn array_addresses(&self, index: usize) -> Option<(usize, usize)> {
+        self.addresses.get(index).copied().flatten()
+    }
+
+    /// Append an array with its summary components and name
+    ///
+    /// `integers` must hold NI - 2 values; the array addresses are assigned
+    /// when the file is written out.
+    pub fn add_array(
+        &mut self,
+        doubles: &[SpiceDouble],
+        integers: &[SpiceInt],
+        name: &str,
+        data: &[SpiceDouble],
+    ) -> SpiceResult<()> {
+        if self.mode != DafOpenMode::Write {
+            return Err(SpiceError::new(
+                SpiceErrorType::FileIOError,
+                format!("DAF '{}' is open read-only; arrays cannot be added", self.internal_name.trim())
+            ));
+        }
+        if doubles.len() != self.nd || integers.len() != self.ni - 2 {
+            return Err(SpiceError::new(
+                SpiceErrorType::InvalidArgument,
+                format!(
+                    "DAF summary needs {} doubles and {} integers, got {} and {}",
+                    self.nd, self.ni - 2, doubles.len(), integers.len()
+                )
+            ));
+        }
+        if data.is_empty() {
+            return Err(SpiceError::new(
+                SpiceErrorType::InvalidArgument,
+                "DAF arrays must contain at least one element".into()
+            ));
+        }
+
+        self.arrays.push((
+            DafSummary {
+                doubles: doubles.to_vec(),
+                integers: integers.to_vec(),
+                name: truncate_name(name, self.name_length()),
+            },
+            data.to_vec(),
+        ));
+        self.addresses.push(None);
+        Ok(())
+    }
+
+    /// Lines of the comment area (equivalent to dafec_c)
+    pub fn comments(&self) -> &[String] {
+        &self.comments
+    }
+
+    /// Append lines to the comment area (equivalent to dafac_c)
+    ///
+    /// Lines must be printable ASCII and fit in one comment record
+    /// (1000 characters); nothing is added if any line is rejected.
+    pub fn add_comments(&mut self, lines: &[&str]) -> SpiceResult<()> {
+        if self.mode != DafOpenMode::Write {
+            return Err(SpiceError::new(
+                SpiceErrorType::FileIOError,
+                format!("DAF '{}' is open read-only; comments cannot be added", self.internal_name.trim())
+            ));
+        }
+        for (i, line) in lines.iter().enumerate() {
+            if line.len() > COMMENT_CHARS_PER_RECORD {
+                return Err(SpiceError::new(
+                    SpiceErrorType::InvalidArgument,
+                    format!(
+                        "Comment line {} has {} characters; the limit is {}",
+                        i, line.len(), COMMENT_CHARS_PER_RECORD
+                    )
+                ));
+            }
+            if let Some(c) = line.chars().find(|c| !(' '..='~').contains(c)) {
+                return Err(SpiceError::new(
+                    SpiceErrorType::InvalidArgument,
+                    format!("Comment line {} contains non-printable character {:?}", i, c)
+                ));
+            }
+        }
+        self.comments.extend(lines.iter().map(|line| line.to_string()));
+        Ok(())
+    }
+
+    /// Comment area bytes: each line ends in EOL, the last is followed by EOT
+    fn comment_bytes(&self) -> Vec<u8> {
+        if self.comments.is_empty() {
+            return Vec::new();
+        }
+        let mut bytes = Vec::new();
+        for line in &self.comments {
+            bytes.extend_from_slice(line.as_bytes());
+            bytes.push(COMMENT_EOL);
+        }
+        bytes.push(COMMENT_EOT);
+        bytes
+    }
+
+    /// Size of one summary in double precision words
+    fn summary_size(&self) -> usize {
+        self.nd + self.ni.div_ceil(2)
+    }
+
+    /// Characters per array name
+    fn name_length(&self) -> usize {
+        8 * self.summary_size()
+    }
+
+    /// Summaries that fit in one summary record after the three control words
+    fn summaries_per_record(&self) -> usize {
+        (WORDS_PER_RECORD - 3) / self.summary_size()
+    }
+
+    /// Serialize the file (the finalize step of dafcls_c for write-mode files)
+    pub fn to_bytes(&self) -> SpiceResult<Vec<u8>> {
+        let order = self.byte_order;
+        let per_record = self.summaries_per_record();
+
+        // Records of the file: the file record is 1, then the comment records,
+        // then summary/name/data groups
+        let mut records: Vec<[u8; DAF_RECORD_BYTES]> = vec![[0u8; DAF_RECORD_BYTES]];
+        for text in self.comment_bytes().chunks(COMMENT_CHARS_PER_RECORD) {
+            let mut record = [b' '; DAF_RECORD_BYTES];
+            record[..text.len()].copy_from_slice(text);
+            records.push(record);
+        }
+        let first_summary = records.len() + 1;
+        let mut summary_records = Vec::new();
+        let mut free = 0;
+        let chunks: Vec<&[(DafSummary, Vec<SpiceDouble>)]> = if self.arrays.is_empty() {
+            vec![&[]]
+        } else {
+            self.arrays.chunks(per_record).collect()
+        };
+
+        for chunk in &chunks {
+            let summary_record = records.len() + 1;
+            summary_records.push(summary_record);
+            records.push([0u8; DAF_RECORD_BYTES]);
+            records.push([b' '; DAF_RECORD_BYTES]);
+
+            // Array data follows the name record, word by word
+            let mut next_word = (summary_record + 1) * WORDS_PER_RECORD + 1;
+            let mut addresses = Vec::new();
+            let mut words = Vec::new();
+            for (_, data) in chunk.iter() {
+                addresses.push((next_word, next_word + data.len() - 1));
+                next_word += data.len();
+                words.extend_from_slice(data);
+            }
+            free = next_word;
+            for block in words.chunks(WORDS_PER_RECORD) {
+                let mut record = [0u8; DAF_RECORD_BYTES];
+                for (i, &word) in block.iter().enumerate() {
+                    record[i * 8..i * 8 + 8].copy_from_slice(&order.f64_bytes(word));
+                }
+                records.push(record);
+            }
+
+            let summary = &mut records[summary_record - 1];
+            summary[16..24].copy_from_slice(&order.f64_bytes(chunk.len() as SpiceDouble));
+            for (i, ((descriptor, _), &(begin, end))) in chunk.iter().zip(&addresses).enumerate() {
+                let mut offset = 24 + i * self.summary_size() * 8;
+                for &value in &descriptor.doubles {
+                    summary[offset..offset + 8].copy_from_slice(&order.f64_bytes(value));
+                    offset += 8;
+                }
+                let integers = descriptor.integers.iter().copied()
+                    .chain([begin as SpiceInt, end as SpiceInt]);
+                for value in integers {
+                    summary[offset..offset + 4].copy_from_slice(&order.i32_bytes(value));
+                    offset += 4;
+                }
+            }
+
+            let names = &mut records[summary_record];
+            let name_length = self.name_length();
+            for (i, (descriptor, _)) in chunk.iter().enumerate() {
+                let bytes = descriptor.name.as_bytes();
+                names[i * name_length..i * name_length + bytes.len()].copy_from_slice(bytes);
+            }
+        }
+
+        // Link the summary records in both directions
+        for (i, &record) in summary_records.iter().enumerate() {
+            let next = summary_records.get(i + 1).copied().unwrap_or(0);
+            let prev = if i == 0 { 0 } else { summary_records[i - 1] };
+            let summary = &mut records[record - 1];
+            summary[0..8].copy_from_slice(&order.f64_bytes(next as SpiceDouble));
+            summary[8..16].copy_from_slice(&order.f64_bytes(prev as SpiceDouble));
+        }
+
+        let bward = *summary_records.last().unwrap_or(&first_summary);
+        let file_record = &mut records[0];
+        write_padded(&mut file_record[0..8], &self.id_word);
+        file_record[8..12].copy_from_slice(&order.i32_bytes(self.nd as SpiceInt));
+        file_record[12..16].copy_from_slice(&order.i32_bytes(self.ni as SpiceInt));
+        write_padded(&mut file_record[16..76], &self.internal_name);
+        file_record[76..80].copy_from_slice(&order.i32_bytes(first_summary as SpiceInt));
+        file_record[80..84].copy_from_slice(&order.i32_bytes(bward as SpiceInt));
+        file_record[84..88].copy_from_slice(&order.i32_bytes(free as SpiceInt));
+        write_padded(&mut file_record[88..96], order.format_name());
+        file_record[699..727].copy_from_slice(FTP_STRING);
+
+        Ok(records.concat())
+    }
+
+    /// Read the file record, then walk the summary record chain
+    fn parse(data: &[u8], mode: DafOpenMode) -> SpiceResult<Self> {
+        if data.len() < DAF_RECORD_BYTES {
+            return Err(SpiceError::new(
+                SpiceErrorType::InvalidKernelData,
+                format!("DAF must be at least {} bytes, got {}", DAF_RECORD_BYTES, data.len())
+            ));
+        }
+        let id_word = String::from_utf8_lossy(&data[0..8]).trim_end().to_string();
+        if !id_word.starts_with("DAF/") && id_word != "NAIF/DAF" {
+            return Err(SpiceError::new(
+                SpiceErrorType::InvalidKernelData,
+                format!("Not a DAF file (ID word '{}')", id_word)
+            ));
+        }
+
+        let byte_order = DafByteOrder::detect(data);
+
+        let nd = byte_order.read_i32(&data[8..12]);
+        let ni = byte_order.read_i32(&data[12..16]);
+        if nd < 0 || ni < 2 {
+            return Err(SpiceError::new(
+                SpiceErrorType::InvalidKernelData,
+                format!("Invalid DAF summary format ND={} NI={}", nd, ni)
+            ));
+        }
+        let (nd, ni) = (nd as usize, ni as usize);
+        validate_component_counts(nd, ni)?;
+        let internal_name = String::from_utf8_lossy(&data[16..76]).trim_end().to_string();
+        let fward = byte_order.read_i32(&data[76..80]);
+
+        let mut file = Self {
+            mode,
+            id_word,
+            internal_name,
+            nd,
+            ni,
+            byte_order,
+            arrays: Vec::new(),
+            comments: read_comments(data, fward),
+            addresses: Vec::new(),
+        };
+
+        let record_count = data.len() / DAF_RECORD_BYTES;
+        let summary_size = file.summary_size();
+        let name_length = file.name_length();
+        let mut record = fward;
+        let mut visited = 0;
+
+        while record != 0 {
+            visited += 1;
+            if record < 2 || record as usize + 1 > record_count || visited > record_count {
+                return Err(SpiceError::new(
+                    SpiceErrorType::InvalidKernelData,
+                    format!("DAF summary record {} is outside the file", record)
+                ));
+            }
+            let summary = record_bytes(data, record as usize);
+            let names = record_bytes(data, record as usize + 1);
+
+            let next = byte_order.read_f64(&summary[0..8]) as i32;
+            let count = byte_order.read_f64(&summary[16..24]) as usize;
+            if count > file.summaries_per_record() {
+                return Err(SpiceError::new(
+                    SpiceErrorType::InvalidKernelData,
+                    format!("DAF summary record {} claims {} summaries", record, count)
+                ));
+            }
+
+            for i in 0..count {
+                let start = 24 + i * summary_size * 8;
+                let doubles: Vec<SpiceDouble> = (0..nd)
+                    .map(|k| byte_order.read_f64(&summary[start + 8 * k..]))
+                    .collect();
+                let int_start = start + 8 * nd;
+                let mut integers: Vec<SpiceInt> = (0..ni)
+                    .map(|k| byte_order.read_i32(&summary[int_start + 4 * k..]))
+                    .collect();
+                let end = integers.pop().unwrap_or(0);
+                let begin = integers.pop().unwrap_or(0);
+
+                let name = String::from_utf8_lossy(&names[i * name_length..(i + 1) * name_length])
+                    .trim_end()
+                    .to_string();
+                let elements = read_words(data, byte_order, begin, end)?;
+                file.arrays.push((DafSummary { doubles, integers, name }, elements));
+                file.addresses.push(Some((begin as usize, end as usize)));
+            }
+
+            record = next;
+        }
+
+        Ok(file)
+    }
+}
+
+/// Check ND and NI against the DAF limits
+fn validate_component_counts(nd: usize, ni: usize) -> SpiceResult<()> {
+    if ni < 2 || nd > 124 || ni > 250 || nd + ni.div_ceil(2) > 125 {
+        return Err(SpiceError::new(
+            SpiceErrorType::InvalidArgument,
+            format!("Invalid DAF summary format ND={} NI={}", nd, ni)
+        ));
+    }
+    Ok(())
+}
+
+/// Lines of the comment area, held in the records between the file record
+/// and the first summary record
+fn read_comments(data: &[u8], fward: SpiceInt) -> Vec<String> {
+    let last = (fward.max(2) as usize - 1).min(data.len() / DAF_RECORD_BYTES);
+    let mut text = Vec::new();
+    for record in 2..=last {
+        let chars = &record_bytes(data, record)[..COMMENT_CHARS_PER_RECORD];
+        match chars.iter().position(|&b| b == COMMENT_EOT) {
+            Some(end) => {
+                text.extend_from_slice(&chars[..end]);
+                break;
+            }
+            None => text.extend_from_slice(chars),
+        }
+    }
+    if text.is_empty() {
+        return Vec::new();
+    }
+
+    // Every line, including the last, is terminated by EOL
+    if text.last() == Some(&COMMENT_EOL) {
+        text.pop();
+    }
+    text.split(|&b| b == COMMENT_EOL)
+        .map(|line| String::from_utf8_lossy(line).into_owned())
+        .collect()
+}
+
+/// Bytes of a 1-based record
+fn record_bytes(data: &[u8], record: usize) -> &[u8] {
+    &data[(record - 1) * DAF_RECORD_BYTES..record * DAF_RECORD_BYTES]
+}
+
+/// Read double precision words `begin..=end` (1-based word addresses)
+fn read_words(data: &[u8], order: DafByteOrder, begin: SpiceInt, end: SpiceInt) -> SpiceResult<Vec<SpiceDouble>> {
+    if begin < 1 || end < begin || (end as usize).checked_mul(8).is_none_or(|bytes| bytes > data.len()) {
+        return Err(SpiceError::new(
+            SpiceErrorType::InvalidKernelData,
+            format!("DAF array addresses {}..{} are outside the file", begin, end)
+        ));
+    }
+    Ok((begin as usize..=end as usize)
+        .map(|word| order.read_f64(&data[(word - 1) * 8..word * 8]))
+        .collect())
+}
+
+/// Copy a string into a fixed-width, blank-padded field
+fn write_padded(field: &mut [u8], value: &str) {
+    field.fill(b' ');
+    let bytes = value.as_bytes();
+    let length = bytes.len().min(field.len());
+    field[..length].copy_from_slice(&bytes[..length]);
+}
+
+/// Truncate a name to the longest prefix that fits a field of `length` bytes
+/// without splitting a character
+fn truncate_name(name: &str, length: usize) -> String {
+    let mut end = name.len().min(length);
+    while !name.is_char_boundary(end) {
+        end -= 1;
+    }
+    name[..end].to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spk_like_file() -> DafFile {
+        let mut daf = DafFile::new("DAF/SPK", 2, 6, "TEST SPK").unwrap();
+        daf.add_array(&[0.0, 100.0], &[399, 3, 1, 2], "EARTH SEGMENT", &[1.0, 2.0, 3.0]).unwrap();
+        daf.add_array(&[50.0, 150.0], &[301, 3, 1, 2], "MOON SEGMENT", &[4.0, 5.0]).unwrap();
+        daf
+    }
+
+    #[test]
+    fn test_write_to_read_only_daf_errors() {
+        let bytes = spk_like_file().to_bytes().unwrap();
+        let mut daf = DafFile::open_read(&bytes).unwrap();
+        assert_eq!(daf.mode(), DafOpenMode::ReadOnly);
+
+        let err = daf.add_array(&[0.0, 1.0], &[499, 4, 1, 2], "MARS", &[1.0]).unwrap_err();
+        assert_eq!(err.error_type, SpiceErrorType::FileIOError);
+        assert!(err.message.contains("read-only"));
+        assert_eq!(daf.array_count(), 2);
+    }
+
+    #[test]
+    fn test_write_mode_daf_accepts_arrays() {
+        let bytes = spk_like_file().to_bytes().unwrap();
+        let mut daf = DafFile::open_write(&bytes).unwrap();
+        daf.add_array(&[0.0, 1.0], &[499, 4, 1, 2], "MARS", &[7.0, 8.0, 9.0, 10.0]).unwrap();
+
+        // Arrays already written are readable before the file is finalized
+        assert_eq!(daf.array_data(0).unwrap(), &[1.0, 2.0, 3.0]);
+        assert_eq!(daf.array_data(2).unwrap(), &[7.0, 8.0, 9.0, 10.0]);
+
+        let reread = DafFile::open_read(&daf.to_bytes().unwrap()).unwrap();
+        assert_eq!(reread.id_word(), "DAF/SPK");
+        assert_eq!(reread.internal_name(), "TEST SPK");
+        assert_eq!(reread.array_count(), 3);
+        let summaries = reread.summaries();
+        assert_eq!(summaries[1].name, "MOON SEGMENT");
+        assert_eq!(summaries[1].doubles, vec![50.0, 150.0]);
+        assert_eq!(summaries[1].integers, vec![301, 3, 1, 2]);
+        assert_eq!(reread.array_data(1).unwrap(), &[4.0, 5.0]);
+        assert_eq!(reread.array_addresses(0), Some((385, 387)));
+        assert_eq!(daf.array_addresses(2), None);
+        assert_eq!(reread.array_data(2).unwrap(), &[7.0, 8.0, 9.0, 10.0]);
+        assert!(reread.array_data(3).is_err());
+    }
+
+    #[test]
+    fn test_summary_record_chain() {
+        // More arrays than fit in one summary record
+        let mut daf = DafFile::new("DAF/SPK", 2, 6, "CHAIN").unwrap();
+        for i in 0..60 {
+            daf.add_array(&[i as f64, i as f64 + 1.0], &[i, 0, 1, 2], &format!("ARRAY {}", i), &[i as f64; 3]).unwrap();
+        }
+        let reread = DafFile::open_read(&daf.to_bytes().unwrap()).unwrap();
+        assert_eq!(reread.array_count(), 60);
+        assert_eq!(reread.summaries()[59].name, "ARRAY 59");
+        assert_eq!(reread.array_data(42).unwrap(), &[42.0; 3]);
+    }
+
+    #[test]
+    fn test_multibyte_names_truncate_on_character_boundaries() {
+        // Both names run one byte past their field in the middle of an 'é'
+        let internal_name = format!("{}é", "I".repeat(59));
+        let segment_name = format!("{}é", "S".repeat(39));
+        let mut daf = DafFile::new("DAF/SPK", 2, 6, &internal_name).unwrap();
+        daf.add_array(&[0.0, 1.0], &[399, 3, 1, 2], &segment_name, &[1.0]).unwrap();
+        assert_eq!(daf.internal_name(), "I".repeat(59));
+        assert_eq!(daf.summaries()[0].name, "S".repeat(39));
+
+        // Names that fit keep their multibyte characters through a reload
+        daf.add_array(&[0.0, 1.0], &[301, 3, 1, 2], "LUNE — ÉTÉ", &[2.0]).unwrap();
+        let reread = DafFile::open_read(&daf.to_bytes().unwrap()).unwrap();
+        assert_eq!(reread.internal_name(), daf.internal_name());
+        assert_eq!(reread.summaries()[0].name, daf.summaries()[0].name);
+        assert_eq!(reread.summaries()[1].name, "LUNE — ÉTÉ");
+    }
+}
@@ -0,0 +1,541 @@
+//! Direct Access, Segregated (DAS) file access for RustSPICE
+//!
+//! This module provides equivalency to the CSPICE DAS read routines:
+//! - dasopr_c → DasFile::open_read() - Open an existing DAS read-only
+//! - dasec_c → comments() - Read the comment area
+//! - daslla_c → last_address() - Last logical address of each data type
+//! - dasrdc_c → read_characters() - Read a range of character data
+//! - dasrdd_c → read_doubles() - Read a range of double precision data
+//! - dasrdi_c → read_integers() - Read a range of integer data
+//!
+//! A DAS holds three independent arrays, one per data type, each addressed by
+//! 1-based logical addresses. Physically the data lives in clusters of
+//! records of a single type; directory records describe the clusters that
+//! follow them and chain to the next directory. This module maps logical
+//! addresses to physical records and is the foundation for DSK and EK files.
+
+#[cfg(not(feature = "std"))]
+use alloc::{string::String, vec::Vec, format};
+#[cfg(feature = "std")]
+use std::{string::String, vec::Vec, format};
+
+use core::ops::Range;
+
+use crate::foundation::{SpiceDouble, SpiceInt};
+use crate::error_handling::{SpiceResult, SpiceError, SpiceErrorType};
+use super::daf::DafByteOrder;
+
+/// DAS physical record length in bytes
+pub const DAS_RECORD_BYTES: usize = 1024;
+
+/// Integer words per directory record
+const DIRECTORY_WORDS: usize = DAS_RECORD_BYTES / 4;
+
+/// Directory word holding the backward pointer (1-based)
+const BACKWARD_POINTER: usize = 1;
+
+/// Directory word holding the forward pointer (1-based)
+const FORWARD_POINTER: usize = 2;
+
+/// Directory word holding the data type of the first cluster (1-based)
+const FIRST_CLUSTER_TYPE: usize = 9;
+
+/// End-of-line marker in the comment area
+const COMMENT_EOL: u8 = 0;
+
+/// The three kinds of DAS data
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DasDataType {
+    Character,
+    Double,
+    Integer,
+}
+
+impl DasDataType {
+    /// Type with the given CSPICE code (1 = character, 2 = double, 3 = integer)
+    pub fn from_code(code: SpiceInt) -> Option<Self> {
+        match code {
+            1 => Some(DasDataType::Character),
+            2 => Some(DasDataType::Double),
+            3 => Some(DasDataType::Integer),
+            _ => None,
+        }
+    }
+
+    /// CSPICE code of the type
+    pub fn code(&self) -> SpiceInt {
+        match self {
+            DasDataType::Character => 1,
+            DasDataType::Double => 2,
+            DasDataType::Integer => 3,
+        }
+    }
+
+    /// Number of elements of this type held in one data record
+    pub fn per_record(&self) -> usize {
+        match self {
+            DasDataType::Character => DAS_RECORD_BYTES,
+            DasDataType::Double => DAS_RECORD_BYTES / 8,
+            DasDataType::Integer => DAS_RECORD_BYTES / 4,
+        }
+    }
+
+    fn index(&self) -> usize {
+        self.code() as usize - 1
+    }
+
+    /// Type of the cluster following one of this type; cluster types cycle
+    /// character → double → integer, forward for a positive descriptor and
+    /// backward for a negative one
+    fn next(&self, descriptor: SpiceInt) -> Self {
+        let step = if descriptor > 0 { 1 } else { 2 };
+        match (self.index() + step) % 3 {
+            0 => DasDataType::Character,
+            1 => DasDataType::Double,
+            _ => DasDataType::Integer,
+        }
+    }
+}
+
+/// A run of consecutive data records of one type
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct DasCluster {
+    data_type: DasDataType,
+    /// 1-based record number of the first record
+    first_record: usize,
+    record_count: usize,
+    /// Logical address of the first element in the cluster
+    first_address: usize,
+}
+
+/// An in-memory DAS opened for reading
+#[derive(Debug, Clone)]
+pub struct DasFile {
+    id_word: String,
+    internal_name: String,
+    byte_order: DafByteOrder,
+    reserved_record_count: usize,
+    comment_record_count: usize,
+    comment_char_count: usize,
+    clusters: Vec<DasCluster>,
+    /// Last logical address in use for each data type
+    last_addresses: [usize; 3],
+    data: Vec<u8>,
+}
+
+impl DasFile {
+    /// Open an existing DAS for reading only (equivalent to dasopr_c)
+    pub fn open_read(data: &[u8]) -> SpiceResult<Self> {
+        if data.len() < DAS_RECORD_BYTES {
+            return Err(SpiceError::new(
+                SpiceErrorType::InvalidKernelData,
+                format!("DAS must be at least {} bytes, got {}", DAS_RECORD_BYTES, data.len())
+            ));
+        }
+        let id_word = String::from_utf8_lossy(&data[0..8]).trim_end().to_string();
+        if !id_word.starts_with("DAS/") && id_word != "NAIF/DAS" {
+            return Err(SpiceError::new(
+                SpiceErrorType::InvalidKernelData,
+                format!("Not a DAS file (ID word '{}')", id_word)
+            ));
+        }
+
+        let byte_order = match &data[84..92] {
+            b"BIG-IEEE" => DafByteOrder::BigEndian,
+            b"LTL-IEEE" => DafByteOrder::LittleEndian,
+            other => {
+                return Err(SpiceError::new(
+                    SpiceErrorType::InvalidKernelData,
+                    format!("Unsupported DAS binary format '{}'", String::from_utf8_lossy(other).trim_end())
+                ));
+            }
+        };
+
+        let internal_name = String::from_utf8_lossy(&data[8..68]).trim_end().to_string();
+        let counts: Vec<SpiceInt> = (0..4)
+            .map(|k| byte_order.read_i32(&data[68 + 4 * k..]))
+            .collect();
+        if counts.iter().any(|&count| count < 0) {
+            return Err(SpiceError::new(
+                SpiceErrorType::InvalidKernelData,
+                format!("Invalid DAS reserved/comment counts {:?}", counts)
+            ));
+        }
+
+        // Reserved and comment records follow the file record
+        let comment_end = 2usize.checked_add(counts[0] as usize)
+            .and_then(|end| end.checked_add(counts[2] as usize));
+        if comment_end.is_none_or(|end| end - 1 > data.len() / DAS_RECORD_BYTES) {
+            return Err(SpiceError::new(
+                SpiceErrorType::InvalidKernelData,
+                "DAS comment area extends past the end of the file".into()
+            ));
+        }
+
+        let mut file = Self {
+            id_word,
+            internal_name,
+            byte_order,
+            reserved_record_count: counts[0] as usize,
+            comment_record_count: counts[2] as usize,
+            comment_char_count: counts[3] as usize,
+            clusters: Vec::new(),
+            last_addresses: [0; 3],
+            data: data.to_vec(),
+        };
+        file.read_directories()?;
+        Ok(file)
+    }
+
+    /// File ID word, e.g. "DAS/DSK"
+    pub fn id_word(&self) -> &str {
+        &self.id_word
+    }
+
+    /// Internal file name
+    pub fn internal_name(&self) -> &str {
+        &self.internal_name
+    }
+
+    /// Binary format of the numeric data
+    pub fn byte_order(&self) -> DafByteOrder {
+        self.byte_order
+    }
+
+    /// Number of physical records in the file
+    pub fn record_count(&self) -> usize {
+        self.data.len() / DAS_RECORD_BYTES
+    }
+
+    /// 1-based record numbers of the reserved area
+    pub fn reserved_records(&self) -> Range<usize> {
+        2..2 + self.reserved_record_count
+    }
+
+    /// 1-based record numbers of the comment area
+    pub fn comment_records(&self) -> Range<usize> {
+        let first = self.reserved_records().end;
+        first..first + self.comment_record_count
+    }
+
+    /// Lines of the comment area (equivalent to dasec_c)
+    pub fn comments(&self) -> Vec<String> {
+        let mut text = Vec::with_capacity(self.comment_char_count);
+        for record in self.comment_records() {
+            let remaining = self.comment_char_count - text.len();
+            let chars = self.record_bytes(record);
+            text.extend_from_slice(&chars[..remaining.min(DAS_RECORD_BYTES)]);
+        }
+        if text.is_empty() {
+            return Vec::new();
+        }
+        if text.last() == Some(&COMMENT_EOL) {
+            text.pop();
+        }
+        text.split(|&b| b == COMMENT_EOL)
+            .map(|line| String::from_utf8_lossy(line).into_owned())
+            .collect()
+    }
+
+    /// Last logical address in use for a data type; zero when the file holds
+    /// no data of that type (equivalent to daslla_c)
+    pub fn last_address(&self, data_type: DasDataType) -> usize {
+        self.last_addresses[data_type.index()]
+    }
+
+    /// Character data at logical addresses `first..=last` (equivalent to dasrdc_c)
+    pub fn read_characters(&self, first: usize, last: usize) -> SpiceResult<Vec<u8>> {
+        self.read_range(DasDataType::Character, first, last, |bytes| bytes[0])
+    }
+
+    /// Double precision data at logical addresses `first..=last` (equivalent to dasrdd_c)
+    pub fn read_doubles(&self, first: usize, last: usize) -> SpiceResult<Vec<SpiceDouble>> {
+        let order = self.byte_order;
+        self.read_range(DasDataType::Double, first, last, |bytes| order.read_f64(bytes))
+    }
+
+    /// Integer data at logical addresses `first..=last` (equivalent to dasrdi_c)
+    pub fn read_integers(&self, first: usize, last: usize) -> SpiceResult<Vec<SpiceInt>> {
+        let order = self.byte_order;
+        self.read_range(DasDataType::Integer, first, last, |bytes| order.read_i32(bytes))
+    }
+
+    /// Read elements of one type, mapping each logical address to the record
+    /// and word that hold it
+    fn read_range<T>(
+        &self,
+        data_type: DasDataType,
+        first: usize,
+        last: usize,
+        decode: impl Fn(&[u8]) -> T,
+    ) -> SpiceResult<Vec<T>> {
+        if first < 1 || last < first || last > self.last_address(data_type) {
+            return Err(SpiceError::new(
+                SpiceErrorType::InvalidArgument,
+                format!(
+                    "DAS {:?} addresses {}..{} are outside 1..{}",
+                    data_type, first, last, self.last_address(data_type)
+                )
+            ));
+        }
+
+        let per_record = data_type.per_record();
+        let size = DAS_RECORD_BYTES / per_record;
+        let mut values = Vec::with_capacity(last - first + 1);
+        let mut address = first;
+        for cluster in self.clusters.iter().filter(|c| c.data_type == data_type) {
+            let cluster_end = cluster.first_address + cluster.record_count * per_record;
+            while address <= last && address < cluster_end {
+                let offset = address - cluster.first_address;
+                let record = self.record_bytes(cluster.first_record + offset / per_record);
+                let word = offset % per_record;
+                values.push(decode(&record[word * size..(word + 1) * size]));
+                address += 1;
+            }
+            if address > last {
+                break;
+            }
+        }
+        Ok(values)
+    }
+
+    /// Walk the directory record chain, building the cluster table
+    fn read_directories(&mut self) -> SpiceResult<()> {
+        let record_count = self.record_count();
+        let mut next_addresses = [1usize; 3];
+        let mut record = self.comment_records().end;
+        let mut previous = 0;
+        let mut visited = 0;
+
+        while record != 0 && record <= record_count {
+            visited += 1;
+            if visited > record_count {
+                return Err(SpiceError::new(
+                    SpiceErrorType::InvalidKernelData,
+                    "DAS directory records form a cycle".into()
+                ));
+            }
+            let words: Vec<SpiceInt> = {
+                let bytes = self.record_bytes(record);
+                (0..DIRECTORY_WORDS)
+                    .map(|k| self.byte_order.read_i32(&bytes[4 * k..]))
+                    .collect()
+            };
+            if words[BACKWARD_POINTER - 1] as usize != previous {
+                return Err(SpiceError::new(
+                    SpiceErrorType::InvalidKernelData,
+                    format!("DAS directory record {} has a broken backward pointer", record)
+                ));
+            }
+
+            let mut data_type = match DasDataType::from_code(words[FIRST_CLUSTER_TYPE - 1]) {
+                Some(data_type) => data_type,
+                // An empty directory holds no clusters
+                None if words[FIRST_CLUSTER_TYPE - 1] == 0 => break,
+                None => {
+                    return Err(SpiceError::new(
+                        SpiceErrorType::InvalidKernelData,
+                        format!("DAS directory record {} has invalid cluster type {}", record, words[FIRST_CLUSTER_TYPE - 1])
+                    ));
+                }
+            };
+
+            let mut cluster_record = record + 1;
+            for (k, &descriptor) in words[FIRST_CLUSTER_TYPE..].iter().enumerate() {
+                if descriptor == 0 {
+                    break;
+                }
+                if k > 0 {
+                    data_type = data_type.next(descriptor);
+                }
+                let record_count_in_cluster = descriptor.unsigned_abs() as usize;
+                let cluster_end = cluster_record.checked_add(record_count_in_cluster);
+                if cluster_end.is_none_or(|end| end - 1 > record_count) {
+                    return Err(SpiceError::new(
+                        SpiceErrorType::InvalidKernelData,
+                        format!("DAS cluster at record {} extends past the end of the file", cluster_record)
+                    ));
+                }
+                let index = data_type.index();
+                let next_address = record_count_in_cluster.checked_mul(data_type.per_record())
+                    .and_then(|addresses| addresses.checked_add(next_addresses[index]))
+                    .ok_or_else(|| SpiceError::new(
+                        SpiceErrorType::InvalidKernelData,
+                        format!("DAS cluster at record {} has too many addresses", cluster_record)
+                    ))?;
+                self.clusters.push(DasCluster {
+                    data_type,
+                    first_record: cluster_record,
+                    record_count: record_count_in_cluster,
+                    first_address: next_addresses[index],
+                });
+                next_addresses[index] = next_address;
+                cluster_record += record_count_in_cluster;
+            }
+
+            // The directory records the last address actually in use; the
+            // final record of each type may be only partly filled
+            for data_type in [DasDataType::Character, DasDataType::Double, DasDataType::Integer] {
+                let last = words[2 + 2 * data_type.index() + 1];
+                if last > 0 {
+                    let index = data_type.index();
+                    if last as usize >= next_addresses[index] {
+                        return Err(SpiceError::new(
+                            SpiceErrorType::InvalidKernelData,
+                            format!("DAS directory record {} claims {:?} address {} beyond its clusters", record, data_type, last)
+                        ));
+                    }
+                    self.last_addresses[index] = self.last_addresses[index].max(last as usize);
+                }
+            }
+
+            previous = record;
+            let forward = words[FORWARD_POINTER - 1];
+            if forward < 0 || (forward != 0 && forward as usize <= record) {
+                return Err(SpiceError::new(
+                    SpiceErrorType::InvalidKernelData,
+                    format!("DAS directory record {} has invalid forward pointer {}", record, forward)
+                ));
+            }
+            record = forward as usize;
+        }
+        Ok(())
+    }
+
+    /// Bytes of a 1-based record
+    fn record_bytes(&self, record: usize) -> &[u8] {
+        &self.data[(record - 1) * DAS_RECORD_BYTES..record * DAS_RECORD_BYTES]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Build a DAS with one comment record and two directories:
+    /// the first describes char, double and int clusters, the second a
+    /// further double cluster
+    fn synthetic_das(order: DafByteOrder) -> Vec<u8> {
+        let mut records: Vec<[u8; DAS_RECORD_BYTES]> = Vec::new();
+
+        let mut file_record = [0u8; DAS_RECORD_BYTES];
+        file_record[0..8].copy_from_slice(b"DAS/DSK ");
+        let name = b"SYNTHETIC DAS";
+        file_record[8..68].fill(b' ');
+        file_record[8..8 + name.len()].copy_from_slice(name);
+        let comment = b"first line\0second line\0";
+        for (k, value) in [0, 0, 1, comment.len() as SpiceInt].iter().enumerate() {
+            file_record[68 + 4 * k..72 + 4 * k].copy_from_slice(&order.i32_bytes(*value));
+        }
+        file_record[84..92].copy_from_slice(order.format_name().as_bytes());
+        records.push(file_record);
+
+        let mut comment_record = [b' '; DAS_RECORD_BYTES];
+        comment_record[..comment.len()].copy_from_slice(comment);
+        records.push(comment_record);
+
+        let directory = |backward: SpiceInt, forward: SpiceInt, ranges: [SpiceInt; 6], first_type: SpiceInt, descriptors: &[SpiceInt]| {
+            let mut words = [0 as SpiceInt; DIRECTORY_WORDS];
+            words[0] = backward;
+            words[1] = forward;
+            words[2..8].copy_from_slice(&ranges);
+            words[8] = first_type;
+            words[9..9 + descriptors.len()].copy_from_slice(descriptors);
+            let mut record = [0u8; DAS_RECORD_BYTES];
+            for (k, word) in words.iter().enumerate() {
+                record[4 * k..4 * k + 4].copy_from_slice(&order.i32_bytes(*word));
+            }
+            record
+        };
+
+        // Record 3: directory; clusters char (1 record), double (2), int (1)
+        records.push(directory(0, 8, [1, 20, 1, 200, 1, 256], 1, &[1, 2, 1]));
+        let mut chars = [0u8; DAS_RECORD_BYTES];
+        chars[..20].copy_from_slice(b"SEGREGATED DATA TEST");
+        records.push(chars);
+        for record in 0..2 {
+            let mut doubles = [0u8; DAS_RECORD_BYTES];
+            for k in 0..128 {
+                let value = (record * 128 + k) as SpiceDouble * 0.5;
+                doubles[8 * k..8 * k + 8].copy_from_slice(&order.f64_bytes(value));
+            }
+            records.push(doubles);
+        }
+        let mut integers = [0u8; DAS_RECORD_BYTES];
+        for k in 0..256 {
+            integers[4 * k..4 * k + 4].copy_from_slice(&order.i32_bytes(1000 - k as SpiceInt));
+        }
+        records.push(integers);
+
+        // Record 8: second directory; one more double cluster, reached from
+        // the integer type by stepping backward
+        records.push(directory(3, 0, [0, 0, 257, 260, 0, 0], 2, &[1]));
+        let mut doubles = [0u8; DAS_RECORD_BYTES];
+        for k in 0..4 {
+            doubles[8 * k..8 * k + 8].copy_from_slice(&order.f64_bytes(-(k as SpiceDouble)));
+        }
+        records.push(doubles);
+
+        records.concat()
+    }
+
+    #[test]
+    fn test_reads_each_data_type_from_synthetic_das() {
+        for order in [DafByteOrder::LittleEndian, DafByteOrder::BigEndian] {
+            let das = DasFile::open_read(&synthetic_das(order)).unwrap();
+            assert_eq!(das.id_word(), "DAS/DSK");
+            assert_eq!(das.internal_name(), "SYNTHETIC DAS");
+            assert_eq!(das.byte_order(), order);
+            assert_eq!(das.reserved_records(), 2..2);
+            assert_eq!(das.comment_records(), 2..3);
+            assert_eq!(das.comments(), vec!["first line", "second line"]);
+
+            assert_eq!(das.last_address(DasDataType::Character), 20);
+            assert_eq!(das.last_address(DasDataType::Double), 260);
+            assert_eq!(das.last_address(DasDataType::Integer), 256);
+
+            assert_eq!(das.read_characters(1, 10).unwrap(), b"SEGREGATED");
+            assert_eq!(das.read_integers(1, 3).unwrap(), vec![1000, 999, 998]);
+            assert_eq!(das.read_integers(256, 256).unwrap(), vec![745]);
+
+            // A range spanning both double records of the first cluster and
+            // the cluster under the second directory
+            let doubles = das.read_doubles(127, 258).unwrap();
+            assert_eq!(doubles.len(), 132);
+            assert_eq!(doubles[0], 63.0);
+            assert_eq!(doubles[2], 64.0);
+            assert_eq!(doubles[129], 127.5);
+            assert_eq!(&doubles[130..], &[0.0, -1.0]);
+
+            assert!(das.read_doubles(259, 261).is_err());
+            assert!(das.read_characters(0, 1).is_err());
+        }
+    }
+
+    #[test]
+    fn test_rejects_non_das_files() {
+        assert!(DasFile::open_read(&[0u8; 100]).is_err());
+        let mut bytes = synthetic_das(DafByteOrder::LittleEndian);
+        bytes[0..8].copy_from_slice(b"DAF/SPK ");
+        assert!(DasFile::open_read(&bytes).is_err());
+    }
+
+    #[test]
+    fn test_rejects_counts_past_the_file() {
+        let order = DafByteOrder::LittleEndian;
+        let corrupt = |offset: usize, value: SpiceInt| {
+            let mut bytes = synthetic_das(order);
+            bytes[offset..offset + 4].copy_from_slice(&order.i32_bytes(value));
+            DasFile::open_read(&bytes).unwrap_err().error_type
+        };
+
+        // Reserved and comment record counts whose sum overflows a 32-bit usize
+        assert_eq!(corrupt(68, SpiceInt::MAX), SpiceErrorType::InvalidKernelData);
+        assert_eq!(corrupt(76, SpiceInt::MAX), SpiceErrorType::InvalidKernelData);
+
+        // A cluster descriptor of the first directory claiming every record
+        let first_descriptor = 2 * DAS_RECORD_BYTES + 4 * FIRST_CLUSTER_TYPE;
+        assert_eq!(corrupt(first_descriptor, SpiceInt::MAX), SpiceErrorType::InvalidKernelData);
+        assert_eq!(corrupt(first_descriptor, SpiceInt::MIN), SpiceErrorType::InvalidKernelData);
+    }
+}
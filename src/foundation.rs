@@ -4,10 +4,10 @@
 //! data types and provides the foundation for all other modules.
 
 #[cfg(not(feature = "std"))]
-use alloc::string::String;
+use alloc::{string::String, vec::Vec, format};
 
 #[cfg(feature = "std")]
-use std::string::String;
+use std::{string::String, vec::Vec, format};
 
 use core::ops::{Add, Sub, Mul, Index, IndexMut};
 use crate::error_handling::{SpiceResult, SpiceError, SpiceErrorType};
@@ -48,25 +48,75 @@ impl SpiceMatrix3x3 {
         SpiceMatrix3x3([[0.0; 3]; 3])
     }
 
-    /// Matrix transpose
-    pub fn transpose(&self) -> Self {
-        let mut result = [[0.0; 3]; 3];
-        for i in 0..3 {
-            for j in 0..3 {
-                result[i][j] = self.0[j][i];
-            }
-        }
-        SpiceMatrix3x3(result)
+    /// Matrix transpose (equivalent to xpose_c)
+    pub const fn transpose(&self) -> Self {
+        let m = &self.0;
+        SpiceMatrix3x3([
+            [m[0][0], m[1][0], m[2][0]],
+            [m[0][1], m[1][1], m[2][1]],
+            [m[0][2], m[1][2], m[2][2]],
+        ])
     }
 
-    /// Matrix determinant
-    pub fn determinant(&self) -> SpiceDouble {
+    /// Matrix determinant (equivalent to det_c)
+    pub const fn determinant(&self) -> SpiceDouble {
         let m = &self.0;
         m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1])
             - m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0])
             + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0])
     }
 
+    /// Matrix inverse by cofactors (equivalent to invert_c)
+    ///
+    /// Fails when the determinant is negligible relative to the scale of the
+    /// matrix entries.
+    pub fn invert(&self) -> SpiceResult<SpiceMatrix3x3> {
+        let m = &self.0;
+        let det = self.determinant();
+        let scale = m.iter().flatten().fold(0.0_f64, |acc, v| acc.max(v.abs()));
+        if det == 0.0 || !det.is_finite() || det.abs() <= 1e-15 * scale * scale * scale {
+            return Err(SpiceError::new(
+                SpiceErrorType::ComputationError,
+                "Matrix is singular (determinant is zero)".into()
+            ));
+        }
+
+        let inv_det = 1.0 / det;
+        Ok(SpiceMatrix3x3([
+            [
+                (m[1][1] * m[2][2] - m[1][2] * m[2][1]) * inv_det,
+                (m[0][2] * m[2][1] - m[0][1] * m[2][2]) * inv_det,
+                (m[0][1] * m[1][2] - m[0][2] * m[1][1]) * inv_det,
+            ],
+            [
+                (m[1][2] * m[2][0] - m[1][0] * m[2][2]) * inv_det,
+                (m[0][0] * m[2][2] - m[0][2] * m[2][0]) * inv_det,
+                (m[0][2] * m[1][0] - m[0][0] * m[1][2]) * inv_det,
+            ],
+            [
+                (m[1][0] * m[2][1] - m[1][1] * m[2][0]) * inv_det,
+                (m[0][1] * m[2][0] - m[0][0] * m[2][1]) * inv_det,
+                (m[0][0] * m[1][1] - m[0][1] * m[1][0]) * inv_det,
+            ],
+        ]))
+    }
+
+    /// Largest deviation of MᵀM from the identity
+    ///
+    /// Zero for an exact rotation (or reflection); use with `determinant` to
+    /// tell how far a matrix is from a proper rotation.
+    pub fn rotation_residual(&self) -> SpiceDouble {
+        let product = self.transpose().multiply_matrix(self);
+        let mut residual: SpiceDouble = 0.0;
+        for i in 0..3 {
+            for j in 0..3 {
+                let expected = if i == j { 1.0 } else { 0.0 };
+                residual = residual.max((product.0[i][j] - expected).abs());
+            }
+        }
+        residual
+    }
+
     /// Get matrix element
     pub fn get(&self, row: usize, col: usize) -> SpiceDouble {
         self.0[row][col]
@@ -79,17 +129,48 @@ impl SpiceMatrix3x3 {
 
     /// Matrix multiplication
     pub fn multiply(&self, other: &SpiceMatrix3x3) -> SpiceMatrix3x3 {
+        self.multiply_matrix(other)
+    }
+
+    /// Matrix times matrix (equivalent to mxm_c)
+    pub const fn multiply_matrix(&self, other: &SpiceMatrix3x3) -> SpiceMatrix3x3 {
         let mut result = [[0.0; 3]; 3];
-        for i in 0..3 {
-            for j in 0..3 {
-                for k in 0..3 {
-                    result[i][j] += self.0[i][k] * other.0[k][j];
-                }
+        let mut i = 0;
+        while i < 3 {
+            let mut j = 0;
+            while j < 3 {
+                result[i][j] = self.0[i][0] * other.0[0][j]
+                    + self.0[i][1] * other.0[1][j]
+                    + self.0[i][2] * other.0[2][j];
+                j += 1;
             }
+            i += 1;
         }
         SpiceMatrix3x3(result)
     }
 
+    /// Matrix times the transpose of another (equivalent to mxmt_c)
+    pub const fn multiply_transpose(&self, other: &SpiceMatrix3x3) -> SpiceMatrix3x3 {
+        let mut result = [[0.0; 3]; 3];
+        let mut i = 0;
+        while i < 3 {
+            let mut j = 0;
+            while j < 3 {
+                result[i][j] = self.0[i][0] * other.0[j][0]
+                    + self.0[i][1] * other.0[j][1]
+                    + self.0[i][2] * other.0[j][2];
+                j += 1;
+            }
+            i += 1;
+        }
+        SpiceMatrix3x3(result)
+    }
+
+    /// Matrix addition
+    pub fn add(&self, other: &SpiceMatrix3x3) -> SpiceMatrix3x3 {
+        SpiceMatrix3x3(core::array::from_fn(|i| core::array::from_fn(|j| self.0[i][j] + other.0[i][j])))
+    }
+
     /// Matrix subtraction
     pub fn subtract(&self, other: &SpiceMatrix3x3) -> SpiceMatrix3x3 {
         let mut result = [[0.0; 3]; 3];
@@ -112,14 +193,23 @@ impl SpiceMatrix3x3 {
         SpiceMatrix3x3(result)
     }
 
-    /// Multiply matrix by vector
-    pub fn multiply_vector(&self, vector: &SpiceVector3) -> SpiceVector3 {
+    /// Multiply matrix by vector (equivalent to mxv_c)
+    pub const fn multiply_vector(&self, vector: &SpiceVector3) -> SpiceVector3 {
         SpiceVector3([
             self.0[0][0] * vector.0[0] + self.0[0][1] * vector.0[1] + self.0[0][2] * vector.0[2],
             self.0[1][0] * vector.0[0] + self.0[1][1] * vector.0[1] + self.0[1][2] * vector.0[2],
             self.0[2][0] * vector.0[0] + self.0[2][1] * vector.0[1] + self.0[2][2] * vector.0[2],
         ])
     }
+
+    /// Multiply the transpose of the matrix by a vector (equivalent to mtxv_c)
+    pub const fn transpose_multiply_vector(&self, vector: &SpiceVector3) -> SpiceVector3 {
+        SpiceVector3([
+            self.0[0][0] * vector.0[0] + self.0[1][0] * vector.0[1] + self.0[2][0] * vector.0[2],
+            self.0[0][1] * vector.0[0] + self.0[1][1] * vector.0[1] + self.0[2][1] * vector.0[2],
+            self.0[0][2] * vector.0[0] + self.0[1][2] * vector.0[1] + self.0[2][2] * vector.0[2],
+        ])
+    }
 }
 
 impl Index<usize> for SpiceMatrix3x3 {
@@ -166,6 +256,13 @@ impl SpiceMatrix6x6 {
         self.0[row][col] = value;
     }
 
+    /// Matrix multiplication (equivalent to mxmg_c for 6x6 matrices)
+    pub fn multiply(&self, other: &SpiceMatrix6x6) -> SpiceMatrix6x6 {
+        SpiceMatrix6x6(core::array::from_fn(|i| {
+            core::array::from_fn(|j| (0..6).map(|k| self.0[i][k] * other.0[k][j]).sum())
+        }))
+    }
+
     /// Multiply matrix by 6D vector
     pub fn multiply_vector(&self, vector: &SpiceVector6) -> SpiceVector6 {
         let mut result = [0.0; 6];
@@ -216,6 +313,17 @@ impl SpiceVector3 {
         ]))
     }
 
+    /// Unit vector and the original magnitude (equivalent to unorm_c)
+    ///
+    /// The zero vector gives `(zeros, 0.0)` rather than an error.
+    pub fn unit_and_magnitude(&self) -> (SpiceVector3, SpiceDouble) {
+        let mag = self.magnitude();
+        if mag == 0.0 {
+            return (SpiceVector3::zeros(), 0.0);
+        }
+        (SpiceVector3([self.0[0] / mag, self.0[1] / mag, self.0[2] / mag]), mag)
+    }
+
     /// Dot product
     pub fn dot(&self, other: &SpiceVector3) -> SpiceDouble {
         self.0[0] * other.0[0] + self.0[1] * other.0[1] + self.0[2] * other.0[2]
@@ -252,6 +360,62 @@ impl SpiceVector3 {
             self.0[2] - other.0[2],
         ])
     }
+
+    /// Angle between two vectors in radians (equivalent to vsep_c)
+    ///
+    /// Uses atan2 of the cross and dot products, so nearly parallel and nearly
+    /// antiparallel vectors keep full precision. Returns 0 if either is zero.
+    pub fn angular_separation(&self, other: &SpiceVector3) -> SpiceDouble {
+        if self.magnitude() == 0.0 || other.magnitude() == 0.0 {
+            return 0.0;
+        }
+        libm::atan2(self.cross(other).magnitude(), self.dot(other))
+    }
+
+    /// Projection onto another vector (equivalent to vproj_c)
+    ///
+    /// Projecting onto the zero vector gives the zero vector.
+    pub fn project(&self, onto: &SpiceVector3) -> SpiceVector3 {
+        let onto_squared = onto.dot(onto);
+        if onto_squared == 0.0 {
+            return SpiceVector3::zeros();
+        }
+        onto.scale(self.dot(onto) / onto_squared)
+    }
+
+    /// Component perpendicular to another vector (equivalent to vperp_c)
+    pub fn perpendicular(&self, to: &SpiceVector3) -> SpiceVector3 {
+        self.subtract(&self.project(to))
+    }
+
+    /// Rotate about an axis by an angle in radians (equivalent to vrotv_c)
+    ///
+    /// A positive angle turns the vector counterclockwise seen from the tip of
+    /// the axis. A zero axis leaves the vector unchanged.
+    pub fn rotate_about_axis(&self, axis: &SpiceVector3, angle: SpiceDouble) -> SpiceVector3 {
+        let axis = match axis.unit() {
+            Ok(axis) => axis,
+            Err(_) => return *self,
+        };
+        let parallel = self.project(&axis);
+        let perpendicular = self.subtract(&parallel);
+        let (sin, cos) = libm::sincos(angle);
+        parallel + perpendicular.scale(cos) + axis.cross(&perpendicular).scale(sin)
+    }
+
+    /// Unit vector along the cross product (equivalent to ucrss_c)
+    ///
+    /// The inputs are scaled before crossing to avoid overflow; parallel or
+    /// zero inputs give the zero vector.
+    pub fn unit_cross(&self, other: &SpiceVector3) -> SpiceVector3 {
+        let max_self = self.0.iter().fold(0.0_f64, |acc, v| acc.max(v.abs()));
+        let max_other = other.0.iter().fold(0.0_f64, |acc, v| acc.max(v.abs()));
+        if max_self == 0.0 || max_other == 0.0 {
+            return SpiceVector3::zeros();
+        }
+        let cross = self.scale(1.0 / max_self).cross(&other.scale(1.0 / max_other));
+        cross.unit().unwrap_or_else(|_| SpiceVector3::zeros())
+    }
 }
 
 impl Add for SpiceVector3 {
@@ -418,6 +582,141 @@ impl JulianDate {
     }
 }
 
+/// Window of double precision intervals (a SPICE d.p. window cell)
+///
+/// Intervals are closed, sorted and disjoint. Every operation keeps that
+/// invariant, so overlapping or touching intervals are merged on insert.
+/// Singleton intervals `[t, t]` are allowed.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SpiceWindow {
+    intervals: Vec<[SpiceDouble; 2]>,
+}
+
+impl SpiceWindow {
+    /// Create an empty window
+    pub fn new() -> Self {
+        SpiceWindow { intervals: Vec::new() }
+    }
+
+    /// Window containing a single interval
+    pub fn from_interval(left: SpiceDouble, right: SpiceDouble) -> SpiceResult<Self> {
+        let mut window = SpiceWindow::new();
+        window.insert_interval(left, right)?;
+        Ok(window)
+    }
+
+    /// The window's intervals in ascending order
+    pub fn intervals(&self) -> &[[SpiceDouble; 2]] {
+        &self.intervals
+    }
+
+    /// Number of intervals in the window (equivalent to wncard_c)
+    pub fn cardinality(&self) -> usize {
+        self.intervals.len()
+    }
+
+    /// Whether the window contains no intervals
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    /// Total length of the window's intervals (equivalent to wnsumd_c's measure)
+    pub fn measure(&self) -> SpiceDouble {
+        self.intervals.iter().map(|[left, right]| right - left).sum()
+    }
+
+    /// Insert an interval, merging it with any intervals it overlaps or
+    /// touches (equivalent to wninsd_c)
+    pub fn insert_interval(&mut self, left: SpiceDouble, right: SpiceDouble) -> SpiceResult<()> {
+        if left.is_nan() || right.is_nan() || left > right {
+            return Err(SpiceError::new(
+                SpiceErrorType::InvalidArgument,
+                format!("Window interval endpoints are out of order: [{}, {}]", left, right)
+            ));
+        }
+
+        // Intervals entirely before the new one stay put; those it reaches
+        // are absorbed into it
+        let first = self.intervals.partition_point(|interval| interval[1] < left);
+        let last = self.intervals.partition_point(|interval| interval[0] <= right);
+        let mut merged = [left, right];
+        if first < last {
+            merged[0] = merged[0].min(self.intervals[first][0]);
+            merged[1] = merged[1].max(self.intervals[last - 1][1]);
+        }
+        self.intervals.splice(first..last, [merged]);
+        Ok(())
+    }
+
+    /// Intervals covered by either window (equivalent to wnunid_c)
+    pub fn union(&self, other: &SpiceWindow) -> SpiceWindow {
+        let mut result = self.clone();
+        for &[left, right] in &other.intervals {
+            // Both endpoints come from a valid window, so the insert cannot fail
+            let _ = result.insert_interval(left, right);
+        }
+        result
+    }
+
+    /// Intervals covered by both windows (equivalent to wnintd_c)
+    pub fn intersect(&self, other: &SpiceWindow) -> SpiceWindow {
+        let mut intervals = Vec::new();
+        let (mut i, mut j) = (0, 0);
+        while i < self.intervals.len() && j < other.intervals.len() {
+            let [a_left, a_right] = self.intervals[i];
+            let [b_left, b_right] = other.intervals[j];
+            let left = a_left.max(b_left);
+            let right = a_right.min(b_right);
+            if left <= right {
+                intervals.push([left, right]);
+            }
+            if a_right < b_right {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        SpiceWindow { intervals }
+    }
+
+    /// Intervals covered by this window but not by `other` (equivalent to wndifd_c)
+    ///
+    /// The pieces on either side of a singleton cut touch, so they are
+    /// inserted rather than pushed and come out merged.
+    pub fn difference(&self, other: &SpiceWindow) -> SpiceWindow {
+        let mut result = SpiceWindow::new();
+        for &[left, right] in &self.intervals {
+            let mut start = left;
+            let mut clipped = false;
+            for &[cut_left, cut_right] in other.intervals.iter()
+                .filter(|interval| interval[1] >= left && interval[0] <= right)
+            {
+                if cut_left > start {
+                    // Ordered endpoints from valid windows, so the insert cannot fail
+                    let _ = result.insert_interval(start, cut_left);
+                }
+                start = start.max(cut_right);
+                clipped = true;
+            }
+            if start < right || (!clipped && start == right) {
+                let _ = result.insert_interval(start, right);
+            }
+        }
+        result
+    }
+
+    /// Parts of `[left, right]` not covered by the window (equivalent to wncomd_c)
+    pub fn complement(&self, left: SpiceDouble, right: SpiceDouble) -> SpiceResult<SpiceWindow> {
+        Ok(SpiceWindow::from_interval(left, right)?.difference(self))
+    }
+
+    /// Uncovered portions of the span `within`, for coverage gap reports;
+    /// an inverted span has none
+    pub fn gaps(&self, within: (SpiceDouble, SpiceDouble)) -> SpiceWindow {
+        self.complement(within.0, within.1).unwrap_or_default()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -437,14 +736,97 @@ mod tests {
         assert_eq!(cross, SpiceVector3::new(-3.0, 6.0, -3.0));
     }
 
+    #[test]
+    fn test_vector_toolkit() {
+        // Nearly parallel: the separation is atan(1e-9) to full precision
+        let a = SpiceVector3::new(1.0, 0.0, 0.0);
+        let b = SpiceVector3::new(1.0, 1e-9, 0.0);
+        assert!((a.angular_separation(&b) - 1e-9).abs() < 1e-24);
+        let anti = SpiceVector3::new(-1.0, 1e-9, 0.0);
+        assert!((anti.angular_separation(&a) - (core::f64::consts::PI - 1e-9)).abs() < 1e-15);
+        assert_eq!(a.angular_separation(&SpiceVector3::zeros()), 0.0);
+
+        let v = SpiceVector3::new(3.0, 4.0, 5.0);
+        let axis = SpiceVector3::new(0.0, 0.0, 2.0);
+        assert_eq!(v.project(&axis), SpiceVector3::new(0.0, 0.0, 5.0));
+        assert_eq!(v.perpendicular(&axis), SpiceVector3::new(3.0, 4.0, 0.0));
+
+        let rotated = a.rotate_about_axis(&axis, core::f64::consts::FRAC_PI_2);
+        assert!((rotated - SpiceVector3::new(0.0, 1.0, 0.0)).magnitude() < 1e-15);
+
+        let up = SpiceVector3::new(1e300, 0.0, 0.0).unit_cross(&SpiceVector3::new(0.0, 1e300, 0.0));
+        assert_eq!(up, SpiceVector3::new(0.0, 0.0, 1.0));
+        assert_eq!(a.unit_cross(&a.scale(2.0)), SpiceVector3::zeros());
+    }
+
+    #[test]
+    fn test_unit_and_magnitude() {
+        let v = SpiceVector3::new(2.0, -3.0, 6.0);
+        let (unit, magnitude) = v.unit_and_magnitude();
+        assert_eq!(magnitude, v.magnitude());
+        assert_eq!(magnitude, 7.0);
+        assert_eq!(unit, v.unit().unwrap());
+
+        let (unit, magnitude) = SpiceVector3::zeros().unit_and_magnitude();
+        assert_eq!(unit, SpiceVector3::zeros());
+        assert_eq!(magnitude, 0.0);
+        assert!(!unit.0.iter().any(|c| c.is_nan()));
+    }
+
     #[test]
     fn test_matrix_operations() {
         let identity = SpiceMatrix3x3::identity();
         assert_eq!(identity.determinant(), 1.0);
 
         let v = SpiceVector3::new(1.0, 2.0, 3.0);
-        // Identity matrix times vector should equal the vector
-        // We'll implement matrix-vector multiplication in math_core module
+        assert_eq!(identity.multiply_vector(&v), v);
+    }
+
+    #[test]
+    fn test_rotation_times_transpose_is_identity() {
+        let (s1, c1) = 0.7_f64.sin_cos();
+        let (s2, c2) = (-1.3_f64).sin_cos();
+        let about_z = SpiceMatrix3x3::new([[c1, s1, 0.0], [-s1, c1, 0.0], [0.0, 0.0, 1.0]]);
+        let about_x = SpiceMatrix3x3::new([[1.0, 0.0, 0.0], [0.0, c2, s2], [0.0, -s2, c2]]);
+        let rotation = about_x.multiply_matrix(&about_z);
+
+        let products = [
+            rotation.multiply_matrix(&rotation.transpose()),
+            rotation.multiply_transpose(&rotation),
+            rotation.transpose().multiply_matrix(&rotation),
+        ];
+        for product in &products {
+            for i in 0..3 {
+                for j in 0..3 {
+                    let expected = if i == j { 1.0 } else { 0.0 };
+                    assert!((product.0[i][j] - expected).abs() < 1e-15);
+                }
+            }
+        }
+        assert!((rotation.determinant() - 1.0).abs() < 1e-15);
+
+        let v = SpiceVector3::new(1.0, -2.0, 0.5);
+        let back = rotation.transpose_multiply_vector(&rotation.multiply_vector(&v));
+        assert!((back - v).magnitude() < 1e-15);
+    }
+
+    #[test]
+    fn test_invert_and_rotation_residual() {
+        let m = SpiceMatrix3x3::new([[2.0, 0.0, 1.0], [1.0, 3.0, 0.0], [0.0, 1.0, 4.0]]);
+        let product = m.multiply_matrix(&m.invert().unwrap());
+        assert!(product.subtract(&SpiceMatrix3x3::identity()).0.iter().flatten().all(|v| v.abs() < 1e-15));
+
+        let singular = SpiceMatrix3x3::new([[1.0, 2.0, 3.0], [2.0, 4.0, 6.0], [0.0, 1.0, 1.0]]);
+        assert!(singular.invert().is_err());
+
+        let (s, c) = 0.4_f64.sin_cos();
+        let rotation = SpiceMatrix3x3::new([[c, s, 0.0], [-s, c, 0.0], [0.0, 0.0, 1.0]]);
+        assert!(rotation.rotation_residual() < 1e-15);
+
+        // Shear the X axis slightly toward Y
+        let mut skewed = rotation;
+        skewed.0[0][1] += 1e-3;
+        assert!(skewed.rotation_residual() > 1e-4);
     }
 
     #[test]
@@ -456,4 +838,47 @@ mod tests {
         assert_eq!(et2.seconds(), 3600.0);
         assert_eq!(et2 - et1, 3600.0);
     }
+
+    #[test]
+    fn test_window_insert_merges_overlaps() {
+        let mut window = SpiceWindow::new();
+        window.insert_interval(10.0, 20.0).unwrap();
+        window.insert_interval(1.0, 3.0).unwrap();
+        window.insert_interval(30.0, 40.0).unwrap();
+        assert_eq!(window.cardinality(), 3);
+
+        // Bridges the first two intervals and touches the third
+        window.insert_interval(2.0, 30.0).unwrap();
+        assert_eq!(window.intervals(), &[[1.0, 40.0]]);
+        assert_eq!(window.measure(), 39.0);
+
+        window.insert_interval(50.0, 50.0).unwrap();
+        assert_eq!(window.intervals(), &[[1.0, 40.0], [50.0, 50.0]]);
+        assert!(window.insert_interval(5.0, 4.0).is_err());
+    }
+
+    #[test]
+    fn test_window_set_operations() {
+        let mut a = SpiceWindow::new();
+        a.insert_interval(0.0, 10.0).unwrap();
+        a.insert_interval(20.0, 30.0).unwrap();
+        let mut b = SpiceWindow::new();
+        b.insert_interval(5.0, 25.0).unwrap();
+
+        assert_eq!(a.union(&b).intervals(), &[[0.0, 30.0]]);
+        assert_eq!(a.intersect(&b).intervals(), &[[5.0, 10.0], [20.0, 25.0]]);
+        assert_eq!(a.difference(&b).intervals(), &[[0.0, 5.0], [25.0, 30.0]]);
+        assert_eq!(b.difference(&a).intervals(), &[[10.0, 20.0]]);
+        assert_eq!(a.complement(-5.0, 35.0).unwrap().intervals(), &[[-5.0, 0.0], [10.0, 20.0], [30.0, 35.0]]);
+
+        // Cutting out a single point leaves touching pieces, which merge
+        let point = SpiceWindow::from_interval(5.0, 5.0).unwrap();
+        assert_eq!(a.difference(&point), a);
+        assert_eq!(a.difference(&a.complement(-5.0, 35.0).unwrap()), a);
+
+        let disjoint = SpiceWindow::from_interval(12.0, 18.0).unwrap();
+        let empty = a.intersect(&disjoint);
+        assert!(empty.is_empty());
+        assert_eq!(empty.measure(), 0.0);
+    }
 }
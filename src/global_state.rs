@@ -0,0 +1,157 @@
+//! Thread-safe container for RustSPICE's global subsystems
+//!
+//! The kernel system, kernel pool and ephemeris readers keep process-wide
+//! state, as CSPICE does. Each piece lives in a `GlobalState`, which hands out
+//! access through closures under a reader-writer lock: `std::sync::RwLock`
+//! with the `std` feature, or a small spin lock for `no_std` builds.
+//!
+//! Locks are taken in the order kernel system, SPK/CK readers, kernel pool, and
+//! never re-entered while held.
+
+#[cfg(feature = "std")]
+use std::sync::RwLock;
+
+#[cfg(not(feature = "std"))]
+use spin_lock::RwLock;
+
+/// A lazily filled global value guarded by a reader-writer lock
+pub struct GlobalState<T> {
+    slot: RwLock<Option<T>>,
+}
+
+impl<T> GlobalState<T> {
+    /// An empty slot, usable in a `static`
+    pub const fn new() -> Self {
+        Self {
+            slot: RwLock::new(None),
+        }
+    }
+
+    /// Replace the stored value
+    pub fn set(&self, value: T) {
+        self.write(|slot| *slot = Some(value));
+    }
+
+    /// Store a value only if the slot is empty
+    pub fn get_or_init(&self, init: impl FnOnce() -> T) {
+        self.write(|slot| {
+            if slot.is_none() {
+                *slot = Some(init());
+            }
+        });
+    }
+
+    /// Whether a value has been stored
+    pub fn is_set(&self) -> bool {
+        self.read(|slot| slot.is_some())
+    }
+
+    /// Run `f` with shared access to the slot
+    #[cfg(feature = "std")]
+    pub fn read<R>(&self, f: impl FnOnce(Option<&T>) -> R) -> R {
+        // A panic in another holder leaves the data usable; SPICE state has no
+        // invariants spanning a single call
+        let guard = self.slot.read().unwrap_or_else(|poisoned| poisoned.into_inner());
+        f(guard.as_ref())
+    }
+
+    /// Run `f` with exclusive access to the slot
+    #[cfg(feature = "std")]
+    pub fn write<R>(&self, f: impl FnOnce(&mut Option<T>) -> R) -> R {
+        f(&mut self.write_guard())
+    }
+
+    /// Exclusive access to the slot until the returned guard drops
+    ///
+    /// Only for compatibility accessors; every other access blocks while
+    /// the guard is alive.
+    #[cfg(feature = "std")]
+    pub fn write_guard(&self) -> std::sync::RwLockWriteGuard<'_, Option<T>> {
+        self.slot.write().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Run `f` with shared access to the slot
+    #[cfg(not(feature = "std"))]
+    pub fn read<R>(&self, f: impl FnOnce(Option<&T>) -> R) -> R {
+        self.slot.read(|slot| f(slot.as_ref()))
+    }
+
+    /// Run `f` with exclusive access to the slot
+    #[cfg(not(feature = "std"))]
+    pub fn write<R>(&self, f: impl FnOnce(&mut Option<T>) -> R) -> R {
+        self.slot.write(f)
+    }
+}
+
+impl<T> Default for GlobalState<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Serialize unit tests that reset or rely on the global kernel state
+///
+/// The test harness runs tests on parallel threads, so without this one
+/// test's `clear_kernels` can land between another's load and query.
+#[cfg(test)]
+pub(crate) fn test_lock() -> std::sync::MutexGuard<'static, ()> {
+    static LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+    LOCK.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+#[cfg(not(feature = "std"))]
+mod spin_lock {
+    use core::cell::UnsafeCell;
+    use core::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Reader count, or `WRITER` while a writer holds the lock
+    const WRITER: usize = usize::MAX;
+
+    /// Minimal spinning reader-writer lock for targets without `std`
+    pub struct RwLock<T> {
+        state: AtomicUsize,
+        value: UnsafeCell<T>,
+    }
+
+    // Access to `value` is serialized by `state`
+    unsafe impl<T: Send> Send for RwLock<T> {}
+    unsafe impl<T: Send + Sync> Sync for RwLock<T> {}
+
+    impl<T> RwLock<T> {
+        pub const fn new(value: T) -> Self {
+            Self {
+                state: AtomicUsize::new(0),
+                value: UnsafeCell::new(value),
+            }
+        }
+
+        pub fn read<R>(&self, f: impl FnOnce(&T) -> R) -> R {
+            loop {
+                let readers = self.state.load(Ordering::Relaxed);
+                if readers != WRITER - 1 && readers != WRITER
+                    && self.state
+                        .compare_exchange_weak(readers, readers + 1, Ordering::Acquire, Ordering::Relaxed)
+                        .is_ok()
+                {
+                    break;
+                }
+                core::hint::spin_loop();
+            }
+            let result = f(unsafe { &*self.value.get() });
+            self.state.fetch_sub(1, Ordering::Release);
+            result
+        }
+
+        pub fn write<R>(&self, f: impl FnOnce(&mut T) -> R) -> R {
+            while self.state
+                .compare_exchange_weak(0, WRITER, Ordering::Acquire, Ordering::Relaxed)
+                .is_err()
+            {
+                core::hint::spin_loop();
+            }
+            let result = f(unsafe { &mut *self.value.get() });
+            self.state.store(0, Ordering::Release);
+            result
+        }
+    }
+}
@@ -15,10 +15,13 @@
 //! - gipool_c → get_integer_pool() - Retrieve integer values from pool
 //! - dtpool_c → describe_pool_variable() - Get variable info (type, size)
 //! - lmpool_c → load_memory_pool() - Load kernel from memory array
+//! - swpool_c → set_watch() - Watch variables on behalf of an agent
+//! - cvpool_c → poll_watch() - Check whether an agent's variables changed
 
 use crate::error_handling::{SpiceResult, SpiceError, SpiceErrorType};
 use crate::foundation::{SpiceDouble, SpiceInt};
-use std::collections::BTreeMap;
+use crate::global_state::GlobalState;
+use std::collections::{BTreeMap, BTreeSet};
 use std::vec::Vec;
 use std::string::String;
 use core::fmt;
@@ -95,6 +98,10 @@ impl PoolValue {
 pub struct KernelPool {
     /// Storage for all kernel variables
     variables: BTreeMap<String, PoolValue>,
+    /// Variables watched by each agent
+    watchers: BTreeMap<String, BTreeSet<String>>,
+    /// Agents with a change not yet reported by `poll_watch`
+    updated_agents: BTreeSet<String>,
 }
 
 impl Default for KernelPool {
@@ -108,12 +115,17 @@ impl KernelPool {
     pub fn new() -> Self {
         Self {
             variables: BTreeMap::new(),
+            watchers: BTreeMap::new(),
+            updated_agents: BTreeSet::new(),
         }
     }
 
     /// Clear all variables from the kernel pool
+    ///
+    /// Watches stay registered and every agent is flagged as updated.
     pub fn clear(&mut self) {
         self.variables.clear();
+        self.updated_agents.extend(self.watchers.keys().cloned());
     }
 
     /// Check if a variable exists in the pool
@@ -131,25 +143,59 @@ impl KernelPool {
         self.validate_variable_name(name)?;
         self.validate_value_count(values.len())?;
         
-        self.variables.insert(name.to_uppercase(), PoolValue::Characters(values));
+        self.store(name.to_uppercase(), PoolValue::Characters(values));
         Ok(())
     }
 
     /// Insert double precision values into the pool
+    ///
+    /// Overwriting an integer variable keeps it integer-typed as long as
+    /// every new value is integral.
     pub fn put_double(&mut self, name: &str, values: Vec<SpiceDouble>) -> SpiceResult<()> {
         self.validate_variable_name(name)?;
         self.validate_value_count(values.len())?;
         
-        self.variables.insert(name.to_uppercase(), PoolValue::Doubles(values));
+        let upper_name = name.to_uppercase();
+        let keep_integer = matches!(self.variables.get(&upper_name), Some(PoolValue::Integers(_)))
+            && values.iter().all(|&v| v.fract() == 0.0
+                && v >= SpiceInt::MIN as SpiceDouble
+                && v <= SpiceInt::MAX as SpiceDouble);
+        
+        let value = if keep_integer {
+            PoolValue::Integers(values.into_iter().map(|v| v as SpiceInt).collect())
+        } else {
+            PoolValue::Doubles(values)
+        };
+        self.store(upper_name, value);
         Ok(())
     }
 
     /// Insert integer values into the pool
+    ///
+    /// Overwriting a double precision variable keeps it double-typed.
     pub fn put_integer(&mut self, name: &str, values: Vec<SpiceInt>) -> SpiceResult<()> {
         self.validate_variable_name(name)?;
         self.validate_value_count(values.len())?;
         
-        self.variables.insert(name.to_uppercase(), PoolValue::Integers(values));
+        let upper_name = name.to_uppercase();
+        let value = if matches!(self.variables.get(&upper_name), Some(PoolValue::Doubles(_))) {
+            PoolValue::Doubles(values.into_iter().map(|v| v as SpiceDouble).collect())
+        } else {
+            PoolValue::Integers(values)
+        };
+        self.store(upper_name, value);
+        Ok(())
+    }
+
+    /// Duplicate a variable under a new name, preserving its stored type
+    pub fn copy_variable(&mut self, from: &str, to: &str) -> SpiceResult<()> {
+        self.validate_variable_name(to)?;
+        
+        let value = self.variables.get(&from.to_uppercase()).cloned().ok_or_else(|| SpiceError::new(
+            SpiceErrorType::InvalidArgument,
+            format!("Cannot copy nonexistent variable '{}'", from)
+        ))?;
+        self.store(to.to_uppercase(), value);
         Ok(())
     }
 
@@ -245,6 +291,35 @@ impl KernelPool {
         }
     }
 
+    /// All values of a numeric variable, which must hold exactly `expected_len`
+    pub fn get_required_doubles(&self, name: &str, expected_len: usize) -> SpiceResult<Vec<SpiceDouble>> {
+        let (values, found) = self.get_double(name, 0, MAX_VALUES_PER_VARIABLE)?;
+        if !found {
+            return Err(SpiceError::new(
+                SpiceErrorType::InsufficientData,
+                format!("Kernel variable {} is not defined", name.to_uppercase())
+            ));
+        }
+        if values.len() != expected_len {
+            return Err(SpiceError::new(
+                SpiceErrorType::InsufficientData,
+                format!("Kernel variable {} holds {} values; expected {}", name.to_uppercase(), values.len(), expected_len)
+            ));
+        }
+        Ok(values)
+    }
+
+    /// The value of a single-valued numeric variable
+    pub fn get_single_double(&self, name: &str) -> SpiceResult<SpiceDouble> {
+        Ok(self.get_required_doubles(name, 1)?[0])
+    }
+
+    /// The value of a single-valued numeric variable, or `default` if it is
+    /// absent or not a single number
+    pub fn get_double_or(&self, name: &str, default: SpiceDouble) -> SpiceDouble {
+        self.get_single_double(name).unwrap_or(default)
+    }
+
     /// Get information about a pool variable (type and size)
     pub fn describe_variable(&self, name: &str) -> SpiceResult<(bool, usize, PoolDataType)> {
         let upper_name = name.to_uppercase();
@@ -259,7 +334,57 @@ impl KernelPool {
     /// Remove a variable from the pool
     pub fn delete_variable(&mut self, name: &str) -> SpiceResult<bool> {
         let upper_name = name.to_uppercase();
-        Ok(self.variables.remove(&upper_name).is_some())
+        let removed = self.variables.remove(&upper_name).is_some();
+        if removed {
+            self.notify(&upper_name);
+        }
+        Ok(removed)
+    }
+
+    /// Watch variables on behalf of an agent (equivalent to swpool_c)
+    ///
+    /// Variables are added to any the agent already watches, and the agent is
+    /// flagged as updated so its first poll reports a change.
+    pub fn set_watch(&mut self, agent: &str, vars: &[String]) -> SpiceResult<()> {
+        if agent.is_empty() {
+            return Err(SpiceError::new(
+                SpiceErrorType::InvalidArgument,
+                "Watcher agent name cannot be empty".to_string()
+            ));
+        }
+        for var in vars {
+            self.validate_variable_name(var)?;
+        }
+
+        self.watchers
+            .entry(agent.to_string())
+            .or_default()
+            .extend(vars.iter().map(|var| var.to_uppercase()));
+        self.updated_agents.insert(agent.to_string());
+        Ok(())
+    }
+
+    /// Report whether an agent's watched variables changed since its last
+    /// poll, clearing the flag (equivalent to cvpool_c)
+    ///
+    /// Agents that never called `set_watch` report no change.
+    pub fn poll_watch(&mut self, agent: &str) -> bool {
+        self.updated_agents.remove(agent)
+    }
+
+    /// Insert a variable and flag the agents watching it
+    fn store(&mut self, name: String, value: PoolValue) {
+        self.notify(&name);
+        self.variables.insert(name, value);
+    }
+
+    /// Flag every agent watching `name` as updated
+    fn notify(&mut self, name: &str) {
+        for (agent, vars) in &self.watchers {
+            if vars.contains(name) {
+                self.updated_agents.insert(agent.clone());
+            }
+        }
     }
 
     /// Get all variable names matching a pattern
@@ -323,6 +448,12 @@ impl KernelPool {
                 current_assignment.push_str(trimmed);
             }
             
+            // Values in parentheses may span several lines
+            if has_open_list(&current_assignment) {
+                current_assignment.push(' ');
+                continue;
+            }
+            
             // Process complete assignment
             if !current_assignment.is_empty() {
                 if let Err(e) = self.parse_assignment(&current_assignment) {
@@ -337,30 +468,81 @@ impl KernelPool {
         Ok(())
     }
 
-    /// Parse a single assignment statement
+    /// Parse a single assignment statement, either `NAME = values` or
+    /// `NAME += values` to append to an existing variable
     fn parse_assignment(&mut self, assignment: &str) -> SpiceResult<()> {
         if let Some(eq_pos) = assignment.find('=') {
-            let var_name = assignment[..eq_pos].trim().to_uppercase();
+            let name_part = assignment[..eq_pos].trim();
+            let (name_part, append) = match name_part.strip_suffix('+') {
+                Some(name) => (name, true),
+                None => (name_part, false),
+            };
+            let var_name = name_part.trim().to_uppercase();
             let value_part = assignment[eq_pos + 1..].trim();
             
             self.validate_variable_name(&var_name)?;
             
             // Parse the value(s)
-            if value_part.starts_with('(') && value_part.ends_with(')') {
+            let value = if value_part.starts_with('(') && value_part.ends_with(')') {
                 // Array assignment
                 let inner = &value_part[1..value_part.len()-1];
-                self.parse_array_values(&var_name, inner)?;
+                self.parse_array_values(inner)
             } else {
                 // Single value assignment
-                self.parse_single_value(&var_name, value_part)?;
+                self.parse_single_value(&var_name, value_part)?
+            };
+            
+            if append {
+                self.append_value(&var_name, value)?;
+            } else {
+                self.put_value(&var_name, value)?;
             }
         }
         
         Ok(())
     }
 
+    /// Store parsed values, replacing any existing ones
+    fn put_value(&mut self, var_name: &str, value: PoolValue) -> SpiceResult<()> {
+        match value {
+            // An empty list assigns nothing
+            PoolValue::Characters(values) if values.is_empty() => Ok(()),
+            PoolValue::Characters(values) => self.put_character(var_name, values),
+            PoolValue::Doubles(values) => self.put_double(var_name, values),
+            PoolValue::Integers(values) => self.put_integer(var_name, values),
+        }
+    }
+
+    /// Append parsed values to a variable, creating it if needed
+    fn append_value(&mut self, var_name: &str, value: PoolValue) -> SpiceResult<()> {
+        let existing = match self.variables.get(var_name) {
+            Some(existing) => existing,
+            None => return self.put_value(var_name, value),
+        };
+        
+        match (existing, value) {
+            (PoolValue::Characters(old), PoolValue::Characters(new)) => {
+                let values = old.iter().cloned().chain(new).collect();
+                self.put_character(var_name, values)
+            },
+            (PoolValue::Doubles(_) | PoolValue::Integers(_), PoolValue::Characters(new)) if new.is_empty() => Ok(()),
+            (PoolValue::Doubles(old), PoolValue::Doubles(new)) => {
+                let values = old.iter().copied().chain(new).collect();
+                self.put_double(var_name, values)
+            },
+            (PoolValue::Integers(old), PoolValue::Doubles(new)) => {
+                let values = old.iter().map(|&v| v as SpiceDouble).chain(new).collect();
+                self.put_double(var_name, values)
+            },
+            (existing, value) => Err(SpiceError::new(
+                SpiceErrorType::InvalidDataType,
+                format!("Cannot append {} values to {} variable '{}'", value.data_type(), existing.data_type(), var_name)
+            )),
+        }
+    }
+
     /// Parse array values from parentheses
-    fn parse_array_values(&mut self, var_name: &str, values_str: &str) -> SpiceResult<()> {
+    fn parse_array_values(&self, values_str: &str) -> PoolValue {
         let mut values = Vec::new();
         let mut current_value = String::new();
         let mut in_quotes = false;
@@ -375,7 +557,7 @@ impl KernelPool {
                 '"' | '\'' if in_quotes && ch == quote_char => {
                     in_quotes = false;
                 },
-                ',' if !in_quotes => {
+                ',' | ' ' | '\t' if !in_quotes => {
                     if !current_value.trim().is_empty() {
                         values.push(current_value.trim().to_string());
                     }
@@ -392,60 +574,62 @@ impl KernelPool {
             values.push(current_value.trim().to_string());
         }
         
-        // Determine type and store
-        if !values.is_empty() {
-            // Check if any value contains quotes, indicating string values
-            let has_quotes = values.iter().any(|v| {
-                v.contains('"') || v.contains('\'')
-            });
+        // Determine type
+        if values.is_empty() {
+            return PoolValue::Characters(values);
+        }
+        
+        // Check if any value contains quotes, indicating string values
+        let has_quotes = values.iter().any(|v| {
+            v.contains('"') || v.contains('\'')
+        });
+        
+        if has_quotes {
+            // Character values
+            let char_values: Vec<String> = values.into_iter()
+                .map(|v| v.trim_matches(|c| c == '"' || c == '\'').to_string())
+                .collect();
+            PoolValue::Characters(char_values)
+        } else {
+            // Try to parse as numeric values
+            let double_values: Option<Vec<SpiceDouble>> = values.iter()
+                .map(|v| self.parse_numeric_value(v))
+                .collect();
             
-            if has_quotes {
-                // Character values
-                let char_values: Vec<String> = values.into_iter()
-                    .map(|v| v.trim_matches(|c| c == '"' || c == '\'').to_string())
-                    .collect();
-                self.put_character(var_name, char_values)?;
-            } else {
-                // Try to parse as numeric values
-                let double_values: Result<Vec<SpiceDouble>, _> = values.iter()
-                    .map(|v| self.parse_spice_number(v))
-                    .collect();
-                
-                match double_values {
-                    Ok(doubles) => self.put_double(var_name, doubles)?,
-                    Err(_) => {
-                        // If numeric parsing fails, treat as character values
-                        self.put_character(var_name, values)?;
-                    }
-                }
+            match double_values {
+                Some(doubles) => PoolValue::Doubles(doubles),
+                // If numeric parsing fails, treat as character values
+                None => PoolValue::Characters(values),
             }
         }
-        
-        Ok(())
     }
 
     /// Parse a single value
-    fn parse_single_value(&mut self, var_name: &str, value_str: &str) -> SpiceResult<()> {
+    fn parse_single_value(&self, var_name: &str, value_str: &str) -> SpiceResult<PoolValue> {
         let trimmed = value_str.trim();
         
         if trimmed.starts_with('"') || trimmed.starts_with('\'') {
             // Character value
             let char_value = trimmed.trim_matches(|c| c == '"' || c == '\'').to_string();
-            self.put_character(var_name, vec![char_value])?;
+            Ok(PoolValue::Characters(vec![char_value]))
         } else {
             // Numeric value - handle SPICE/FORTRAN D notation
-            match self.parse_spice_number(trimmed) {
-                Ok(double_val) => self.put_double(var_name, vec![double_val])?,
-                Err(_) => {
-                    return Err(SpiceError::new(
-                        SpiceErrorType::InvalidFormat,
-                        format!("Could not parse value '{}' for variable '{}'", trimmed, var_name)
-                    ));
-                }
+            match self.parse_numeric_value(trimmed) {
+                Some(double_val) => Ok(PoolValue::Doubles(vec![double_val])),
+                None => Err(SpiceError::new(
+                    SpiceErrorType::InvalidFormat,
+                    format!("Could not parse value '{}' for variable '{}'", trimmed, var_name)
+                )),
             }
         }
-        
-        Ok(())
+    }
+
+    /// Parse a number or an `@` date, which is stored as seconds past J2000
+    fn parse_numeric_value(&self, value_str: &str) -> Option<SpiceDouble> {
+        match value_str.strip_prefix('@') {
+            Some(date) => crate::time_system::pool_date_seconds(date).ok(),
+            None => self.parse_spice_number(value_str).ok(),
+        }
     }
 
     /// Parse SPICE/FORTRAN number format (handles 'D' for scientific notation)
@@ -487,151 +671,150 @@ impl KernelPool {
     }
 }
 
+/// Whether an assignment has a parenthesized value list not yet closed
+fn has_open_list(assignment: &str) -> bool {
+    let mut depth = 0;
+    let mut quote = None;
+    for ch in assignment.chars() {
+        match (quote, ch) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {},
+            (None, '\'' | '"') => quote = Some(ch),
+            (None, '(') => depth += 1,
+            (None, ')') => depth -= 1,
+            _ => {},
+        }
+    }
+    depth > 0
+}
+
 /// Global kernel pool instance
-static mut GLOBAL_POOL: Option<KernelPool> = None;
-static mut POOL_INITIALIZED: bool = false;
+static GLOBAL_POOL: GlobalState<KernelPool> = GlobalState::new();
 
 /// Initialize the global kernel pool
 pub fn initialize_pool() -> SpiceResult<()> {
-    unsafe {
-        GLOBAL_POOL = Some(KernelPool::new());
-        POOL_INITIALIZED = true;
-    }
+    GLOBAL_POOL.set(KernelPool::new());
     Ok(())
 }
 
 /// Check if the kernel pool is initialized
 pub fn is_pool_initialized() -> bool {
-    unsafe { POOL_INITIALIZED }
+    GLOBAL_POOL.is_set()
 }
 
-/// Get mutable access to the global pool
-unsafe fn get_global_pool_mut() -> SpiceResult<&'static mut KernelPool> {
-    if !POOL_INITIALIZED {
-        return Err(SpiceError::new(
-            SpiceErrorType::PoolNotInitialized,
-            "Kernel pool has not been initialized. Call initialize_pool() first.".to_string()
-        ));
-    }
-    
-    GLOBAL_POOL.as_mut().ok_or_else(|| SpiceError::new(
+/// Error for access to the pool before `initialize_pool`
+fn pool_not_initialized() -> SpiceError {
+    SpiceError::new(
         SpiceErrorType::PoolNotInitialized,
-        "Global kernel pool is None".to_string()
-    ))
+        "Kernel pool has not been initialized. Call initialize_pool() first.".to_string()
+    )
 }
 
-/// Get immutable access to the global pool
-unsafe fn get_global_pool() -> SpiceResult<&'static KernelPool> {
-    if !POOL_INITIALIZED {
-        return Err(SpiceError::new(
-            SpiceErrorType::PoolNotInitialized,
-            "Kernel pool has not been initialized. Call initialize_pool() first.".to_string()
-        ));
-    }
-    
-    GLOBAL_POOL.as_ref().ok_or_else(|| SpiceError::new(
-        SpiceErrorType::PoolNotInitialized,
-        "Global kernel pool is None".to_string()
-    ))
+/// Run `f` with mutable access to the global pool
+fn with_global_pool_mut<R>(f: impl FnOnce(&mut KernelPool) -> SpiceResult<R>) -> SpiceResult<R> {
+    GLOBAL_POOL.write(|pool| f(pool.as_mut().ok_or_else(pool_not_initialized)?))
+}
+
+/// Run `f` with shared access to the global pool
+pub(crate) fn with_global_pool<R>(f: impl FnOnce(&KernelPool) -> SpiceResult<R>) -> SpiceResult<R> {
+    GLOBAL_POOL.read(|pool| f(pool.ok_or_else(pool_not_initialized)?))
 }
 
 // Public API functions that mirror CSPICE
 
 /// Clear the kernel pool (equivalent to clpool_c)
 pub fn clear_pool() -> SpiceResult<()> {
-    unsafe {
-        let pool = get_global_pool_mut()?;
+    with_global_pool_mut(|pool| {
         pool.clear();
-    }
-    Ok(())
+        Ok(())
+    })
 }
 
 /// Put character values into the kernel pool (equivalent to pcpool_c)
 pub fn put_character_pool(name: &str, values: Vec<String>) -> SpiceResult<()> {
-    unsafe {
-        let pool = get_global_pool_mut()?;
-        pool.put_character(name, values)
-    }
+    with_global_pool_mut(|pool| pool.put_character(name, values))
 }
 
 /// Put double precision values into the kernel pool (equivalent to pdpool_c)
 pub fn put_double_pool(name: &str, values: Vec<SpiceDouble>) -> SpiceResult<()> {
-    unsafe {
-        let pool = get_global_pool_mut()?;
-        pool.put_double(name, values)
-    }
+    with_global_pool_mut(|pool| pool.put_double(name, values))
 }
 
 /// Put integer values into the kernel pool (equivalent to pipool_c)
 pub fn put_integer_pool(name: &str, values: Vec<SpiceInt>) -> SpiceResult<()> {
-    unsafe {
-        let pool = get_global_pool_mut()?;
-        pool.put_integer(name, values)
-    }
+    with_global_pool_mut(|pool| pool.put_integer(name, values))
 }
 
 /// Get character values from the kernel pool (equivalent to gcpool_c)
 pub fn get_character_pool(name: &str, start: usize, max_values: usize) -> SpiceResult<(Vec<String>, bool)> {
-    unsafe {
-        let pool = get_global_pool()?;
-        pool.get_character(name, start, max_values)
-    }
+    with_global_pool(|pool| pool.get_character(name, start, max_values))
 }
 
 /// Get double precision values from the kernel pool (equivalent to gdpool_c)
 pub fn get_double_pool(name: &str, start: usize, max_values: usize) -> SpiceResult<(Vec<SpiceDouble>, bool)> {
-    unsafe {
-        let pool = get_global_pool()?;
-        pool.get_double(name, start, max_values)
-    }
+    with_global_pool(|pool| pool.get_double(name, start, max_values))
 }
 
 /// Get integer values from the kernel pool (equivalent to gipool_c)
 pub fn get_integer_pool(name: &str, start: usize, max_values: usize) -> SpiceResult<(Vec<SpiceInt>, bool)> {
-    unsafe {
-        let pool = get_global_pool()?;
-        pool.get_integer(name, start, max_values)
-    }
+    with_global_pool(|pool| pool.get_integer(name, start, max_values))
+}
+
+/// Single numeric pool value, or `default` when it is unavailable
+pub fn get_double_or(name: &str, default: SpiceDouble) -> SpiceDouble {
+    with_global_pool(|pool| Ok(pool.get_double_or(name, default))).unwrap_or(default)
+}
+
+/// All values of a numeric pool variable, erroring unless exactly
+/// `expected_len` are present
+pub fn get_required_doubles(name: &str, expected_len: usize) -> SpiceResult<Vec<SpiceDouble>> {
+    with_global_pool(|pool| pool.get_required_doubles(name, expected_len))
+}
+
+/// Value of a single-valued numeric pool variable
+pub fn get_single_double(name: &str) -> SpiceResult<SpiceDouble> {
+    with_global_pool(|pool| pool.get_single_double(name))
 }
 
 /// Get information about a pool variable (equivalent to dtpool_c)
 pub fn describe_pool_variable(name: &str) -> SpiceResult<(bool, usize, PoolDataType)> {
-    unsafe {
-        let pool = get_global_pool()?;
-        pool.describe_variable(name)
-    }
+    with_global_pool(|pool| pool.describe_variable(name))
 }
 
 /// Check if a variable exists in the pool (equivalent to expool_c)
 pub fn exists_in_pool(name: &str) -> SpiceResult<bool> {
-    unsafe {
-        let pool = get_global_pool()?;
-        Ok(pool.contains_variable(name))
-    }
+    with_global_pool(|pool| Ok(pool.contains_variable(name)))
 }
 
 /// Delete a variable from the pool (equivalent to dvpool_c)
 pub fn delete_pool_variable(name: &str) -> SpiceResult<bool> {
-    unsafe {
-        let pool = get_global_pool_mut()?;
-        pool.delete_variable(name)
-    }
+    with_global_pool_mut(|pool| pool.delete_variable(name))
+}
+
+/// Copy a variable to a new name, preserving its stored type
+pub fn copy_pool_variable(from: &str, to: &str) -> SpiceResult<()> {
+    with_global_pool_mut(|pool| pool.copy_variable(from, to))
 }
 
 /// Get variable names matching a pattern (equivalent to gnpool_c)
 pub fn get_pool_variable_names(pattern: &str) -> SpiceResult<Vec<String>> {
-    unsafe {
-        let pool = get_global_pool()?;
-        pool.get_variable_names(pattern)
-    }
+    with_global_pool(|pool| pool.get_variable_names(pattern))
 }
 
 /// Load text kernel from memory (equivalent to lmpool_c)
 pub fn load_memory_pool(lines: &[String]) -> SpiceResult<()> {
-    unsafe {
-        let pool = get_global_pool_mut()?;
-        pool.load_from_memory(lines)
-    }
+    with_global_pool_mut(|pool| pool.load_from_memory(lines))
+}
+
+/// Watch kernel pool variables on behalf of an agent (equivalent to swpool_c)
+pub fn set_watch(agent: &str, vars: &[String]) -> SpiceResult<()> {
+    with_global_pool_mut(|pool| pool.set_watch(agent, vars))
+}
+
+/// Check whether an agent's watched variables changed since its last poll
+/// (equivalent to cvpool_c)
+pub fn poll_watch(agent: &str) -> SpiceResult<bool> {
+    with_global_pool_mut(|pool| Ok(pool.poll_watch(agent)))
 }
 
 /// Load text kernel from file (equivalent to ldpool_c)
@@ -646,6 +829,7 @@ mod tests {
 
     #[test]
     fn test_initialize_pool() {
+        let _lock = crate::global_state::test_lock();
         let result = initialize_pool();
         assert!(result.is_ok());
         assert!(is_pool_initialized());
@@ -653,6 +837,7 @@ mod tests {
 
     #[test]
     fn test_character_pool_operations() {
+        let _lock = crate::global_state::test_lock();
         initialize_pool().unwrap();
         clear_pool().unwrap();
 
@@ -677,6 +862,7 @@ mod tests {
 
     #[test]
     fn test_double_pool_operations() {
+        let _lock = crate::global_state::test_lock();
         initialize_pool().unwrap();
         clear_pool().unwrap();
 
@@ -697,6 +883,7 @@ mod tests {
 
     #[test]
     fn test_integer_pool_operations() {
+        let _lock = crate::global_state::test_lock();
         initialize_pool().unwrap();
         clear_pool().unwrap();
 
@@ -712,6 +899,7 @@ mod tests {
 
     #[test]
     fn test_text_kernel_parsing() {
+        let _lock = crate::global_state::test_lock();
         initialize_pool().unwrap();
         clear_pool().unwrap();
 
@@ -747,6 +935,7 @@ This is a comment section.
 
     #[test]
     fn test_variable_name_patterns() {
+        let _lock = crate::global_state::test_lock();
         initialize_pool().unwrap();
         clear_pool().unwrap();
 
@@ -768,6 +957,7 @@ This is a comment section.
 
     #[test]
     fn test_delete_variable() {
+        let _lock = crate::global_state::test_lock();
         initialize_pool().unwrap();
         clear_pool().unwrap();
 
@@ -783,8 +973,44 @@ This is a comment section.
         assert!(!deleted);
     }
 
+    #[test]
+    fn test_numeric_type_preserved_across_put() {
+        let _lock = crate::global_state::test_lock();
+        let mut pool = KernelPool::new();
+
+        pool.put_integer("COUNTS", vec![1, 2, 3]).unwrap();
+        let (values, _) = pool.get_double("COUNTS", 0, 10).unwrap();
+        pool.put_double("COUNTS", values).unwrap();
+        assert_eq!(pool.describe_variable("COUNTS").unwrap().2, PoolDataType::Integer);
+
+        // Non-integral values cannot stay integers
+        pool.put_double("COUNTS", vec![1.5]).unwrap();
+        assert_eq!(pool.describe_variable("COUNTS").unwrap().2, PoolDataType::Double);
+
+        pool.put_double("RADII", vec![3396.0, 3396.0, 3376.0]).unwrap();
+        let (values, _) = pool.get_integer("RADII", 0, 10).unwrap();
+        pool.put_integer("RADII", values).unwrap();
+        assert_eq!(pool.describe_variable("RADII").unwrap().2, PoolDataType::Double);
+    }
+
+    #[test]
+    fn test_copy_variable() {
+        let mut pool = KernelPool::new();
+        pool.put_double("BODY499_RADII", vec![3396.0, 3396.0, 3376.0]).unwrap();
+
+        pool.copy_variable("BODY499_RADII", "saved_radii").unwrap();
+        let (found, count, data_type) = pool.describe_variable("SAVED_RADII").unwrap();
+        assert!(found);
+        assert_eq!(count, 3);
+        assert_eq!(data_type, PoolDataType::Double);
+
+        assert!(pool.copy_variable("MISSING", "ANYWHERE").is_err());
+        assert!(!pool.contains_variable("ANYWHERE"));
+    }
+
     #[test]
     fn test_clear_pool() {
+        let _lock = crate::global_state::test_lock();
         initialize_pool().unwrap();
         clear_pool().unwrap();
 
@@ -801,4 +1027,111 @@ This is a comment section.
         let all_names = get_pool_variable_names("*").unwrap();
         assert_eq!(all_names.len(), 0);
     }
+
+    #[test]
+    fn test_watcher_reports_each_change_once() {
+        let mut pool = KernelPool::new();
+        let vars = vec!["WATCHED_GM".to_string()];
+        pool.set_watch("GM_CACHE", &vars).unwrap();
+
+        // A new watch starts out flagged so the agent loads its cache
+        assert!(pool.poll_watch("GM_CACHE"));
+        assert!(!pool.poll_watch("GM_CACHE"));
+
+        // Unwatched variables leave the agent alone
+        pool.put_double("OTHER_GM", vec![1.0]).unwrap();
+        assert!(!pool.poll_watch("GM_CACHE"));
+
+        pool.put_double("watched_gm", vec![398600.4418]).unwrap();
+        assert!(pool.poll_watch("GM_CACHE"));
+        assert!(!pool.poll_watch("GM_CACHE"));
+
+        // Assignments from a text kernel, deletion and clearing all count
+        pool.load_from_memory(&["\\begindata".to_string(), "WATCHED_GM = 1.0".to_string()]).unwrap();
+        assert!(pool.poll_watch("GM_CACHE"));
+        assert!(pool.delete_variable("WATCHED_GM").unwrap());
+        assert!(pool.poll_watch("GM_CACHE"));
+        pool.clear();
+        assert!(pool.poll_watch("GM_CACHE"));
+        assert!(!pool.poll_watch("GM_CACHE"));
+
+        assert!(!pool.poll_watch("UNKNOWN_AGENT"));
+    }
+
+    #[test]
+    fn test_typed_double_getters() {
+        let mut pool = KernelPool::new();
+        pool.put_double("BODY399_RADII", vec![6378.1366, 6378.1366, 6356.7519]).unwrap();
+        pool.put_double("BODY399_GM", vec![398600.435436]).unwrap();
+        pool.put_character("BODY399_NAME", vec!["EARTH".to_string()]).unwrap();
+
+        assert_eq!(pool.get_required_doubles("body399_radii", 3).unwrap().len(), 3);
+        assert_eq!(pool.get_single_double("BODY399_GM").unwrap(), 398600.435436);
+        assert_eq!(pool.get_double_or("BODY399_GM", 0.0), 398600.435436);
+
+        // Missing variable
+        let missing = pool.get_required_doubles("BODY499_RADII", 3).unwrap_err();
+        assert_eq!(missing.error_type, SpiceErrorType::InsufficientData);
+        assert!(missing.message.contains("not defined"));
+        assert_eq!(pool.get_double_or("BODY499_GM", 42.0), 42.0);
+
+        // Wrong length
+        let wrong = pool.get_required_doubles("BODY399_RADII", 2).unwrap_err();
+        assert!(wrong.message.contains("holds 3 values; expected 2"));
+        assert!(pool.get_single_double("BODY399_RADII").is_err());
+        assert_eq!(pool.get_double_or("BODY399_RADII", -1.0), -1.0);
+
+        // Character data is not numeric
+        assert!(pool.get_single_double("BODY399_NAME").is_err());
+        assert_eq!(pool.get_double_or("BODY399_NAME", 7.0), 7.0);
+    }
+
+    #[test]
+    fn test_append_assignment_extends_variable() {
+        let mut pool = KernelPool::new();
+        let kernel_content = r#"
+\begindata
+
+KERNELS_TO_LOAD = ( 'spk/de442.bsp', 'lsk/naif0012.tls' )
+KERNELS_TO_LOAD += ( 'pck/pck00011.tpc' )
+KERNELS_TO_LOAD += 'fk/mission.tf'
+OFFSETS += ( 1, 2 )
+OFFSETS += 2.5
+"#;
+        let lines: Vec<String> = kernel_content.lines().map(String::from).collect();
+        pool.load_from_memory(&lines).unwrap();
+
+        let (kernels, found) = pool.get_character("KERNELS_TO_LOAD", 0, 10).unwrap();
+        assert!(found);
+        assert_eq!(kernels, vec!["spk/de442.bsp", "lsk/naif0012.tls", "pck/pck00011.tpc", "fk/mission.tf"]);
+
+        // Appending to an undefined variable creates it
+        let (offsets, _) = pool.get_double("OFFSETS", 0, 10).unwrap();
+        assert_eq!(offsets, vec![1.0, 2.0, 2.5]);
+
+        // Strings cannot extend numbers and vice versa
+        let mismatch = pool.parse_assignment("OFFSETS += 'three'").unwrap_err();
+        assert_eq!(mismatch.error_type, SpiceErrorType::InvalidDataType);
+        assert!(pool.parse_assignment("KERNELS_TO_LOAD += 4").is_err());
+        assert_eq!(pool.describe_variable("KERNELS_TO_LOAD").unwrap(), (true, 4, PoolDataType::Character));
+    }
+
+    #[test]
+    fn test_blank_separated_values_and_dates() {
+        let mut pool = KernelPool::new();
+        let kernel_content = r#"
+\begindata
+DELTET/M = ( 6.239996D0 1.99096871D-7 )
+DELTET/DELTA_AT = ( 10, @1972-JAN-1
+                    11, @1972-JUL-1 )
+EPOCH = @2000-JAN-01/12:00:00
+"#;
+        let lines: Vec<String> = kernel_content.lines().map(String::from).collect();
+        pool.load_from_memory(&lines).unwrap();
+
+        assert_eq!(pool.get_required_doubles("DELTET/M", 2).unwrap(), vec![6.239996, 1.99096871e-7]);
+        let table = pool.get_required_doubles("DELTET/DELTA_AT", 4).unwrap();
+        assert_eq!(table, vec![10.0, -883656000.0, 11.0, -867931200.0]);
+        assert_eq!(pool.get_single_double("EPOCH").unwrap(), 0.0);
+    }
 }
@@ -12,7 +12,8 @@
 
 use crate::error_handling::{SpiceResult, SpiceError, SpiceErrorType};
 use crate::file_system::{VirtualFileSystem, KernelInfo, FileHandle, KernelType, FileArchitecture};
-use crate::kernel_pool;
+use crate::kernel_pool::{self, KernelPool, MAX_VALUES_PER_VARIABLE};
+use crate::global_state::GlobalState;
 use std::vec::Vec;
 use std::string::String;
 use core::sync::atomic::{AtomicBool, Ordering};
@@ -21,8 +22,7 @@ use core::sync::atomic::{AtomicBool, Ordering};
 static KERNEL_SYSTEM_INITIALIZED: AtomicBool = AtomicBool::new(false);
 
 /// Global virtual file system instance
-/// In a real implementation, this would be behind a mutex or similar synchronization
-static mut GLOBAL_VFS: Option<VirtualFileSystem> = None;
+static GLOBAL_VFS: GlobalState<VirtualFileSystem> = GlobalState::new();
 
 /// Initialize the kernel system
 pub fn initialize_kernel_system() -> SpiceResult<()> {
@@ -30,10 +30,8 @@ pub fn initialize_kernel_system() -> SpiceResult<()> {
         return Ok(());
     }
     
-    // Initialize global VFS
-    unsafe {
-        GLOBAL_VFS = Some(VirtualFileSystem::new());
-    }
+    // Initialize global VFS, keeping one another thread may have just created
+    GLOBAL_VFS.get_or_init(VirtualFileSystem::new);
     
     KERNEL_SYSTEM_INITIALIZED.store(true, Ordering::Release);
     Ok(())
@@ -44,37 +42,12 @@ pub fn is_initialized() -> bool {
     KERNEL_SYSTEM_INITIALIZED.load(Ordering::Acquire)
 }
 
-/// Get mutable reference to global VFS (unsafe - for internal use)
-unsafe fn get_global_vfs_mut() -> SpiceResult<&'static mut VirtualFileSystem> {
-    if !is_initialized() {
-        initialize_kernel_system()?;
-    }
-    
-    match &mut GLOBAL_VFS {
-        Some(vfs) => Ok(vfs),
-        None => Err(SpiceError::new(
-            SpiceErrorType::SpiceError,
-            "Global VFS not initialized".into()
-        ))
-    }
-}
-
-/// Get reference to global VFS (unsafe - for internal use)  
-unsafe fn get_global_vfs() -> SpiceResult<&'static VirtualFileSystem> {
-    if !is_initialized() {
-        return Err(SpiceError::new(
-            SpiceErrorType::SpiceError,
-            "Kernel system not initialized".into()
-        ));
-    }
-    
-    match &GLOBAL_VFS {
-        Some(vfs) => Ok(vfs),
-        None => Err(SpiceError::new(
-            SpiceErrorType::SpiceError,
-            "Global VFS not initialized".into()
-        ))
-    }
+/// Error for access to the VFS before it exists
+fn vfs_not_initialized() -> SpiceError {
+    SpiceError::new(
+        SpiceErrorType::SpiceError,
+        "Global VFS not initialized".into()
+    )
 }
 
 /// Load a SPICE kernel from file path (equivalent to furnsh_c)
@@ -85,16 +58,13 @@ pub fn furnish_kernel(filename: &str) -> SpiceResult<()> {
         furnish_kernel_from_file(filename)?;
     }
     
-    unsafe {
-        let vfs = get_global_vfs()?;
-        if vfs.is_kernel_loaded(filename) {
-            Ok(())
-        } else {
-            Err(SpiceError::new(
-                SpiceErrorType::KernelNotFound,
-                format!("Kernel '{}' not found in virtual file system", filename)
-            ))
-        }
+    if is_kernel_loaded(filename)? {
+        Ok(())
+    } else {
+        Err(SpiceError::new(
+            SpiceErrorType::KernelNotFound,
+            format!("Kernel '{}' not found in virtual file system", filename)
+        ))
     }
 }
 
@@ -148,10 +118,7 @@ pub fn furnish_kernel_from_bytes(data: Vec<u8>, filename: &str) -> SpiceResult<F
     }
     
     // Load into virtual file system
-    let handle = unsafe {
-        let vfs = get_global_vfs_mut()?;
-        vfs.load_kernel_from_bytes(data.clone(), filename)?
-    };
+    let handle = with_global_vfs_mut(|vfs| vfs.load_kernel_from_bytes(data.clone(), filename))?;
 
     // Get kernel information to determine type
     if let Ok(kernel_info) = kernel_info(filename) {
@@ -159,16 +126,11 @@ pub fn furnish_kernel_from_bytes(data: Vec<u8>, filename: &str) -> SpiceResult<F
             // Register SPK files with the SPK reader
             KernelType::SPK => {
                 // Load SPK data into the SPK reader
-                unsafe {
-                    if let Ok(vfs) = get_global_vfs() {
-                        if let Err(e) = crate::spk_reader::load_spk_file_global(filename, vfs) {
-                            return Err(SpiceError::new(
-                                SpiceErrorType::KernelLoadError,
-                                format!("Failed to load SPK file '{}': {}", filename, e)
-                            ));
-                        }
-                    }
-                }
+                with_global_vfs(|vfs| crate::spk_reader::load_spk_file_global(filename, vfs))
+                    .map_err(|e| SpiceError::new(
+                        SpiceErrorType::KernelLoadError,
+                        format!("Failed to load SPK file '{}': {}", filename, e)
+                    ))?;
             },
             
             // Handle text kernels
@@ -188,6 +150,10 @@ pub fn furnish_kernel_from_bytes(data: Vec<u8>, filename: &str) -> SpiceResult<F
 
                 // Load the text kernel into the kernel pool
                 kernel_pool::load_text_kernel(text_content)?;
+
+                if kernel_info.kernel_type == KernelType::MK {
+                    furnish_meta_kernel_entries(text_content)?;
+                }
             },
             
             // Other kernel types (CK, EK, etc.) can be handled here in the future
@@ -202,67 +168,61 @@ pub fn furnish_kernel_from_bytes(data: Vec<u8>, filename: &str) -> SpiceResult<F
 
 /// Unload a SPICE kernel (equivalent to unload_c)
 pub fn unload_kernel(filename: &str) -> SpiceResult<()> {
-    unsafe {
-        let vfs = get_global_vfs_mut()?;
-        vfs.unload_kernel(filename)
-    }
+    with_global_vfs_mut(|vfs| vfs.unload_kernel(filename))
 }
 
 /// Clear all loaded kernels (equivalent to kclear_c)
 pub fn clear_kernels() -> SpiceResult<()> {
-    unsafe {
-        let vfs = get_global_vfs_mut()?;
+    with_global_vfs_mut(|vfs| {
         vfs.clear_all_kernels();
-    }
+        Ok(())
+    })?;
     
-    // Also clear the kernel pool
-    kernel_pool::clear_pool()?;
+    // Also clear the kernel pool and the SPK data registered from the files
+    if kernel_pool::is_pool_initialized() {
+        kernel_pool::clear_pool()?;
+    }
+    crate::spk_reader::clear_spk_reader();
     
     Ok(())
 }
 
 /// Get information about a loaded kernel (equivalent to kinfo_c)
 pub fn kernel_info(filename: &str) -> SpiceResult<KernelInfo> {
-    unsafe {
-        let vfs = get_global_vfs()?;
-        vfs.kernel_info(filename)
-    }
+    with_global_vfs(|vfs| vfs.kernel_info(filename))
 }
 
 /// Get list of all loaded kernel paths
 pub fn loaded_kernels() -> SpiceResult<Vec<String>> {
-    unsafe {
-        let vfs = get_global_vfs()?;
-        Ok(vfs.loaded_kernel_paths().to_vec())
-    }
+    with_global_vfs(|vfs| Ok(vfs.loaded_kernel_paths().to_vec()))
 }
 
 /// Get count of loaded kernels
 pub fn kernel_count() -> SpiceResult<usize> {
-    unsafe {
-        let vfs = get_global_vfs()?;
-        Ok(vfs.kernel_count())
-    }
+    with_global_vfs(|vfs| Ok(vfs.kernel_count()))
 }
 
 /// Check if a specific kernel is loaded
 pub fn is_kernel_loaded(filename: &str) -> SpiceResult<bool> {
-    unsafe {
-        let vfs = get_global_vfs()?;
-        Ok(vfs.is_kernel_loaded(filename))
-    }
+    with_global_vfs(|vfs| Ok(vfs.is_kernel_loaded(filename)))
 }
 
 /// Get access to the global VFS for advanced operations
 /// This is primarily for internal use by other modules
+///
+/// The VFS is locked for the duration of `f`, which must not call back into
+/// functions that modify loaded kernels.
 pub fn with_global_vfs<F, R>(f: F) -> SpiceResult<R> 
 where
     F: FnOnce(&VirtualFileSystem) -> SpiceResult<R>
 {
-    unsafe {
-        let vfs = get_global_vfs()?;
-        f(vfs)
+    if !is_initialized() {
+        return Err(SpiceError::new(
+            SpiceErrorType::SpiceError,
+            "Kernel system not initialized".into()
+        ));
     }
+    GLOBAL_VFS.read(|vfs| f(vfs.ok_or_else(vfs_not_initialized)?))
 }
 
 /// Get mutable access to the global VFS for advanced operations
@@ -271,10 +231,10 @@ pub fn with_global_vfs_mut<F, R>(f: F) -> SpiceResult<R>
 where
     F: FnOnce(&mut VirtualFileSystem) -> SpiceResult<R>
 {
-    unsafe {
-        let vfs = get_global_vfs_mut()?;
-        f(vfs)
+    if !is_initialized() {
+        initialize_kernel_system()?;
     }
+    GLOBAL_VFS.write(|vfs| f(vfs.as_mut().ok_or_else(vfs_not_initialized)?))
 }
 
 // ============================================================================
@@ -439,28 +399,53 @@ pub fn load_meta_kernel(metakernel_content: &str, metakernel_path: &str) -> Spic
     // First load the meta-kernel content into the kernel pool
     kernel_pool::load_text_kernel(metakernel_content)?;
     
-    // Check if KERNELS_TO_LOAD variable exists in the pool
-    if kernel_pool::exists_in_pool("KERNELS_TO_LOAD")? {
-        // Get the list of kernels to load
-        let (kernel_paths, _) = kernel_pool::get_character_pool("KERNELS_TO_LOAD", 0, 1000)?;
-        
-        for kernel_path in &kernel_paths {
-            // For now, just register that this kernel should be loaded
-            // In a full implementation, this would resolve relative paths
-            // and attempt to load the referenced kernels
-            
-            // Check if kernel is already loaded
-            if !is_kernel_loaded(&kernel_path)? {
-                // In a real implementation, we would try to load the kernel
-                // For now, we'll create a placeholder entry
-                let placeholder_data = format!("Placeholder for meta-kernel reference: {}", kernel_path).into_bytes();
-                
-                // Try to load as placeholder - this will fail gracefully if the actual file isn't available
-                let _ = furnish_kernel_from_bytes(placeholder_data, &kernel_path);
-            }
-        }
+    // Then furnish the kernels it lists
+    furnish_meta_kernel_entries(metakernel_content)
+}
+
+/// Kernels listed in a meta-kernel's KERNELS_TO_LOAD, with `$SYMBOL`
+/// references replaced by the matching PATH_VALUES entry
+fn meta_kernel_entries(metakernel_content: &str) -> SpiceResult<Vec<String>> {
+    // Read the meta-kernel on its own, so lists from other meta-kernels in the
+    // global pool do not mix in
+    let mut pool = KernelPool::new();
+    let lines: Vec<String> = metakernel_content.lines().map(String::from).collect();
+    pool.load_from_memory(&lines)?;
+
+    let (kernels, _) = pool.get_character("KERNELS_TO_LOAD", 0, MAX_VALUES_PER_VARIABLE)?;
+    let (symbols, _) = pool.get_character("PATH_SYMBOLS", 0, MAX_VALUES_PER_VARIABLE)?;
+    let (values, _) = pool.get_character("PATH_VALUES", 0, MAX_VALUES_PER_VARIABLE)?;
+    if symbols.len() != values.len() {
+        return Err(SpiceError::new(
+            SpiceErrorType::InvalidFormat,
+            format!("Meta-kernel has {} PATH_SYMBOLS but {} PATH_VALUES", symbols.len(), values.len())
+        ));
+    }
+
+    // Longer symbols first, so $KERNELS_SPK is not read as $KERNELS + _SPK
+    let mut substitutions: Vec<(String, &String)> = symbols.iter()
+        .map(|symbol| format!("${}", symbol))
+        .zip(values.iter())
+        .collect();
+    substitutions.sort_by_key(|(symbol, _)| core::cmp::Reverse(symbol.len()));
+
+    Ok(kernels.into_iter()
+        .map(|kernel| substitutions.iter().fold(kernel, |path, (symbol, value)| path.replace(symbol.as_str(), value)))
+        .collect())
+}
+
+/// Furnish every kernel a meta-kernel lists
+///
+/// Kernels already loaded are skipped. A meta-kernel is loaded before its
+/// entries are read, so one that lists itself, directly or through another
+/// meta-kernel, does not recurse.
+fn furnish_meta_kernel_entries(metakernel_content: &str) -> SpiceResult<()> {
+    for kernel in meta_kernel_entries(metakernel_content)? {
+        furnish_kernel(&kernel).map_err(|e| SpiceError::new(
+            SpiceErrorType::KernelLoadError,
+            format!("Failed to furnish meta-kernel entry '{}': {}", kernel, e.message)
+        ))?;
     }
-    
     Ok(())
 }
 
@@ -475,6 +460,46 @@ pub fn is_kernel_pool_initialized() -> bool {
     kernel_pool::is_pool_initialized()
 }
 
+/// Holds the global-state test lock over fresh SPK and CK readers, kernel pool
+/// and body registry, and clears every kernel and `define_body` definition
+/// again on drop, even when the test panics
+#[cfg(test)]
+pub(crate) struct IsolatedKernels {
+    _lock: std::sync::MutexGuard<'static, ()>,
+}
+
+#[cfg(test)]
+impl IsolatedKernels {
+    pub(crate) fn new() -> Self {
+        let lock = crate::global_state::test_lock();
+        initialize_kernel_system().unwrap();
+        crate::spk_reader::initialize_spk_reader().unwrap();
+        crate::ck_reader::initialize_ck_system().unwrap();
+        kernel_pool::initialize_pool().unwrap();
+        crate::body_data::reset_body_registry();
+        crate::coordinates::frame_kernel::reset_tk_frame_cache();
+        Self { _lock: lock }
+    }
+
+    /// Register SPK file contents with the global reader
+    pub(crate) fn load_spk(&self, name: &str, bytes: &[u8]) {
+        crate::spk_reader::with_spk_reader_mut(|reader| reader.load_spk_data(name, bytes)).unwrap();
+    }
+
+    /// Register CK file contents with the global reader
+    pub(crate) fn load_ck(&self, name: &str, bytes: &[u8]) {
+        crate::ck_reader::ck_load_data(name, bytes).unwrap();
+    }
+}
+
+#[cfg(test)]
+impl Drop for IsolatedKernels {
+    fn drop(&mut self) {
+        let _ = clear_kernels();
+        crate::body_data::reset_body_registry();
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -488,6 +513,7 @@ mod tests {
     
     #[test]
     fn test_furnish_kernel_from_bytes() {
+        let _lock = crate::global_state::test_lock();
         initialize_kernel_system().unwrap();
         initialize_kernel_pool().unwrap();
         clear_kernels().unwrap(); // Clear any previous test state
@@ -506,6 +532,7 @@ mod tests {
     
     #[test]
     fn test_kernel_info() {
+        let _lock = crate::global_state::test_lock();
         initialize_kernel_system().unwrap();
         
         let data = b"DAF/CK  test CK kernel".to_vec();
@@ -518,6 +545,7 @@ mod tests {
     
     #[test]
     fn test_unload_kernel() {
+        let _lock = crate::global_state::test_lock();
         initialize_kernel_system().unwrap();
         initialize_kernel_pool().unwrap();
         clear_kernels().unwrap(); // Clear any previous test state
@@ -534,6 +562,7 @@ mod tests {
     
     #[test]
     fn test_clear_kernels() {
+        let _lock = crate::global_state::test_lock();
         initialize_kernel_system().unwrap();
         initialize_kernel_pool().unwrap();
         clear_kernels().unwrap(); // Clear any previous test state
@@ -550,6 +579,7 @@ mod tests {
     
     #[test]
     fn test_loaded_kernels() {
+        let _lock = crate::global_state::test_lock();
         initialize_kernel_system().unwrap();
         initialize_kernel_pool().unwrap();
         clear_kernels().unwrap(); // Clear any previous test state
@@ -565,6 +595,7 @@ mod tests {
     
     #[test]
     fn test_furnish_nonexistent_kernel() {
+        let _lock = crate::global_state::test_lock();
         initialize_kernel_system().unwrap();
         
         // Try to furnish a kernel that wasn't loaded via bytes
@@ -589,6 +620,7 @@ mod tests {
 
     #[test]
     fn test_kernel_data() {
+        let _lock = crate::global_state::test_lock();
         initialize_kernel_system().unwrap();
         initialize_kernel_pool().unwrap();
         clear_kernels().unwrap();
@@ -613,6 +645,7 @@ mod tests {
 
     #[test]
     fn test_kernel_total() {
+        let _lock = crate::global_state::test_lock();
         initialize_kernel_system().unwrap();
         initialize_kernel_pool().unwrap();
         clear_kernels().unwrap();
@@ -631,6 +664,7 @@ mod tests {
 
     #[test]
     fn test_kernel_info_by_type() {
+        let _lock = crate::global_state::test_lock();
         initialize_kernel_system().unwrap();
         initialize_kernel_pool().unwrap();
         clear_kernels().unwrap();
@@ -664,6 +698,7 @@ Test text kernel
 
     #[test]
     fn test_load_meta_kernel() {
+        let _lock = crate::global_state::test_lock();
         initialize_kernel_system().unwrap();
         initialize_kernel_pool().unwrap();
         clear_kernels().unwrap();
@@ -671,6 +706,7 @@ Test text kernel
         let meta_content = r#"
 #[test]
     fn test_load_meta_kernel() {
+        let _lock = crate::global_state::test_lock();
         initialize_kernel_system().unwrap();
         clear_kernels().unwrap();
         
@@ -696,6 +732,7 @@ This is a test meta-kernel without file references
 
     #[test]
     fn test_kernel_pool_integration() {
+        let _lock = crate::global_state::test_lock();
         initialize_kernel_system().unwrap();
         initialize_kernel_pool().unwrap();
         clear_kernels().unwrap();
@@ -715,6 +752,7 @@ This is a test meta-kernel without file references
 
     #[test]
     fn test_text_kernel_integration() {
+        let _lock = crate::global_state::test_lock();
         initialize_kernel_system().unwrap();
         initialize_kernel_pool().unwrap();
         clear_kernels().unwrap();
@@ -754,4 +792,106 @@ This is a test text kernel
         let (test_array, _) = kernel_pool::get_double_pool("TEST_ARRAY", 0, 10).unwrap();
         assert_eq!(test_array, vec![1.0, 2.0, 3.0]);
     }
+
+    #[test]
+    fn test_concurrent_furnish_and_query() {
+        use crate::spk_reader::{initialize_spk_reader, write};
+        use crate::ephemeris::ephemeris_state;
+        use crate::foundation::EphemerisTime;
+        use std::sync::Arc;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::thread;
+
+        let _lock = crate::global_state::test_lock();
+        initialize_kernel_system().unwrap();
+        initialize_kernel_pool().unwrap();
+        initialize_spk_reader().unwrap();
+
+        let record = |x: f64| write::ChebyshevRecord {
+            start: 0.0, end: 1000.0, x: vec![x, 0.0], y: vec![0.0, 0.0], z: vec![0.0, 0.0],
+        };
+        let mut spk = write::new_spk("CONCURRENT").unwrap();
+        write::add_type2_segment(&mut spk, 399, 0, "J2000", 0.0, 1000.0, "EARTH", &[record(1.5e8)]).unwrap();
+        write::add_type2_segment(&mut spk, -7701, 399, "J2000", 0.0, 1000.0, "PROBE", &[record(1000.0)]).unwrap();
+        let spk_bytes = spk.to_bytes().unwrap();
+
+        // The furnisher waits for a query after each load, so loads and
+        // queries interleave; once a load has finished, queries must see it
+        let loaded = Arc::new(AtomicUsize::new(0));
+        let queries = Arc::new(AtomicUsize::new(0));
+        let furnisher = {
+            let (loaded, queries) = (Arc::clone(&loaded), Arc::clone(&queries));
+            thread::spawn(move || {
+                let mut seen = 0;
+                for i in 0..20 {
+                    while i > 0 && queries.load(Ordering::SeqCst) == seen {
+                        thread::yield_now();
+                    }
+                    furnish_kernel_from_bytes(spk_bytes.clone(), &format!("/concurrent/probe_{}.bsp", i)).unwrap();
+                    let text = b"\\begindata\nBODY7701_CONCURRENT_VALUE = 42\n".to_vec();
+                    furnish_kernel_from_bytes(text, &format!("/concurrent/value_{}.tpc", i)).unwrap();
+                    seen = queries.load(Ordering::SeqCst);
+                    loaded.fetch_add(1, Ordering::SeqCst);
+                }
+            })
+        };
+        let querier = {
+            let (loaded, queries) = (Arc::clone(&loaded), Arc::clone(&queries));
+            thread::spawn(move || {
+                while loaded.load(Ordering::SeqCst) < 20 {
+                    let after_load = loaded.load(Ordering::SeqCst) > 0;
+                    let state = ephemeris_state("-7701", EphemerisTime::new(500.0), "J2000", "NONE", "399");
+                    let value = crate::kernel_pool::get_double_pool("BODY7701_CONCURRENT_VALUE", 0, 1);
+                    match state {
+                        Ok(state) => assert!((state.position.x() - 1000.0).abs() < 1e-6),
+                        Err(error) => assert!(!after_load, "query after a finished load failed: {}", error),
+                    }
+                    match value {
+                        Ok((values, true)) => assert_eq!(values, vec![42.0]),
+                        _ => assert!(!after_load),
+                    }
+                    queries.fetch_add(1, Ordering::SeqCst);
+                }
+            })
+        };
+
+        furnisher.join().unwrap();
+        querier.join().unwrap();
+        assert!(queries.load(Ordering::SeqCst) >= 19);
+        clear_kernels().unwrap();
+    }
+
+    #[test]
+    fn test_meta_kernel_furnishes_listed_kernels() {
+        let _lock = crate::global_state::test_lock();
+        initialize_kernel_system().unwrap();
+        initialize_kernel_pool().unwrap();
+
+        furnish_kernel_from_bytes(b"\\begindata\nMK_ENTRY_A = 1\n".to_vec(), "/mem/kernels/a.tpc").unwrap();
+        furnish_kernel_from_bytes(b"\\begindata\nMK_ENTRY_B = 2\n".to_vec(), "/mem/kernels/b.tf").unwrap();
+
+        // Lists itself as well, which must not recurse
+        let meta = r#"
+\begindata
+PATH_VALUES     = ( '/mem/kernels' )
+PATH_SYMBOLS    = ( 'KERNELS' )
+KERNELS_TO_LOAD = ( '$KERNELS/a.tpc',
+                    '$KERNELS/b.tf',
+                    '/mem/self_listing.mk' )
+\begintext
+"#;
+        assert_eq!(
+            meta_kernel_entries(meta).unwrap(),
+            vec!["/mem/kernels/a.tpc", "/mem/kernels/b.tf", "/mem/self_listing.mk"]
+        );
+        furnish_kernel_from_bytes(meta.as_bytes().to_vec(), "/mem/self_listing.mk").unwrap();
+
+        let missing = meta.replace("b.tf", "missing.bsp");
+        let error = furnish_kernel_from_bytes(missing.into_bytes(), "/mem/missing_entry.mk").unwrap_err();
+        assert_eq!(error.error_type, SpiceErrorType::KernelLoadError);
+        assert!(error.message.contains("/mem/kernels/missing.bsp"));
+
+        let unpaired = meta.replace("( 'KERNELS' )", "( 'KERNELS', 'EXTRA' )");
+        assert!(meta_kernel_entries(&unpaired).is_err());
+    }
 }
@@ -21,6 +21,7 @@ use std::format;
 
 // Core modules
 pub mod error_handling;
+pub mod global_state;
 pub mod math_core;
 pub mod foundation;
 pub mod time_system;
@@ -43,18 +44,18 @@ pub use error_handling::{SpiceError, SpiceResult, ErrorTrace};
 pub use foundation::{
     SpiceDouble, SpiceInt, SpiceChar, SpiceBoolean,
     SpiceMatrix3x3, SpiceMatrix6x6, SpiceVector3, SpiceVector6,
-    StateVector, EphemerisTime, JulianDate
+    StateVector, EphemerisTime, JulianDate, SpiceWindow
 };
 pub use time_system::{
-    str_to_et, et_to_utc, time_parse, time_output, delta_et_utc,
+    str_to_et, et_to_utc, et_to_utc_format, et_to_time_string, TimeSystem, time_parse, time_output, time_output_picture, delta_et_utc, unit_time,
     ParsedTime, CalendarType, Era, is_leap_year,
     day_of_year_to_month_day, month_day_to_day_of_year
 };
-pub use ephemeris::{ephemeris_state, ephemeris_position};
+pub use ephemeris::{ephemeris_state, ephemeris_states, ephemeris_position, geometric_state, barycentric_state, stellar_aberration, stellar_aberration_transmission, propagate_two_body, propagate, define_body, occultation_type, target_in_fov, closest_approach, gf_distance, compare_to_conic, ResidualReport};
 pub use kernel_system::{furnish_kernel, unload_kernel, clear_kernels};
 pub use ck_reader::{
-    ck_get_pointing, ck_get_pointing_and_av, ck_find_frame_rotation,
-    initialize_ck_system, CMatrix, AngularVelocity, AttitudeState,
+    ck_get_pointing, ck_get_pointing_and_av, ck_find_frame_rotation, ck_load_data, ck_objects, ck_coverage,
+    initialize_ck_system, attitude_state_to_state_transform, CMatrix, AngularVelocity, AttitudeState,
     CkSegmentType, CkSegmentInfo
 };
 
@@ -89,7 +90,11 @@ pub fn build_info() -> String {
 pub use coordinates::{
     get_position_transformation, get_state_transformation,
     rotate_vector, rotation_matrix_axis_angle, axis_angle_rotation,
-    matrix_to_euler, euler_to_matrix, transform_position, transform_state,
-    rotation_between_vectors, is_rotation_matrix,
-    ReferenceFrame, EulerSequence, RotationAxis, SpacecraftOrientation
+    matrix_to_euler, euler_to_matrix, euler_rates_to_state_transform, state_transform_to_euler_rates, transform_position, transform_state,
+    rotation_between_vectors, is_rotation_matrix, two_vectors_to_matrix,
+    rotation_and_av_to_state_transform, body_orientation,
+    rectangular_to_latitudinal, latitudinal_to_rectangular, rectangular_to_ra_dec, ra_dec_to_rectangular, rectangular_to_geodetic, geodetic_to_rectangular, rectangular_to_planetographic, planetographic_to_rectangular, frame_name_to_id, frame_id_to_name, position_transform,
+    rectangular_to_latitudinal_batch, latitudinal_to_rectangular_batch,
+    sub_observer_point, surface_intercept, surface_normal, nearest_point, illumination_angles,
+    ReferenceFrame, EulerSequence, RotationAxis, SpacecraftOrientation, Plane
 };
@@ -3,6 +3,11 @@
 //! This module implements the mathematical functions that are heavily used
 //! throughout CSPICE, corresponding to the v*.c and m*.c functions.
 
+#[cfg(not(feature = "std"))]
+use alloc::format;
+#[cfg(feature = "std")]
+use std::format;
+
 use crate::foundation::{
     SpiceDouble, SpiceVector3, SpiceMatrix3x3
 };
@@ -118,32 +123,7 @@ pub fn matrix_determinant(m: &SpiceMatrix3x3) -> SpiceDouble {
 
 /// Matrix inverse (equivalent to invert_c)
 pub fn matrix_invert(m: &SpiceMatrix3x3) -> SpiceResult<SpiceMatrix3x3> {
-    let det = m.determinant();
-    
-    if det.abs() < 1e-15 {
-        return Err(SpiceError::new(
-            SpiceErrorType::ComputationError,
-            "Matrix is singular (determinant is zero)".into()
-        ));
-    }
-    
-    let inv_det = 1.0 / det;
-    let mut inv = [[0.0; 3]; 3];
-    
-    // Calculate adjugate matrix and divide by determinant
-    inv[0][0] = (m[1][1] * m[2][2] - m[1][2] * m[2][1]) * inv_det;
-    inv[0][1] = (m[0][2] * m[2][1] - m[0][1] * m[2][2]) * inv_det;
-    inv[0][2] = (m[0][1] * m[1][2] - m[0][2] * m[1][1]) * inv_det;
-    
-    inv[1][0] = (m[1][2] * m[2][0] - m[1][0] * m[2][2]) * inv_det;
-    inv[1][1] = (m[0][0] * m[2][2] - m[0][2] * m[2][0]) * inv_det;
-    inv[1][2] = (m[0][2] * m[1][0] - m[0][0] * m[1][2]) * inv_det;
-    
-    inv[2][0] = (m[1][0] * m[2][1] - m[1][1] * m[2][0]) * inv_det;
-    inv[2][1] = (m[0][1] * m[2][0] - m[0][0] * m[2][1]) * inv_det;
-    inv[2][2] = (m[0][0] * m[1][1] - m[0][1] * m[1][0]) * inv_det;
-    
-    Ok(SpiceMatrix3x3::new(inv))
+    m.invert()
 }
 
 /// Angle between vectors (equivalent to vsep_c)
@@ -158,12 +138,7 @@ pub fn vector_separation(v1: &SpiceVector3, v2: &SpiceVector3) -> SpiceResult<Sp
         ));
     }
     
-    let cos_angle = v1.dot(v2) / (mag1 * mag2);
-    
-    // Clamp to avoid numerical errors with acos
-    let cos_angle = cos_angle.max(-1.0).min(1.0);
-    
-    Ok(libm::acos(cos_angle))
+    Ok(v1.angular_separation(v2))
 }
 
 /// Distance between vectors (equivalent to vdist_c)
@@ -191,6 +166,34 @@ pub fn vectors_parallel(v1: &SpiceVector3, v2: &SpiceVector3, tolerance: SpiceDo
     }
 }
 
+/// Components of `v` parallel and perpendicular to `reference` (vproj_c and
+/// vperp_c in one call)
+///
+/// The two components sum to `v`. A zero reference has no direction and is
+/// an error.
+pub fn decompose(v: &SpiceVector3, reference: &SpiceVector3) -> SpiceResult<(SpiceVector3, SpiceVector3)> {
+    if reference.magnitude() == 0.0 {
+        return Err(SpiceError::new(
+            SpiceErrorType::InvalidArgument,
+            "Cannot decompose a vector along the zero vector".into()
+        ));
+    }
+    Ok((v.project(reference), v.perpendicular(reference)))
+}
+
+/// Arc cosine with the argument clamped into [-1, 1]
+///
+/// Rounding can push dot products of unit vectors slightly outside the domain;
+/// clamping keeps those from turning into NaN.
+pub fn safe_acos(x: SpiceDouble) -> SpiceDouble {
+    libm::acos(x.clamp(-1.0, 1.0))
+}
+
+/// Arc sine with the argument clamped into [-1, 1]
+pub fn safe_asin(x: SpiceDouble) -> SpiceDouble {
+    libm::asin(x.clamp(-1.0, 1.0))
+}
+
 /// Mathematical constants
 pub mod constants {
     use crate::foundation::SpiceDouble;
@@ -230,6 +233,200 @@ pub fn radians_to_degrees(radians: SpiceDouble) -> SpiceDouble {
     radians * constants::DEGREES_PER_RADIAN
 }
 
+/// Index of the last element of an ascending array that is <= `value`
+/// (equivalent to lstled_c)
+///
+/// Returns `None` when every element exceeds `value`. Among equal elements
+/// the last one is chosen.
+pub fn last_le(array: &[SpiceDouble], value: SpiceDouble) -> Option<usize> {
+    array.partition_point(|&x| x <= value).checked_sub(1)
+}
+
+/// Index of the last element of an ascending array that is < `value`
+/// (equivalent to lstltd_c)
+///
+/// Returns `None` when no element is below `value`.
+pub fn last_lt(array: &[SpiceDouble], value: SpiceDouble) -> Option<usize> {
+    array.partition_point(|&x| x < value).checked_sub(1)
+}
+
+/// Physical dimension of a unit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnitFamily {
+    Angle,
+    Time,
+    Distance,
+}
+
+/// Units understood by the conversion routines
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Unit {
+    Radians,
+    Degrees,
+    Arcminutes,
+    Arcseconds,
+    /// Hours of right ascension (15 degrees)
+    HourAngle,
+    /// Minutes of right ascension
+    MinuteAngle,
+    /// Seconds of right ascension
+    SecondAngle,
+    Seconds,
+    Minutes,
+    Hours,
+    Days,
+    Weeks,
+    JulianYears,
+    TropicalYears,
+    JulianCenturies,
+    Millimeters,
+    Centimeters,
+    Meters,
+    Kilometers,
+    Inches,
+    Feet,
+    Yards,
+    StatuteMiles,
+    NauticalMiles,
+    AstronomicalUnits,
+    Parsecs,
+    LightSeconds,
+    LightYears,
+}
+
+impl Unit {
+    /// Dimension this unit measures
+    pub fn family(&self) -> UnitFamily {
+        match self {
+            Unit::Radians | Unit::Degrees | Unit::Arcminutes | Unit::Arcseconds |
+            Unit::HourAngle | Unit::MinuteAngle | Unit::SecondAngle => UnitFamily::Angle,
+            Unit::Seconds | Unit::Minutes | Unit::Hours | Unit::Days | Unit::Weeks |
+            Unit::JulianYears | Unit::TropicalYears | Unit::JulianCenturies => UnitFamily::Time,
+            _ => UnitFamily::Distance,
+        }
+    }
+
+    /// Unit named as in convrt_c, e.g. "KM", "DEGREES" or "JULIAN_YEARS"
+    ///
+    /// Names are case-insensitive; "YEARS" means Julian years.
+    pub fn from_name(name: &str) -> SpiceResult<Self> {
+        Ok(match name.trim().to_uppercase().as_str() {
+            "RADIANS" => Unit::Radians,
+            "DEGREES" => Unit::Degrees,
+            "ARCMINUTES" => Unit::Arcminutes,
+            "ARCSECONDS" => Unit::Arcseconds,
+            "HOURANGLE" => Unit::HourAngle,
+            "MINUTEANGLE" => Unit::MinuteAngle,
+            "SECONDANGLE" => Unit::SecondAngle,
+            "SECONDS" => Unit::Seconds,
+            "MINUTES" => Unit::Minutes,
+            "HOURS" => Unit::Hours,
+            "DAYS" => Unit::Days,
+            "WEEKS" => Unit::Weeks,
+            "YEARS" | "JULIAN_YEARS" => Unit::JulianYears,
+            "TROPICAL_YEARS" => Unit::TropicalYears,
+            "JULIAN_CENTURIES" => Unit::JulianCenturies,
+            "MM" | "MILLIMETERS" | "MILLIMETRES" => Unit::Millimeters,
+            "CM" | "CENTIMETERS" | "CENTIMETRES" => Unit::Centimeters,
+            "M" | "METERS" | "METRES" => Unit::Meters,
+            "KM" | "KILOMETERS" | "KILOMETRES" => Unit::Kilometers,
+            "INCHES" => Unit::Inches,
+            "FEET" => Unit::Feet,
+            "YARDS" => Unit::Yards,
+            "STATUTE_MILES" => Unit::StatuteMiles,
+            "NAUTICAL_MILES" => Unit::NauticalMiles,
+            "AU" => Unit::AstronomicalUnits,
+            "PC" | "PARSECS" => Unit::Parsecs,
+            "LIGHTSECS" => Unit::LightSeconds,
+            "LIGHTYEARS" => Unit::LightYears,
+            _ => return Err(SpiceError::new(
+                SpiceErrorType::InvalidArgument,
+                format!("Unknown unit '{}'", name)
+            )),
+        })
+    }
+
+    /// Size of the unit in radians, seconds or meters
+    pub fn in_base_units(&self) -> SpiceDouble {
+        const DAY: SpiceDouble = 86400.0;
+        const KM: SpiceDouble = 1000.0;
+        const INCH: SpiceDouble = 0.0254;
+        match self {
+            Unit::Radians => 1.0,
+            Unit::Degrees => constants::RADIANS_PER_DEGREE,
+            Unit::Arcminutes => constants::RADIANS_PER_DEGREE / 60.0,
+            Unit::Arcseconds => constants::RADIANS_PER_DEGREE / 3600.0,
+            Unit::HourAngle => 15.0 * constants::RADIANS_PER_DEGREE,
+            Unit::MinuteAngle => 15.0 * constants::RADIANS_PER_DEGREE / 60.0,
+            Unit::SecondAngle => 15.0 * constants::RADIANS_PER_DEGREE / 3600.0,
+            Unit::Seconds => 1.0,
+            Unit::Minutes => 60.0,
+            Unit::Hours => 3600.0,
+            Unit::Days => DAY,
+            Unit::Weeks => 7.0 * DAY,
+            Unit::JulianYears => constants::JULIAN_YEAR,
+            Unit::TropicalYears => 365.24219878 * DAY,
+            Unit::JulianCenturies => 100.0 * constants::JULIAN_YEAR,
+            Unit::Millimeters => 1e-3,
+            Unit::Centimeters => 1e-2,
+            Unit::Meters => 1.0,
+            Unit::Kilometers => KM,
+            Unit::Inches => INCH,
+            Unit::Feet => 12.0 * INCH,
+            Unit::Yards => 36.0 * INCH,
+            Unit::StatuteMiles => 5280.0 * 12.0 * INCH,
+            Unit::NauticalMiles => 1852.0,
+            Unit::AstronomicalUnits => constants::ASTRONOMICAL_UNIT * KM,
+            Unit::Parsecs => constants::ASTRONOMICAL_UNIT * KM * 648000.0 / constants::PI,
+            Unit::LightSeconds => constants::SPEED_OF_LIGHT * KM,
+            Unit::LightYears => constants::SPEED_OF_LIGHT * KM * constants::JULIAN_YEAR,
+        }
+    }
+}
+
+/// Require `unit` to belong to `family`; `role` names it in the error
+fn check_unit_family(unit: Unit, family: UnitFamily, role: &str) -> SpiceResult<()> {
+    if unit.family() != family {
+        return Err(SpiceError::new(
+            SpiceErrorType::InvalidArgument,
+            format!("{} unit {:?} is not a unit of {:?}", role, unit, family)
+        ));
+    }
+    Ok(())
+}
+
+/// Convert a measurement between units of one family (equivalent to convrt_c)
+///
+/// Units are named as in `Unit::from_name`; converting between families,
+/// such as DEGREES to KM, is an error.
+pub fn convert_units(value: SpiceDouble, from: &str, to: &str) -> SpiceResult<SpiceDouble> {
+    let from = Unit::from_name(from)?;
+    let to = Unit::from_name(to)?;
+    check_unit_family(to, from.family(), "Target")?;
+    Ok(value * from.in_base_units() / to.in_base_units())
+}
+
+/// Convert an angular rate between compound angle/time units
+///
+/// For example deg/day to rad/s is
+/// `convert_rate(x, Unit::Degrees, Unit::Days, Unit::Radians, Unit::Seconds)`.
+/// Both numerators must be angle units and both denominators time units.
+pub fn convert_rate(
+    value: SpiceDouble,
+    from_angle: Unit,
+    from_time: Unit,
+    to_angle: Unit,
+    to_time: Unit,
+) -> SpiceResult<SpiceDouble> {
+    check_unit_family(from_angle, UnitFamily::Angle, "Source numerator")?;
+    check_unit_family(from_time, UnitFamily::Time, "Source denominator")?;
+    check_unit_family(to_angle, UnitFamily::Angle, "Target numerator")?;
+    check_unit_family(to_time, UnitFamily::Time, "Target denominator")?;
+
+    let radians_per_second = value * from_angle.in_base_units() / from_time.in_base_units();
+    Ok(radians_per_second * to_time.in_base_units() / to_angle.in_base_units())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -278,4 +475,79 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_convert_units_within_families() {
+        assert_eq!(convert_units(1.0, "AU", "KM").unwrap(), 149597870.7);
+        assert!((convert_units(180.0, "DEGREES", "RADIANS").unwrap() - constants::PI).abs() < 1e-15);
+        assert!((convert_units(1.0, "lightyears", "km").unwrap() - 9.4607304725808e12).abs() < 1.0);
+        assert!((convert_units(1.0, "STATUTE_MILES", "FEET").unwrap() - 5280.0).abs() < 1e-9);
+        assert!((convert_units(2.0, "YEARS", "DAYS").unwrap() - 730.5).abs() < 1e-12);
+
+        let error = convert_units(1.0, "DEGREES", "KM").unwrap_err();
+        assert_eq!(error.error_type, SpiceErrorType::InvalidArgument);
+        assert!(convert_units(1.0, "FURLONGS", "KM").is_err());
+    }
+
+    #[test]
+    fn test_safe_inverse_trig_clamps_domain() {
+        assert_eq!(safe_acos(1.0 + 1e-15), 0.0);
+        assert_eq!(safe_acos(-1.0 - 1e-15), constants::PI);
+        assert_eq!(safe_asin(1.0 + 1e-15), constants::HALF_PI);
+        for x in [-0.75, 0.0, 0.3, 1.0] {
+            assert_eq!(safe_acos(x), libm::acos(x));
+            assert_eq!(safe_asin(x), libm::asin(x));
+        }
+    }
+
+    #[test]
+    fn test_convert_rate_factors() {
+        let deg_per_day = convert_rate(1.0, Unit::Degrees, Unit::Days, Unit::Radians, Unit::Seconds).unwrap();
+        assert!((deg_per_day - constants::PI / 180.0 / 86400.0).abs() < 1e-22);
+
+        let arcsec_per_century = convert_rate(
+            1.0, Unit::Arcseconds, Unit::JulianCenturies, Unit::Radians, Unit::Seconds
+        ).unwrap();
+        let expected = constants::PI / (180.0 * 3600.0) / (36525.0 * 86400.0);
+        assert!((arcsec_per_century / expected - 1.0).abs() < 1e-15);
+
+        let back = convert_rate(deg_per_day, Unit::Radians, Unit::Seconds, Unit::Degrees, Unit::Days).unwrap();
+        assert!((back - 1.0).abs() < 1e-14);
+
+        assert!(convert_rate(1.0, Unit::Days, Unit::Days, Unit::Radians, Unit::Seconds).is_err());
+        assert!(convert_rate(1.0, Unit::Degrees, Unit::Degrees, Unit::Radians, Unit::Seconds).is_err());
+    }
+
+    #[test]
+    fn test_ordered_search_boundaries() {
+        let epochs = [10.0, 20.0, 20.0, 30.0];
+
+        assert_eq!(last_le(&epochs, 5.0), None);
+        assert_eq!(last_lt(&epochs, 5.0), None);
+        assert_eq!(last_le(&epochs, 40.0), Some(3));
+        assert_eq!(last_lt(&epochs, 40.0), Some(3));
+
+        assert_eq!(last_le(&epochs, 10.0), Some(0));
+        assert_eq!(last_lt(&epochs, 10.0), None);
+        assert_eq!(last_le(&epochs, 20.0), Some(2));
+        assert_eq!(last_lt(&epochs, 20.0), Some(0));
+
+        assert_eq!(last_le(&epochs, 25.0), Some(2));
+        assert_eq!(last_lt(&epochs, 25.0), Some(2));
+        assert_eq!(last_le(&[], 1.0), None);
+    }
+
+    #[test]
+    fn test_decompose_reconstructs_vector() {
+        let v = SpiceVector3::new(3.0, -1.0, 2.5);
+        let reference = SpiceVector3::new(1.0, 2.0, -2.0);
+        let (parallel, perpendicular) = decompose(&v, &reference).unwrap();
+
+        assert!((parallel + perpendicular - v).magnitude() < 1e-15);
+        assert!(parallel.cross(&reference).magnitude() < 1e-15);
+        assert!(perpendicular.dot(&reference).abs() < 1e-14);
+        assert!((parallel - v.project(&reference)).magnitude() < 1e-15);
+
+        assert!(decompose(&v, &SpiceVector3::zeros()).is_err());
+    }
 }
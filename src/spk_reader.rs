@@ -23,11 +23,27 @@
 use crate::foundation::{SpiceDouble, StateVector, SpiceVector3, EphemerisTime};
 use crate::error_handling::{SpiceResult, SpiceError, SpiceErrorType};
 use crate::file_system::VirtualFileSystem;
+use crate::file_system::daf::{DafFile, DafByteOrder};
+use crate::global_state::GlobalState;
+use crate::advanced_math::{HermiteInterpolatorND, LagrangeInterpolator};
+use crate::math_core::last_le;
 use std::collections::HashMap;
+use std::sync::Mutex;
+
+pub mod write;
 
 /// Speed of light in km/s (exact value used by CSPICE)
 const LIGHT_SPEED: f64 = 299792.458;
 
+/// NAIF ID of the solar system barycenter, the root of every segment chain
+const SOLAR_SYSTEM_BARYCENTER: i32 = 0;
+
+/// Longest segment chain followed before giving up
+const MAX_CHAIN_LENGTH: usize = 100;
+
+/// Decoded Type 2 records a reader keeps by default
+pub const DEFAULT_RECORD_CACHE_SIZE: usize = 64;
+
 /// DAF file record size in double precision words
 const DAF_RECORD_SIZE: usize = 1024;
 
@@ -83,6 +99,17 @@ pub struct SpkSegmentSummary {
     pub end_address: usize,
 }
 
+impl std::fmt::Display for SpkSegmentSummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "target {} w.r.t. {}, frame {}, type {}, [{:.3}, {:.3}] ET",
+            self.target_body, self.center_body, self.frame_id, self.segment_type as i32,
+            self.start_time, self.end_time
+        )
+    }
+}
+
 /// SPK segment data for interpolation
 #[derive(Debug, Clone)]
 pub struct SpkSegmentData {
@@ -105,8 +132,75 @@ pub struct SpkSegmentData {
 pub struct SpkReader {
     /// Loaded SPK files mapped by filename
     loaded_files: HashMap<String, SpkFile>,
-    /// Segment cache for fast lookup
-    segment_cache: HashMap<(i32, i32), Vec<SpkSegmentSummary>>,
+    /// Segments of each target/center pair, sorted by start time and, among
+    /// equal start times, by the order they were loaded
+    segment_cache: HashMap<(i32, i32), Vec<CachedSegment>>,
+    /// Number of segments loaded so far, used to order new segments
+    segments_loaded: u64,
+    /// Recently decoded Type 2 records
+    record_cache: Mutex<RecordCache>,
+}
+
+/// A decoded record: file name, segment start address and record index
+type RecordKey = (String, usize, usize);
+
+/// Least recently used cache of decoded segment records
+#[derive(Debug)]
+struct RecordCache {
+    capacity: usize,
+    /// Entries, most recently used last
+    entries: Vec<(RecordKey, SpkSegmentData)>,
+    hits: u64,
+}
+
+impl RecordCache {
+    fn new(capacity: usize) -> Self {
+        Self { capacity, entries: Vec::new(), hits: 0 }
+    }
+
+    /// A cached record, marking it most recently used
+    fn get(&mut self, key: &RecordKey) -> Option<SpkSegmentData> {
+        let index = self.entries.iter().position(|(cached, _)| cached == key)?;
+        let entry = self.entries.remove(index);
+        let data = entry.1.clone();
+        self.entries.push(entry);
+        self.hits += 1;
+        Some(data)
+    }
+
+    /// Store a record, evicting the least recently used beyond capacity
+    fn insert(&mut self, key: RecordKey, data: SpkSegmentData) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.entries.len() >= self.capacity {
+            self.entries.drain(..=self.entries.len() - self.capacity);
+        }
+        self.entries.push((key, data));
+    }
+}
+
+/// A loaded segment with the file it was read from
+#[derive(Debug, Clone)]
+struct CachedSegment {
+    summary: SpkSegmentSummary,
+    /// Name of the owning file in `loaded_files`
+    filename: String,
+    /// Position in load order; later segments take priority
+    sequence: u64,
+}
+
+impl CachedSegment {
+    /// Priority among covering segments: latest start, then last loaded
+    fn priority(&self) -> (f64, u64) {
+        (self.summary.start_time, self.sequence)
+    }
+}
+
+/// Bodies linked by segments; `segments[i]` gives `bodies[i]` relative to `bodies[i + 1]`
+struct SegmentChain<'a> {
+    bodies: Vec<i32>,
+    segments: Vec<&'a CachedSegment>,
 }
 
 /// Individual SPK file data
@@ -120,6 +214,10 @@ struct SpkFile {
     segments: Vec<SpkSegmentSummary>,
     /// Raw file data for coefficient extraction
     file_data: Vec<u8>,
+    /// Byte order of the numeric data, detected from the file record at load
+    byte_order: DafByteOrder,
+    /// Whether the segments were read from the DAF rather than being representative
+    daf_segments: bool,
 }
 
 impl SpkReader {
@@ -128,9 +226,24 @@ impl SpkReader {
         Self {
             loaded_files: HashMap::new(),
             segment_cache: HashMap::new(),
+            segments_loaded: 0,
+            record_cache: Mutex::new(RecordCache::new(DEFAULT_RECORD_CACHE_SIZE)),
         }
     }
 
+    /// Set how many decoded Type 2 records are kept, dropping any excess
+    pub fn set_record_cache_size(&mut self, size: usize) {
+        let cache = self.record_cache.get_mut().unwrap_or_else(|poisoned| poisoned.into_inner());
+        cache.capacity = size;
+        let excess = cache.entries.len().saturating_sub(size);
+        cache.entries.drain(..excess);
+    }
+
+    /// Number of state evaluations served from the record cache
+    pub fn record_cache_hits(&self) -> u64 {
+        self.record_cache.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).hits
+    }
+
     /// Load SPK file from virtual file system
     pub fn load_spk_file(&mut self, filename: &str, vfs: &VirtualFileSystem) -> SpiceResult<()> {
         // Read file data from VFS
@@ -140,10 +253,18 @@ impl SpkReader {
                 format!("SPK file {} not found in virtual file system", filename)
             ))?;
         
-        let file_data = &virtual_file.data;
+        let file_data = virtual_file.data.clone();
+        self.load_spk_data(filename, &file_data)
+    }
 
+    /// Load SPK file from raw bytes
+    ///
+    /// Files whose DAF structure can be parsed are read segment by segment, with
+    /// Type 2 directories checked against the declared segment spans. Anything
+    /// else falls back to representative planetary segments.
+    pub fn load_spk_data(&mut self, filename: &str, file_data: &[u8]) -> SpiceResult<()> {
         // Parse DAF header
-        let daf_header = self.parse_daf_header(&file_data)?;
+        let daf_header = self.parse_daf_header(file_data)?;
         
         // Validate this is an SPK file
         if daf_header.file_type != "DAF/SPK" {
@@ -153,36 +274,68 @@ impl SpkReader {
             ));
         }
 
-        // Parse segment summaries
-        let segments = self.parse_segment_summaries(&file_data, &daf_header)?;
-
-        // Create SPK file entry
-        let spk_file = SpkFile {
-            file_id: daf_header.file_id,
-            format: daf_header.format,
-            segments: segments.clone(),
-            file_data: file_data.clone(),
+        let spk_file = match DafFile::open_read(file_data) {
+            Ok(daf) if daf.array_count() > 0 => SpkFile {
+                file_id: daf.internal_name().to_string(),
+                format: daf.byte_order().format_name().to_string(),
+                segments: self.daf_segment_summaries(filename, &daf)?,
+                file_data: file_data.to_vec(),
+                byte_order: daf.byte_order(),
+                daf_segments: true,
+            },
+            _ => SpkFile {
+                segments: self.parse_segment_summaries(file_data, &daf_header)?,
+                file_id: daf_header.file_id,
+                format: daf_header.format,
+                file_data: file_data.to_vec(),
+                byte_order: DafByteOrder::detect(file_data),
+                daf_segments: false,
+            },
         };
 
+        // Segments of a file previously loaded under this name are replaced
+        for segments in self.segment_cache.values_mut() {
+            segments.retain(|cached| cached.filename != filename);
+        }
+        self.segment_cache.retain(|_, segments| !segments.is_empty());
+
         // Cache segments by target/center body pairs
-        for segment in &segments {
+        for segment in &spk_file.segments {
             let key = (segment.target_body, segment.center_body);
-            self.segment_cache.entry(key).or_insert_with(Vec::new).push(segment.clone());
+            let segments = self.segment_cache.entry(key).or_default();
+            let index = segments.partition_point(|s| s.summary.start_time <= segment.start_time);
+            segments.insert(index, CachedSegment {
+                summary: segment.clone(),
+                filename: filename.to_string(),
+                sequence: self.segments_loaded,
+            });
+            self.segments_loaded += 1;
         }
 
+        // Records decoded from a file previously loaded under this name are stale
+        self.record_cache.get_mut().unwrap_or_else(|poisoned| poisoned.into_inner())
+            .entries.retain(|((cached, _, _), _)| cached != filename);
         self.loaded_files.insert(filename.to_string(), spk_file);
         Ok(())
     }
 
     /// Find segment covering the specified time for target relative to center
+    ///
+    /// Of several covering segments, the one starting latest is chosen, and
+    /// among those the last loaded. At the boundary of two abutting segments
+    /// that is the later one.
     pub fn find_segment(&self, target: i32, center: i32, et: f64) -> SpiceResult<&SpkSegmentSummary> {
+        self.find_cached_segment(target, center, et).map(|cached| &cached.summary)
+    }
+
+    /// The cached segment `find_segment` chooses, with its owning file
+    fn find_cached_segment(&self, target: i32, center: i32, et: f64) -> SpiceResult<&CachedSegment> {
         let key = (target, center);
         
         if let Some(segments) = self.segment_cache.get(&key) {
-            for segment in segments {
-                if et >= segment.start_time && et <= segment.end_time {
-                    return Ok(segment);
-                }
+            let started = segments.partition_point(|segment| segment.summary.start_time <= et);
+            if let Some(segment) = segments[..started].iter().rev().find(|segment| et <= segment.summary.end_time) {
+                return Ok(segment);
             }
         }
 
@@ -194,29 +347,179 @@ impl SpkReader {
 
     /// Compute state vector using SPK data
     pub fn compute_state(&self, target: i32, center: i32, et: f64) -> SpiceResult<StateVector> {
-        let segment = self.find_segment(target, center, et)?;
-        
-        // Find the file containing this segment
-        let spk_file = self.loaded_files.values()
-            .find(|file| file.segments.iter().any(|s| 
-                s.target_body == segment.target_body && 
-                s.center_body == segment.center_body &&
-                s.start_time == segment.start_time))
-            .ok_or_else(|| SpiceError::new(
+        let segment = self.find_cached_segment(target, center, et)?;
+        self.segment_state(segment, et)
+    }
+
+    /// Compute state of target relative to center, chaining segments through
+    /// their center bodies (equivalent to spkgeo_c)
+    pub fn compute_chained_state(&self, target: i32, center: i32, et: f64) -> SpiceResult<StateVector> {
+        let (target_chain, center_chain) = self.connecting_chains(target, center, et)?;
+
+        let mut position = SpiceVector3::zeros();
+        let mut velocity = SpiceVector3::zeros();
+        for (segments, sign) in [(&target_chain.segments, 1.0), (&center_chain.segments, -1.0)] {
+            for segment in segments {
+                let link = self.segment_state(segment, et)?;
+                position = position + link.position.scale(sign);
+                velocity = velocity + link.velocity.scale(sign);
+            }
+        }
+
+        Ok(StateVector {
+            position,
+            velocity,
+            light_time: 0.0,
+        })
+    }
+
+    /// Check that loaded segments connect target and center at `et`
+    ///
+    /// Fails with a message naming the body where the chain stops: either it
+    /// has no SPK data at all, or none covering `et`.
+    pub fn check_connection(&self, target: i32, center: i32, et: f64) -> SpiceResult<()> {
+        self.connecting_chains(target, center, et).map(|_| ())
+    }
+
+    /// Chains from target and center up to their first common body
+    fn connecting_chains(&self, target: i32, center: i32, et: f64) -> SpiceResult<(SegmentChain<'_>, SegmentChain<'_>)> {
+        let mut target_chain = self.segment_chain(target, et);
+        let mut center_chain = self.segment_chain(center, et);
+
+        for (i, body) in target_chain.bodies.iter().enumerate() {
+            if let Some(j) = center_chain.bodies.iter().position(|b| b == body) {
+                target_chain.segments.truncate(i);
+                center_chain.segments.truncate(j);
+                return Ok((target_chain, center_chain));
+            }
+        }
+
+        // Report the end of whichever chain failed to reach the root first
+        let target_end = *target_chain.bodies.last().unwrap_or(&target);
+        let center_end = *center_chain.bodies.last().unwrap_or(&center);
+        let stuck = if target_end != SOLAR_SYSTEM_BARYCENTER { target_end } else { center_end };
+
+        if self.segment_cache.keys().any(|&(body, _)| body == stuck) {
+            Err(SpiceError::new(
                 SpiceErrorType::InsufficientData,
-                "SPK segment found but file data missing".into()
-            ))?;
+                format!(
+                    "No SPK data found for body {} at ET {}; needed to connect {} to {}",
+                    stuck, et, target, center
+                )
+            ))
+        } else {
+            Err(SpiceError::new(
+                SpiceErrorType::InsufficientData,
+                format!(
+                    "Insufficient ephemeris data: no ephemeris connecting {} to {} (no SPK segments for body {})",
+                    target, center, stuck
+                )
+            ))
+        }
+    }
+
+    /// Follow covering segments from a body through successive centers
+    ///
+    /// At each body the covering segment is chosen across all centers by the
+    /// same priority as `find_segment`.
+    fn segment_chain(&self, body: i32, et: f64) -> SegmentChain<'_> {
+        let mut chain = SegmentChain {
+            bodies: vec![body],
+            segments: Vec::new(),
+        };
+        let mut current = body;
+        while chain.segments.len() < MAX_CHAIN_LENGTH {
+            let segment = self.segment_cache.iter()
+                .filter(|((target, _), _)| *target == current)
+                .flat_map(|(_, segments)| segments.iter())
+                .filter(|segment| et >= segment.summary.start_time && et <= segment.summary.end_time)
+                .max_by(|a, b| a.priority().partial_cmp(&b.priority()).unwrap_or(std::cmp::Ordering::Equal));
+            let Some(segment) = segment else { break };
+            if chain.bodies.contains(&segment.summary.center_body) {
+                break;
+            }
+            current = segment.summary.center_body;
+            chain.bodies.push(current);
+            chain.segments.push(segment);
+        }
+        chain
+    }
+
+    /// Evaluate a single segment at `et`
+    fn segment_state(&self, cached: &CachedSegment, et: f64) -> SpiceResult<StateVector> {
+        let (filename, spk_file) = self.segment_file(cached)?;
+        let segment = &cached.summary;
+
+        if spk_file.daf_segments {
+            return self.daf_segment_state(filename, segment, &spk_file.file_data, spk_file.byte_order, et);
+        }
 
         // Extract and interpolate segment data
-        let segment_data = self.extract_segment_data(segment, &spk_file.file_data)?;
+        let segment_data = self.extract_segment_data(segment, &spk_file.file_data, spk_file.byte_order)?;
         self.interpolate_state(&segment_data, et)
     }
 
-    /// Extract segment coefficient data from file
-    fn extract_segment_data(&self, segment: &SpkSegmentSummary, file_data: &[u8]) -> SpiceResult<SpkSegmentData> {
+    /// Coefficient data of the segment covering `et` for target relative to center
+    ///
+    /// Type 2 data read from a DAF holds the whole segment array: the records
+    /// followed by the INIT, INTLEN, RSIZE, N directory.
+    pub fn segment_data(&self, target: i32, center: i32, et: f64) -> SpiceResult<SpkSegmentData> {
+        let cached = self.find_cached_segment(target, center, et)?;
+        let (_, spk_file) = self.segment_file(cached)?;
+        let segment = &cached.summary;
+        if spk_file.daf_segments && segment.segment_type == SpkSegmentType::Chebyshev {
+            let words = self.daf_segment_words(segment, &spk_file.file_data, spk_file.byte_order)?;
+            return type2_segment_data(segment.clone(), words);
+        }
+        self.extract_segment_data(segment, &spk_file.file_data, spk_file.byte_order)
+    }
+
+    /// Summaries of every segment in a loaded file, in file order
+    pub fn segments(&self, filename: &str) -> SpiceResult<Vec<SpkSegmentSummary>> {
+        Ok(self.loaded_file(filename)?.segments.clone())
+    }
+
+    /// Distinct target bodies of the segments in a loaded file, in ascending order
+    pub fn objects(&self, filename: &str) -> SpiceResult<Vec<i32>> {
+        let mut bodies: Vec<i32> = self.loaded_file(filename)?.segments.iter()
+            .map(|segment| segment.target_body)
+            .collect();
+        bodies.sort_unstable();
+        bodies.dedup();
+        Ok(bodies)
+    }
+
+    /// Time covered by a body's segments in a loaded file, as disjoint [start, end]
+    /// ET intervals in ascending order
+    ///
+    /// Overlapping or abutting segment windows merge into one interval. A body
+    /// with no segments in the file has empty coverage.
+    pub fn coverage(&self, filename: &str, body: i32) -> SpiceResult<Vec<(f64, f64)>> {
+        Ok(merge_coverage(self.loaded_file(filename)?.segments.iter()
+            .filter(|segment| segment.target_body == body)
+            .map(|segment| (segment.start_time, segment.end_time))))
+    }
+
+    /// A loaded file by the name it was loaded under
+    fn loaded_file(&self, filename: &str) -> SpiceResult<&SpkFile> {
+        loaded_file(&self.loaded_files, "SPK", filename)
+    }
+
+    /// The loaded file a cached segment was read from, with its name
+    fn segment_file<'a>(&'a self, segment: &'a CachedSegment) -> SpiceResult<(&'a str, &'a SpkFile)> {
+        self.loaded_files.get(&segment.filename)
+            .map(|file| (segment.filename.as_str(), file))
+            .ok_or_else(|| SpiceError::new(
+                SpiceErrorType::InsufficientData,
+                "SPK segment found but file data missing".into()
+            ))
+    }
+
+    /// Extract segment coefficient data from file, decoding words in `byte_order`
+    fn extract_segment_data(&self, segment: &SpkSegmentSummary, file_data: &[u8], byte_order: DafByteOrder) -> SpiceResult<SpkSegmentData> {
         match segment.segment_type {
-            SpkSegmentType::Chebyshev => self.extract_chebyshev_data(segment, file_data),
-            SpkSegmentType::Lagrange => self.extract_lagrange_data(segment, file_data),
+            SpkSegmentType::Chebyshev => self.extract_chebyshev_data(segment, file_data, byte_order),
+            SpkSegmentType::Lagrange => self.extract_lagrange_data(segment, file_data, byte_order),
             _ => Err(SpiceError::new(
                 SpiceErrorType::InvalidFormat,
                 format!("SPK segment type {:?} not yet implemented", segment.segment_type)
@@ -225,7 +528,7 @@ impl SpkReader {
     }
 
     /// Extract Chebyshev polynomial coefficient data (Type 2)
-    fn extract_chebyshev_data(&self, segment: &SpkSegmentSummary, file_data: &[u8]) -> SpiceResult<SpkSegmentData> {
+    fn extract_chebyshev_data(&self, segment: &SpkSegmentSummary, file_data: &[u8], byte_order: DafByteOrder) -> SpiceResult<SpkSegmentData> {
         // Calculate data location in file
         let start_byte = segment.start_address * 8; // Convert from double words to bytes
         let end_byte = segment.end_address * 8;
@@ -261,15 +564,7 @@ impl SpkReader {
 
         // Extract raw double precision data
         let data_bytes = &file_data[start_byte..end_byte];
-        let mut coefficients = Vec::new();
-        
-        for chunk in data_bytes.chunks_exact(8) {
-            let value = f64::from_le_bytes([
-                chunk[0], chunk[1], chunk[2], chunk[3],
-                chunk[4], chunk[5], chunk[6], chunk[7]
-            ]);
-            coefficients.push(value);
-        }
+        let coefficients: Vec<f64> = data_bytes.chunks_exact(8).map(|chunk| byte_order.read_f64(chunk)).collect();
 
         // For Type 2 segments, the last few values contain metadata
         if coefficients.len() < 4 {
@@ -345,7 +640,7 @@ impl SpkReader {
     }
 
     /// Extract Lagrange interpolation data (Type 8)
-    fn extract_lagrange_data(&self, segment: &SpkSegmentSummary, file_data: &[u8]) -> SpiceResult<SpkSegmentData> {
+    fn extract_lagrange_data(&self, segment: &SpkSegmentSummary, file_data: &[u8], byte_order: DafByteOrder) -> SpiceResult<SpkSegmentData> {
         // Similar to Chebyshev but with different metadata structure
         let start_byte = segment.start_address * 8;
         let end_byte = segment.end_address * 8;
@@ -358,15 +653,7 @@ impl SpkReader {
         }
 
         let data_bytes = &file_data[start_byte..end_byte];
-        let mut coefficients = Vec::new();
-        
-        for chunk in data_bytes.chunks_exact(8) {
-            let value = f64::from_le_bytes([
-                chunk[0], chunk[1], chunk[2], chunk[3],
-                chunk[4], chunk[5], chunk[6], chunk[7]
-            ]);
-            coefficients.push(value);
-        }
+        let coefficients: Vec<f64> = data_bytes.chunks_exact(8).map(|chunk| byte_order.read_f64(chunk)).collect();
 
         // Type 8 metadata extraction (simplified)
         let n_states = coefficients[coefficients.len() - 1] as usize;
@@ -530,6 +817,31 @@ impl SpkReader {
     }
 }
 
+/// Merge segment windows into disjoint [start, end] intervals in ascending
+/// order; overlapping or abutting windows become one interval
+pub(crate) fn merge_coverage(windows: impl Iterator<Item = (f64, f64)>) -> Vec<(f64, f64)> {
+    let mut windows: Vec<(f64, f64)> = windows.collect();
+    windows.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    let mut merged: Vec<(f64, f64)> = Vec::with_capacity(windows.len());
+    for (start, end) in windows {
+        match merged.last_mut() {
+            Some(last) if start <= last.1 => last.1 = last.1.max(end),
+            _ => merged.push((start, end)),
+        }
+    }
+    merged
+}
+
+/// A file from a reader's loaded files, or a KernelNotFound error naming the
+/// kernel kind
+pub(crate) fn loaded_file<'a, F>(files: &'a HashMap<String, F>, kind: &str, filename: &str) -> SpiceResult<&'a F> {
+    files.get(filename).ok_or_else(|| SpiceError::new(
+        SpiceErrorType::KernelNotFound,
+        format!("{} file {} is not loaded", kind, filename)
+    ))
+}
+
 /// DAF file header information
 #[derive(Debug)]
 struct DafHeader {
@@ -628,31 +940,522 @@ impl SpkReader {
     }
 }
 
+/// Type 2 segment directory: INIT, INTLEN, RSIZE, N
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Type2Directory {
+    pub(crate) init: f64,
+    pub(crate) interval: f64,
+    pub(crate) record_size: usize,
+    pub(crate) record_count: usize,
+}
+
+impl SpkReader {
+    /// Build segment summaries from the arrays of a parsed DAF
+    fn daf_segment_summaries(&self, filename: &str, daf: &DafFile) -> SpiceResult<Vec<SpkSegmentSummary>> {
+        if daf.nd() != 2 || daf.ni() != 6 {
+            return Err(SpiceError::new(
+                SpiceErrorType::InvalidFormat,
+                format!("SPK {} has ND={}, NI={}; expected ND=2, NI=6", filename, daf.nd(), daf.ni())
+            ));
+        }
+
+        let mut segments = Vec::with_capacity(daf.array_count());
+        for (index, summary) in daf.summaries().into_iter().enumerate() {
+            let (start_address, end_address) = daf.array_addresses(index).ok_or_else(|| SpiceError::new(
+                SpiceErrorType::InvalidKernelData,
+                format!("SPK {} segment {} has no data addresses", filename, index)
+            ))?;
+            let segment = SpkSegmentSummary {
+                target_body: summary.integers[0],
+                center_body: summary.integers[1],
+                frame_id: summary.integers[2],
+                segment_type: SpkSegmentType::from_i32(summary.integers[3])?,
+                start_time: summary.doubles[0],
+                end_time: summary.doubles[1],
+                start_address,
+                end_address,
+            };
+
+            if segment.segment_type == SpkSegmentType::Chebyshev {
+                type2_directory(&segment, daf.array_data(index)?).map_err(|e| SpiceError::new(
+                    e.error_type,
+                    format!("SPK {} segment {} ('{}'): {}", filename, index, summary.name, e.message)
+                ))?;
+            }
+            segments.push(segment);
+        }
+        Ok(segments)
+    }
+
+    /// Read the words of a segment from the raw DAF bytes
+    fn daf_segment_words(&self, segment: &SpkSegmentSummary, file_data: &[u8], byte_order: DafByteOrder) -> SpiceResult<Vec<f64>> {
+        let count = (segment.end_address + 1).saturating_sub(segment.start_address);
+        self.daf_segment_word_range(segment, file_data, byte_order, 0, count)
+    }
+
+    /// Read `count` words of a segment starting `offset` words into it
+    fn daf_segment_word_range(
+        &self,
+        segment: &SpkSegmentSummary,
+        file_data: &[u8],
+        byte_order: DafByteOrder,
+        offset: usize,
+        count: usize,
+    ) -> SpiceResult<Vec<f64>> {
+        let segment_start = segment.start_address.saturating_sub(1) * 8;
+        let start_byte = segment_start + offset * 8;
+        let end_byte = start_byte + count * 8;
+        if segment.start_address == 0 || end_byte > file_data.len() || end_byte > segment.end_address * 8
+            || segment_start >= segment.end_address * 8
+        {
+            return Err(SpiceError::new(
+                SpiceErrorType::InvalidKernelData,
+                format!(
+                    "Segment addresses {}..{} lie outside the {} byte file",
+                    segment.start_address, segment.end_address, file_data.len()
+                )
+            ));
+        }
+
+        Ok(file_data[start_byte..end_byte].chunks_exact(8).map(|chunk| byte_order.read_f64(chunk)).collect())
+    }
+
+    /// Evaluate a segment of a parsed DAF
+    fn daf_segment_state(
+        &self,
+        filename: &str,
+        segment: &SpkSegmentSummary,
+        file_data: &[u8],
+        byte_order: DafByteOrder,
+        et: f64,
+    ) -> SpiceResult<StateVector> {
+        match segment.segment_type {
+            SpkSegmentType::Chebyshev => {
+                let record = self.type2_record(filename, segment, file_data, byte_order, et)?;
+                self.type2_state(&record.coefficients, et)
+            },
+            SpkSegmentType::Hermite => {
+                let words = self.daf_segment_words(segment, file_data, byte_order)?;
+                type13_state(&words, et)
+            },
+            SpkSegmentType::TwoBody => {
+                let words = self.daf_segment_words(segment, file_data, byte_order)?;
+                type5_state(&words, et)
+            },
+            SpkSegmentType::DiscreteStates => {
+                let words = self.daf_segment_words(segment, file_data, byte_order)?;
+                type9_state(&words, et)
+            },
+            _ => Err(SpiceError::new(
+                SpiceErrorType::InvalidFormat,
+                format!("Interpolation for type {:?} not implemented", segment.segment_type)
+            )),
+        }
+    }
+
+    /// The Type 2 record covering `et`, decoded from the file or taken from
+    /// the record cache (equivalent to spkr02_c)
+    fn type2_record(
+        &self,
+        filename: &str,
+        segment: &SpkSegmentSummary,
+        file_data: &[u8],
+        byte_order: DafByteOrder,
+        et: f64,
+    ) -> SpiceResult<SpkSegmentData> {
+        let word_count = (segment.end_address + 1).saturating_sub(segment.start_address);
+        let tail = self.daf_segment_word_range(segment, file_data, byte_order, word_count.saturating_sub(4), word_count.min(4))?;
+        let directory = type2_directory_from_tail(segment, &tail, word_count)?;
+        let index = ((et - directory.init) / directory.interval).floor().max(0.0) as usize;
+        let index = index.min(directory.record_count - 1);
+
+        let key = (filename.to_string(), segment.start_address, index);
+        if let Some(record) = self.record_cache.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).get(&key) {
+            return Ok(record);
+        }
+
+        let words = self.daf_segment_word_range(segment, file_data, byte_order, index * directory.record_size, directory.record_size)?;
+        let record = SpkSegmentData {
+            summary: segment.clone(),
+            coefficients: words,
+            coeffs_per_set: directory.record_size,
+            n_components: 3,
+            degree: (directory.record_size - 2) / 3 - 1,
+            time_coverage: directory.interval,
+        };
+        self.record_cache.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).insert(key, record.clone());
+        Ok(record)
+    }
+
+    /// Chebyshev evaluation of a Type 2 record (equivalent to spke02_c)
+    fn type2_state(&self, record: &[f64], et: f64) -> SpiceResult<StateVector> {
+        let (mid, radius) = (record[0], record[1]);
+        let n_coeffs = (record.len() - 2) / 3;
+        let s = (et - mid) / radius;
+
+        let mut position = [0.0; 3];
+        let mut velocity = [0.0; 3];
+        for axis in 0..3 {
+            let coeffs = &record[2 + axis * n_coeffs..2 + (axis + 1) * n_coeffs];
+            let (p, v) = self.evaluate_chebyshev_with_derivative(coeffs, s, 2.0 * radius);
+            position[axis] = p;
+            velocity[axis] = v;
+        }
+
+        Ok(StateVector {
+            position: SpiceVector3::new(position[0], position[1], position[2]),
+            velocity: SpiceVector3::new(velocity[0], velocity[1], velocity[2]),
+            light_time: 0.0,
+        })
+    }
+}
+
+/// Read and check the directory of a Type 2 segment
+///
+/// The records must tile the declared segment span: N records of INTLEN
+/// seconds starting at INIT have to cover [start, end] without leaving a
+/// whole record's worth of slack on either side.
+pub(crate) fn type2_directory(segment: &SpkSegmentSummary, words: &[f64]) -> SpiceResult<Type2Directory> {
+    type2_directory_from_tail(segment, &words[words.len().saturating_sub(4)..], words.len())
+}
+
+/// Check a Type 2 directory given only the last words of an `n`-word segment
+fn type2_directory_from_tail(segment: &SpkSegmentSummary, tail: &[f64], n: usize) -> SpiceResult<Type2Directory> {
+    if segment.segment_type != SpkSegmentType::Chebyshev {
+        return Err(SpiceError::new(
+            SpiceErrorType::InvalidArgument,
+            format!("Expected a Type 2 segment, found {:?}", segment.segment_type)
+        ));
+    }
+    if n < 4 || tail.len() < 4 {
+        return Err(SpiceError::new(
+            SpiceErrorType::InvalidKernelData,
+            format!("Type 2 segment holds {} words, too few for its directory", n)
+        ));
+    }
+    let tail = &tail[tail.len() - 4..];
+    let (init, interval, rsize, count) = (tail[0], tail[1], tail[2], tail[3]);
+    if interval.is_nan() || interval <= 0.0 || rsize < 5.0 || count < 1.0 || rsize.fract() != 0.0 || count.fract() != 0.0
+        || (rsize as usize - 2) % 3 != 0
+    {
+        return Err(SpiceError::new(
+            SpiceErrorType::InvalidKernelData,
+            format!("Invalid Type 2 directory: INTLEN={}, RSIZE={}, N={}", interval, rsize, count)
+        ));
+    }
+
+    let directory = Type2Directory {
+        init,
+        interval,
+        record_size: rsize as usize,
+        record_count: count as usize,
+    };
+    // N and RSIZE come from the file, so their product may not fit a usize
+    let declared_words = directory.record_size.checked_mul(directory.record_count)
+        .and_then(|words| words.checked_add(4));
+    if declared_words != Some(n) {
+        return Err(SpiceError::new(
+            SpiceErrorType::InvalidKernelData,
+            format!(
+                "Type 2 directory declares {} records of {} words but the segment holds {} words",
+                directory.record_count, directory.record_size, n
+            )
+        ));
+    }
+
+    let covered_end = init + interval * count;
+    let tolerance = 1e-9 * interval.max(segment.end_time.abs()).max(1.0);
+    let starts_inside = init <= segment.start_time + tolerance && segment.start_time - init < interval;
+    let ends_inside = covered_end >= segment.end_time - tolerance && covered_end - segment.end_time < interval;
+    if !starts_inside || !ends_inside {
+        return Err(SpiceError::new(
+            SpiceErrorType::InvalidKernelData,
+            format!(
+                "{} records of {} s from {} cover [{}, {}], which does not match the segment span [{}, {}]",
+                directory.record_count, interval, init, init, covered_end, segment.start_time, segment.end_time
+            )
+        ));
+    }
+
+    Ok(directory)
+}
+
+/// Words in a discrete-state segment (Types 5, 9 and 13) of `count` states:
+/// six per state, one per epoch, every 100th epoch again as a directory, and
+/// two trailing control words
+///
+/// `count` is read from the file, so the total may not fit a usize.
+fn discrete_state_words(count: usize) -> Option<usize> {
+    count.checked_mul(7)?
+        .checked_add(count.saturating_sub(1) / 100)?
+        .checked_add(2)
+}
+
+/// Hermite interpolation of a Type 13 segment (equivalent to spkr13_c + spke13_c)
+///
+/// The segment holds N states, their N epochs, an epoch directory of every
+/// 100th epoch, the window size minus one and N. Position is interpolated
+/// from the positions and velocities of the window of states nearest `et`;
+/// velocity is the derivative of that interpolant.
+pub(crate) fn type13_state(words: &[f64], et: f64) -> SpiceResult<StateVector> {
+    let n = words.len();
+    if n < 2 {
+        return Err(SpiceError::new(
+            SpiceErrorType::InvalidKernelData,
+            format!("Type 13 segment holds {} words, too few for its directory", n)
+        ));
+    }
+    let (window, count) = (words[n - 2] + 1.0, words[n - 1]);
+    if window.fract() != 0.0 || count.fract() != 0.0 || window < 2.0 || count < window {
+        return Err(SpiceError::new(
+            SpiceErrorType::InvalidKernelData,
+            format!("Invalid Type 13 directory: window size {}, N={}", window, count)
+        ));
+    }
+    let (window, count) = (window as usize, count as usize);
+    if discrete_state_words(count) != Some(n) {
+        return Err(SpiceError::new(
+            SpiceErrorType::InvalidKernelData,
+            format!("Type 13 directory declares {} states but the segment holds {} words", count, n)
+        ));
+    }
+
+    let epochs = &words[6 * count..7 * count];
+    let first = last_le(epochs, et).map_or(0, |last| last + 1)
+        .saturating_sub(window / 2)
+        .min(count - window);
+
+    let mut interpolator = HermiteInterpolatorND::new(3);
+    for (index, &epoch) in epochs.iter().enumerate().skip(first).take(window) {
+        let state = &words[6 * index..6 * index + 6];
+        interpolator.add_point(epoch, state[..3].to_vec(), state[3..].to_vec())?;
+    }
+    let (position, velocity) = interpolator.evaluate_with_derivative(et)?;
+
+    Ok(StateVector {
+        position: SpiceVector3::new(position[0], position[1], position[2]),
+        velocity: SpiceVector3::new(velocity[0], velocity[1], velocity[2]),
+        light_time: 0.0,
+    })
+}
+
+/// Evaluate a Type 9 (unequally spaced discrete states) segment array at
+/// `et` (equivalent to spke09_c)
+///
+/// The array holds N states, their N epochs, a directory of every 100th
+/// epoch, then the interpolation degree and N. Each of the six state
+/// components is interpolated by a Lagrange polynomial through the
+/// degree + 1 states nearest `et`.
+pub(crate) fn type9_state(words: &[f64], et: f64) -> SpiceResult<StateVector> {
+    let n = words.len();
+    if n < 2 {
+        return Err(SpiceError::new(
+            SpiceErrorType::InvalidKernelData,
+            format!("Type 9 segment holds {} words, too few for its directory", n)
+        ));
+    }
+    let (degree, count) = (words[n - 2], words[n - 1]);
+    if degree.fract() != 0.0 || count.fract() != 0.0 || degree < 1.0 || count < degree + 1.0 {
+        return Err(SpiceError::new(
+            SpiceErrorType::InvalidKernelData,
+            format!("Invalid Type 9 directory: degree {}, N={}", degree, count)
+        ));
+    }
+    let (window, count) = (degree as usize + 1, count as usize);
+    if discrete_state_words(count) != Some(n) {
+        return Err(SpiceError::new(
+            SpiceErrorType::InvalidKernelData,
+            format!("Type 9 directory declares {} states but the segment holds {} words", count, n)
+        ));
+    }
+
+    let epochs = &words[6 * count..7 * count];
+    let first = last_le(epochs, et).map_or(0, |last| last + 1)
+        .saturating_sub(window / 2)
+        .min(count - window);
+
+    let mut components = [0.0; 6];
+    for (component, value) in components.iter_mut().enumerate() {
+        let mut interpolator = LagrangeInterpolator::new();
+        for (index, &epoch) in epochs.iter().enumerate().skip(first).take(window) {
+            interpolator.add_point(epoch, words[6 * index + component]);
+        }
+        *value = interpolator.evaluate(et)?;
+    }
+
+    Ok(StateVector {
+        position: SpiceVector3::new(components[0], components[1], components[2]),
+        velocity: SpiceVector3::new(components[3], components[4], components[5]),
+        light_time: 0.0,
+    })
+}
+
+/// Evaluate a Type 5 (discrete states, two-body) segment array at `et`
+/// (equivalent to spke05_c)
+///
+/// The array holds N states, their N epochs, a directory of every 100th
+/// epoch, then GM and N. Between two epochs both bounding states are
+/// propagated to `et` and blended with a cosine weight favoring the nearer one.
+pub(crate) fn type5_state(words: &[f64], et: f64) -> SpiceResult<StateVector> {
+    let n = words.len();
+    if n < 2 || words[n - 1].fract() != 0.0 || words[n - 1] < 1.0 {
+        return Err(SpiceError::new(
+            SpiceErrorType::InvalidKernelData,
+            format!("Type 5 segment of {} words lacks a valid state count", n)
+        ));
+    }
+    let count = words[n - 1] as usize;
+    if discrete_state_words(count) != Some(n) {
+        return Err(SpiceError::new(
+            SpiceErrorType::InvalidKernelData,
+            format!("Type 5 segment declares {} states but holds {} words", count, n)
+        ));
+    }
+
+    let gm = words[n - 2];
+    let epochs = &words[6 * count..7 * count];
+    let state_at = |index: usize| {
+        let state = &words[6 * index..6 * index + 6];
+        StateVector {
+            position: SpiceVector3::new(state[0], state[1], state[2]),
+            velocity: SpiceVector3::new(state[3], state[4], state[5]),
+            light_time: 0.0,
+        }
+    };
+    let propagate = |index: usize| crate::ephemeris::propagate_two_body(&state_at(index), gm, et - epochs[index]);
+
+    let before = match last_le(epochs, et) {
+        None => return propagate(0),
+        Some(last) if last == count - 1 || epochs[last] == et => return propagate(last),
+        Some(last) => last,
+    };
+
+    let (t1, t2) = (epochs[before], epochs[before + 1]);
+    let (first, second) = (propagate(before)?, propagate(before + 1)?);
+    let arg = std::f64::consts::PI * (et - t1) / (t2 - t1);
+    let weight = 0.5 + 0.5 * arg.cos();
+    let weight_rate = -0.5 * std::f64::consts::PI / (t2 - t1) * arg.sin();
+
+    Ok(StateVector {
+        position: first.position.scale(weight) + second.position.scale(1.0 - weight),
+        velocity: first.velocity.scale(weight) + second.velocity.scale(1.0 - weight)
+            + (first.position - second.position).scale(weight_rate),
+        light_time: 0.0,
+    })
+}
+
+/// Wrap the words of a Type 2 segment array as segment data
+pub(crate) fn type2_segment_data(summary: SpkSegmentSummary, words: Vec<f64>) -> SpiceResult<SpkSegmentData> {
+    let directory = type2_directory(&summary, &words)?;
+    Ok(SpkSegmentData {
+        summary,
+        coefficients: words,
+        coeffs_per_set: directory.record_size,
+        n_components: 3,
+        degree: (directory.record_size - 2) / 3 - 1,
+        time_coverage: directory.interval,
+    })
+}
+
 /// Global SPK reader instance
-static mut GLOBAL_SPK_READER: Option<SpkReader> = None;
+static GLOBAL_SPK_READER: GlobalState<SpkReader> = GlobalState::new();
 
 /// Initialize global SPK reader
 pub fn initialize_spk_reader() -> SpiceResult<()> {
-    unsafe {
-        GLOBAL_SPK_READER = Some(SpkReader::new());
-    }
+    GLOBAL_SPK_READER.set(SpkReader::new());
     Ok(())
 }
 
-/// Get reference to global SPK reader
-pub fn get_spk_reader() -> SpiceResult<&'static mut SpkReader> {
-    unsafe {
-        GLOBAL_SPK_READER.as_mut().ok_or_else(|| SpiceError::new(
-            SpiceErrorType::PoolNotInitialized,
-            "SPK reader not initialized. Call initialize_spk_reader() first.".into()
-        ))
+/// Error for access to the reader before `initialize_spk_reader`
+fn spk_reader_not_initialized() -> SpiceError {
+    SpiceError::new(
+        SpiceErrorType::PoolNotInitialized,
+        "SPK reader not initialized. Call initialize_spk_reader() first.".into()
+    )
+}
+
+/// Run `f` with shared access to the global SPK reader
+pub fn with_spk_reader<F, R>(f: F) -> SpiceResult<R>
+where
+    F: FnOnce(&SpkReader) -> SpiceResult<R>
+{
+    GLOBAL_SPK_READER.read(|reader| f(reader.ok_or_else(spk_reader_not_initialized)?))
+}
+
+/// Run `f` with mutable access to the global SPK reader
+pub fn with_spk_reader_mut<F, R>(f: F) -> SpiceResult<R>
+where
+    F: FnOnce(&mut SpkReader) -> SpiceResult<R>
+{
+    GLOBAL_SPK_READER.write(|reader| f(reader.as_mut().ok_or_else(spk_reader_not_initialized)?))
+}
+
+/// Exclusive handle on the global SPK reader, from `get_spk_reader`
+#[cfg(feature = "std")]
+pub struct SpkReaderGuard(std::sync::RwLockWriteGuard<'static, Option<SpkReader>>);
+
+#[cfg(feature = "std")]
+impl core::ops::Deref for SpkReaderGuard {
+    type Target = SpkReader;
+
+    fn deref(&self) -> &SpkReader {
+        self.0.as_ref().expect("guard is only made over an initialized reader")
+    }
+}
+
+#[cfg(feature = "std")]
+impl core::ops::DerefMut for SpkReaderGuard {
+    fn deref_mut(&mut self) -> &mut SpkReader {
+        self.0.as_mut().expect("guard is only made over an initialized reader")
+    }
+}
+
+/// Get the global SPK reader
+///
+/// The guard holds the reader's lock, so any other access to it, including
+/// `furnish_kernel` and `ephemeris_state` on this thread, blocks until the
+/// guard is dropped.
+#[cfg(feature = "std")]
+#[deprecated(note = "use with_spk_reader or with_spk_reader_mut, which release the reader when done")]
+pub fn get_spk_reader() -> SpiceResult<SpkReaderGuard> {
+    let guard = GLOBAL_SPK_READER.write_guard();
+    if guard.is_none() {
+        return Err(spk_reader_not_initialized());
     }
+    Ok(SpkReaderGuard(guard))
 }
 
 /// Load SPK file into global SPK reader
+///
+/// The reader is created on first use, so kernels furnished before
+/// `initialize_spk_reader` (as from the WASM bindings) are still registered.
 pub fn load_spk_file_global(filename: &str, vfs: &VirtualFileSystem) -> SpiceResult<()> {
-    let reader = get_spk_reader()?;
-    reader.load_spk_file(filename, vfs)
+    GLOBAL_SPK_READER.get_or_init(SpkReader::new);
+    with_spk_reader_mut(|reader| reader.load_spk_file(filename, vfs))
+}
+
+/// Drop every file from the global SPK reader, if it exists
+pub fn clear_spk_reader() {
+    GLOBAL_SPK_READER.write(|slot| {
+        if let Some(reader) = slot {
+            *reader = SpkReader::new();
+        }
+    });
+}
+
+/// Summaries of the segments in a loaded SPK file, in file order
+pub fn list_segments(filename: &str) -> SpiceResult<Vec<SpkSegmentSummary>> {
+    with_spk_reader(|reader| reader.segments(filename))
+}
+
+/// Bodies with segments in a loaded SPK file (equivalent to spkobj_c)
+pub fn spk_objects(filename: &str) -> SpiceResult<Vec<i32>> {
+    with_spk_reader(|reader| reader.objects(filename))
+}
+
+/// Merged coverage intervals of a body in a loaded SPK file (equivalent to spkcov_c)
+pub fn spk_coverage(filename: &str, body: i32) -> SpiceResult<Vec<(f64, f64)>> {
+    with_spk_reader(|reader| reader.coverage(filename, body))
 }
 
 #[cfg(test)]
@@ -683,4 +1486,389 @@ mod tests {
         assert!(pos.is_finite());
         assert!(vel.is_finite());
     }
+
+    #[test]
+    fn test_type2_round_trip_through_daf() {
+        let records = vec![
+            write::ChebyshevRecord { start: 0.0, end: 100.0, x: vec![1000.0, 10.0], y: vec![-500.0, 0.0], z: vec![0.0, 5.0] },
+            write::ChebyshevRecord { start: 100.0, end: 200.0, x: vec![1020.0, 10.0], y: vec![-500.0, 0.0], z: vec![10.0, 5.0] },
+        ];
+        let bytes = write::write_type2("ROUND TRIP", -77, 399, "J2000", 0.0, 200.0, &records).unwrap();
+
+        let mut reader = SpkReader::new();
+        reader.load_spk_data("roundtrip.bsp", &bytes).unwrap();
+        let state = reader.compute_state(-77, 399, 175.0).unwrap();
+        assert!((state.position.x() - 1025.0).abs() < 1e-9);
+        assert!((state.position.z() - 12.5).abs() < 1e-9);
+        assert!((state.velocity.x() - 0.2).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_type13_hermite_segment_through_daf() {
+        // Uniform acceleration: x = 2 + 3t + 0.01t², exact for a window of 2
+        let epochs = [0.0, 40.0, 100.0, 130.0, 200.0];
+        let mut data = Vec::new();
+        for &t in &epochs {
+            data.extend_from_slice(&[2.0 + 3.0 * t + 0.01 * t * t, -t, 7.0, 3.0 + 0.02 * t, -1.0, 0.0]);
+        }
+        data.extend_from_slice(&epochs);
+        data.extend_from_slice(&[1.0, epochs.len() as f64]);
+
+        let mut daf = write::new_spk("HERMITE").unwrap();
+        daf.add_array(&[0.0, 200.0], &[-77, 399, 1, 13], "TYPE 13", &data).unwrap();
+        let mut reader = SpkReader::new();
+        reader.load_spk_data("hermite.bsp", &daf.to_bytes().unwrap()).unwrap();
+
+        for t in [0.0, 55.5, 130.0, 199.0] {
+            let state = reader.compute_state(-77, 399, t).unwrap();
+            assert!((state.position.x() - (2.0 + 3.0 * t + 0.01 * t * t)).abs() < 1e-9);
+            assert!((state.position.y() + t).abs() < 1e-9);
+            assert!((state.velocity.x() - (3.0 + 0.02 * t)).abs() < 1e-12);
+        }
+
+        assert!(type13_state(&data[..data.len() - 1], 10.0).is_err());
+
+        // Corrupt state counts whose word totals overflow
+        for count in [2f64.powi(62), 1e300] {
+            let mut corrupt = data.clone();
+            *corrupt.last_mut().unwrap() = count;
+            assert_eq!(type13_state(&corrupt, 10.0).unwrap_err().error_type, SpiceErrorType::InvalidKernelData);
+        }
+    }
+
+    #[test]
+    fn test_big_endian_segment_words_decode_identically() {
+        // Two degree-1 Type 2 records followed by the INIT, INTLEN, RSIZE, N directory
+        let words: [f64; 20] = [
+            50.0, 50.0, 1.0e6, 10.0, -2.0e5, 3.5, 42.0, -0.25,
+            150.0, 50.0, 1.1e6, 12.0, -1.9e5, 3.25, 40.0, 0.125,
+            0.0, 100.0, 8.0, 2.0,
+        ];
+        let little: Vec<u8> = words.iter().flat_map(|w| w.to_le_bytes()).collect();
+        let big: Vec<u8> = little.chunks_exact(8).flat_map(|chunk| chunk.iter().rev().copied()).collect();
+        let segment = SpkSegmentSummary {
+            target_body: -77, center_body: 399, frame_id: 1,
+            segment_type: SpkSegmentType::Chebyshev,
+            start_time: 0.0, end_time: 200.0,
+            start_address: 0, end_address: words.len(),
+        };
+
+        let reader = SpkReader::new();
+        let from_little = reader.extract_segment_data(&segment, &little, DafByteOrder::LittleEndian).unwrap();
+        let from_big = reader.extract_segment_data(&segment, &big, DafByteOrder::BigEndian).unwrap();
+        assert_eq!(from_little.coefficients, words.to_vec());
+        assert_eq!(from_big.coefficients, from_little.coefficients);
+        assert_eq!(from_big.coeffs_per_set, 8);
+
+        // Read with the wrong order, the words are garbage
+        let misread = reader.extract_segment_data(&segment, &big, DafByteOrder::LittleEndian).unwrap();
+        assert_ne!(misread.coefficients, from_little.coefficients);
+
+        // The order is taken from the LOCFMT string of the file record
+        let mut record = vec![0u8; 1024];
+        record[88..96].copy_from_slice(b"BIG-IEEE");
+        assert_eq!(DafByteOrder::detect(&record), DafByteOrder::BigEndian);
+        record[88..96].copy_from_slice(b"LTL-IEEE");
+        assert_eq!(DafByteOrder::detect(&record), DafByteOrder::LittleEndian);
+    }
+
+    #[test]
+    fn test_type5_two_body_segment_through_daf() {
+        let gm = 398600.4418;
+        let state = StateVector::new(SpiceVector3::new(7000.0, 100.0, -50.0), SpiceVector3::new(0.3, 7.4, 1.1), 0.0);
+        let epoch = 1800.0;
+        let mut data = vec![
+            state.position.x(), state.position.y(), state.position.z(),
+            state.velocity.x(), state.velocity.y(), state.velocity.z(),
+        ];
+        data.extend_from_slice(&[epoch, gm, 1.0]);
+
+        let mut daf = write::new_spk("TWO BODY").unwrap();
+        daf.add_array(&[0.0, 3600.0], &[-77, 399, 1, 5], "TYPE 5", &data).unwrap();
+        let mut reader = SpkReader::new();
+        reader.load_spk_data("twobody.bsp", &daf.to_bytes().unwrap()).unwrap();
+
+        for t in [0.0, 1200.0, 1800.0, 3600.0] {
+            let evaluated = reader.compute_state(-77, 399, t).unwrap();
+            let expected = crate::ephemeris::propagate_two_body(&state, gm, t - epoch).unwrap();
+            assert!((evaluated.position - expected.position).magnitude() < 1e-9);
+            assert!((evaluated.velocity - expected.velocity).magnitude() < 1e-12);
+        }
+
+        // Two states on the same conic blend back to that conic
+        let later = crate::ephemeris::propagate_two_body(&state, gm, 1000.0).unwrap();
+        let mut two = data[..6].to_vec();
+        two.extend_from_slice(&[
+            later.position.x(), later.position.y(), later.position.z(),
+            later.velocity.x(), later.velocity.y(), later.velocity.z(),
+        ]);
+        two.extend_from_slice(&[epoch, epoch + 1000.0, gm, 2.0]);
+        let blended = type5_state(&two, epoch + 400.0).unwrap();
+        let expected = crate::ephemeris::propagate_two_body(&state, gm, 400.0).unwrap();
+        assert!((blended.position - expected.position).magnitude() < 1e-6);
+        assert!((blended.velocity - expected.velocity).magnitude() < 1e-9);
+
+        assert!(type5_state(&data[..data.len() - 1], 10.0).is_err());
+
+        // Corrupt state counts whose word totals overflow
+        for count in [2f64.powi(62), 1e300] {
+            let mut corrupt = data.clone();
+            *corrupt.last_mut().unwrap() = count;
+            assert_eq!(type5_state(&corrupt, 10.0).unwrap_err().error_type, SpiceErrorType::InvalidKernelData);
+        }
+    }
+
+    #[test]
+    fn test_find_segment_among_many_sequential_segments() {
+        // 500 abutting 10 s segments, each holding a constant X of its index
+        let mut daf = write::new_spk("SEQUENTIAL").unwrap();
+        for i in 0..500 {
+            let start = i as f64 * 10.0;
+            let data = [start + 5.0, 5.0, i as f64, 0.0, 0.0, start, 10.0, 5.0, 1.0];
+            daf.add_array(&[start, start + 10.0], &[-77, 399, 1, 2], &format!("SEGMENT {}", i), &data).unwrap();
+        }
+        // A later segment overlapping segment 250 takes precedence over it
+        let data = [2505.0, 5.0, -1.0, 0.0, 0.0, 2500.0, 10.0, 5.0, 1.0];
+        daf.add_array(&[2500.0, 2510.0], &[-77, 399, 1, 2], "PATCH", &data).unwrap();
+
+        let mut reader = SpkReader::new();
+        reader.load_spk_data("sequential.bsp", &daf.to_bytes().unwrap()).unwrap();
+        let x_at = |et: f64| reader.compute_state(-77, 399, et).unwrap().position.x();
+
+        assert_eq!(x_at(0.0), 0.0);
+        assert_eq!(x_at(1234.5), 123.0);
+        // At a shared boundary the later segment is used
+        assert_eq!(x_at(1240.0), 124.0);
+        assert_eq!(x_at(2500.0), -1.0);
+        assert_eq!(x_at(2509.0), -1.0);
+        assert_eq!(x_at(5000.0), 499.0);
+        assert!(reader.find_segment(-77, 399, 5000.5).is_err());
+        assert!(reader.find_segment(-77, 399, -0.5).is_err());
+    }
+
+    #[test]
+    fn test_record_cache_serves_nearby_epochs() {
+        let records = vec![
+            write::ChebyshevRecord { start: 0.0, end: 100.0, x: vec![1000.0, 10.0], y: vec![-500.0, 0.0], z: vec![0.0, 5.0] },
+            write::ChebyshevRecord { start: 100.0, end: 200.0, x: vec![1020.0, 10.0], y: vec![-500.0, 0.0], z: vec![10.0, 5.0] },
+        ];
+        let bytes = write::write_type2("CACHED", -77, 399, "J2000", 0.0, 200.0, &records).unwrap();
+        let mut reader = SpkReader::new();
+        reader.load_spk_data("cached.bsp", &bytes).unwrap();
+
+        let first = reader.compute_state(-77, 399, 150.0).unwrap();
+        assert_eq!(reader.record_cache_hits(), 0);
+        let nearby = reader.compute_state(-77, 399, 150.5).unwrap();
+        assert_eq!(reader.record_cache_hits(), 1);
+        assert!((nearby.position.x() - first.position.x() - 0.1).abs() < 1e-9);
+
+        // Another record is decoded, then both stay cached
+        reader.compute_state(-77, 399, 50.0).unwrap();
+        assert_eq!(reader.record_cache_hits(), 1);
+        reader.compute_state(-77, 399, 175.0).unwrap();
+        reader.compute_state(-77, 399, 25.0).unwrap();
+        assert_eq!(reader.record_cache_hits(), 3);
+
+        // With room for one record, alternating between two always decodes
+        reader.set_record_cache_size(1);
+        reader.compute_state(-77, 399, 150.0).unwrap();
+        reader.compute_state(-77, 399, 50.0).unwrap();
+        reader.compute_state(-77, 399, 150.0).unwrap();
+        assert_eq!(reader.record_cache_hits(), 3);
+    }
+
+    #[test]
+    fn test_reader_flags_inconsistent_type2_segment() {
+        // Two 100 s records, but the summary claims the segment runs to 400 s
+        let mut daf = write::new_spk("INCONSISTENT").unwrap();
+        let mut data = Vec::new();
+        for mid in [50.0, 150.0] {
+            data.extend_from_slice(&[mid, 50.0, 1.0, 0.0, 2.0, 0.0, 3.0, 0.0]);
+        }
+        data.extend_from_slice(&[0.0, 100.0, 8.0, 2.0]);
+        daf.add_array(&[0.0, 400.0], &[-77, 399, 1, 2], "BAD SPAN", &data).unwrap();
+
+        let mut reader = SpkReader::new();
+        let err = reader.load_spk_data("bad.bsp", &daf.to_bytes().unwrap()).unwrap_err();
+        assert_eq!(err.error_type, SpiceErrorType::InvalidKernelData);
+        assert!(err.message.contains("BAD SPAN"));
+        assert!(reader.segment_cache.is_empty());
+
+        // A corrupt N whose word count overflows is rejected, not a panic
+        let summary = SpkSegmentSummary {
+            target_body: -77, center_body: 399, frame_id: 1, segment_type: SpkSegmentType::Chebyshev,
+            start_time: 0.0, end_time: 200.0, start_address: 1, end_address: 20,
+        };
+        let huge = [0.0, 100.0, 8.0, 2.0f64.powi(62)];
+        let err = type2_directory_from_tail(&summary, &huge, 20).unwrap_err();
+        assert_eq!(err.error_type, SpiceErrorType::InvalidKernelData);
+        let err = type2_directory_from_tail(&summary, &[0.0, 100.0, 8.0, 1e300], 20).unwrap_err();
+        assert_eq!(err.error_type, SpiceErrorType::InvalidKernelData);
+
+        // The directory layout is only checked on Type 2 segments
+        let type13 = SpkSegmentSummary { segment_type: SpkSegmentType::Hermite, ..summary };
+        assert!(type2_directory_from_tail(&type13, &data[data.len() - 4..], data.len()).is_err());
+    }
+
+    #[test]
+    fn test_missing_spacecraft_ephemeris_names_the_gap() {
+        let record = |start: f64, x: f64| write::ChebyshevRecord {
+            start, end: start + 1000.0, x: vec![x, 0.0], y: vec![0.0, 0.0], z: vec![0.0, 0.0],
+        };
+        let mut spk = write::new_spk("PLANETS").unwrap();
+        write::add_type2_segment(&mut spk, 3, 0, "J2000", 0.0, 1000.0, "EMB", &[record(0.0, 1.5e8)]).unwrap();
+        write::add_type2_segment(&mut spk, 399, 3, "J2000", 0.0, 1000.0, "EARTH", &[record(0.0, -4.6e3)]).unwrap();
+        write::add_type2_segment(&mut spk, 301, 3, "J2000", 0.0, 1000.0, "MOON", &[record(0.0, 3.8e5)]).unwrap();
+
+        let mut reader = SpkReader::new();
+        reader.load_spk_data("planets.bsp", &spk.to_bytes().unwrap()).unwrap();
+
+        // Moon relative to Earth chains through the Earth-Moon barycenter
+        let moon = reader.compute_chained_state(301, 399, 500.0).unwrap();
+        assert!((moon.position.x() - (3.8e5 + 4.6e3)).abs() < 1e-6);
+        let earth = reader.compute_chained_state(399, 0, 500.0).unwrap();
+        assert!((earth.position.x() - (1.5e8 - 4.6e3)).abs() < 1e-6);
+
+        let missing = reader.compute_chained_state(-82, 399, 500.0).unwrap_err();
+        assert_eq!(missing.error_type, SpiceErrorType::InsufficientData);
+        assert!(missing.message.contains("no ephemeris connecting -82 to 399"));
+
+        // A known body outside its coverage is a different failure
+        let uncovered = reader.check_connection(301, 399, 5000.0).unwrap_err();
+        assert!(uncovered.message.contains("No SPK data found for body 301"));
+        assert!(!uncovered.message.contains("no ephemeris connecting"));
+    }
+
+    #[test]
+    fn test_chained_state_prefers_latest_segment() {
+        let record = |start: f64, x: f64| write::ChebyshevRecord {
+            start, end: start + 1000.0, x: vec![x, 0.0], y: vec![0.0, 0.0], z: vec![0.0, 0.0],
+        };
+        let file = |segments: &[(i32, i32, f64, f64)]| {
+            let mut spk = write::new_spk("OVERLAP").unwrap();
+            for &(body, center, start, x) in segments {
+                write::add_type2_segment(&mut spk, body, center, "J2000", start, 1000.0, "SEG", &[record(start, x)]).unwrap();
+            }
+            spk.to_bytes().unwrap()
+        };
+        let old = file(&[(399, 0, 0.0, 10.0), (-77, 399, 0.0, 1.0)]);
+        let new = file(&[(-77, 399, 0.0, -1.0)]);
+
+        // Identical segments in two files: the file loaded last wins, whatever the hash order
+        for _ in 0..20 {
+            let mut reader = SpkReader::new();
+            reader.load_spk_data("old.bsp", &old).unwrap();
+            reader.load_spk_data("new.bsp", &new).unwrap();
+            assert_eq!(reader.compute_state(-77, 399, 500.0).unwrap().position.x(), -1.0);
+            assert_eq!(reader.compute_chained_state(-77, 399, 500.0).unwrap().position.x(), -1.0);
+            assert_eq!(reader.segment_data(-77, 399, 500.0).unwrap().coefficients[2], -1.0);
+        }
+
+        // A later-starting segment about another center takes over the chain
+        let mut reader = SpkReader::new();
+        reader.load_spk_data("old.bsp", &old).unwrap();
+        reader.load_spk_data("new.bsp", &new).unwrap();
+        reader.load_spk_data("late.bsp", &file(&[(-77, 0, 100.0, 5.0)])).unwrap();
+        assert_eq!(reader.compute_chained_state(-77, 399, 500.0).unwrap().position.x(), 5.0 - 10.0);
+        assert_eq!(reader.compute_chained_state(-77, 399, 50.0).unwrap().position.x(), -1.0);
+
+        // Reloading a file under its name replaces its segments and makes them the latest
+        reader.load_spk_data("old.bsp", &old).unwrap();
+        assert_eq!(reader.compute_state(-77, 399, 500.0).unwrap().position.x(), 1.0);
+        assert_eq!(reader.segment_cache[&(-77, 399)].len(), 2);
+    }
+
+    /// Four days of the Earth, Moon and Earth-Moon barycenter Type 2
+    /// segments of the CSPICE cookbook ephemeris (cook_01.tsp), cut out with
+    /// spksub_c so every byte was written by CSPICE itself
+    const DE_FRAGMENT: &[u8] = include_bytes!("../kernels/spk/de_fragment.bsp");
+
+    #[test]
+    fn test_type2_reader_matches_cspice_byte_layout() {
+        let mut reader = SpkReader::new();
+        reader.load_spk_data("de_fragment.bsp", DE_FRAGMENT).unwrap();
+
+        // spkgeo_c states in the segments' DE-125 frame (km, km/s)
+        let reference = [
+            (301, 3, -299999000.0, [-384460.454847336, -60857.544273848, -61716.957912154], [0.158078538394, -0.859386995294, -0.417400780953]),
+            (301, 3, -299870276.75, [-344523.292457696, -166474.978198287, -111344.856099525], [0.455448854347, -0.768111606758, -0.347474003813]),
+            (301, 3, -299654460.0, [-202704.749231915, -301737.951619419, -167501.416512044], [0.826755143504, -0.456666183335, -0.161143344614]),
+            (399, 3, -299999000.0, [4728.876740427, 748.549875462, 759.120692599], [-0.001944371427, 0.010570489427, 0.005134043878]),
+            (399, 3, -299740800.0, [3307.899925102, 3147.323647778, 1844.337851800], [-0.008630004309, 0.007400267996, 0.003001218956]),
+            (3, 0, -299870276.75, [24096661.616148017, -137727351.305193216, -59723094.999388017], [28.937405155327, 4.228060478191, 1.832789498741]),
+            (3, 0, -299654460.0, [30318882.361824542, -136694105.061705828, -59275189.133661814], [28.716231470394, 5.345742972856, 2.317395121466]),
+            (301, 0, -299999000.0, [19980154.902520277, -138289340.104938418, -60002039.267648570], [29.203236797905, 2.698318572884, 1.124735927713]),
+            (301, 0, -299740800.0, [27566448.532912102, -137392287.374426782, -59616873.041804440], [29.512413011399, 4.297981142852, 1.879967025686]),
+        ];
+        for (target, center, et, position, velocity) in reference {
+            let state = reader.compute_chained_state(target, center, et).unwrap();
+            for i in 0..3 {
+                // Position to the meter, velocity to the mm/s
+                assert!((state.position.0[i] - position[i]).abs() < 1e-3, "{} ET {} position {}", target, et, i);
+                assert!((state.velocity.0[i] - velocity[i]).abs() < 1e-6, "{} ET {} velocity {}", target, et, i);
+            }
+        }
+    }
+
+    #[test]
+    fn test_coverage_merges_abutting_segments() {
+        let record = |start: f64| write::ChebyshevRecord {
+            start, end: start + 100.0, x: vec![7000.0, 1.0], y: vec![0.0, 0.0], z: vec![0.0, 0.0],
+        };
+        let mut spk = write::new_spk("COVERAGE").unwrap();
+        write::add_type2_segment(&mut spk, -90, 399, "J2000", 100.0, 200.0, "LATE", &[record(100.0)]).unwrap();
+        write::add_type2_segment(&mut spk, -90, 399, "J2000", 0.0, 100.0, "EARLY", &[record(0.0)]).unwrap();
+        write::add_type2_segment(&mut spk, -90, 399, "J2000", 500.0, 600.0, "LATER", &[record(500.0)]).unwrap();
+        write::add_type2_segment(&mut spk, 399, 0, "J2000", 0.0, 600.0, "EARTH", &[write::ChebyshevRecord {
+            start: 0.0, end: 600.0, x: vec![1.5e8], y: vec![0.0], z: vec![0.0],
+        }]).unwrap();
+
+        let mut reader = SpkReader::new();
+        reader.load_spk_data("coverage.bsp", &spk.to_bytes().unwrap()).unwrap();
+
+        assert_eq!(reader.objects("coverage.bsp").unwrap(), vec![-90, 399]);
+        assert_eq!(reader.coverage("coverage.bsp", -90).unwrap(), vec![(0.0, 200.0), (500.0, 600.0)]);
+        assert!(reader.coverage("coverage.bsp", 301).unwrap().is_empty());
+        assert_eq!(reader.objects("missing.bsp").unwrap_err().error_type, SpiceErrorType::KernelNotFound);
+    }
+
+    #[test]
+    fn test_segment_listing_describes_each_segment() {
+        let record = |start: f64| write::ChebyshevRecord {
+            start, end: start + 100.0, x: vec![7000.0, 1.0], y: vec![0.0, 0.0], z: vec![0.0, 0.0],
+        };
+        let mut spk = write::new_spk("LISTING").unwrap();
+        write::add_type2_segment(&mut spk, -90, 399, "J2000", 100.0, 200.0, "LATE", &[record(100.0)]).unwrap();
+        write::add_type2_segment(&mut spk, -90, 399, "J2000", 0.0, 100.0, "EARLY", &[record(0.0)]).unwrap();
+        write::add_type2_segment(&mut spk, 399, 0, "J2000", 0.0, 100.0, "EARTH", &[record(0.0)]).unwrap();
+
+        let mut reader = SpkReader::new();
+        reader.load_spk_data("listing.bsp", &spk.to_bytes().unwrap()).unwrap();
+
+        let segments = reader.segments("listing.bsp").unwrap();
+        assert_eq!(segments.len(), 3);
+        assert_eq!(segments[0].to_string(), "target -90 w.r.t. 399, frame 1, type 2, [100.000, 200.000] ET");
+        assert_eq!(segments[2].to_string(), "target 399 w.r.t. 0, frame 1, type 2, [0.000, 100.000] ET");
+        assert!(reader.segments("missing.bsp").is_err());
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_deprecated_reader_accessor_still_loads() {
+        let _kernels = crate::kernel_system::IsolatedKernels::new();
+        let mut spk = write::new_spk("SHIM").unwrap();
+        write::add_type2_segment(&mut spk, -91, 399, "J2000", 0.0, 100.0, "SHIM", &[write::ChebyshevRecord {
+            start: 0.0, end: 100.0, x: vec![7000.0], y: vec![0.0], z: vec![0.0],
+        }]).unwrap();
+
+        {
+            let mut reader = get_spk_reader().unwrap();
+            reader.load_spk_data("shim.bsp", &spk.to_bytes().unwrap()).unwrap();
+        }
+        assert_eq!(spk_objects("shim.bsp").unwrap(), vec![-91]);
+
+        GLOBAL_SPK_READER.write(|slot| *slot = None);
+        assert_eq!(get_spk_reader().err().unwrap().error_type, SpiceErrorType::PoolNotInitialized);
+        initialize_spk_reader().unwrap();
+    }
 }
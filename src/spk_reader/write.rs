@@ -0,0 +1,731 @@
+//! SPK Segment Writers for RustSPICE
+//!
+//! This module provides equivalency to the CSPICE SPK writer routines:
+//! - spkopn_c → DafFile::new("DAF/SPK", 2, 6, ...) - Start a new SPK
+//! - spkw02_c → add_type2_segment() / write_type2() - Chebyshev position segments
+//! - fit_type2_records() - Chebyshev records fitted to a position function
+//! - spkw09_c → add_type9_segment() / write_type9() - Discrete state segments
+//! - spkopa_c → append_type9() - Add a Type 9 segment to an existing SPK
+//! - dafac_c → DafFile::add_comments() - Provenance text in the comment area
+//! - merge_type2_segments() / add_type2_data() - Combine contiguous Type 2 segments
+//!
+//! Segments are added to an in-memory `DafFile`; `DafFile::to_bytes()` gives
+//! the kernel, which loads back through `SpkReader::load_spk_data`.
+
+#[cfg(not(feature = "std"))]
+use alloc::{string::String, vec, vec::Vec, format};
+#[cfg(feature = "std")]
+use std::{string::String, vec, vec::Vec, format};
+
+use crate::foundation::{SpiceDouble, SpiceVector3, StateVector};
+use crate::error_handling::{SpiceResult, SpiceError, SpiceErrorType};
+use crate::coordinates::frame_name_to_id;
+use crate::file_system::daf::DafFile;
+use super::{SpkSegmentData, SpkSegmentSummary, SpkSegmentType, type2_directory, type2_segment_data};
+
+/// Record intervals may differ from each other by this fraction of their length
+const INTERVAL_TOLERANCE: SpiceDouble = 1e-9;
+
+/// One Type 2 record: Chebyshev coefficients for X, Y and Z over [start, end]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChebyshevRecord {
+    pub start: SpiceDouble,
+    pub end: SpiceDouble,
+    pub x: Vec<SpiceDouble>,
+    pub y: Vec<SpiceDouble>,
+    pub z: Vec<SpiceDouble>,
+}
+
+/// How to choose the record length when fitting Type 2 records
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RecordInterval {
+    /// Records of exactly this many seconds; the last record runs past the
+    /// segment end when the span is not a whole number of records
+    Explicit(SpiceDouble),
+    /// Split the span into the fewest equal records no longer than this many
+    /// seconds; a span shorter than one record gives a single record
+    Auto(SpiceDouble),
+}
+
+/// Start a new, empty SPK (equivalent to spkopn_c)
+pub fn new_spk(internal_name: &str) -> SpiceResult<DafFile> {
+    DafFile::new("DAF/SPK", 2, 6, internal_name)
+}
+
+/// Resolve a frame name to the ID stored in SPK segment summaries
+fn spk_frame_id(frame: &str) -> SpiceResult<i32> {
+    frame_name_to_id(frame)
+}
+
+/// Check that Type 2 records tile [start, end] with equal, contiguous intervals
+///
+/// The last record may run past `end`, as it does when the span is not a
+/// whole number of records, but must begin before it. Returns the record interval length and polynomial degree.
+pub fn validate_type2_records(
+    start: SpiceDouble,
+    end: SpiceDouble,
+    records: &[ChebyshevRecord],
+) -> SpiceResult<(SpiceDouble, usize)> {
+    let first = records.first().ok_or_else(|| SpiceError::new(
+        SpiceErrorType::InvalidArgument,
+        "Type 2 segment needs at least one record".into()
+    ))?;
+    if end <= start {
+        return Err(SpiceError::new(
+            SpiceErrorType::InvalidArgument,
+            format!("Segment end {} is not after start {}", end, start)
+        ));
+    }
+
+    let coefficient_count = first.x.len();
+    let interval = first.end - first.start;
+    let tolerance = INTERVAL_TOLERANCE * interval.abs().max(1.0);
+
+    for (i, record) in records.iter().enumerate() {
+        if coefficient_count == 0
+            || record.x.len() != coefficient_count
+            || record.y.len() != coefficient_count
+            || record.z.len() != coefficient_count
+        {
+            return Err(SpiceError::new(
+                SpiceErrorType::InvalidArgument,
+                format!("Record {} does not have {} coefficients per component", i, coefficient_count)
+            ));
+        }
+        if record.end <= record.start {
+            return Err(SpiceError::new(
+                SpiceErrorType::InvalidArgument,
+                format!("Record {} interval [{}, {}] is empty", i, record.start, record.end)
+            ));
+        }
+        if ((record.end - record.start) - interval).abs() > tolerance {
+            return Err(SpiceError::new(
+                SpiceErrorType::InvalidArgument,
+                format!(
+                    "Record {} spans {} s but Type 2 records must all span {} s",
+                    i, record.end - record.start, interval
+                )
+            ));
+        }
+        if i > 0 {
+            let previous_end = records[i - 1].end;
+            if record.start > previous_end + tolerance {
+                return Err(SpiceError::new(
+                    SpiceErrorType::InvalidArgument,
+                    format!("Gap between records {} and {}: {} to {}", i - 1, i, previous_end, record.start)
+                ));
+            }
+            if record.start < previous_end - tolerance {
+                return Err(SpiceError::new(
+                    SpiceErrorType::InvalidArgument,
+                    format!("Records {} and {} overlap: {} to {}", i - 1, i, record.start, previous_end)
+                ));
+            }
+        }
+    }
+
+    let last = &records[records.len() - 1];
+    if (first.start - start).abs() > tolerance || last.end < end - tolerance || last.start >= end {
+        return Err(SpiceError::new(
+            SpiceErrorType::InvalidArgument,
+            format!(
+                "Records cover [{}, {}] but the segment declares [{}, {}]",
+                first.start, last.end, start, end
+            )
+        ));
+    }
+
+    Ok((interval, coefficient_count - 1))
+}
+
+/// Add a Type 2 (Chebyshev position) segment to an SPK (equivalent to spkw02_c)
+#[allow(clippy::too_many_arguments)]
+pub fn add_type2_segment(
+    spk: &mut DafFile,
+    body: i32,
+    center: i32,
+    frame: &str,
+    start: SpiceDouble,
+    end: SpiceDouble,
+    segment_id: &str,
+    records: &[ChebyshevRecord],
+) -> SpiceResult<()> {
+    let frame_id = spk_frame_id(frame)?;
+    let (interval, degree) = validate_type2_records(start, end, records)?;
+    let record_size = 2 + 3 * (degree + 1);
+
+    let mut data = Vec::with_capacity(records.len() * record_size + 4);
+    for record in records {
+        let radius = (record.end - record.start) / 2.0;
+        data.push(record.start + radius);
+        data.push(radius);
+        data.extend_from_slice(&record.x);
+        data.extend_from_slice(&record.y);
+        data.extend_from_slice(&record.z);
+    }
+    // Segment directory: INIT, INTLEN, RSIZE, N
+    data.extend_from_slice(&[records[0].start, interval, record_size as SpiceDouble, records.len() as SpiceDouble]);
+
+    spk.add_array(&[start, end], &[body, center, frame_id, 2], segment_id, &data)
+}
+
+/// Build an SPK holding a single Type 2 segment and the given comment lines
+#[allow(clippy::too_many_arguments)]
+pub fn write_type2_with_comments(
+    path: &str,
+    body: i32,
+    center: i32,
+    frame: &str,
+    start: SpiceDouble,
+    end: SpiceDouble,
+    records: &[ChebyshevRecord],
+    comments: &[&str],
+) -> SpiceResult<Vec<u8>> {
+    let mut spk = new_spk(path)?;
+    spk.add_comments(comments)?;
+    add_type2_segment(&mut spk, body, center, frame, start, end, &format!("BODY {} TYPE 2", body), records)?;
+    spk.to_bytes()
+}
+
+/// Build an SPK holding a single Type 2 segment
+pub fn write_type2(
+    path: &str,
+    body: i32,
+    center: i32,
+    frame: &str,
+    start: SpiceDouble,
+    end: SpiceDouble,
+    records: &[ChebyshevRecord],
+) -> SpiceResult<Vec<u8>> {
+    let mut spk = new_spk(path)?;
+    add_type2_segment(&mut spk, body, center, frame, start, end, &format!("BODY {} TYPE 2", body), records)?;
+    spk.to_bytes()
+}
+
+/// Highest interpolation degree of a Type 9 segment
+const MAX_TYPE9_DEGREE: usize = 27;
+
+/// Add a Type 9 (unequally spaced discrete states) segment to an SPK
+/// (equivalent to spkw09_c)
+///
+/// The segment covers the first to the last epoch, which must strictly
+/// increase; `degree` is that of the Lagrange polynomials used to
+/// interpolate between states.
+pub fn add_type9_segment(
+    spk: &mut DafFile,
+    body: i32,
+    center: i32,
+    frame: &str,
+    segment_id: &str,
+    states: &[(SpiceDouble, StateVector)],
+    degree: usize,
+) -> SpiceResult<()> {
+    let frame_id = spk_frame_id(frame)?;
+    if !(1..=MAX_TYPE9_DEGREE).contains(&degree) {
+        return Err(SpiceError::new(
+            SpiceErrorType::InvalidArgument,
+            format!("Type 9 degree must be between 1 and {}, got {}", MAX_TYPE9_DEGREE, degree)
+        ));
+    }
+    if states.len() < degree + 1 {
+        return Err(SpiceError::new(
+            SpiceErrorType::InvalidArgument,
+            format!("Degree {} interpolation needs at least {} states, got {}", degree, degree + 1, states.len())
+        ));
+    }
+    if let Some(i) = states.windows(2).position(|pair| pair[1].0 <= pair[0].0) {
+        return Err(SpiceError::new(
+            SpiceErrorType::InvalidArgument,
+            format!("Epochs must strictly increase: state {} at {} follows {}", i + 1, states[i + 1].0, states[i].0)
+        ));
+    }
+
+    let count = states.len();
+    let mut data = Vec::with_capacity(7 * count + count / 100 + 2);
+    for (_, state) in states {
+        data.extend_from_slice(&state.position.0);
+        data.extend_from_slice(&state.velocity.0);
+    }
+    data.extend(states.iter().map(|(epoch, _)| *epoch));
+    // Epoch directory: every 100th epoch, then DEGREE and N
+    data.extend(states.iter().skip(99).step_by(100).take((count - 1) / 100).map(|(epoch, _)| *epoch));
+    data.extend_from_slice(&[degree as SpiceDouble, count as SpiceDouble]);
+
+    let (start, end) = (states[0].0, states[count - 1].0);
+    spk.add_array(&[start, end], &[body, center, frame_id, 9], segment_id, &data)
+}
+
+/// Build an SPK holding a single Type 9 segment
+pub fn write_type9(
+    path: &str,
+    body: i32,
+    center: i32,
+    frame: &str,
+    states: &[(SpiceDouble, StateVector)],
+    degree: usize,
+) -> SpiceResult<Vec<u8>> {
+    let mut spk = new_spk(path)?;
+    add_type9_segment(&mut spk, body, center, frame, &format!("BODY {} TYPE 9", body), states, degree)?;
+    spk.to_bytes()
+}
+
+/// Add a Type 9 segment to an existing SPK (equivalent to spkopa_c followed
+/// by spkw09_c)
+///
+/// The existing file's comments, internal name, byte order and segments are
+/// kept; the new segment's summary joins the summary record chain, starting
+/// a new summary record when the last one is full.
+pub fn append_type9(
+    existing: &[u8],
+    body: i32,
+    center: i32,
+    frame: &str,
+    states: &[(SpiceDouble, StateVector)],
+    degree: usize,
+) -> SpiceResult<Vec<u8>> {
+    let mut spk = DafFile::open_write(existing)?;
+    if spk.id_word() != "DAF/SPK" || spk.nd() != 2 || spk.ni() != 6 {
+        return Err(SpiceError::new(
+            SpiceErrorType::InvalidArgument,
+            format!("Cannot append SPK segments to a {} file with ND={} NI={}", spk.id_word(), spk.nd(), spk.ni())
+        ));
+    }
+    add_type9_segment(&mut spk, body, center, frame, &format!("BODY {} TYPE 9", body), states, degree)?;
+    spk.to_bytes()
+}
+
+/// Fit Type 2 records of the given degree to a position function over [start, end]
+///
+/// Each record interpolates `position` at the Chebyshev nodes of its
+/// interval, so the fit is exact for polynomials up to `degree`.
+pub fn fit_type2_records<F>(
+    start: SpiceDouble,
+    end: SpiceDouble,
+    degree: usize,
+    interval: RecordInterval,
+    position: F,
+) -> SpiceResult<Vec<ChebyshevRecord>>
+where
+    F: Fn(SpiceDouble) -> SpiceResult<SpiceVector3>,
+{
+    if end <= start {
+        return Err(SpiceError::new(
+            SpiceErrorType::InvalidArgument,
+            format!("Segment end {} is not after start {}", end, start)
+        ));
+    }
+    let span = end - start;
+    let (length, count) = match interval {
+        RecordInterval::Explicit(length) | RecordInterval::Auto(length) if length <= 0.0 || length.is_nan() => {
+            return Err(SpiceError::new(
+                SpiceErrorType::InvalidArgument,
+                format!("Record interval must be positive, got {}", length)
+            ));
+        }
+        RecordInterval::Explicit(length) => (length, record_count(span, length)),
+        RecordInterval::Auto(target) => {
+            let count = record_count(span, target);
+            (span / count as SpiceDouble, count)
+        }
+    };
+
+    let nodes = degree + 1;
+    (0..count)
+        .map(|i| {
+            let record_start = start + length * i as SpiceDouble;
+            let radius = length / 2.0;
+            let midpoint = record_start + radius;
+
+            let mut record = ChebyshevRecord {
+                start: record_start,
+                end: if i + 1 == count && matches!(interval, RecordInterval::Auto(_)) { end } else { record_start + length },
+                x: vec![0.0; nodes],
+                y: vec![0.0; nodes],
+                z: vec![0.0; nodes],
+            };
+            for j in 0..nodes {
+                let theta = core::f64::consts::PI * (j as SpiceDouble + 0.5) / nodes as SpiceDouble;
+                let sample = position(midpoint + radius * theta.cos())?;
+                for k in 0..nodes {
+                    let weight = 2.0 / nodes as SpiceDouble * (k as SpiceDouble * theta).cos();
+                    record.x[k] += weight * sample.x();
+                    record.y[k] += weight * sample.y();
+                    record.z[k] += weight * sample.z();
+                }
+            }
+            record.x[0] /= 2.0;
+            record.y[0] /= 2.0;
+            record.z[0] /= 2.0;
+            Ok(record)
+        })
+        .collect()
+}
+
+/// Number of records of `length` seconds needed to cover `span`
+fn record_count(span: SpiceDouble, length: SpiceDouble) -> usize {
+    // Spans within rounding of a whole number of records need no extra record
+    let count = span / length;
+    let whole = count.round();
+    if (count - whole).abs() <= INTERVAL_TOLERANCE * whole.max(1.0) {
+        (whole as usize).max(1)
+    } else {
+        count.ceil() as usize
+    }
+}
+
+/// Combine contiguous Type 2 segments into one
+///
+/// The segments must share body, center, frame, degree and record interval,
+/// and each must end exactly where the next begins, on a record boundary.
+/// Segments may be given in any order.
+pub fn merge_type2_segments(segments: &[SpkSegmentData]) -> SpiceResult<SpkSegmentData> {
+    let mut ordered: Vec<&SpkSegmentData> = segments.iter().collect();
+    ordered.sort_by(|a, b| a.summary.start_time.total_cmp(&b.summary.start_time));
+    let first = *ordered.first().ok_or_else(|| SpiceError::new(
+        SpiceErrorType::InvalidArgument,
+        "No segments to merge".into()
+    ))?;
+
+    let mut directories = Vec::with_capacity(ordered.len());
+    for segment in &ordered {
+        let summary = &segment.summary;
+        if summary.segment_type != SpkSegmentType::Chebyshev {
+            return Err(SpiceError::new(
+                SpiceErrorType::InvalidArgument,
+                format!("Only Type 2 segments can be merged, found {:?}", summary.segment_type)
+            ));
+        }
+        if (summary.target_body, summary.center_body, summary.frame_id)
+            != (first.summary.target_body, first.summary.center_body, first.summary.frame_id)
+        {
+            return Err(SpiceError::new(
+                SpiceErrorType::InvalidArgument,
+                format!(
+                    "Segment for body {} center {} frame {} cannot merge with body {} center {} frame {}",
+                    summary.target_body, summary.center_body, summary.frame_id,
+                    first.summary.target_body, first.summary.center_body, first.summary.frame_id
+                )
+            ));
+        }
+        let directory = type2_directory(summary, &segment.coefficients)?;
+        if segment.degree != first.degree {
+            return Err(SpiceError::new(
+                SpiceErrorType::InvalidArgument,
+                format!("Cannot merge degree {} records with degree {} records", segment.degree, first.degree)
+            ));
+        }
+        directories.push(directory);
+    }
+
+    let interval = directories[0].interval;
+    let tolerance = INTERVAL_TOLERANCE * interval.max(first.summary.end_time.abs()).max(1.0);
+    for (i, pair) in directories.windows(2).enumerate() {
+        let (previous, next) = (&ordered[i].summary, &ordered[i + 1].summary);
+        if (pair[1].interval - interval).abs() > INTERVAL_TOLERANCE * interval {
+            return Err(SpiceError::new(
+                SpiceErrorType::InvalidArgument,
+                format!("Record intervals differ: {} s and {} s", interval, pair[1].interval)
+            ));
+        }
+        let previous_records_end = pair[0].init + pair[0].interval * pair[0].record_count as SpiceDouble;
+        if (next.start_time - previous.end_time).abs() > tolerance
+            || (previous_records_end - previous.end_time).abs() > tolerance
+            || (pair[1].init - next.start_time).abs() > tolerance
+        {
+            return Err(SpiceError::new(
+                SpiceErrorType::InvalidArgument,
+                format!(
+                    "Segments [{}, {}] and [{}, {}] are not contiguous on record boundaries",
+                    previous.start_time, previous.end_time, next.start_time, next.end_time
+                )
+            ));
+        }
+    }
+
+    let record_count: usize = directories.iter().map(|d| d.record_count).sum();
+    let record_size = directories[0].record_size;
+    let mut words = Vec::with_capacity(record_count * record_size + 4);
+    for (segment, directory) in ordered.iter().zip(&directories) {
+        words.extend_from_slice(&segment.coefficients[..directory.record_count * record_size]);
+    }
+    words.extend_from_slice(&[directories[0].init, interval, record_size as SpiceDouble, record_count as SpiceDouble]);
+
+    let last = ordered[ordered.len() - 1];
+    let summary = SpkSegmentSummary {
+        start_time: first.summary.start_time,
+        end_time: last.summary.end_time,
+        // Not yet stored in a file
+        start_address: 0,
+        end_address: 0,
+        ..first.summary.clone()
+    };
+    type2_segment_data(summary, words)
+}
+
+/// Add Type 2 segment data, such as a merged segment, to an SPK
+pub fn add_type2_data(spk: &mut DafFile, data: &SpkSegmentData, segment_id: &str) -> SpiceResult<()> {
+    let summary = &data.summary;
+    if summary.segment_type != SpkSegmentType::Chebyshev {
+        return Err(SpiceError::new(
+            SpiceErrorType::InvalidArgument,
+            format!("Expected Type 2 segment data, found {:?}", summary.segment_type)
+        ));
+    }
+    type2_directory(summary, &data.coefficients)?;
+    spk.add_array(
+        &[summary.start_time, summary.end_time],
+        &[summary.target_body, summary.center_body, summary.frame_id, 2],
+        segment_id,
+        &data.coefficients
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn linear_record(start: f64, end: f64) -> ChebyshevRecord {
+        ChebyshevRecord {
+            start,
+            end,
+            x: vec![1000.0, 10.0],
+            y: vec![-500.0, 0.0],
+            z: vec![0.0, 5.0],
+        }
+    }
+
+    #[test]
+    fn test_writer_rejects_gapped_records() {
+        let records = vec![linear_record(0.0, 100.0), linear_record(110.0, 210.0)];
+        let err = write_type2("GAP", 399, 0, "J2000", 0.0, 210.0, &records).unwrap_err();
+        assert_eq!(err.error_type, SpiceErrorType::InvalidArgument);
+        assert!(err.message.contains("Gap"));
+
+        let overlapping = vec![linear_record(0.0, 100.0), linear_record(90.0, 190.0)];
+        assert!(write_type2("OVERLAP", 399, 0, "J2000", 0.0, 190.0, &overlapping).unwrap_err().message.contains("overlap"));
+
+        // Records must reach the declared segment bounds
+        let tiled = vec![linear_record(0.0, 100.0), linear_record(100.0, 200.0)];
+        assert!(write_type2("SHORT", 399, 0, "J2000", 0.0, 250.0, &tiled).is_err());
+        assert!(write_type2("OK", 399, 0, "J2000", 0.0, 200.0, &tiled).is_ok());
+        assert!(write_type2("FRAME", 399, 0, "NO_SUCH_FRAME", 0.0, 200.0, &tiled).is_err());
+    }
+
+    #[test]
+    fn test_merge_contiguous_type2_segments() {
+        use crate::spk_reader::SpkReader;
+
+        let records: Vec<ChebyshevRecord> = (0..4)
+            .map(|i| {
+                let start = 100.0 * i as f64;
+                ChebyshevRecord { start, end: start + 100.0, x: vec![10.0 * i as f64, 1.0], y: vec![0.0, -2.0], z: vec![5.0, 0.0] }
+            })
+            .collect();
+        let mut spk = new_spk("PIECES").unwrap();
+        add_type2_segment(&mut spk, -77, 399, "J2000", 0.0, 200.0, "FIRST", &records[..2]).unwrap();
+        add_type2_segment(&mut spk, -77, 399, "J2000", 200.0, 400.0, "SECOND", &records[2..]).unwrap();
+
+        let mut reader = SpkReader::new();
+        reader.load_spk_data("pieces.bsp", &spk.to_bytes().unwrap()).unwrap();
+        let first = reader.segment_data(-77, 399, 50.0).unwrap();
+        let second = reader.segment_data(-77, 399, 300.0).unwrap();
+
+        let merged = merge_type2_segments(&[second.clone(), first.clone()]).unwrap();
+        assert_eq!((merged.summary.start_time, merged.summary.end_time), (0.0, 400.0));
+
+        let mut merged_spk = new_spk("MERGED").unwrap();
+        add_type2_data(&mut merged_spk, &merged, "MERGED").unwrap();
+        let mut merged_reader = SpkReader::new();
+        merged_reader.load_spk_data("merged.bsp", &merged_spk.to_bytes().unwrap()).unwrap();
+        for et in [25.0, 199.0, 275.0, 390.0] {
+            let expected = reader.compute_state(-77, 399, et).unwrap();
+            let actual = merged_reader.compute_state(-77, 399, et).unwrap();
+            assert_eq!(actual.position, expected.position);
+            assert_eq!(actual.velocity, expected.velocity);
+        }
+
+        // Degree mismatch
+        let cubic: Vec<ChebyshevRecord> = (4..6)
+            .map(|i| {
+                let start = 100.0 * i as f64;
+                ChebyshevRecord { start, end: start + 100.0, x: vec![0.0; 4], y: vec![0.0; 4], z: vec![0.0; 4] }
+            })
+            .collect();
+        let mut cubic_spk = new_spk("CUBIC").unwrap();
+        add_type2_segment(&mut cubic_spk, -77, 399, "J2000", 400.0, 600.0, "CUBIC", &cubic).unwrap();
+        let mut cubic_reader = SpkReader::new();
+        cubic_reader.load_spk_data("cubic.bsp", &cubic_spk.to_bytes().unwrap()).unwrap();
+        let third = cubic_reader.segment_data(-77, 399, 500.0).unwrap();
+        assert!(merge_type2_segments(&[second.clone(), third]).unwrap_err().message.contains("degree"));
+
+        // Overlapping segments
+        assert!(merge_type2_segments(&[first, merged]).is_err());
+    }
+
+    #[test]
+    fn test_auto_record_interval_fits_span() {
+        use crate::spk_reader::SpkReader;
+
+        const DAY: f64 = 86400.0;
+        let orbit = |et: f64| {
+            let angle = et / (30.0 * DAY);
+            Ok(SpiceVector3::new(7000.0 * angle.cos(), 7000.0 * angle.sin(), 10.0 * angle))
+        };
+        let span = 20.0 * DAY;
+
+        let records = fit_type2_records(0.0, span, 15, RecordInterval::Auto(8.0 * DAY), orbit).unwrap();
+        assert_eq!(records.len(), 3);
+        assert!((records[2].end - span).abs() < 1e-6);
+
+        let mut spk = new_spk("FITTED").unwrap();
+        add_type2_segment(&mut spk, -90, 399, "J2000", 0.0, span, "AUTO", &records).unwrap();
+        let mut reader = SpkReader::new();
+        reader.load_spk_data("fitted.bsp", &spk.to_bytes().unwrap()).unwrap();
+
+        // Either side of each record boundary and the segment ends
+        let boundary = span / 3.0;
+        for et in [0.0, boundary - 1.0, boundary, boundary + 1.0, 2.0 * boundary, span - 1.0, span] {
+            let state = reader.compute_state(-90, 399, et).unwrap();
+            let expected = orbit(et).unwrap();
+            assert!((state.position - expected).magnitude() < 1e-6, "position error at {}", et);
+        }
+
+        // Shorter than one record
+        let short = fit_type2_records(0.0, 2.0 * DAY, 15, RecordInterval::Auto(8.0 * DAY), orbit).unwrap();
+        assert_eq!(short.len(), 1);
+        assert_eq!((short[0].start, short[0].end), (0.0, 2.0 * DAY));
+
+        // An explicit interval leaves a partial final record past the segment end
+        let explicit = fit_type2_records(0.0, span, 15, RecordInterval::Explicit(8.0 * DAY), orbit).unwrap();
+        assert_eq!(explicit.len(), 3);
+        assert_eq!(explicit[2].end, 24.0 * DAY);
+        let bytes = write_type2("EXPLICIT", -90, 399, "J2000", 0.0, span, &explicit).unwrap();
+        let mut reader = SpkReader::new();
+        reader.load_spk_data("explicit.bsp", &bytes).unwrap();
+        let state = reader.compute_state(-90, 399, span - 1.0).unwrap();
+        assert!((state.position - orbit(span - 1.0).unwrap()).magnitude() < 1e-6);
+
+        assert!(fit_type2_records(0.0, span, 15, RecordInterval::Auto(0.0), orbit).is_err());
+    }
+
+    #[test]
+    fn test_type9_round_trip() {
+        use crate::spk_reader::SpkReader;
+
+        // A cubic trajectory is reproduced exactly by degree 3 interpolation
+        let state = |t: f64| StateVector {
+            position: SpiceVector3::new(1.0e4 + 2.0 * t, -3.0e3 + 1.0e-3 * t * t, 5.0e-7 * t * t * t),
+            velocity: SpiceVector3::new(2.0, 2.0e-3 * t, 1.5e-6 * t * t),
+            light_time: 0.0,
+        };
+        // 150 unequally spaced epochs, enough for a directory entry
+        let epochs: Vec<f64> = (0..150).map(|i| 60.0 * i as f64 + 7.0 * (i as f64 * 0.7).sin()).collect();
+        let states: Vec<(f64, StateVector)> = epochs.iter().map(|&t| (t, state(t))).collect();
+
+        let bytes = write_type9("DISCRETE", -44, 399, "J2000", &states, 3).unwrap();
+        let daf = DafFile::open_read(&bytes).unwrap();
+        assert_eq!(daf.summaries()[0].integers, vec![-44, 399, 1, 9]);
+        assert_eq!(daf.summaries()[0].doubles, vec![epochs[0], epochs[149]]);
+        assert_eq!(daf.array_data(0).unwrap().len(), 7 * 150 + 1 + 2);
+
+        let mut reader = SpkReader::new();
+        reader.load_spk_data("discrete.bsp", &bytes).unwrap();
+        for et in [epochs[0], 12.5, epochs[40], 4321.0, 6001.0, epochs[149]] {
+            let actual = reader.compute_state(-44, 399, et).unwrap();
+            let expected = state(et);
+            assert!((actual.position - expected.position).magnitude() < 1e-6, "position error at {}", et);
+            assert!((actual.velocity - expected.velocity).magnitude() < 1e-9, "velocity error at {}", et);
+        }
+
+        // Corrupt state counts whose word totals overflow
+        for count in [2f64.powi(62), 1e300] {
+            let mut corrupt = daf.array_data(0).unwrap().to_vec();
+            *corrupt.last_mut().unwrap() = count;
+            assert_eq!(crate::spk_reader::type9_state(&corrupt, 10.0).unwrap_err().error_type, SpiceErrorType::InvalidKernelData);
+        }
+
+        assert!(write_type9("LOW", -44, 399, "J2000", &states[..3], 3).is_err());
+        assert!(write_type9("DEGREE", -44, 399, "J2000", &states, 0).is_err());
+        let mut unordered = states[..5].to_vec();
+        unordered.swap(1, 2);
+        assert!(write_type9("ORDER", -44, 399, "J2000", &unordered, 3).unwrap_err().message.contains("increase"));
+    }
+
+    #[test]
+    fn test_append_type9_keeps_existing_segments() {
+        use crate::spk_reader::SpkReader;
+
+        let line = |body: i32| move |t: f64| StateVector {
+            position: SpiceVector3::new(body as f64 + t, 2.0 * t, -t),
+            velocity: SpiceVector3::new(1.0, 2.0, -1.0),
+            light_time: 0.0,
+        };
+        let states_of = |body: i32| -> Vec<(f64, StateVector)> {
+            (0..4).map(|i| (100.0 * i as f64, line(body)(100.0 * i as f64))).collect()
+        };
+
+        let mut base = new_spk("BASE").unwrap();
+        base.add_comments(&["Original comments", "survive appends"]).unwrap();
+        add_type9_segment(&mut base, -501, 399, "J2000", "FIRST", &states_of(-501), 1).unwrap();
+        let bytes = append_type9(&base.to_bytes().unwrap(), -502, 399, "J2000", &states_of(-502), 1).unwrap();
+
+        let daf = DafFile::open_read(&bytes).unwrap();
+        assert_eq!(daf.internal_name(), "BASE");
+        assert_eq!(daf.comments(), &["Original comments", "survive appends"]);
+        assert_eq!(daf.array_count(), 2);
+        let mut reader = SpkReader::new();
+        reader.load_spk_data("appended.bsp", &bytes).unwrap();
+        for body in [-501, -502] {
+            let state = reader.compute_state(body, 399, 150.0).unwrap();
+            assert!((state.position - line(body)(150.0).position).magnitude() < 1e-9);
+        }
+
+        // Fill the first summary record (25 SPK summaries) so the next
+        // append has to start and link a second one
+        let mut full = bytes;
+        for body in -525..=-503 {
+            full = append_type9(&full, body, 399, "J2000", &states_of(body), 1).unwrap();
+        }
+        let full = append_type9(&full, -526, 399, "J2000", &states_of(-526), 1).unwrap();
+        let daf = DafFile::open_read(&full).unwrap();
+        assert_eq!(daf.array_count(), 26);
+        let mut reader = SpkReader::new();
+        reader.load_spk_data("full.bsp", &full).unwrap();
+        for body in [-501, -513, -525, -526] {
+            let state = reader.compute_state(body, 399, 250.0).unwrap();
+            assert!((state.position - line(body)(250.0).position).magnitude() < 1e-9);
+        }
+
+        let ck = DafFile::new("DAF/CK", 2, 6, "CK").unwrap().to_bytes().unwrap();
+        assert!(append_type9(&ck, -502, 399, "J2000", &states_of(-502), 1).is_err());
+    }
+
+    #[test]
+    fn test_comment_area_round_trips() {
+        use crate::spk_reader::SpkReader;
+
+        // Long enough to continue into a second comment record
+        let long_line = "x".repeat(990);
+        let comments = ["Generated by the Type 2 writer tests", "", "  indented: a=1, b=2", long_line.as_str(), "END"];
+        let records = vec![linear_record(0.0, 100.0), linear_record(100.0, 200.0)];
+        let bytes = write_type2_with_comments("COMMENTED", -31, 399, "J2000", 0.0, 200.0, &records, &comments).unwrap();
+
+        let daf = DafFile::open_read(&bytes).unwrap();
+        assert_eq!(daf.comments(), &comments);
+        let mut reader = SpkReader::new();
+        reader.load_spk_data("commented.bsp", &bytes).unwrap();
+        let uncommented = write_type2("PLAIN", -31, 399, "J2000", 0.0, 200.0, &records).unwrap();
+        let mut plain = SpkReader::new();
+        plain.load_spk_data("plain.bsp", &uncommented).unwrap();
+        assert_eq!(
+            reader.compute_state(-31, 399, 150.0).unwrap().position,
+            plain.compute_state(-31, 399, 150.0).unwrap().position
+        );
+        assert!(DafFile::open_read(&uncommented).unwrap().comments().is_empty());
+
+        let too_long = "y".repeat(1001);
+        let err = write_type2_with_comments("LONG", -31, 399, "J2000", 0.0, 200.0, &records, &[&too_long]).unwrap_err();
+        assert!(err.message.contains("1001 characters"));
+        assert!(new_spk("TAB").unwrap().add_comments(&["a\tb"]).is_err());
+    }
+}
@@ -3,9 +3,15 @@
 //! This module provides full equivalency to CSPICE time functions:
 //! - str2et_c → str_to_et() - Parse time strings to Ephemeris Time
 //! - et2utc_c → et_to_utc() - Format Ephemeris Time to UTC strings  
+//! - et2utc_c → et_to_utc_format() - Leap-second aware C/D/J/ISOC/ISOD formats
+//! - et2utc_c → et_to_time_string() - The same formats in UTC, TDB or TDT
 //! - tparse_c → time_parse() - Advanced time string parsing with validation
 //! - timout_c → time_output() - Custom picture string formatting
-//! - deltet_c → delta_et_utc() - Leap second handling
+//! - timout_c → time_output_picture() - Rounded picture formatting with ::UTC/::TDB
+//! - deltet_c → delta_et_utc() - ET - UTC from the leap seconds of the loaded LSK
+//! - unitim_c → unit_time() - Convert between uniform time scales
+//! - scs2e_c / sce2s_c → sclk::sclk_string_to_et() / sclk::et_to_sclk_string()
+//! - et2lst_c → et_to_local_solar_time() - Local true solar time on a body
 //!
 //! Maintains numerical accuracy and format compatibility with original CSPICE.
 
@@ -18,6 +24,8 @@ use crate::foundation::{EphemerisTime, JulianDate, SpiceDouble, SpiceChar, Spice
 use crate::error_handling::{SpiceResult, SpiceError, SpiceErrorType};
 use crate::math_core::constants;
 
+pub mod sclk;
+
 /// Leap second data structure for accurate ET-UTC conversions
 #[derive(Debug, Clone)]
 pub struct LeapSecondData {
@@ -71,17 +79,20 @@ impl ParsedTime {
         }
     }
 
-    /// Convert parsed time to Ephemeris Time seconds past J2000
+    /// Convert parsed time, taken as UTC, to Ephemeris Time seconds past J2000
+    ///
+    /// Leap seconds come from the loaded LSK, or naif0012.tls values if none
+    /// is loaded.
     pub fn to_ephemeris_time(&self) -> SpiceResult<EphemerisTime> {
         // Convert to Julian Date first
         let jd = self.to_julian_date()?;
         
-        // Convert JD to seconds past J2000 epoch
+        // Calendar seconds past the J2000 epoch, without leap seconds
         let j2000_jd = 2451545.0; // J2000.0 epoch
         let days_since_j2000 = jd - j2000_jd;
-        let seconds_since_j2000 = days_since_j2000 * 86400.0;
+        let utc_seconds = days_since_j2000 * 86400.0;
         
-        Ok(EphemerisTime::new(seconds_since_j2000))
+        Ok(EphemerisTime::new(leap_seconds()?.utc_to_et(utc_seconds)))
     }
 
     /// Convert to Julian Date
@@ -109,16 +120,173 @@ const MONTH_ABBREV: &[&str] = &[
     "JUL", "AUG", "SEP", "OCT", "NOV", "DEC"
 ];
 
-/// Comprehensive leap second data (approximation for now)
-/// In production, this would be loaded from LSK kernels
-fn get_leap_second_data() -> Vec<LeapSecondData> {
-    vec![
-        LeapSecondData { epoch_et: -1577923200.0, offset: 63.184 }, // 1950
-        LeapSecondData { epoch_et: 0.0, offset: 64.184 },           // J2000
-        LeapSecondData { epoch_et: 315532800.0, offset: 65.184 },   // 2010
-        LeapSecondData { epoch_et: 631152000.0, offset: 66.184 },   // 2020
-        LeapSecondData { epoch_et: 946684800.0, offset: 67.184 },   // 2030
-    ]
+/// TDB - TDT periodic term constants (standard LSK values)
+const DELTET_K: SpiceDouble = 1.657e-3;
+const DELTET_EB: SpiceDouble = 1.671e-2;
+const DELTET_M: [SpiceDouble; 2] = [6.239996, 1.99096871e-7];
+
+/// TDT - TAI in seconds
+const TDT_MINUS_TAI: SpiceDouble = 32.184;
+
+/// TAI - UTC leap second table as (year, month, seconds) effective at 00:00 UTC
+/// on the first day of that month (DELTET/DELTA_AT in naif0012.tls)
+const DELTA_AT: &[(SpiceInt, SpiceInt, SpiceInt)] = &[
+    (1972, 1, 10), (1972, 7, 11), (1973, 1, 12), (1974, 1, 13), (1975, 1, 14),
+    (1976, 1, 15), (1977, 1, 16), (1978, 1, 17), (1979, 1, 18), (1980, 1, 19),
+    (1981, 7, 20), (1982, 7, 21), (1983, 7, 22), (1985, 7, 23), (1988, 1, 24),
+    (1990, 1, 25), (1991, 1, 26), (1992, 7, 27), (1993, 7, 28), (1994, 7, 29),
+    (1996, 1, 30), (1997, 7, 31), (1999, 1, 32), (2006, 1, 33), (2009, 1, 34),
+    (2012, 7, 35), (2015, 7, 36), (2017, 1, 37),
+];
+
+/// Leap seconds and TDB - TDT constants, the `DELTET/*` variables of an LSK
+#[derive(Debug, Clone, PartialEq)]
+struct LeapSeconds {
+    /// TDT - TAI in seconds (DELTET/DELTA_T_A)
+    delta_t_a: SpiceDouble,
+    /// Amplitude of the periodic TDB - TDT term (DELTET/K)
+    k: SpiceDouble,
+    /// Eccentricity of the Earth-Moon barycenter orbit (DELTET/EB)
+    eb: SpiceDouble,
+    /// Mean anomaly at J2000 and its rate (DELTET/M)
+    m: [SpiceDouble; 2],
+    /// TAI - UTC and the start of its validity as UTC seconds past
+    /// 2000-01-01T00:00:00, in ascending order (DELTET/DELTA_AT)
+    delta_at: Vec<(SpiceInt, i128)>,
+}
+
+impl LeapSeconds {
+    /// The values of naif0012.tls, used when no LSK is loaded
+    fn standard() -> SpiceResult<Self> {
+        let delta_at = DELTA_AT.iter()
+            .map(|&(year, month, offset)| Ok((offset, leap_boundary_utc(year, month)?)))
+            .collect::<SpiceResult<Vec<_>>>()?;
+        Ok(LeapSeconds { delta_t_a: TDT_MINUS_TAI, k: DELTET_K, eb: DELTET_EB, m: DELTET_M, delta_at })
+    }
+
+    /// The values in `pool`, or `None` if it holds no DELTET/DELTA_AT
+    fn from_pool(pool: &crate::kernel_pool::KernelPool) -> SpiceResult<Option<Self>> {
+        if !pool.contains_variable("DELTET/DELTA_AT") {
+            return Ok(None);
+        }
+
+        let (table, _) = pool.get_double("DELTET/DELTA_AT", 0, usize::MAX)?;
+        if table.is_empty() || table.len() % 2 != 0 {
+            return Err(SpiceError::new(
+                SpiceErrorType::InvalidFormat,
+                format!("DELTET/DELTA_AT must hold (TAI-UTC, epoch) pairs; found {} values", table.len()),
+            ));
+        }
+        // Epochs are seconds past J2000, which is noon
+        let delta_at = table.chunks_exact(2)
+            .map(|pair| (pair[0].round() as SpiceInt, (pair[1] + 43200.0).round() as i128))
+            .collect();
+
+        let m = pool.get_required_doubles("DELTET/M", 2)?;
+        Ok(Some(LeapSeconds {
+            delta_t_a: pool.get_single_double("DELTET/DELTA_T_A")?,
+            k: pool.get_single_double("DELTET/K")?,
+            eb: pool.get_single_double("DELTET/EB")?,
+            m: [m[0], m[1]],
+            delta_at,
+        }))
+    }
+
+    /// Periodic TDB - TDT difference in seconds
+    fn tdb_minus_tdt(&self, seconds: SpiceDouble) -> SpiceDouble {
+        let m = self.m[0] + self.m[1] * seconds;
+        self.k * (m + self.eb * m.sin()).sin()
+    }
+
+    /// TDT seconds past J2000 for an epoch given in TDB seconds past J2000
+    ///
+    /// Inverts `tdt + tdb_minus_tdt(tdt)`; the periodic term changes by less than
+    /// 1e-9 s per second, so the fixed-point iteration settles in a few steps.
+    fn tdb_to_tdt(&self, tdb: SpiceDouble) -> SpiceDouble {
+        let mut tdt = tdb;
+        for _ in 0..4 {
+            tdt = tdb - self.tdb_minus_tdt(tdt);
+        }
+        tdt
+    }
+
+    /// TAI seconds past J2000 of an epoch
+    fn et_to_tai(&self, et: SpiceDouble) -> SpiceDouble {
+        et - self.delta_t_a - self.tdb_minus_tdt(et)
+    }
+
+    /// TAI - UTC in effect at a TAI epoch (seconds past J2000)
+    fn tai_minus_utc(&self, tai: SpiceDouble) -> SpiceInt {
+        let mut delta_at = self.delta_at[0].0;
+        for &(offset, boundary_utc) in &self.delta_at {
+            let boundary_tai = (boundary_utc + offset as i128) as SpiceDouble - 43200.0;
+            if tai < boundary_tai {
+                break;
+            }
+            delta_at = offset;
+        }
+        delta_at
+    }
+
+    /// ET of a UTC calendar epoch counted as seconds past J2000 without
+    /// leap seconds
+    fn utc_to_et(&self, utc: SpiceDouble) -> SpiceDouble {
+        let mut delta_at = self.delta_at[0].0;
+        for &(offset, boundary_utc) in &self.delta_at {
+            if utc + 43200.0 < boundary_utc as SpiceDouble {
+                break;
+            }
+            delta_at = offset;
+        }
+        let tdt = utc + delta_at as SpiceDouble + self.delta_t_a;
+        tdt + self.tdb_minus_tdt(tdt)
+    }
+
+    /// ET - UTC at an epoch
+    fn delta_et_utc(&self, et: SpiceDouble) -> SpiceDouble {
+        let tai = self.et_to_tai(et);
+        et - tai + self.tai_minus_utc(tai) as SpiceDouble
+    }
+}
+
+/// Leap seconds from the kernel pool if an LSK is loaded, else the
+/// naif0012.tls values
+fn leap_seconds() -> SpiceResult<LeapSeconds> {
+    if crate::kernel_pool::is_pool_initialized() {
+        if let Some(leaps) = crate::kernel_pool::with_global_pool(LeapSeconds::from_pool)? {
+            return Ok(leaps);
+        }
+    }
+    LeapSeconds::standard()
+}
+
+/// Seconds past J2000 of a kernel pool date such as `1972-JAN-1` or
+/// `2000-JAN-01/12:00:00`, counted without leap seconds
+pub(crate) fn pool_date_seconds(date: &str) -> SpiceResult<SpiceDouble> {
+    let invalid = || SpiceError::new(
+        SpiceErrorType::InvalidTime,
+        format!("Invalid kernel pool date: @{}", date),
+    );
+
+    let (day_part, time_part) = date.split_once('/').unwrap_or((date, ""));
+    let fields: Vec<&str> = day_part.split('-').collect();
+    if fields.len() != 3 {
+        return Err(invalid());
+    }
+    let year: SpiceInt = fields[0].parse().map_err(|_| invalid())?;
+    let month = match fields[1].parse::<SpiceInt>() {
+        Ok(month) => month,
+        Err(_) => month_name_to_number(fields[1])?,
+    };
+    let day: SpiceInt = fields[2].parse().map_err(|_| invalid())?;
+
+    let mut clock = [0.0; 3];
+    for (slot, field) in clock.iter_mut().zip(time_part.split(':').filter(|f| !f.is_empty())) {
+        *slot = field.parse().map_err(|_| invalid())?;
+    }
+
+    let jd = calendar_to_julian_date(year, month, day, 0, 0, 0.0, &CalendarType::Gregorian)?;
+    Ok((jd - J2000_JULIAN_DATE) * 86400.0 + clock[0] * 3600.0 + clock[1] * 60.0 + clock[2])
 }
 
 // ============================================================================
@@ -261,29 +429,355 @@ pub fn time_output(et: EphemerisTime, picture: &str) -> SpiceResult<SpiceChar> {
     Ok(result)
 }
 
+/// Format Ephemeris Time using a picture string (equivalent to timout_c)
+///
+/// Supported tokens are `YYYY`, `MM`, `MON`, `DD`, `DOY`, `HR`, `MN` and `SC`,
+/// where `SC` may be followed by `.###` to request fractional seconds. The
+/// markers `::UTC` (the default) and `::TDB` select the output time system and
+/// are not copied to the output. The time is rounded to the last digit shown
+/// before being split into fields, so seconds roll over into minutes, hours
+/// and days; UTC output honours leap seconds (`23:59:60`).
+pub fn time_output_picture(et: SpiceDouble, picture: &str) -> SpiceResult<String> {
+    let tokens = tokenize_picture(picture);
+
+    let tdb = tokens.contains(&PictureToken::Tdb);
+    let precision = tokens.iter().filter_map(|t| match t {
+        PictureToken::Second(digits) => Some(*digits),
+        _ => None,
+    }).max().unwrap_or(0);
+
+    let fields = if tdb {
+        tdb_fields(et, precision)?
+    } else {
+        utc_fields(&leap_seconds()?, et, precision)?
+    };
+
+    let mut result = String::new();
+    for token in &tokens {
+        match token {
+            PictureToken::Literal(c) => result.push(*c),
+            PictureToken::Year => result.push_str(&format!("{:04}", fields.year)),
+            PictureToken::Month => result.push_str(&format!("{:02}", fields.month)),
+            PictureToken::MonthAbbrev => result.push_str(MONTH_ABBREV[(fields.month - 1) as usize]),
+            PictureToken::Day => result.push_str(&format!("{:02}", fields.day)),
+            PictureToken::DayOfYear => {
+                let doy = month_day_to_day_of_year(fields.year, fields.month, fields.day)?;
+                result.push_str(&format!("{:03}", doy));
+            },
+            PictureToken::Hour => result.push_str(&format!("{:02}", fields.hour)),
+            PictureToken::Minute => result.push_str(&format!("{:02}", fields.minute)),
+            PictureToken::Second(digits) => {
+                // Drop unrequested digits from the finest rounding in use
+                let scale = 10_i128.pow(precision - digits);
+                let units = fields.second_units / scale;
+                let per_second = 10_i128.pow(*digits);
+                result.push_str(&format!("{:02}", units / per_second));
+                if *digits > 0 {
+                    result.push_str(&format!(".{:0width$}", units % per_second, width = *digits as usize));
+                }
+            },
+            PictureToken::Utc | PictureToken::Tdb => {},
+        }
+    }
+
+    Ok(result.trim_end().to_string())
+}
+
+/// Format Ephemeris Time as a leap-second aware UTC string (equivalent to et2utc_c)
+///
+/// Formats, shown for `precision` 3:
+/// - "C": "2000 JAN 01 11:58:55.816"
+/// - "D": "2000-001 // 11:58:55.816"
+/// - "J": "JD 2451544.999" (`precision` applies to the day fraction)
+/// - "ISOC": "2000-01-01T11:58:55.816"
+/// - "ISOD": "2000-001T11:58:55.816"
+pub fn et_to_utc_format(et: SpiceDouble, format: &str, precision: SpiceInt) -> SpiceResult<String> {
+    let precision = precision.clamp(0, 9) as u32;
+    let leaps = leap_seconds()?;
+    let fields = utc_fields(&leaps, et, precision)?;
+    let julian = || Ok(J2000_JULIAN_DATE + (et - leaps.delta_et_utc(et)) / 86400.0);
+    format_fields(&fields, format, precision, julian)
+}
+
+/// Output time systems of `et_to_time_string`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeSystem {
+    /// Coordinated Universal Time; needs leap seconds from an LSK
+    Utc,
+    /// Barycentric Dynamical Time, the time system of ET itself
+    Tdb,
+    /// Terrestrial Dynamical Time
+    Tdt,
+}
+
+/// Format Ephemeris Time as a calendar string in a chosen time system
+///
+/// `format` and `precision` are those of `et_to_utc_format`. TDB and TDT are
+/// uniform scales and need no kernels, so ET 0 is "2000 JAN 01 12:00:00" TDB.
+/// UTC output requires leap seconds (`DELTET/DELTA_AT`) in the kernel pool.
+pub fn et_to_time_string(et: SpiceDouble, system: TimeSystem, format: &str, precision: SpiceInt) -> SpiceResult<String> {
+    let leap_seconds_loaded = crate::kernel_pool::is_pool_initialized()
+        && crate::kernel_pool::exists_in_pool("DELTET/DELTA_AT")?;
+    time_string(et, system, format, precision, leap_seconds_loaded)
+}
+
+/// `et_to_time_string` with the availability of leap seconds given
+fn time_string(
+    et: SpiceDouble,
+    system: TimeSystem,
+    format: &str,
+    precision: SpiceInt,
+    leap_seconds_loaded: bool,
+) -> SpiceResult<String> {
+    let uniform = match system {
+        TimeSystem::Utc if !leap_seconds_loaded => {
+            return Err(SpiceError::new(
+                SpiceErrorType::InsufficientData,
+                "UTC output requires leap seconds; load an LSK or request TDB or TDT".into()
+            ));
+        },
+        TimeSystem::Utc => return et_to_utc_format(et, format, precision),
+        TimeSystem::Tdb => et,
+        TimeSystem::Tdt => leap_seconds()?.tdb_to_tdt(et),
+    };
+
+    let precision = precision.clamp(0, 9) as u32;
+    let fields = tdb_fields(uniform, precision)?;
+    format_fields(&fields, format, precision, || Ok(J2000_JULIAN_DATE + uniform / 86400.0))
+}
+
+/// Render rounded calendar fields in one of the et2utc_c formats, with the
+/// Julian date for "J" supplied on demand
+fn format_fields<F>(fields: &RoundedFields, format: &str, precision: u32, julian_date: F) -> SpiceResult<String>
+where
+    F: FnOnce() -> SpiceResult<SpiceDouble>,
+{
+    let doy = month_day_to_day_of_year(fields.year, fields.month, fields.day)?;
+
+    let per_second = 10_i128.pow(precision);
+    let mut seconds = format!("{:02}", fields.second_units / per_second);
+    if precision > 0 {
+        seconds.push_str(&format!(".{:0width$}", fields.second_units % per_second, width = precision as usize));
+    }
+    let clock = format!("{:02}:{:02}:{}", fields.hour, fields.minute, seconds);
+
+    match format.trim().to_uppercase().as_str() {
+        "C" => Ok(format!(
+            "{:04} {} {:02} {}",
+            fields.year, MONTH_ABBREV[(fields.month - 1) as usize], fields.day, clock
+        )),
+        "D" => Ok(format!("{:04}-{:03} // {}", fields.year, doy, clock)),
+        "J" => Ok(format!("JD {:.prec$}", julian_date()?, prec = precision as usize)),
+        "ISOC" => Ok(format!("{:04}-{:02}-{:02}T{}", fields.year, fields.month, fields.day, clock)),
+        "ISOD" => Ok(format!("{:04}-{:03}T{}", fields.year, doy, clock)),
+        _ => Err(SpiceError::new(
+            SpiceErrorType::InvalidArgument,
+            format!("Unknown time format: {}", format),
+        )),
+    }
+}
+
+/// Picture string elements understood by `time_output_picture`
+#[derive(Debug, Clone, PartialEq)]
+enum PictureToken {
+    Literal(char),
+    Year,
+    Month,
+    MonthAbbrev,
+    Day,
+    DayOfYear,
+    Hour,
+    Minute,
+    Second(u32),
+    Utc,
+    Tdb,
+}
+
+/// Split a picture string into tokens, matching the longest token first
+fn tokenize_picture(picture: &str) -> Vec<PictureToken> {
+    const KEYWORDS: &[(&str, PictureToken)] = &[
+        ("::UTC", PictureToken::Utc),
+        ("::TDB", PictureToken::Tdb),
+        ("YYYY", PictureToken::Year),
+        ("MON", PictureToken::MonthAbbrev),
+        ("MM", PictureToken::Month),
+        ("DOY", PictureToken::DayOfYear),
+        ("DD", PictureToken::Day),
+        ("HR", PictureToken::Hour),
+        ("MN", PictureToken::Minute),
+    ];
+
+    let chars: Vec<char> = picture.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    'outer: while i < chars.len() {
+        let rest: String = chars[i..].iter().collect();
+        for (keyword, token) in KEYWORDS {
+            if rest.starts_with(keyword) {
+                tokens.push(token.clone());
+                i += keyword.chars().count();
+                continue 'outer;
+            }
+        }
+        if rest.starts_with("SC") {
+            i += 2;
+            let mut digits = 0;
+            if i + 1 < chars.len() && chars[i] == '.' && chars[i + 1] == '#' {
+                i += 1;
+                while i < chars.len() && chars[i] == '#' && digits < 9 {
+                    digits += 1;
+                    i += 1;
+                }
+            }
+            tokens.push(PictureToken::Second(digits));
+            continue;
+        }
+        tokens.push(PictureToken::Literal(chars[i]));
+        i += 1;
+    }
+
+    tokens
+}
+
+/// Calendar fields with seconds held as integer units of the output precision
+struct RoundedFields {
+    year: SpiceInt,
+    month: SpiceInt,
+    day: SpiceInt,
+    hour: SpiceInt,
+    minute: SpiceInt,
+    second_units: i128,
+}
+
+/// Build calendar fields from a day offset from 2000-01-01 and the time of day
+fn fields_from_day(day_offset: i128, hour: SpiceInt, minute: SpiceInt, second_units: i128) -> SpiceResult<RoundedFields> {
+    // Noon of the day avoids any rounding at the day boundary
+    let (year, month, day, _, _, _) = julian_date_to_calendar(2451545.0 + day_offset as SpiceDouble)?;
+    Ok(RoundedFields { year, month, day, hour, minute, second_units })
+}
+
+/// Split a count of rounded units past 2000-01-01T00:00:00 into fields
+fn split_uniform_units(units: i128, scale: i128) -> SpiceResult<RoundedFields> {
+    let per_day = 86400 * scale;
+    let day_offset = units.div_euclid(per_day);
+    let in_day = units.rem_euclid(per_day);
+    let hour = (in_day / (3600 * scale)) as SpiceInt;
+    let minute = ((in_day % (3600 * scale)) / (60 * scale)) as SpiceInt;
+    fields_from_day(day_offset, hour, minute, in_day % (60 * scale))
+}
+
+/// TDB calendar fields of an epoch, rounded to `precision` decimal places
+fn tdb_fields(et: SpiceDouble, precision: u32) -> SpiceResult<RoundedFields> {
+    let scale = 10_i128.pow(precision);
+    let units = ((et + 43200.0) * scale as SpiceDouble).round() as i128;
+    split_uniform_units(units, scale)
+}
+
+/// Start of each DELTA_AT entry as UTC seconds past 2000-01-01T00:00:00
+fn leap_boundary_utc(year: SpiceInt, month: SpiceInt) -> SpiceResult<i128> {
+    let boundary_day = calendar_to_julian_date(year, month, 1, 12, 0, 0.0, &CalendarType::Gregorian)? - 2451545.0;
+    Ok(boundary_day.round() as i128 * 86400)
+}
+
+/// UTC calendar fields of an epoch, rounded to `precision` decimal places
+fn utc_fields(leaps: &LeapSeconds, et: SpiceDouble, precision: u32) -> SpiceResult<RoundedFields> {
+    let scale = 10_i128.pow(precision);
+    let tai = leaps.et_to_tai(et);
+    // TAI counted from 2000-01-01T00:00:00 so leap boundaries fall on whole units
+    let tai_units = ((tai + 43200.0) * scale as SpiceDouble).round() as i128;
+
+    let mut delta_at = leaps.delta_at[0].0 as i128;
+    for &(offset, boundary_utc) in &leaps.delta_at {
+        let boundary_tai = (boundary_utc + offset as i128) * scale;
+
+        if tai_units >= boundary_tai {
+            delta_at = offset as i128;
+        } else {
+            // The inserted second is reported as 23:59:60 of the previous day
+            if offset as i128 > delta_at && tai_units >= boundary_tai - scale {
+                let second_units = 60 * scale + tai_units - (boundary_tai - scale);
+                return fields_from_day(boundary_utc / 86400 - 1, 23, 59, second_units);
+            }
+            break;
+        }
+    }
+
+    split_uniform_units(tai_units - delta_at * scale, scale)
+}
+
 /// Calculate ET-UTC difference in seconds (equivalent to deltet_c)
 /// 
-/// This accounts for leap seconds and relativistic effects.
-/// In production, this would load data from LSK kernels.
+/// The sum of TDT - TAI, TAI - UTC in effect at `et` and the periodic
+/// TDB - TDT term. The `DELTET/*` variables of a loaded LSK are used, or
+/// naif0012.tls values if none is loaded.
 pub fn delta_et_utc(et: EphemerisTime) -> SpiceResult<SpiceDouble> {
-    let leap_data = get_leap_second_data();
-    
-    // Find the appropriate leap second entry
-    let mut offset = 64.184; // Default J2000 offset
-    
-    for data in leap_data.iter() {
-        if et.seconds() >= data.epoch_et {
-            offset = data.offset;
-        } else {
-            break;
+    Ok(leap_seconds()?.delta_et_utc(et.seconds()))
+}
+
+/// Julian date of the J2000 epoch
+const J2000_JULIAN_DATE: SpiceDouble = 2451545.0;
+
+/// Uniform time scales accepted by `unit_time`
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum UniformScale {
+    Tai,
+    Tdt,
+    Tdb,
+    JulianTdb,
+    JulianTdt,
+}
+
+impl UniformScale {
+    fn from_name(name: &str) -> SpiceResult<Self> {
+        match name.trim().to_uppercase().as_str() {
+            "TAI" => Ok(Self::Tai),
+            "TDT" | "TT" => Ok(Self::Tdt),
+            "TDB" | "ET" => Ok(Self::Tdb),
+            "JDTDB" | "JED" => Ok(Self::JulianTdb),
+            "JDTDT" => Ok(Self::JulianTdt),
+            _ => Err(SpiceError::new(
+                SpiceErrorType::InvalidArgument,
+                format!("Unknown time scale '{}'", name)
+            )),
         }
     }
+}
+
+/// Convert an epoch between uniform time scales (equivalent to unitim_c)
+///
+/// Recognized scales are TAI, TDT (or TT), TDB (or ET), JDTDB (or JED) and
+/// JDTDT. Seconds-based scales count from J2000; the JD scales are Julian dates.
+/// TDT - TAI and the TDB - TDT term come from the loaded LSK, as in `str_to_et`.
+pub fn unit_time(epoch: SpiceDouble, from: &str, to: &str) -> SpiceResult<SpiceDouble> {
+    let from = UniformScale::from_name(from)?;
+    let to = UniformScale::from_name(to)?;
+    if from == to {
+        return Ok(epoch);
+    }
 
-    // Add small periodic variation to approximate relativistic effects
-    let years_since_j2000 = et.seconds() / constants::JULIAN_YEAR;
-    let periodic_correction = 0.001657 * (years_since_j2000 * 0.0172).sin();
-    
-    Ok(offset + periodic_correction)
+    let leaps = leap_seconds()?;
+    let tdb = match from {
+        UniformScale::Tai => {
+            let tdt = epoch + leaps.delta_t_a;
+            tdt + leaps.tdb_minus_tdt(tdt)
+        },
+        UniformScale::Tdt => epoch + leaps.tdb_minus_tdt(epoch),
+        UniformScale::Tdb => epoch,
+        UniformScale::JulianTdb => (epoch - J2000_JULIAN_DATE) * 86400.0,
+        UniformScale::JulianTdt => {
+            let tdt = (epoch - J2000_JULIAN_DATE) * 86400.0;
+            tdt + leaps.tdb_minus_tdt(tdt)
+        },
+    };
+
+    Ok(match to {
+        UniformScale::Tai => leaps.tdb_to_tdt(tdb) - leaps.delta_t_a,
+        UniformScale::Tdt => leaps.tdb_to_tdt(tdb),
+        UniformScale::Tdb => tdb,
+        UniformScale::JulianTdb => J2000_JULIAN_DATE + tdb / 86400.0,
+        UniformScale::JulianTdt => J2000_JULIAN_DATE + leaps.tdb_to_tdt(tdb) / 86400.0,
+    })
 }
 
 // ============================================================================
@@ -760,6 +1254,118 @@ fn julian_date_to_calendar(jd: SpiceDouble) -> SpiceResult<(SpiceInt, SpiceInt,
     Ok((year, month, day, hour, minute, second))
 }
 
+/// First Julian Day Number of the Gregorian calendar (1582 October 15)
+const GREGORIAN_REFORM_JDN: i64 = 2299161;
+
+/// Whether `year` (astronomical numbering, 0 = 1 BC) is a leap year in the
+/// Gregorian or Julian calendar
+fn is_leap_year_in(year: SpiceInt, gregorian: bool) -> bool {
+    if gregorian {
+        is_leap_year(year)
+    } else {
+        year.rem_euclid(4) == 0
+    }
+}
+
+/// Julian Day Number of a calendar date, valid for all years after -4800
+fn julian_day_number(year: SpiceInt, month: SpiceInt, day: SpiceInt, gregorian: bool) -> i64 {
+    let a = ((14 - month) / 12) as i64;
+    let y = year as i64 + 4800 - a;
+    let m = month as i64 + 12 * a - 3;
+    let days = day as i64 + (153 * m + 2) / 5 + 365 * y + y.div_euclid(4);
+    if gregorian {
+        days - y.div_euclid(100) + y.div_euclid(400) - 32045
+    } else {
+        days - 32083
+    }
+}
+
+impl JulianDate {
+    /// Julian date at the start of a calendar day
+    ///
+    /// `year` uses astronomical numbering (0 = 1 BC, -1 = 2 BC) and `day` may
+    /// carry a fraction of a day. In the `Mixed` calendar, dates up to
+    /// 1582 October 4 are Julian, dates from 1582 October 15 are Gregorian,
+    /// and the ten days between do not exist.
+    pub fn from_calendar(
+        year: SpiceInt,
+        month: SpiceInt,
+        day: SpiceDouble,
+        calendar: CalendarType,
+    ) -> SpiceResult<JulianDate> {
+        if !(1..=12).contains(&month) {
+            return Err(SpiceError::new(
+                SpiceErrorType::InvalidTime,
+                format!("Invalid month: {}", month),
+            ));
+        }
+
+        let whole_day = day.floor() as SpiceInt;
+        let gregorian = match calendar {
+            CalendarType::Gregorian => true,
+            CalendarType::Julian => false,
+            CalendarType::Mixed => {
+                let date = (year, month, whole_day);
+                if date > (1582, 10, 4) && date < (1582, 10, 15) {
+                    return Err(SpiceError::new(
+                        SpiceErrorType::InvalidTime,
+                        format!("Date 1582-10-{:02} falls in the Gregorian reform gap", whole_day),
+                    ));
+                }
+                date >= (1582, 10, 15)
+            }
+        };
+
+        if !day.is_finite() || whole_day < 1 || whole_day > month_length(month, is_leap_year_in(year, gregorian)) {
+            return Err(SpiceError::new(
+                SpiceErrorType::InvalidTime,
+                format!("Invalid day: {} for month {}", day, month),
+            ));
+        }
+
+        let jdn = julian_day_number(year, month, whole_day, gregorian);
+        Ok(JulianDate(jdn as SpiceDouble - 0.5 + (day - whole_day as SpiceDouble)))
+    }
+
+    /// Calendar year, month and (fractional) day of this Julian date
+    ///
+    /// The inverse of `from_calendar`; `Mixed` switches to the Gregorian
+    /// calendar at JD 2299160.5.
+    pub fn to_calendar(&self, calendar: CalendarType) -> SpiceResult<(SpiceInt, SpiceInt, SpiceDouble)> {
+        if !self.0.is_finite() {
+            return Err(SpiceError::new(
+                SpiceErrorType::InvalidTime,
+                format!("Invalid Julian date: {}", self.0),
+            ));
+        }
+
+        let jdn = (self.0 + 0.5).floor() as i64;
+        let fraction = self.0 + 0.5 - jdn as SpiceDouble;
+        let gregorian = match calendar {
+            CalendarType::Gregorian => true,
+            CalendarType::Julian => false,
+            CalendarType::Mixed => jdn >= GREGORIAN_REFORM_JDN,
+        };
+
+        // Estimate the year, then settle it against the first day of the year
+        let mut year = ((jdn - 1721058) as SpiceDouble / 365.2425).floor() as SpiceInt;
+        while julian_day_number(year + 1, 1, 1, gregorian) <= jdn {
+            year += 1;
+        }
+        while julian_day_number(year, 1, 1, gregorian) > jdn {
+            year -= 1;
+        }
+
+        let doy = (jdn - julian_day_number(year, 1, 1, gregorian) + 1) as SpiceInt;
+        let (month, day) = month_and_day(doy, is_leap_year_in(year, gregorian)).ok_or_else(|| SpiceError::new(
+            SpiceErrorType::InvalidTime,
+            "Internal error in day-of-year conversion".into(),
+        ))?;
+
+        Ok((year, month, day as SpiceDouble + fraction))
+    }
+}
+
 /// Check if year is leap year with full historical accuracy
 pub fn is_leap_year(year: SpiceInt) -> bool {
     if year % 400 == 0 {
@@ -775,10 +1381,15 @@ pub fn is_leap_year(year: SpiceInt) -> bool {
 
 /// Get number of days in month
 fn days_in_month(year: SpiceInt, month: SpiceInt) -> SpiceInt {
+    month_length(month, is_leap_year(year))
+}
+
+/// Number of days in a month of a leap or common year
+fn month_length(month: SpiceInt, leap: bool) -> SpiceInt {
     match month {
         1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
         4 | 6 | 9 | 11 => 30,
-        2 => if is_leap_year(year) { 29 } else { 28 },
+        2 => if leap { 29 } else { 28 },
         _ => 0,
     }
 }
@@ -818,21 +1429,25 @@ pub fn day_of_year_to_month_day(year: SpiceInt, doy: SpiceInt) -> SpiceResult<(S
         ));
     }
     
+    month_and_day(doy, is_leap_year(year)).ok_or_else(|| SpiceError::new(
+        SpiceErrorType::InvalidTime,
+        "Internal error in day-of-year conversion".into(),
+    ))
+}
+
+/// Month and day of month of a day of year, for a leap or common year
+fn month_and_day(doy: SpiceInt, leap: bool) -> Option<(SpiceInt, SpiceInt)> {
     let mut remaining_days = doy;
     
     for month in 1..=12 {
-        let days_this_month = days_in_month(year, month);
+        let days_this_month = month_length(month, leap);
         if remaining_days <= days_this_month {
-            return Ok((month, remaining_days));
+            return Some((month, remaining_days));
         }
         remaining_days -= days_this_month;
     }
     
-    // Should never reach here with valid input
-    Err(SpiceError::new(
-        SpiceErrorType::InvalidTime,
-        "Internal error in day-of-year conversion".into(),
-    ))
+    None
 }
 
 /// Convert month and day to day of year
@@ -919,10 +1534,104 @@ fn validate_and_complete_parsed_time(parsed: &mut ParsedTime) -> SpiceResult<()>
     };
     
     parsed.era = if parsed.year > 0 { Era::AD } else { Era::BC };
-    
+
     Ok(())
 }
 
+// ============================================================================
+// TIME WINDOWS
+// ============================================================================
+
+/// Set of disjoint, sorted ET intervals (closed on both ends)
+///
+/// The same type as `foundation::SpiceWindow`: overlapping or touching
+/// intervals merge, so a window never reports a zero-length gap between
+/// adjacent intervals.
+pub type TimeWindow = crate::foundation::SpiceWindow;
+
+// ============================================================================
+// LOCAL SOLAR TIME
+// ============================================================================
+
+/// Local true solar time at a surface longitude (equivalent to et2lst_c)
+///
+/// `longitude` is in radians; `coord_type` is "PLANETOCENTRIC" (positive east)
+/// or "PLANETOGRAPHIC" (in the body's sense, as for `planetographic_to_rectangular`:
+/// positive west for prograde rotators other than the Earth, Moon and Sun,
+/// unless `BODY<code>_PGR_POSITIVE_LON` says otherwise). Returns hours, minutes,
+/// seconds and the 24-hour ("HH:MM:SS") and 12-hour ("HH:MM:SS A.M.") strings,
+/// where an hour is 1/24 of the body's solar day. The rotation sense comes
+/// from the sign of the `BODY<code>_PM` rate.
+pub fn et_to_local_solar_time(
+    et: SpiceDouble,
+    body: SpiceInt,
+    longitude: SpiceDouble,
+    coord_type: &str,
+) -> SpiceResult<(SpiceInt, SpiceInt, SpiceInt, String, String)> {
+    let east_longitude = match coord_type.trim().to_uppercase().as_str() {
+        "PLANETOCENTRIC" => longitude,
+        "PLANETOGRAPHIC" => {
+            if crate::coordinates::planetographic_east_positive(body)? { longitude } else { -longitude }
+        },
+        other => return Err(SpiceError::new(
+            SpiceErrorType::InvalidArgument,
+            format!("Unknown coordinate type '{}'", other)
+        )),
+    };
+
+    // It is always noon on the Sun
+    if body == 10 {
+        return Ok(local_solar_time(0.0, 0.0));
+    }
+
+    // Apparent Sun position as seen from the body, in the body-fixed frame.
+    // The frame is centered on the observer, so it is evaluated at `et`
+    // rather than at the light-time corrected epoch.
+    let sun = crate::ephemeris::ephemeris_state(
+        "SUN",
+        EphemerisTime::new(et),
+        "J2000",
+        "LT+S",
+        &body.to_string(),
+    )?;
+    let rotation = crate::coordinates::body_fixed_rotation(body, et)?;
+    let sun_fixed = rotation.multiply_vector(&sun.position);
+    let sun_longitude = sun_fixed.y().atan2(sun_fixed.x());
+
+    // On a body rotating retrograde the Sun moves east across the sky, so the
+    // hour angle runs the other way
+    let pm = crate::body_data::body_data(&body.to_string(), "PM")?;
+    if pm.get(1).is_some_and(|&rate| rate < 0.0) {
+        return Ok(local_solar_time(sun_longitude, east_longitude));
+    }
+    Ok(local_solar_time(east_longitude, sun_longitude))
+}
+
+/// Local solar time from the east longitude and the sub-solar longitude
+fn local_solar_time(
+    east_longitude: SpiceDouble,
+    sun_longitude: SpiceDouble,
+) -> (SpiceInt, SpiceInt, SpiceInt, String, String) {
+    // Local noon is where the hour angle of the Sun is zero
+    let hour_angle = (east_longitude - sun_longitude + constants::PI).rem_euclid(constants::TWO_PI);
+    let total_seconds = hour_angle * 86400.0 / constants::TWO_PI;
+
+    let hours = ((total_seconds / 3600.0) as SpiceInt).min(23);
+    let minutes = (((total_seconds - hours as SpiceDouble * 3600.0) / 60.0) as SpiceInt).min(59);
+    let seconds = ((total_seconds - hours as SpiceDouble * 3600.0 - minutes as SpiceDouble * 60.0) as SpiceInt).min(59);
+
+    let time = format!("{:02}:{:02}:{:02}", hours, minutes, seconds);
+    let (ampm_hour, suffix) = match hours {
+        0 => (12, "A.M."),
+        1..=11 => (hours, "A.M."),
+        12 => (12, "P.M."),
+        _ => (hours - 12, "P.M."),
+    };
+    let ampm = format!("{:02}:{:02}:{:02} {}", ampm_hour, minutes, seconds, suffix);
+
+    (hours, minutes, seconds, time, ampm)
+}
+
 // ============================================================================
 // COMPREHENSIVE TESTS
 // ============================================================================
@@ -932,6 +1641,49 @@ mod tests {
     use super::*;
     use approx::assert_relative_eq;
 
+    #[test]
+    fn test_julian_date_across_gregorian_reform() {
+        let last_julian = JulianDate::from_calendar(1582, 10, 4.0, CalendarType::Mixed).unwrap();
+        let first_gregorian = JulianDate::from_calendar(1582, 10, 15.0, CalendarType::Mixed).unwrap();
+        assert_eq!(last_julian.days(), 2299159.5);
+        assert_eq!(first_gregorian.days(), 2299160.5);
+
+        for day in 5..=14 {
+            assert!(JulianDate::from_calendar(1582, 10, day as SpiceDouble, CalendarType::Mixed).is_err());
+        }
+        // Proleptic calendars have no gap
+        let proleptic = JulianDate::from_calendar(1582, 10, 10.0, CalendarType::Gregorian).unwrap();
+        assert_eq!(proleptic.days(), 2299155.5);
+        assert_eq!(proleptic.to_calendar(CalendarType::Julian).unwrap(), (1582, 9, 30.0));
+
+        assert_eq!(last_julian.to_calendar(CalendarType::Mixed).unwrap(), (1582, 10, 4.0));
+        assert_eq!(first_gregorian.to_calendar(CalendarType::Mixed).unwrap(), (1582, 10, 15.0));
+        assert_eq!(first_gregorian.to_calendar(CalendarType::Julian).unwrap(), (1582, 10, 5.0));
+
+        let j2000 = JulianDate::from_calendar(2000, 1, 1.5, CalendarType::Mixed).unwrap();
+        assert_eq!(j2000, JulianDate::j2000());
+    }
+
+    #[test]
+    fn test_julian_date_proleptic_julian_ancient_dates() {
+        // Meeus, Astronomical Algorithms, example 7.b
+        let jd = JulianDate::from_calendar(-584, 5, 28.63, CalendarType::Julian).unwrap();
+        assert_relative_eq!(jd.days(), 1507900.13, epsilon = 1e-6);
+        let (year, month, day) = jd.to_calendar(CalendarType::Mixed).unwrap();
+        assert_eq!((year, month), (-584, 5));
+        assert_relative_eq!(day, 28.63, epsilon = 1e-6);
+
+        // The epoch of Julian dates
+        let epoch = JulianDate::from_calendar(-4712, 1, 1.5, CalendarType::Julian).unwrap();
+        assert_eq!(epoch.days(), 0.0);
+
+        // Century years are leap years only in the Julian calendar
+        assert!(JulianDate::from_calendar(1500, 2, 29.0, CalendarType::Julian).is_ok());
+        assert!(JulianDate::from_calendar(1500, 2, 29.0, CalendarType::Gregorian).is_err());
+        let leap_day = JulianDate::from_calendar(1500, 2, 29.0, CalendarType::Mixed).unwrap();
+        assert_eq!(leap_day.to_calendar(CalendarType::Mixed).unwrap(), (1500, 2, 29.0));
+    }
+
     #[test]
     fn test_str_to_et_iso8601() {
         let et = str_to_et("2025-07-23T12:00:00.000Z").unwrap();
@@ -954,8 +1706,8 @@ mod tests {
     #[test]
     fn test_str_to_et_julian_date() {
         let et = str_to_et("JD 2451545.0").unwrap();
-        // J2000 epoch should be exactly 0 seconds
-        assert_relative_eq!(et.seconds(), 0.0, epsilon = 1.0);
+        // The UTC Julian date of J2000 is 32 leap seconds plus TDT - TAI past ET 0
+        assert_relative_eq!(et.seconds(), 64.184, epsilon = 1e-3);
     }
 
     #[test]
@@ -1131,4 +1883,272 @@ mod tests {
         let back_to_string = et_to_utc(future, "D", 3).unwrap();
         assert!(back_to_string.contains("2100"));
     }
+
+    /// Window holding the given intervals
+    fn time_window(intervals: &[[f64; 2]]) -> TimeWindow {
+        let mut window = TimeWindow::new();
+        for &[start, end] in intervals {
+            window.insert_interval(start, end).unwrap();
+        }
+        window
+    }
+
+    #[test]
+    fn test_time_window_difference() {
+        let coverage = time_window(&[[0.0, 10.0], [20.0, 30.0]]);
+        let cut = time_window(&[[5.0, 22.0], [28.0, 40.0]]);
+
+        let diff = coverage.difference(&cut);
+        assert_eq!(diff.intervals(), &[[0.0, 5.0], [22.0, 28.0]]);
+
+        // Other fully containing self leaves nothing
+        let all = time_window(&[[-1.0, 50.0]]);
+        assert!(coverage.difference(&all).is_empty());
+
+        // Disjoint other leaves self unchanged
+        let far = time_window(&[[100.0, 200.0]]);
+        assert_eq!(coverage.difference(&far), coverage);
+        assert!(TimeWindow::new().difference(&cut).is_empty());
+    }
+
+    #[test]
+    fn test_time_window_gaps() {
+        // Adjacent intervals merge, so there is no gap at 10.0
+        let coverage = time_window(&[[0.0, 10.0], [10.0, 15.0], [20.0, 30.0]]);
+        assert_eq!(coverage.cardinality(), 2);
+
+        let gaps = coverage.gaps((-5.0, 40.0));
+        assert_eq!(gaps.intervals(), &[[-5.0, 0.0], [15.0, 20.0], [30.0, 40.0]]);
+
+        // Span fully inside one interval has no gaps
+        assert!(coverage.gaps((1.0, 9.0)).is_empty());
+        assert_eq!(TimeWindow::new().gaps((1.0, 2.0)).intervals(), &[[1.0, 2.0]]);
+        assert!(coverage.gaps((9.0, 1.0)).is_empty());
+        assert!(TimeWindow::new().insert_interval(3.0, 1.0).is_err());
+    }
+
+    #[test]
+    fn test_mars_local_solar_time_matches_cspice_et2lst() {
+        use crate::spk_reader::write;
+        use crate::kernel_system::{furnish_kernel_from_bytes, IsolatedKernels};
+
+        // The Sun at the barycenter, and Mars and Venus in uniform motion over
+        // two days with their IAU rotation models
+        let span = 2.0 * 86400.0;
+        let record = |x: [f64; 2], y: [f64; 2], z: [f64; 2]| write::ChebyshevRecord {
+            start: 0.0, end: span, x: x.to_vec(), y: y.to_vec(), z: z.to_vec(),
+        };
+        let mut spk = write::new_spk("LST").unwrap();
+        let sun = record([0.0, 0.0], [0.0, 0.0], [0.0, 0.0]);
+        let mars = record([-1.9e8, -12.0 * 86400.0], [1.1e8, -20.0 * 86400.0], [4.0e7, -9.0 * 86400.0]);
+        write::add_type2_segment(&mut spk, 10, 0, "J2000", 0.0, span, "SUN", &[sun]).unwrap();
+        let venus = record([1.0e8, 10.0 * 86400.0], [-3.0e7, 32.0 * 86400.0], [1.0e7, 14.0 * 86400.0]);
+        write::add_type2_segment(&mut spk, 499, 0, "J2000", 0.0, span, "MARS", &[mars]).unwrap();
+        write::add_type2_segment(&mut spk, 299, 0, "J2000", 0.0, span, "VENUS", &[venus]).unwrap();
+        let pck = "\\begindata
+BODY499_POLE_RA = ( 317.68143 -0.1061 0.0 )
+BODY499_POLE_DEC = ( 52.88650 -0.0609 0.0 )
+BODY499_PM = ( 176.630 350.89198226 0.0 )
+BODY299_POLE_RA = ( 272.76 0.0 0.0 )
+BODY299_POLE_DEC = ( 67.16 0.0 0.0 )
+BODY299_PM = ( 160.20 -1.4813688 0.0 )
+";
+        let kernels = IsolatedKernels::new();
+        kernels.load_spk("lst.bsp", &spk.to_bytes().unwrap());
+        furnish_kernel_from_bytes(pck.as_bytes().to_vec(), "/pck/mars.tpc").unwrap();
+
+        // et2lst_c from CSPICE N0067 with the same kernels; Mars
+        // planetographic longitudes are positive west
+        let expected = [
+            (30000.0, 0.0, "13:01:46", "13:01:46"),
+            (30000.0, 1.0, "16:50:57", "09:12:35"),
+            (30000.0, -2.5, "03:28:49", "22:34:44"),
+            (30000.0, 3.0, "00:29:19", "01:34:13"),
+            (86400.0, 0.0, "04:16:25", "04:16:25"),
+            (86400.0, 1.0, "08:05:36", "00:27:14"),
+            (86400.0, -2.5, "18:43:28", "13:49:23"),
+            (86400.0, 3.0, "15:43:58", "16:48:52"),
+            (150000.0, 1.0, "01:17:01", "17:38:39"),
+            (150000.0, -2.5, "11:54:52", "07:00:47"),
+        ];
+        for (et, longitude, planetocentric, planetographic) in expected {
+            let (hours, minutes, seconds, time, _) = et_to_local_solar_time(et, 499, longitude, "PLANETOCENTRIC").unwrap();
+            assert_eq!(time, planetocentric, "et {} longitude {}", et, longitude);
+            assert_eq!(format!("{:02}:{:02}:{:02}", hours, minutes, seconds), time);
+            let (_, _, _, time, _) = et_to_local_solar_time(et, 499, longitude, "planetographic").unwrap();
+            assert_eq!(time, planetographic, "et {} longitude {}", et, longitude);
+        }
+
+        // Venus rotates retrograde, so both longitude types run east
+        for (longitude, expected) in [(0.5, "10:21:10"), (-2.0, "19:54:07"), (2.8, "01:34:02")] {
+            for coord_type in ["PLANETOCENTRIC", "PLANETOGRAPHIC"] {
+                let (_, _, _, time, _) = et_to_local_solar_time(50000.0, 299, longitude, coord_type).unwrap();
+                assert_eq!(time, expected, "Venus {} longitude {}", coord_type, longitude);
+            }
+        }
+
+        let (_, _, _, _, ampm) = et_to_local_solar_time(30000.0, 499, 0.0, "PLANETOCENTRIC").unwrap();
+        assert_eq!(ampm, "01:01:46 P.M.");
+        let (_, _, _, _, ampm) = et_to_local_solar_time(30000.0, 499, 3.0, "PLANETOCENTRIC").unwrap();
+        assert_eq!(ampm, "12:29:19 A.M.");
+        assert_eq!(et_to_local_solar_time(30000.0, 10, 1.0, "PLANETOCENTRIC").unwrap().4, "12:00:00 P.M.");
+        assert!(et_to_local_solar_time(30000.0, 499, 0.0, "GEODETIC").is_err());
+    }
+
+    /// Calendar seconds past J2000 of a UTC time string, without leap seconds
+    fn utc_seconds(time: &str) -> SpiceDouble {
+        (time_parse(time).unwrap().julian_date - J2000_JULIAN_DATE) * 86400.0
+    }
+
+    /// ET of a UTC calendar instant expressed as uniform seconds past J2000
+    fn et_from_utc(utc_seconds: SpiceDouble, delta_at: SpiceDouble) -> SpiceDouble {
+        let tai = utc_seconds + delta_at;
+        let tdt = tai + TDT_MINUS_TAI;
+        tdt + LeapSeconds::standard().unwrap().tdb_minus_tdt(tdt)
+    }
+
+    #[test]
+    fn test_time_string_in_uniform_systems_needs_no_leap_seconds() {
+        assert_eq!(time_string(0.0, TimeSystem::Tdb, "C", 0, false).unwrap(), "2000 JAN 01 12:00:00");
+        assert_eq!(time_string(0.0, TimeSystem::Tdb, "ISOD", 3, false).unwrap(), "2000-001T12:00:00.000");
+        assert_eq!(time_string(0.0, TimeSystem::Tdb, "J", 1, false).unwrap(), "JD 2451545.0");
+
+        // TDT differs from TDB only by the periodic term, under 2 ms
+        let tdt = time_string(0.0, TimeSystem::Tdt, "ISOC", 6, false).unwrap();
+        assert!(tdt.starts_with("2000-01-01T11:59:59.99") || tdt.starts_with("2000-01-01T12:00:00.00"), "{}", tdt);
+
+        assert!(time_string(0.0, TimeSystem::Utc, "C", 0, false).is_err());
+        assert_eq!(
+            time_string(0.0, TimeSystem::Utc, "C", 3, true).unwrap(),
+            et_to_utc_format(0.0, "C", 3).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_leap_seconds_from_furnished_lsk() {
+        // A synthetic LSK with a single leap second at the start of 2030
+        let lsk = r#"
+\begindata
+DELTET/DELTA_T_A = 32.184
+DELTET/K         = 1.657D-3
+DELTET/EB        = 1.671D-2
+DELTET/M         = ( 6.239996D0 1.99096871D-7 )
+DELTET/DELTA_AT  = ( 37, @2017-JAN-1
+                     38, @2030-JAN-1 )
+\begintext
+"#;
+        let mut pool = crate::kernel_pool::KernelPool::new();
+        let lines: Vec<String> = lsk.lines().map(String::from).collect();
+        pool.load_from_memory(&lines).unwrap();
+        let leaps = LeapSeconds::from_pool(&pool).unwrap().unwrap();
+        assert_eq!(leaps.delta_at.len(), 2);
+
+        let boundary = utc_seconds("2030-01-01T00:00:00");
+        let before = leaps.utc_to_et(boundary - 1.0);
+        let after = leaps.utc_to_et(boundary);
+        // One UTC second apart, two seconds apart in ET
+        assert_relative_eq!(after - before, 2.0, epsilon = 1e-6);
+        assert_relative_eq!(leaps.delta_et_utc(after) - leaps.delta_et_utc(before), 1.0, epsilon = 1e-6);
+        assert_relative_eq!(leaps.delta_et_utc(before), 37.0 + 32.184, epsilon = 2e-3);
+
+        // naif0012.tls has no leap second there
+        let standard = LeapSeconds::standard().unwrap();
+        assert_relative_eq!(standard.utc_to_et(boundary) - standard.utc_to_et(boundary - 1.0), 1.0, epsilon = 1e-6);
+        assert_relative_eq!(standard.utc_to_et(boundary - 1.0), before, epsilon = 1e-9);
+
+        // The inserted second is reported as 23:59:60
+        let fields = utc_fields(&leaps, before + 1.0, 0).unwrap();
+        assert_eq!((fields.year, fields.month, fields.day, fields.hour, fields.minute), (2029, 12, 31, 23, 59));
+        assert_eq!(fields.second_units, 60);
+    }
+
+    #[test]
+    fn test_time_output_picture_leap_second() {
+        let picture = "YYYY-MM-DDTHR:MN:SC.### ::UTC";
+        let new_year = utc_seconds("2017-01-01T00:00:00");
+
+        let before = et_from_utc(new_year - 0.5, 36.0);
+        assert_eq!(time_output_picture(before, picture).unwrap(), "2016-12-31T23:59:59.500");
+
+        // The inserted second lies between the old and new TAI-UTC offsets
+        let leap = et_from_utc(new_year - 0.5, 37.0);
+        assert_eq!(time_output_picture(leap, picture).unwrap(), "2016-12-31T23:59:60.500");
+
+        let after = et_from_utc(new_year + 0.25, 37.0);
+        assert_eq!(time_output_picture(after, picture).unwrap(), "2017-01-01T00:00:00.250");
+        assert_eq!(time_output_picture(after, "YYYY-DOY MON DD").unwrap(), "2017-001 JAN 01");
+    }
+
+    #[test]
+    fn test_time_output_picture_rounding_rollover() {
+        let picture = "YYYY-MM-DDTHR:MN:SC ::UTC";
+        let midnight = utc_seconds("2021-01-01T00:00:00");
+
+        let almost = et_from_utc(midnight - 0.4, 37.0);
+        assert_eq!(time_output_picture(almost, picture).unwrap(), "2021-01-01T00:00:00");
+        assert_eq!(time_output_picture(almost, "YYYY-MM-DDTHR:MN:SC.## ::UTC").unwrap(), "2020-12-31T23:59:59.60");
+
+        let nearly = et_from_utc(midnight - 0.0004, 37.0);
+        assert_eq!(time_output_picture(nearly, "HR:MN:SC.###").unwrap(), "00:00:00.000");
+
+        // TDB output needs no leap seconds: ET 0 is the J2000 epoch
+        assert_eq!(time_output_picture(0.0, "YYYY MON DD HR:MN:SC ::TDB").unwrap(), "2000 JAN 01 12:00:00");
+        assert_eq!(time_output_picture(-43200.2, "YYYY-MM-DD HR:MN:SC.# ::TDB").unwrap(), "1999-12-31 23:59:59.8");
+    }
+
+    #[test]
+    fn test_et_to_utc_format_j2000() {
+        // ET 0 is 64.184 s (plus the periodic TDB term) after 2000-01-01T11:58:55.816 UTC
+        assert_eq!(et_to_utc_format(0.0, "C", 3).unwrap(), "2000 JAN 01 11:58:55.816");
+        assert_eq!(et_to_utc_format(0.0, "D", 3).unwrap(), "2000-001 // 11:58:55.816");
+        assert_eq!(et_to_utc_format(0.0, "J", 4).unwrap(), "JD 2451544.9993");
+        assert_eq!(et_to_utc_format(0.0, "ISOC", 3).unwrap(), "2000-01-01T11:58:55.816");
+        assert_eq!(et_to_utc_format(0.0, "ISOD", 3).unwrap(), "2000-001T11:58:55.816");
+
+        // Precision controls the seconds field and rounds
+        assert_eq!(et_to_utc_format(0.0, "ISOC", 0).unwrap(), "2000-01-01T11:58:56");
+        assert_eq!(et_to_utc_format(0.0, "isod", 5).unwrap(), "2000-001T11:58:55.81607");
+        assert!(et_to_utc_format(0.0, "X", 3).is_err());
+    }
+
+    #[test]
+    fn test_unit_time_tdb_tt_tai_round_trip() {
+        let _kernels = crate::kernel_system::IsolatedKernels::new();
+
+        // At J2000 the periodic TDB - TT term is about -73 microseconds
+        let periodic = LeapSeconds::standard().unwrap().tdb_minus_tdt(0.0);
+        assert!(periodic.abs() > 1e-5 && periodic.abs() < DELTET_K);
+
+        let tt = unit_time(0.0, "TDB", "TT").unwrap();
+        assert_relative_eq!(0.0 - tt, periodic, epsilon = 1e-12);
+        let tai = unit_time(0.0, "ET", "TAI").unwrap();
+        assert_relative_eq!(0.0 - tai, TDT_MINUS_TAI + periodic, epsilon = 1e-12);
+        assert_relative_eq!(unit_time(0.0, "TDB", "JED").unwrap(), 2451545.0);
+
+        for epoch in [-3.2e9, 0.0, 7.5e8] {
+            for scale in ["TAI", "TDT"] {
+                let converted = unit_time(epoch, "TDB", scale).unwrap();
+                assert_relative_eq!(unit_time(converted, scale, "TDB").unwrap(), epoch, epsilon = 1e-9);
+            }
+            // Julian dates only resolve about 40 microseconds near J2000
+            let jdtdt = unit_time(epoch, "TDB", "JDTDT").unwrap();
+            assert_relative_eq!(unit_time(jdtdt, "JDTDT", "TDB").unwrap(), epoch, epsilon = 1e-4);
+        }
+        assert!(unit_time(0.0, "TDB", "UTC").is_err());
+
+        // A loaded LSK's DELTET values replace the standard ones
+        crate::kernel_pool::load_text_kernel(r#"
+\begindata
+DELTET/DELTA_T_A = 32.0
+DELTET/K         = 3.314D-3
+DELTET/EB        = 1.671D-2
+DELTET/M         = ( 6.239996D0 1.99096871D-7 )
+DELTET/DELTA_AT  = ( 37, @2017-JAN-1 )
+\begintext
+"#).unwrap();
+        let tt = unit_time(0.0, "TDB", "TT").unwrap();
+        assert_relative_eq!(0.0 - tt, leap_seconds().unwrap().tdb_minus_tdt(tt), epsilon = 1e-12);
+        assert_relative_eq!(0.0 - tt, 2.0 * periodic, epsilon = 1e-8);
+        assert_relative_eq!(unit_time(0.0, "TDB", "TAI").unwrap(), tt - 32.0, epsilon = 1e-12);
+    }
 }
@@ -0,0 +1,416 @@
+//! Spacecraft Clock (SCLK) Conversions for RustSPICE
+//!
+//! This module provides equivalency to the CSPICE type 1 SCLK functions:
+//! - scs2e_c → sclk_string_to_et() - Spacecraft clock string to Ephemeris Time
+//! - sce2s_c → et_to_sclk_string() - Ephemeris Time to spacecraft clock string
+//!
+//! Clock parameters are read from the kernel pool using the standard SCLK
+//! kernel variable names (`SCLK01_MODULI_nnn`, `SCLK01_COEFFICIENTS_nnn`, ...)
+//! where `nnn` is the negated spacecraft ID.
+
+#[cfg(not(feature = "std"))]
+use alloc::{string::String, vec::Vec, format};
+#[cfg(feature = "std")]
+use std::{string::String, vec::Vec, format};
+
+use crate::foundation::{SpiceDouble, SpiceInt};
+use crate::error_handling::{SpiceResult, SpiceError, SpiceErrorType};
+use crate::kernel_pool::{self, MAX_VALUES_PER_VARIABLE};
+use super::leap_seconds;
+
+
+/// Parallel time system of a type 1 spacecraft clock
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SclkTimeSystem {
+    /// Barycentric Dynamical Time (ET)
+    Tdb,
+    /// Terrestrial Dynamical Time
+    Tdt,
+}
+
+/// One SCLK01 coefficient record
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SclkCoefficient {
+    /// Encoded SCLK (ticks since clock start)
+    pub ticks: SpiceDouble,
+    /// Parallel time at `ticks`
+    pub parallel_time: SpiceDouble,
+    /// Parallel-time seconds per most significant clock count
+    pub rate: SpiceDouble,
+}
+
+/// Type 1 spacecraft clock parameters
+#[derive(Debug, Clone)]
+pub struct Sclk01 {
+    pub spacecraft: SpiceInt,
+    pub time_system: SclkTimeSystem,
+    pub moduli: Vec<SpiceDouble>,
+    pub offsets: Vec<SpiceDouble>,
+    pub delimiter: char,
+    pub partition_start: Vec<SpiceDouble>,
+    pub partition_end: Vec<SpiceDouble>,
+    pub coefficients: Vec<SclkCoefficient>,
+}
+
+impl Sclk01 {
+    /// Load the clock parameters for a spacecraft from the kernel pool
+    pub fn from_pool(spacecraft: SpiceInt) -> SpiceResult<Self> {
+        Self::load(spacecraft, |name| {
+            kernel_pool::get_double_pool(name, 0, MAX_VALUES_PER_VARIABLE)
+        })
+    }
+
+    /// Load the clock parameters using a numeric variable lookup
+    pub(crate) fn load<F>(spacecraft: SpiceInt, lookup: F) -> SpiceResult<Self>
+    where
+        F: Fn(&str) -> SpiceResult<(Vec<SpiceDouble>, bool)>,
+    {
+        let suffix = -spacecraft;
+        let required = |name: String| -> SpiceResult<Vec<SpiceDouble>> {
+            match lookup(&name)? {
+                (values, true) if !values.is_empty() => Ok(values),
+                _ => Err(SpiceError::new(
+                    SpiceErrorType::KernelNotFound,
+                    format!("SCLK kernel variable {} not found for spacecraft {}", name, spacecraft),
+                )),
+            }
+        };
+
+        if let (values, true) = lookup(&format!("SCLK_DATA_TYPE_{}", suffix))? {
+            if values.first().map(|&t| t as SpiceInt) != Some(1) {
+                return Err(SpiceError::new(
+                    SpiceErrorType::InvalidKernelData,
+                    format!("Unsupported SCLK data type for spacecraft {}: {:?}", spacecraft, values),
+                ));
+            }
+        }
+
+        let time_system = match lookup(&format!("SCLK01_TIME_SYSTEM_{}", suffix))? {
+            (values, true) if values.first() == Some(&2.0) => SclkTimeSystem::Tdt,
+            (values, true) if values.first() != Some(&1.0) => {
+                return Err(SpiceError::new(
+                    SpiceErrorType::InvalidKernelData,
+                    format!("Unsupported SCLK time system for spacecraft {}: {:?}", spacecraft, values),
+                ));
+            }
+            _ => SclkTimeSystem::Tdb,
+        };
+
+        let n_fields = required(format!("SCLK01_N_FIELDS_{}", suffix))?[0] as usize;
+        let moduli = required(format!("SCLK01_MODULI_{}", suffix))?;
+        let offsets = required(format!("SCLK01_OFFSETS_{}", suffix))?;
+        if n_fields == 0 || moduli.len() != n_fields || offsets.len() != n_fields {
+            return Err(SpiceError::new(
+                SpiceErrorType::InvalidKernelData,
+                format!("Inconsistent SCLK field definitions for spacecraft {}", spacecraft),
+            ));
+        }
+        if moduli.iter().any(|&m| m < 1.0) {
+            return Err(SpiceError::new(
+                SpiceErrorType::InvalidKernelData,
+                format!("SCLK moduli must be positive for spacecraft {}", spacecraft),
+            ));
+        }
+
+        let delimiter = match lookup(&format!("SCLK01_OUTPUT_DELIM_{}", suffix))? {
+            (values, true) if !values.is_empty() => match values[0] as SpiceInt {
+                1 => '.',
+                2 => ':',
+                3 => '-',
+                4 => ',',
+                5 => ' ',
+                code => {
+                    return Err(SpiceError::new(
+                        SpiceErrorType::InvalidKernelData,
+                        format!("Invalid SCLK output delimiter code {}", code),
+                    ));
+                }
+            },
+            _ => '.',
+        };
+
+        let partition_start = required(format!("SCLK_PARTITION_START_{}", suffix))?;
+        let partition_end = required(format!("SCLK_PARTITION_END_{}", suffix))?;
+        if partition_start.len() != partition_end.len() {
+            return Err(SpiceError::new(
+                SpiceErrorType::InvalidKernelData,
+                format!("Partition start/end counts differ for spacecraft {}", spacecraft),
+            ));
+        }
+
+        let raw = required(format!("SCLK01_COEFFICIENTS_{}", suffix))?;
+        if raw.len() % 3 != 0 {
+            return Err(SpiceError::new(
+                SpiceErrorType::InvalidKernelData,
+                format!("SCLK coefficients for spacecraft {} are not triples", spacecraft),
+            ));
+        }
+        let coefficients = raw
+            .chunks(3)
+            .map(|c| SclkCoefficient { ticks: c[0], parallel_time: c[1], rate: c[2] })
+            .collect();
+
+        Ok(Self {
+            spacecraft,
+            time_system,
+            moduli,
+            offsets,
+            delimiter,
+            partition_start,
+            partition_end,
+            coefficients,
+        })
+    }
+
+    /// Number of ticks in one count of each field
+    fn field_weights(&self) -> Vec<SpiceDouble> {
+        let mut weights = vec![1.0; self.moduli.len()];
+        for i in (0..self.moduli.len() - 1).rev() {
+            weights[i] = weights[i + 1] * self.moduli[i + 1];
+        }
+        weights
+    }
+
+    /// Parse a clock string such as `1/12345:067:890` into encoded ticks
+    pub fn string_to_ticks(&self, sclk: &str) -> SpiceResult<SpiceDouble> {
+        let (partition, clock) = match sclk.split_once('/') {
+            Some((p, rest)) => {
+                let p = p.trim().parse::<usize>().map_err(|_| SpiceError::new(
+                    SpiceErrorType::InvalidTime,
+                    format!("Invalid SCLK partition in '{}'", sclk),
+                ))?;
+                (Some(p), rest)
+            }
+            None => (None, sclk),
+        };
+
+        let fields: Vec<&str> = clock
+            .split(['.', ':', '-', ',', ' '])
+            .filter(|f| !f.is_empty())
+            .collect();
+        if fields.is_empty() || fields.len() > self.moduli.len() {
+            return Err(SpiceError::new(
+                SpiceErrorType::InvalidTime,
+                format!("SCLK string '{}' must have 1 to {} fields", sclk, self.moduli.len()),
+            ));
+        }
+
+        let weights = self.field_weights();
+        let mut ticks = 0.0;
+        for (i, field) in fields.iter().enumerate() {
+            let value = field.parse::<SpiceDouble>().map_err(|_| SpiceError::new(
+                SpiceErrorType::InvalidTime,
+                format!("Invalid SCLK field '{}' in '{}'", field, sclk),
+            ))?;
+            ticks += (value - self.offsets[i]) * weights[i];
+        }
+
+        let index = match partition {
+            Some(p) if p >= 1 && p <= self.partition_start.len() => p - 1,
+            Some(p) => {
+                return Err(SpiceError::new(
+                    SpiceErrorType::InvalidTime,
+                    format!("SCLK partition {} does not exist for spacecraft {}", p, self.spacecraft),
+                ));
+            }
+            None => (0..self.partition_start.len())
+                .find(|&i| ticks >= self.partition_start[i] && ticks <= self.partition_end[i])
+                .ok_or_else(|| SpiceError::new(
+                    SpiceErrorType::InvalidTime,
+                    format!("SCLK string '{}' is not in any partition", sclk),
+                ))?,
+        };
+
+        if ticks < self.partition_start[index] || ticks > self.partition_end[index] {
+            return Err(SpiceError::new(
+                SpiceErrorType::InvalidTime,
+                format!("SCLK string '{}' is outside partition {}", sclk, index + 1),
+            ));
+        }
+
+        let preceding: SpiceDouble = (0..index)
+            .map(|i| self.partition_end[i] - self.partition_start[i])
+            .sum();
+        Ok(preceding + ticks - self.partition_start[index])
+    }
+
+    /// Format encoded ticks as a partitioned clock string
+    pub fn ticks_to_string(&self, ticks: SpiceDouble) -> SpiceResult<String> {
+        let ticks = ticks.round();
+        if ticks < 0.0 {
+            return Err(SpiceError::new(
+                SpiceErrorType::InvalidTime,
+                format!("Encoded SCLK {} precedes the spacecraft clock start", ticks),
+            ));
+        }
+
+        let last = self.partition_start.len() - 1;
+        let mut preceding = 0.0;
+        let mut index = last;
+        for i in 0..=last {
+            let length = self.partition_end[i] - self.partition_start[i];
+            if ticks <= preceding + length || i == last {
+                index = i;
+                break;
+            }
+            preceding += length;
+        }
+
+        let mut remainder = ticks - preceding + self.partition_start[index];
+        if remainder > self.partition_end[index] {
+            return Err(SpiceError::new(
+                SpiceErrorType::InvalidTime,
+                format!("Encoded SCLK {} is beyond the last partition", ticks),
+            ));
+        }
+
+        let weights = self.field_weights();
+        let mut fields = Vec::with_capacity(weights.len());
+        for (i, &weight) in weights.iter().enumerate() {
+            let count = (remainder / weight).floor();
+            remainder -= count * weight;
+            let width = format!("{}", (self.moduli[i] - 1.0 + self.offsets[i]) as u64).len();
+            fields.push(format!("{:0width$}", (count + self.offsets[i]) as u64, width = width));
+        }
+
+        Ok(format!("{}/{}", index + 1, fields.join(&self.delimiter.to_string())))
+    }
+
+    /// Convert encoded ticks to Ephemeris Time (equivalent to sct2e_c)
+    pub fn ticks_to_et(&self, ticks: SpiceDouble) -> SpiceResult<SpiceDouble> {
+        let weights = self.field_weights();
+        let record = self.coefficients
+            .iter()
+            .rev()
+            .find(|c| c.ticks <= ticks)
+            .or_else(|| self.coefficients.first())
+            .ok_or_else(|| SpiceError::new(
+                SpiceErrorType::InsufficientData,
+                format!("No SCLK coefficients for spacecraft {}", self.spacecraft),
+            ))?;
+
+        let parallel = record.parallel_time + (ticks - record.ticks) * record.rate / weights[0];
+        Ok(match self.time_system {
+            SclkTimeSystem::Tdb => parallel,
+            SclkTimeSystem::Tdt => parallel + leap_seconds()?.tdb_minus_tdt(parallel),
+        })
+    }
+
+    /// Convert Ephemeris Time to encoded ticks (equivalent to sce2c_c)
+    pub fn et_to_ticks(&self, et: SpiceDouble) -> SpiceResult<SpiceDouble> {
+        let parallel = match self.time_system {
+            SclkTimeSystem::Tdb => et,
+            SclkTimeSystem::Tdt => et - leap_seconds()?.tdb_minus_tdt(et),
+        };
+
+        let weights = self.field_weights();
+        let record = self.coefficients
+            .iter()
+            .rev()
+            .find(|c| c.parallel_time <= parallel)
+            .or_else(|| self.coefficients.first())
+            .ok_or_else(|| SpiceError::new(
+                SpiceErrorType::InsufficientData,
+                format!("No SCLK coefficients for spacecraft {}", self.spacecraft),
+            ))?;
+
+        if record.rate == 0.0 {
+            return Err(SpiceError::new(
+                SpiceErrorType::InvalidKernelData,
+                format!("Zero SCLK rate for spacecraft {}", self.spacecraft),
+            ));
+        }
+
+        Ok(record.ticks + (parallel - record.parallel_time) * weights[0] / record.rate)
+    }
+}
+
+/// Convert a spacecraft clock string to Ephemeris Time (equivalent to scs2e_c)
+pub fn sclk_string_to_et(sc: SpiceInt, sclk: &str) -> SpiceResult<SpiceDouble> {
+    let clock = Sclk01::from_pool(sc)?;
+    let ticks = clock.string_to_ticks(sclk)?;
+    clock.ticks_to_et(ticks)
+}
+
+/// Convert Ephemeris Time to a spacecraft clock string (equivalent to sce2s_c)
+pub fn et_to_sclk_string(sc: SpiceInt, et: SpiceDouble) -> SpiceResult<String> {
+    let clock = Sclk01::from_pool(sc)?;
+    let ticks = clock.et_to_ticks(et)?;
+    clock.ticks_to_string(ticks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kernel_pool::KernelPool;
+    use approx::assert_relative_eq;
+
+    /// Synthetic SCLK kernel for fictitious spacecraft -9999
+    const TEST_SCLK: &str = r"
+\begindata
+SCLK_DATA_TYPE_9999 = ( 1 )
+SCLK01_TIME_SYSTEM_9999 = ( 1 )
+SCLK01_N_FIELDS_9999 = ( 3 )
+SCLK01_MODULI_9999 = ( 4294967296, 256, 1000 )
+SCLK01_OFFSETS_9999 = ( 0, 0, 0 )
+SCLK01_OUTPUT_DELIM_9999 = ( 2 )
+SCLK_PARTITION_START_9999 = ( 0.0, 5.12D10 )
+SCLK_PARTITION_END_9999 = ( 2.56D10, 1.0D15 )
+SCLK01_COEFFICIENTS_9999 = ( 0.0, 1.0D8, 1.0, 2.56D10, 1.001D8, 1.000001 )
+\begintext
+";
+
+    fn test_clock() -> Sclk01 {
+        let mut pool = KernelPool::new();
+        let lines: Vec<String> = TEST_SCLK.lines().map(|l| l.to_string()).collect();
+        pool.load_from_memory(&lines).unwrap();
+        Sclk01::load(-9999, |name| pool.get_double(name, 0, MAX_VALUES_PER_VARIABLE)).unwrap()
+    }
+
+    #[test]
+    fn test_sclk_string_to_ticks() {
+        let clock = test_clock();
+        assert_eq!(clock.delimiter, ':');
+
+        // 12345 counts of 256000 ticks plus 67 * 1000 + 890
+        let ticks = clock.string_to_ticks("1/12345:067:890").unwrap();
+        assert_eq!(ticks, 12345.0 * 256000.0 + 67890.0);
+
+        // Other delimiters and the implicit partition are accepted
+        assert_eq!(clock.string_to_ticks("12345.67.890").unwrap(), ticks);
+
+        // Second partition starts after the full length of the first
+        let second = clock.string_to_ticks("2/200000:000:000").unwrap();
+        assert_eq!(second, 2.56e10 + 200000.0 * 256000.0 - 5.12e10);
+
+        assert!(clock.string_to_ticks("3/1:0:0").is_err());
+        assert!(clock.string_to_ticks("1/1:2:3:4").is_err());
+        assert!(clock.string_to_ticks("1/abc").is_err());
+        assert!(clock.string_to_ticks("2/1:0:0").is_err());
+    }
+
+    #[test]
+    fn test_sclk_et_round_trip() {
+        let clock = test_clock();
+
+        let ticks = clock.string_to_ticks("1/12345:067:890").unwrap();
+        let et = clock.ticks_to_et(ticks).unwrap();
+        assert_relative_eq!(et, 1.0e8 + 12345.0 + 67890.0 / 256000.0, epsilon = 1e-6);
+
+        let back = clock.et_to_ticks(et).unwrap();
+        assert_eq!(clock.ticks_to_string(back).unwrap(), "1/0000012345:067:890");
+
+        // Second coefficient record uses its own rate and offset
+        let et2 = clock.ticks_to_et(2.56e10 + 256000.0 * 1000.0).unwrap();
+        assert_relative_eq!(et2, 1.001e8 + 1000.0 * 1.000001, epsilon = 1e-6);
+        let string2 = clock.ticks_to_string(clock.et_to_ticks(et2).unwrap()).unwrap();
+        assert_eq!(string2, "2/0000201000:000:000");
+    }
+
+    #[test]
+    fn test_sclk_missing_kernel() {
+        let pool = KernelPool::new();
+        let result = Sclk01::load(-4242, |name| pool.get_double(name, 0, MAX_VALUES_PER_VARIABLE));
+        assert!(result.is_err());
+    }
+}
@@ -1,7 +1,7 @@
-//! WASM bindings for RustSPICE time system functions
+//! WASM bindings for RustSPICE time system and ephemeris functions
 //! 
-//! This module provides WebAssembly bindings for time conversion functions,
-//! making them accessible from JavaScript/TypeScript.
+//! This module provides WebAssembly bindings for time conversion and state
+//! lookup functions, making them accessible from JavaScript/TypeScript.
 
 #[cfg(target_arch = "wasm32")]
 use wasm_bindgen::prelude::*;
@@ -9,6 +9,8 @@ use wasm_bindgen::prelude::*;
 #[cfg(target_arch = "wasm32")]
 use crate::foundation::EphemerisTime;
 #[cfg(target_arch = "wasm32")]
+use crate::error_handling::SpiceError;
+#[cfg(target_arch = "wasm32")]
 use crate::time_system::{str_to_et, et_to_utc, time_parse, time_output};
 
 /// WASM-compatible time result
@@ -256,6 +258,109 @@ pub fn wasm_delta_et_utc(et_seconds: f64) -> f64 {
     crate::time_system::delta_et_utc(et).unwrap_or(64.184)
 }
 
+/// State of `target` relative to `center` from the SPK segment that stores
+/// exactly that pair, in the segment's own frame: [x, y, z, vx, vy, vz] in km
+/// and km/s. No chaining through other centers is attempted.
+#[cfg(target_arch = "wasm32")]
+#[wasm_bindgen]
+pub fn wasm_segment_state(target: i32, center: i32, et: f64) -> Result<Vec<f64>, JsValue> {
+    crate::spk_reader::with_spk_reader(|reader| reader.compute_state(target, center, et))
+        .map(|state| {
+            let p = state.position.0;
+            let v = state.velocity.0;
+            vec![p[0], p[1], p[2], v[0], v[1], v[2]]
+        })
+        .map_err(to_js_error)
+}
+
+/// Convert a SpiceError into a JS `Error` carrying its long message, named
+/// by its short message (for example `SPICE(INVALIDTARGET)`)
+#[cfg(target_arch = "wasm32")]
+fn to_js_error(error: SpiceError) -> JsValue {
+    let js_error = js_sys::Error::new(&error.message);
+    js_error.set_name(&error.short_message());
+    js_error.into()
+}
+
+/// State vector returned to JavaScript: position in km, velocity in km/s,
+/// and one-way light time in seconds
+#[cfg(target_arch = "wasm32")]
+#[wasm_bindgen]
+#[derive(Clone)]
+pub struct WasmStateVector {
+    x: f64,
+    y: f64,
+    z: f64,
+    vx: f64,
+    vy: f64,
+    vz: f64,
+    light_time: f64,
+}
+
+#[cfg(target_arch = "wasm32")]
+#[wasm_bindgen]
+impl WasmStateVector {
+    #[wasm_bindgen(getter)]
+    pub fn x(&self) -> f64 { self.x }
+
+    #[wasm_bindgen(getter)]
+    pub fn y(&self) -> f64 { self.y }
+
+    #[wasm_bindgen(getter)]
+    pub fn z(&self) -> f64 { self.z }
+
+    #[wasm_bindgen(getter)]
+    pub fn vx(&self) -> f64 { self.vx }
+
+    #[wasm_bindgen(getter)]
+    pub fn vy(&self) -> f64 { self.vy }
+
+    #[wasm_bindgen(getter)]
+    pub fn vz(&self) -> f64 { self.vz }
+
+    #[wasm_bindgen(getter = lightTime)]
+    pub fn light_time(&self) -> f64 { self.light_time }
+}
+
+/// State of a target relative to an observer (WASM version of spkezr_c)
+///
+/// Returns `{ x, y, z, vx, vy, vz, lightTime }`; failures are thrown as JS
+/// `Error`s.
+#[cfg(target_arch = "wasm32")]
+#[wasm_bindgen]
+pub fn wasm_ephemeris_state(target: &str, et: f64, frame: &str, abcorr: &str, observer: &str) -> Result<WasmStateVector, JsValue> {
+    let state = crate::ephemeris::ephemeris_state(target, EphemerisTime::new(et), frame, abcorr, observer)
+        .map_err(to_js_error)?;
+    let [x, y, z] = state.position.0;
+    let [vx, vy, vz] = state.velocity.0;
+    Ok(WasmStateVector { x, y, z, vx, vy, vz, light_time: state.light_time })
+}
+
+/// Furnish a kernel from its bytes (WASM version of furnsh_c)
+///
+/// No filesystem is needed: SPK files are registered with the SPK reader and
+/// text kernels loaded into the kernel pool, as by `furnish_kernel_from_bytes`.
+///
+/// ```js
+/// const response = await fetch("kernels/de440s.bsp");
+/// wasm_furnish_from_bytes(new Uint8Array(await response.arrayBuffer()), "de440s.bsp");
+/// const moon = wasm_ephemeris_state("MOON", 0.0, "J2000", "NONE", "EARTH");
+/// ```
+#[cfg(target_arch = "wasm32")]
+#[wasm_bindgen]
+pub fn wasm_furnish_from_bytes(data: &[u8], name: &str) -> Result<(), JsValue> {
+    crate::kernel_system::furnish_kernel_from_bytes(data.to_vec(), name)
+        .map(|_| ())
+        .map_err(to_js_error)
+}
+
+/// Unload every kernel (WASM version of kclear_c)
+#[cfg(target_arch = "wasm32")]
+#[wasm_bindgen]
+pub fn wasm_clear_kernels() -> Result<(), JsValue> {
+    crate::kernel_system::clear_kernels().map_err(to_js_error)
+}
+
 // Console logging for WASM debugging
 #[cfg(target_arch = "wasm32")]
 #[wasm_bindgen]
@@ -279,4 +384,86 @@ pub fn wasm_initialize() {
     console_log!("- wasm_et_to_utc(etSeconds, format, precision)");
     console_log!("- wasm_time_parse(timeString)");
     console_log!("- wasm_time_output(etSeconds, picture)");
+    console_log!("- wasm_ephemeris_state(target, et, frame, abcorr, observer)");
+    console_log!("- wasm_furnish_from_bytes(bytes, name)");
+    console_log!("- wasm_clear_kernels()");
+}
+
+#[cfg(all(test, target_arch = "wasm32"))]
+mod tests {
+    use super::*;
+    use crate::spk_reader::{initialize_spk_reader, write};
+    use crate::kernel_system::furnish_kernel_from_bytes;
+    use wasm_bindgen_test::*;
+
+    #[wasm_bindgen_test]
+    fn test_segment_state_reads_native_center() {
+        initialize_spk_reader().unwrap();
+
+        let record = |x: f64| write::ChebyshevRecord {
+            start: 0.0, end: 1000.0, x: vec![x, 0.0], y: vec![0.0, 0.0], z: vec![0.0, 2.0],
+        };
+        let mut spk = write::new_spk("WASM SEGMENTS").unwrap();
+        write::add_type2_segment(&mut spk, 3, 0, "J2000", 0.0, 1000.0, "EMB", &[record(1.5e8)]).unwrap();
+        write::add_type2_segment(&mut spk, 301, 3, "J2000", 0.0, 1000.0, "MOON", &[record(3.8e5)]).unwrap();
+        furnish_kernel_from_bytes(spk.to_bytes().unwrap(), "/wasm/segments.bsp").unwrap();
+
+        // The Moon segment is stored relative to the Earth-Moon barycenter
+        let state = wasm_segment_state(301, 3, 500.0).unwrap();
+        assert_eq!(state.len(), 6);
+        assert!((state[0] - 3.8e5).abs() < 1e-6);
+        assert!((state[5] - 2.0 / 500.0).abs() < 1e-12);
+
+        // No segment stores the Moon relative to the Sun
+        let error: js_sys::Error = wasm_segment_state(301, 10, 500.0).unwrap_err().dyn_into().unwrap();
+        assert!(String::from(error.message()).contains("No SPK data found for body 301 relative to 10"));
+        assert_eq!(String::from(error.name()), "SPICE(INSUFFICIENTDATA)");
+    }
+
+    #[wasm_bindgen_test]
+    fn test_ephemeris_state_returns_state_object() {
+        initialize_spk_reader().unwrap();
+
+        let record = |x: f64| write::ChebyshevRecord {
+            start: 0.0, end: 1000.0, x: vec![x, 500.0], y: vec![0.0, 0.0], z: vec![0.0, 0.0],
+        };
+        let mut spk = write::new_spk("WASM STATES").unwrap();
+        write::add_type2_segment(&mut spk, -9201, 399, "J2000", 0.0, 1000.0, "PROBE", &[record(7000.0)]).unwrap();
+        furnish_kernel_from_bytes(spk.to_bytes().unwrap(), "/wasm/states.bsp").unwrap();
+
+        let state = wasm_ephemeris_state("-9201", 500.0, "J2000", "NONE", "EARTH").unwrap();
+        let expected = crate::ephemeris::ephemeris_state("-9201", EphemerisTime::new(500.0), "J2000", "NONE", "EARTH").unwrap();
+        assert_eq!([state.x(), state.y(), state.z()], expected.position.0);
+        assert_eq!([state.vx(), state.vy(), state.vz()], expected.velocity.0);
+        assert_eq!(state.light_time(), expected.light_time);
+        assert!((state.vx() - 1.0).abs() < 1e-12);
+
+        // Errors surface as JS Error objects with the long message
+        let error: js_sys::Error = wasm_ephemeris_state("NOT A BODY", 500.0, "J2000", "NONE", "EARTH")
+            .unwrap_err()
+            .dyn_into()
+            .unwrap();
+        assert!(String::from(error.message()).contains("NOT A BODY"));
+        assert!(String::from(error.name()).starts_with("SPICE("));
+    }
+
+    #[wasm_bindgen_test]
+    fn test_furnish_from_bytes_registers_spk() {
+        let record = write::ChebyshevRecord {
+            start: 0.0, end: 100.0, x: vec![12.0, 0.0], y: vec![-3.0, 0.0], z: vec![0.5, 0.0],
+        };
+        let mut spk = write::new_spk("WASM BYTES").unwrap();
+        write::add_type2_segment(&mut spk, -9301, 399, "J2000", 0.0, 100.0, "TINY", &[record]).unwrap();
+        let bytes = spk.to_bytes().unwrap();
+
+        // The bytes arrive as a Uint8Array, with no filesystem behind them
+        let array = js_sys::Uint8Array::from(bytes.as_slice());
+        wasm_furnish_from_bytes(&array.to_vec(), "tiny.bsp").unwrap();
+        let state = wasm_ephemeris_state("-9301", 50.0, "J2000", "NONE", "399").unwrap();
+        assert_eq!([state.x(), state.y(), state.z()], [12.0, -3.0, 0.5]);
+
+        wasm_clear_kernels().unwrap();
+        assert!(wasm_ephemeris_state("-9301", 50.0, "J2000", "NONE", "399").is_err());
+        assert!(wasm_furnish_from_bytes(&[], "empty.bsp").is_err());
+    }
 }